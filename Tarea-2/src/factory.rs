@@ -4,6 +4,15 @@ use std::time::{Duration, Instant};
 use crate::product::Product;
 use crate::scheduler::{Scheduler, SchedulingAlgorithm};
 
+// Trazas de las estaciones, solo si la fábrica es verbose
+macro_rules! trace {
+    ($verbose:expr, $($arg:tt)*) => {
+        if $verbose {
+            println!($($arg)*);
+        }
+    };
+}
+
 pub struct Factory {
     tx_input: mpsc::SyncSender<Product>,       // Canal para enviar productos a la primera estación
     start: Instant,                            // Marca de tiempo del inicio de la simulación
@@ -32,8 +41,8 @@ pub struct StationTimes {
     pub packaging_ms: u64,
 }
 
-impl StationTimes {
-    pub fn default() -> Self {
+impl Default for StationTimes {
+    fn default() -> Self {
         StationTimes {
             cutting_ms: 2000,
             assembly_ms: 3000,
@@ -109,6 +118,11 @@ impl Factory {
     
     // Inicializa la fábrica con tiempos personalizados para cada estación
     pub fn new_with_times(capacity: usize, algorithm: SchedulingAlgorithm, times: StationTimes) -> Self {
+        Self::new_with_options(capacity, algorithm, times, true)
+    }
+    
+    // Igual que `new_with_times`; con `verbose = false` las estaciones no imprimen trazas
+    pub fn new_with_options(capacity: usize, algorithm: SchedulingAlgorithm, times: StationTimes, verbose: bool) -> Self {
         let (tx_input, rx_input) = mpsc::sync_channel::<Product>(capacity);
         let start = Instant::now();
         let mut handles = Vec::new();
@@ -120,7 +134,7 @@ impl Factory {
         // === ESTACIÓN DE CORTE ===
         {
             let algorithm_cut = algorithm.clone();
-            let start_clone = start.clone();
+            let start_clone = start;
             let tx_next = tx_complete.clone();
             let cutting_time = times.cutting_ms;
             
@@ -146,7 +160,7 @@ impl Factory {
                             product.entry_cutting = Some(start_clone.elapsed());
                         }
                         
-                        println!("▶️  Product {} procesando en Corte ({}ms, acumulado: {}ms)", 
+                        trace!(verbose, "▶️  Product {} procesando en Corte ({}ms, acumulado: {}ms)", 
                                  product.id, time_to_process, product.accumulated_cutting_ms);
                         
                         // Simula el tiempo de procesamiento
@@ -156,7 +170,7 @@ impl Factory {
                         if product.accumulated_cutting_ms >= cutting_time {
                             // Producto completó la estación
                             product.exit_cutting = Some(start_clone.elapsed());
-                            println!("✂️  Product {} completó Corte (total: {}ms)", product.id, product.accumulated_cutting_ms);
+                            trace!(verbose, "✂️  Product {} completó Corte (total: {}ms)", product.id, product.accumulated_cutting_ms);
                             
                             if let Err(e) = tx_next.send(product) {
                                 eprintln!("❌ Error enviando de Corte: {:?}", e);
@@ -166,7 +180,7 @@ impl Factory {
                             // Producto interrumpido, se reprograma
                             let remaining = cutting_time - product.accumulated_cutting_ms;
                             let accumulated = product.accumulated_cutting_ms;
-                            println!("🔄 Product {} interrumpido en Corte (quedan {}ms)", product.id, remaining);
+                            trace!(verbose, "🔄 Product {} interrumpido en Corte (quedan {}ms)", product.id, remaining);
                             scheduler.return_incomplete(product, accumulated, cutting_time);
                         }
                     } else {
@@ -182,7 +196,7 @@ impl Factory {
         // === ESTACIÓN DE ENSAMBLAJE ===
         {
             let algorithm_asm = algorithm.clone();
-            let start_clone = start.clone();
+            let start_clone = start;
             let rx_from_cutting = rx_complete;
             let (tx_to_packaging, rx_to_packaging) = mpsc::sync_channel::<Product>(capacity);
             let assembly_time = times.assembly_ms;
@@ -209,7 +223,7 @@ impl Factory {
                             product.entry_assembly = Some(start_clone.elapsed());
                         }
                         
-                        println!("▶️  Product {} procesando en Ensamblaje ({}ms, acumulado: {}ms)", 
+                        trace!(verbose, "▶️  Product {} procesando en Ensamblaje ({}ms, acumulado: {}ms)", 
                                  product.id, time_to_process, product.accumulated_assembly_ms);
                         
                         thread::sleep(Duration::from_millis(time_to_process));
@@ -217,7 +231,7 @@ impl Factory {
                         
                         if product.accumulated_assembly_ms >= assembly_time {
                             product.exit_assembly = Some(start_clone.elapsed());
-                            println!("🔧 Product {} completó Ensamblaje (total: {}ms)", product.id, product.accumulated_assembly_ms);
+                            trace!(verbose, "🔧 Product {} completó Ensamblaje (total: {}ms)", product.id, product.accumulated_assembly_ms);
                             
                             if let Err(e) = tx_to_packaging.send(product) {
                                 eprintln!("❌ Error enviando de Ensamblaje: {:?}", e);
@@ -227,7 +241,7 @@ impl Factory {
                             // Si no termina, vuelve al scheduler con el progreso guardado
                             let remaining = assembly_time - product.accumulated_assembly_ms;
                             let accumulated = product.accumulated_assembly_ms;
-                            println!("🔄 Product {} interrumpido en Ensamblaje (quedan {}ms)", product.id, remaining);
+                            trace!(verbose, "🔄 Product {} interrumpido en Ensamblaje (quedan {}ms)", product.id, remaining);
                             scheduler.return_incomplete(product, accumulated, assembly_time);
                         }
                     } else {
@@ -242,7 +256,7 @@ impl Factory {
             // === ESTACIÓN DE EMPAQUE ===
             let algorithm_pack = algorithm.clone();
             let stats_clone = Arc::clone(&stats_collector);
-            let start_clone = start.clone();
+            let start_clone = start;
             let packaging_time = times.packaging_ms;
             
             // Hilo que simula la estación de empaque final
@@ -267,7 +281,7 @@ impl Factory {
                             product.entry_packaging = Some(start_clone.elapsed());
                         }
                         
-                        println!("▶️  Product {} procesando en Empaque ({}ms, acumulado: {}ms)", 
+                        trace!(verbose, "▶️  Product {} procesando en Empaque ({}ms, acumulado: {}ms)", 
                                  product.id, time_to_process, product.accumulated_packaging_ms);
                         
                         thread::sleep(Duration::from_millis(time_to_process));
@@ -276,8 +290,8 @@ impl Factory {
                         if product.accumulated_packaging_ms >= packaging_time {
                             // Producto finalizado completamente
                             product.exit_packaging = Some(start_clone.elapsed());
-                            println!("📦 Product {} completó Empaque (total: {}ms)", product.id, product.accumulated_packaging_ms);
-                            println!("✅ Product {} TERMINADO", product.id);
+                            trace!(verbose, "📦 Product {} completó Empaque (total: {}ms)", product.id, product.accumulated_packaging_ms);
+                            trace!(verbose, "✅ Product {} TERMINADO", product.id);
                             
                             // Se guarda en el recolector de estadísticas
                             if let Ok(mut collector) = stats_clone.lock() {
@@ -286,7 +300,7 @@ impl Factory {
                         } else {
                             let remaining = packaging_time - product.accumulated_packaging_ms;
                             let accumulated = product.accumulated_packaging_ms;
-                            println!("🔄 Product {} interrumpido en Empaque (quedan {}ms)", product.id, remaining);
+                            trace!(verbose, "🔄 Product {} interrumpido en Empaque (quedan {}ms)", product.id, remaining);
                             scheduler.return_incomplete(product, accumulated, packaging_time);
                         }
                     } else {
@@ -306,9 +320,9 @@ impl Factory {
     }
     
     // Envía un nuevo producto al canal de entrada
-    pub fn send_product(&self, id: u32) -> Result<(), mpsc::SendError<Product>> {
+    pub fn send_product(&self, id: u32) -> Result<(), Box<mpsc::SendError<Product>>> {
         let p = Product::new(id, self.start.elapsed());
-        self.tx_input.send(p)
+        self.tx_input.send(p).map_err(Box::new)
     }
     
     // Finaliza la ejecución de la fábrica y devuelve las estadísticas globales
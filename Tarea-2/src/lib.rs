@@ -0,0 +1,9 @@
+//! Simulación de fábrica con planificadores (FCFS y Round Robin).
+//!
+//! Tres estaciones (Corte → Ensamblaje → Empaque), cada una en su hilo y
+//! conectadas por canales `mpsc`. El binario `Tarea-2` corre la simulación
+//! de ejemplo; `http_server` la expone como el job `factory`.
+
+pub mod product;
+pub mod factory;
+pub mod scheduler;
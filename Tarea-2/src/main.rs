@@ -1,9 +1,5 @@
-mod product;
-mod factory;
-mod scheduler;
-
-use factory::Factory;
-use scheduler::SchedulingAlgorithm;
+use tarea2::factory::Factory;
+use tarea2::scheduler::SchedulingAlgorithm;
 use std::thread;
 use std::time::Duration;
 
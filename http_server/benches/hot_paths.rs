@@ -0,0 +1,162 @@
+//! # Benchmarks de Rutas Calientes
+//! benches/hot_paths.rs
+//!
+//! Micro-benchmarks (Criterion) de las partes que corren en cada request,
+//! para detectar regresiones antes de llegar a las pruebas de carga:
+//!
+//! - `Request::parse`
+//! - `Router::route` (con las rutas del servidor y con 600 rutas)
+//! - `Response::to_bytes`
+//! - `JobQueue` enqueue/dequeue
+//! - `MetricsCollector::record_request`
+//!
+//! ```text
+//! cargo bench --bench hot_paths                           # todos
+//! cargo bench --bench hot_paths -- router                 # solo los que contienen "router"
+//! cargo bench --bench hot_paths -- --save-baseline main   # guarda resultados
+//! cargo bench --bench hot_paths -- --baseline main        # compara contra "main"
+//! ```
+//!
+//! Criterion guarda los resultados (y el reporte HTML) en `target/criterion/`.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use http_server::http::request::Method;
+use http_server::http::{Request, Response, StatusCode};
+use http_server::jobs::queue::JobQueue;
+use http_server::jobs::{JobMetadata, JobPriority, JobType};
+use http_server::metrics::MetricsCollector;
+use http_server::router::Router;
+use std::time::Duration;
+
+/// Request típico de un cliente (curl) con algunos headers
+const RAW_REQUEST: &[u8] = b"GET /fibonacci?num=30&format=json HTTP/1.0\r\n\
+Host: localhost:8080\r\n\
+User-Agent: curl/8.5.0\r\n\
+Accept: */*\r\n\
+X-Request-Id: bench-0001\r\n\r\n";
+
+fn ok_handler(_req: &Request) -> Response {
+    Response::json(r#"{"ok":true}"#)
+}
+
+type HandlerFn = fn(&Request) -> Response;
+
+/// El router de antes del `HashMap` y el árbol por segmentos: una lista
+/// que se recorre comparando cada path (solo paths fijos)
+struct VecRouter {
+    routes: Vec<(String, Option<Method>, HandlerFn)>,
+}
+
+impl VecRouter {
+    fn route(&self, request: &Request) -> Response {
+        let path = request.path();
+        for (route_path, method, handler) in &self.routes {
+            if route_path != path {
+                continue;
+            }
+            match method {
+                // GET también atiende HEAD
+                Some(method) if *method != request.method()
+                    && !(*method == Method::GET && request.method() == Method::HEAD) => continue,
+                _ => {
+                    let mut response = handler(request);
+                    response.add_header("Server", "RedUnix-HTTP/1.0");
+                    response.add_header("Connection", "close");
+                    return response;
+                }
+            }
+        }
+        Response::error(StatusCode::NotFound, &format!("Route not found: {}", path))
+    }
+}
+
+fn job(i: usize, priority: JobPriority) -> JobMetadata {
+    JobMetadata::new(format!("job-{}", i), JobType::IsPrime, r#"{"n":97}"#.to_string(), priority)
+}
+
+fn http(c: &mut Criterion) {
+    c.bench_function("request/parse", |b| {
+        b.iter(|| Request::parse(black_box(RAW_REQUEST)).unwrap())
+    });
+
+    let response = Response::json(r#"{"n":30,"result":832040,"elapsed_ms":0}"#)
+        .with_header("X-Request-Id", "bench-0001");
+    c.bench_function("response/to_bytes", |b| b.iter(|| black_box(&response).to_bytes()));
+    let error = Response::error(StatusCode::NotFound, "Route not found: /nope");
+    c.bench_function("response/to_bytes_error", |b| b.iter(|| black_box(&error).to_bytes()));
+}
+
+fn router(c: &mut Criterion) {
+    // Router con tantas rutas como el servidor real
+    let mut router = Router::new();
+    for path in [
+        "/status", "/fibonacci", "/reverse", "/toupper", "/timestamp", "/random", "/hash",
+        "/createfile", "/deletefile", "/simulate", "/sleep", "/loadtest", "/help", "/isprime",
+        "/factor", "/pi", "/mandelbrot", "/matrixmul", "/sortfile", "/wordcount", "/grep",
+        "/compress", "/hashfile", "/download", "/uploadfile", "/fetch", "/factory",
+    ] {
+        router.register(path, ok_handler);
+    }
+    let hit = Request::parse(RAW_REQUEST).unwrap();
+    let miss = Request::parse(b"GET /nope HTTP/1.0\r\n\r\n").unwrap();
+    c.bench_function("router/route_hit", |b| b.iter(|| router.route(black_box(&hit))));
+    c.bench_function("router/route_miss", |b| b.iter(|| router.route(black_box(&miss))));
+
+    // Cientos de rutas: la búsqueda no depende de la cantidad.
+    // `vec_router_hit_last` rutea la misma ruta (la última registrada) con
+    // el router de lista de antes, para comparar
+    let mut large = Router::new();
+    let paths: Vec<String> = (0..500).map(|i| format!("/api/v1/resource{}", i)).collect();
+    for path in &paths {
+        large.register(path, ok_handler);
+    }
+    for i in 0..100 {
+        large.register(&format!("/api/v2/resource{}/:id", i), ok_handler);
+    }
+    let last = Request::parse(b"GET /api/v1/resource499 HTTP/1.0\r\n\r\n").unwrap();
+    let param = Request::parse(b"GET /api/v2/resource99/42 HTTP/1.0\r\n\r\n").unwrap();
+    c.bench_function("router/600_routes_hit_last", |b| b.iter(|| large.route(black_box(&last))));
+    c.bench_function("router/600_routes_hit_param", |b| b.iter(|| large.route(black_box(&param))));
+    let vec_router = VecRouter {
+        routes: paths.iter()
+            .cloned()
+            .chain((0..100).map(|i| format!("/api/v2/resource{}/:id", i)))
+            .map(|path| (path, None, ok_handler as HandlerFn))
+            .collect(),
+    };
+    c.bench_function("router/600_routes_vec_router_hit_last", |b| {
+        b.iter(|| vec_router.route(black_box(&last)))
+    });
+}
+
+fn jobqueue(c: &mut Criterion) {
+    // Ciclo enqueue + dequeue con cola parcialmente llena
+    let queue = JobQueue::new(1024);
+    for i in 0..256 {
+        queue.enqueue(job(i, JobPriority::Normal)).unwrap();
+    }
+    let mut next = 256;
+    c.bench_function("jobqueue/enqueue_dequeue", |b| {
+        b.iter(|| {
+            next += 1;
+            let priority = [JobPriority::Low, JobPriority::Normal, JobPriority::High][next % 3];
+            queue.enqueue(job(next, priority)).unwrap();
+            queue.try_dequeue()
+        })
+    });
+}
+
+fn metrics(c: &mut Criterion) {
+    let metrics = MetricsCollector::new();
+    let paths = ["/fibonacci", "/status", "/isprime", "/jobs/status"];
+    let mut i = 0usize;
+    c.bench_function("metrics/record_request", |b| {
+        b.iter(|| {
+            i += 1;
+            metrics.record_request(paths[i % paths.len()], 200, Duration::from_micros((i % 5000) as u64));
+        })
+    });
+}
+
+criterion_group!(hot_paths, http, router, jobqueue, metrics);
+criterion_main!(hot_paths);
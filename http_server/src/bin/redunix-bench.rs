@@ -0,0 +1,391 @@
+//! # RedUnix Bench
+//! src/bin/redunix-bench.rs
+//!
+//! Generador de carga para comparar configuraciones de planificación de
+//! forma reproducible. Lanza `--concurrency` clientes durante
+//! `--duration-secs`, cada uno eligiendo endpoints de la mezcla según su
+//! peso, y al final imprime throughput, percentiles de latencia y el
+//! desglose de errores.
+//!
+//! ```text
+//! redunix-bench --concurrency 16 --duration-secs 30 \
+//!     --endpoint "/fibonacci?num=25@3" --endpoint "/isprime?n=1000003@1"
+//! ```
+//!
+//! Cada endpoint es `PATH[@PESO]` (peso 1 por defecto). La secuencia de
+//! endpoints de cada cliente depende solo de `--seed`, así dos corridas con
+//! los mismos argumentos envían la misma mezcla.
+
+use clap::Parser;
+use http_server::client::{Client, ClientError};
+use std::collections::BTreeMap;
+use std::process;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Mezcla por defecto: comandos básicos, CPU-bound y métricas
+const DEFAULT_MIX: &[&str] = &["/fibonacci?num=20@4", "/isprime?n=1000003@2", "/timestamp@2", "/status@1"];
+
+#[derive(Parser, Debug)]
+#[command(name = "redunix-bench")]
+#[command(about = "Generador de carga para el servidor RedUnix")]
+struct Cli {
+    /// Dirección del servidor (host:puerto)
+    #[arg(short, long, env = "REDUNIX_SERVER", default_value = "127.0.0.1:8080")]
+    server: String,
+
+    /// Clientes concurrentes
+    #[arg(short, long, default_value = "8")]
+    concurrency: usize,
+
+    /// Duración de la prueba en segundos
+    #[arg(short, long, default_value = "10")]
+    duration_secs: u64,
+
+    /// Endpoint de la mezcla como PATH[@PESO] (repetible)
+    #[arg(short, long = "endpoint")]
+    endpoints: Vec<String>,
+
+    /// Semilla para la secuencia de endpoints
+    #[arg(long, default_value = "42")]
+    seed: u64,
+
+    /// Timeout de cada request en milisegundos
+    #[arg(long, default_value = "10000")]
+    timeout_ms: u64,
+
+    /// Imprimir el reporte como JSON
+    #[arg(long)]
+    json: bool,
+}
+
+/// Endpoint de la mezcla con su peso relativo
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Endpoint {
+    path: String,
+    weight: u32,
+}
+
+impl Endpoint {
+    /// Parsea `PATH[@PESO]`
+    fn parse(spec: &str) -> Result<Self, String> {
+        let (path, weight) = match spec.rsplit_once('@') {
+            Some((path, weight)) => {
+                let weight = weight.parse::<u32>()
+                    .ok()
+                    .filter(|w| *w > 0)
+                    .ok_or_else(|| format!("Invalid endpoint '{}': weight must be a positive integer", spec))?;
+                (path, weight)
+            }
+            None => (spec, 1),
+        };
+
+        if !path.starts_with('/') {
+            return Err(format!("Invalid endpoint '{}': path must start with '/'", spec));
+        }
+        Ok(Self { path: path.to_string(), weight })
+    }
+}
+
+/// Generador xorshift64* (determinista y sin dependencias)
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        // El estado no puede ser 0
+        Self(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1)
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+}
+
+/// Elige un endpoint según los pesos
+fn pick(endpoints: &[Endpoint], total_weight: u64, rng: &mut Rng) -> usize {
+    let mut roll = rng.next() % total_weight;
+    for (i, endpoint) in endpoints.iter().enumerate() {
+        if roll < endpoint.weight as u64 {
+            return i;
+        }
+        roll -= endpoint.weight as u64;
+    }
+    endpoints.len() - 1
+}
+
+/// Resultados acumulados (de un cliente o de toda la prueba)
+#[derive(Debug, Default)]
+struct Stats {
+    /// Latencias en microsegundos de todos los requests
+    latencies: Vec<u64>,
+    /// Latencias por endpoint (índice de la mezcla)
+    per_endpoint: BTreeMap<usize, Vec<u64>>,
+    /// Errores por endpoint
+    endpoint_errors: BTreeMap<usize, u64>,
+    /// Desglose de errores (`HTTP 503`, `io: TimedOut`...)
+    errors: BTreeMap<String, u64>,
+}
+
+impl Stats {
+    fn record(&mut self, endpoint: usize, latency: Duration, error: Option<String>) {
+        let micros = latency.as_micros() as u64;
+        self.latencies.push(micros);
+        self.per_endpoint.entry(endpoint).or_default().push(micros);
+        if let Some(error) = error {
+            *self.endpoint_errors.entry(endpoint).or_default() += 1;
+            *self.errors.entry(error).or_default() += 1;
+        }
+    }
+
+    fn merge(&mut self, other: Stats) {
+        self.latencies.extend(other.latencies);
+        for (endpoint, latencies) in other.per_endpoint {
+            self.per_endpoint.entry(endpoint).or_default().extend(latencies);
+        }
+        for (endpoint, count) in other.endpoint_errors {
+            *self.endpoint_errors.entry(endpoint).or_default() += count;
+        }
+        for (error, count) in other.errors {
+            *self.errors.entry(error).or_default() += count;
+        }
+    }
+
+    fn error_count(&self) -> u64 {
+        self.errors.values().sum()
+    }
+}
+
+/// Percentil por rango más cercano (`p` entre 0 y 100) de latencias ordenadas
+fn percentile(sorted: &[u64], p: usize) -> u64 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    sorted[(sorted.len() * p / 100).min(sorted.len() - 1)]
+}
+
+/// Clasifica el resultado de un request (None = éxito)
+fn classify(result: Result<u16, ClientError>) -> Option<String> {
+    match result {
+        Ok(status) if (200..300).contains(&status) => None,
+        Ok(status) => Some(format!("HTTP {}", status)),
+        Err(ClientError::Io(e)) => Some(format!("io: {:?}", e.kind())),
+        Err(e) => Some(format!("invalid: {}", e)),
+    }
+}
+
+/// Corre un cliente hasta `deadline`
+fn run_worker(client: Client, endpoints: &[Endpoint], seed: u64, deadline: Instant) -> Stats {
+    let total_weight: u64 = endpoints.iter().map(|e| e.weight as u64).sum();
+    let mut rng = Rng::new(seed);
+    let mut stats = Stats::default();
+
+    while Instant::now() < deadline {
+        let index = pick(endpoints, total_weight, &mut rng);
+        let started = Instant::now();
+        let result = client.get(&endpoints[index].path).map(|response| response.status);
+        stats.record(index, started.elapsed(), classify(result));
+    }
+    stats
+}
+
+fn main() {
+    let cli = Cli::parse();
+
+    let specs: Vec<String> = if cli.endpoints.is_empty() {
+        DEFAULT_MIX.iter().map(|s| s.to_string()).collect()
+    } else {
+        cli.endpoints.clone()
+    };
+    let endpoints = match specs.iter().map(|s| Endpoint::parse(s)).collect::<Result<Vec<_>, _>>() {
+        Ok(endpoints) => endpoints,
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            process::exit(2);
+        }
+    };
+    if cli.concurrency == 0 || cli.duration_secs == 0 {
+        eprintln!("❌ --concurrency and --duration-secs must be at least 1");
+        process::exit(2);
+    }
+
+    let client = Client::new(cli.server.clone()).with_timeout(Duration::from_millis(cli.timeout_ms.max(1)));
+    if let Err(e) = client.get("/status") {
+        eprintln!("❌ Cannot reach {}: {}", cli.server, e);
+        process::exit(1);
+    }
+
+    if !cli.json {
+        println!(
+            "🚀 {} clientes contra {} durante {}s ({} endpoints, seed {})",
+            cli.concurrency, cli.server, cli.duration_secs, endpoints.len(), cli.seed
+        );
+    }
+
+    let started = Instant::now();
+    let deadline = started + Duration::from_secs(cli.duration_secs);
+    let stats = thread::scope(|scope| {
+        let handles: Vec<_> = (0..cli.concurrency)
+            .map(|i| {
+                let client = client.clone();
+                let endpoints = &endpoints;
+                let seed = cli.seed.wrapping_add(i as u64);
+                scope.spawn(move || run_worker(client, endpoints, seed, deadline))
+            })
+            .collect();
+
+        let mut total = Stats::default();
+        for handle in handles {
+            total.merge(handle.join().expect("bench worker panicked"));
+        }
+        total
+    });
+    let elapsed = started.elapsed();
+
+    let report = build_report(&cli, &endpoints, stats, elapsed);
+    if cli.json {
+        println!("{}", serde_json::to_string_pretty(&report).unwrap_or_default());
+    } else {
+        print_report(&report);
+    }
+}
+
+/// Arma el reporte final como JSON (también se usa para el formato de texto)
+fn build_report(cli: &Cli, endpoints: &[Endpoint], mut stats: Stats, elapsed: Duration) -> serde_json::Value {
+    let latency = |sorted: &[u64]| serde_json::json!({
+        "p50_us": percentile(sorted, 50),
+        "p90_us": percentile(sorted, 90),
+        "p99_us": percentile(sorted, 99),
+        "max_us": sorted.last().copied().unwrap_or(0),
+    });
+
+    stats.latencies.sort_unstable();
+    let total = stats.latencies.len() as u64;
+    let errors = stats.error_count();
+
+    let per_endpoint: Vec<serde_json::Value> = endpoints.iter()
+        .enumerate()
+        .map(|(i, endpoint)| {
+            let mut sorted = stats.per_endpoint.remove(&i).unwrap_or_default();
+            sorted.sort_unstable();
+            serde_json::json!({
+                "path": endpoint.path,
+                "weight": endpoint.weight,
+                "requests": sorted.len(),
+                "errors": stats.endpoint_errors.get(&i).copied().unwrap_or(0),
+                "latency": latency(&sorted),
+            })
+        })
+        .collect();
+
+    serde_json::json!({
+        "server": cli.server,
+        "concurrency": cli.concurrency,
+        "seed": cli.seed,
+        "elapsed_secs": elapsed.as_secs_f64(),
+        "requests": total,
+        "errors": errors,
+        "throughput_rps": total as f64 / elapsed.as_secs_f64().max(f64::EPSILON),
+        "latency": latency(&stats.latencies),
+        "endpoints": per_endpoint,
+        "error_breakdown": stats.errors,
+    })
+}
+
+/// Imprime el reporte en formato de texto
+fn print_report(report: &serde_json::Value) {
+    let ms = |v: &serde_json::Value| v.as_u64().unwrap_or(0) as f64 / 1000.0;
+    let latency = &report["latency"];
+
+    println!("\n📊 Resultados ({:.1}s)", report["elapsed_secs"].as_f64().unwrap_or(0.0));
+    println!(
+        "   requests    {}  ({} errores)",
+        report["requests"], report["errors"]
+    );
+    println!("   throughput  {:.1} req/s", report["throughput_rps"].as_f64().unwrap_or(0.0));
+    println!(
+        "   latency     p50 {:.2}ms  p90 {:.2}ms  p99 {:.2}ms  max {:.2}ms",
+        ms(&latency["p50_us"]), ms(&latency["p90_us"]), ms(&latency["p99_us"]), ms(&latency["max_us"])
+    );
+
+    println!("\n   {:<32} {:>8} {:>7} {:>10} {:>10}", "ENDPOINT", "REQS", "ERRS", "P50 ms", "P99 ms");
+    for endpoint in report["endpoints"].as_array().into_iter().flatten() {
+        println!(
+            "   {:<32} {:>8} {:>7} {:>10.2} {:>10.2}",
+            endpoint["path"].as_str().unwrap_or_default(),
+            endpoint["requests"].as_u64().unwrap_or(0),
+            endpoint["errors"].as_u64().unwrap_or(0),
+            ms(&endpoint["latency"]["p50_us"]),
+            ms(&endpoint["latency"]["p99_us"])
+        );
+    }
+
+    let breakdown = report["error_breakdown"].as_object();
+    if let Some(breakdown) = breakdown.filter(|b| !b.is_empty()) {
+        println!("\n   ⚠️  Errores:");
+        for (kind, count) in breakdown {
+            println!("   {:<32} {:>8}", kind, count.as_u64().unwrap_or(0));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_endpoint() {
+        assert_eq!(
+            Endpoint::parse("/fibonacci?num=20@3").unwrap(),
+            Endpoint { path: "/fibonacci?num=20".to_string(), weight: 3 }
+        );
+        assert_eq!(Endpoint::parse("/status").unwrap().weight, 1);
+        assert!(Endpoint::parse("/status@0").is_err());
+        assert!(Endpoint::parse("/status@x").is_err());
+        assert!(Endpoint::parse("status").is_err());
+    }
+
+    #[test]
+    fn test_pick_is_reproducible_and_weighted() {
+        let endpoints = vec![
+            Endpoint::parse("/a@3").unwrap(),
+            Endpoint::parse("/b@1").unwrap(),
+        ];
+        let sequence = |seed| {
+            let mut rng = Rng::new(seed);
+            (0..4000).map(|_| pick(&endpoints, 4, &mut rng)).collect::<Vec<_>>()
+        };
+
+        assert_eq!(sequence(7), sequence(7));
+        assert_ne!(sequence(7), sequence(8));
+
+        let hits_a = sequence(7).iter().filter(|i| **i == 0).count();
+        assert!((2700..3300).contains(&hits_a), "hits_a = {}", hits_a);
+    }
+
+    #[test]
+    fn test_percentile() {
+        let sorted: Vec<u64> = (1..=100).collect();
+        assert_eq!(percentile(&sorted, 50), 51);
+        assert_eq!(percentile(&sorted, 99), 100);
+        assert_eq!(percentile(&[], 99), 0);
+    }
+
+    #[test]
+    fn test_stats_merge_and_classify() {
+        let mut a = Stats::default();
+        a.record(0, Duration::from_millis(2), classify(Ok(200)));
+        let mut b = Stats::default();
+        b.record(0, Duration::from_millis(4), classify(Ok(503)));
+        b.record(1, Duration::from_millis(1), classify(Err(ClientError::TimedOut)));
+
+        a.merge(b);
+        assert_eq!(a.latencies.len(), 3);
+        assert_eq!(a.per_endpoint[&0].len(), 2);
+        assert_eq!(a.endpoint_errors[&0], 1);
+        assert_eq!(a.errors["HTTP 503"], 1);
+        assert_eq!(a.error_count(), 2);
+    }
+}
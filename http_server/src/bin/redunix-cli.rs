@@ -0,0 +1,320 @@
+//! # RedUnix CLI
+//! src/bin/redunix-cli.rs
+//!
+//! Cliente de línea de comandos para la API de jobs de un servidor en
+//! ejecución. Usa `http_server::client` para hablar con el servidor.
+//!
+//! ```text
+//! redunix-cli submit isprime n=982451653 --priority high --wait
+//! redunix-cli status job-abc123
+//! redunix-cli result job-abc123
+//! redunix-cli cancel job-abc123
+//! redunix-cli list --status running
+//! redunix-cli watch job-abc123
+//! ```
+
+use clap::{Parser, Subcommand};
+use http_server::client::{status_name, Client, ClientError, JobStatusInfo};
+use http_server::jobs::types::{JobPriority, JobStatus};
+use std::io::{self, IsTerminal, Write};
+use std::process;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Ancho de la barra de progreso de `watch`
+const BAR_WIDTH: usize = 30;
+
+#[derive(Parser, Debug)]
+#[command(name = "redunix-cli")]
+#[command(about = "Cliente de línea de comandos para la API de jobs de RedUnix")]
+struct Cli {
+    /// Dirección del servidor (host:puerto)
+    #[arg(short, long, env = "REDUNIX_SERVER", default_value = "127.0.0.1:8080")]
+    server: String,
+
+    /// Timeout de cada request HTTP en segundos
+    #[arg(long, default_value = "30")]
+    timeout_secs: u64,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Encola un job (parámetros como clave=valor)
+    Submit {
+        /// Tarea (isprime, factor, pi, mandelbrot, matrixmul, sortfile, ...)
+        task: String,
+
+        /// Parámetros del job (ej: n=97)
+        params: Vec<String>,
+
+        /// Prioridad: low, normal o high
+        #[arg(short, long, default_value = "normal")]
+        priority: String,
+
+        /// Esperar a que termine mostrando el progreso
+        #[arg(short, long)]
+        wait: bool,
+    },
+
+    /// Muestra el estado de un job
+    Status {
+        id: String,
+    },
+
+    /// Muestra el resultado de un job terminado
+    Result {
+        id: String,
+    },
+
+    /// Cancela un job en cola
+    Cancel {
+        id: String,
+    },
+
+    /// Lista los jobs más recientes
+    List {
+        /// Solo jobs en este estado (queued, running, done, error, canceled, timeout)
+        #[arg(long)]
+        status: Option<String>,
+
+        /// Máximo de jobs a mostrar
+        #[arg(short, long, default_value = "20")]
+        limit: usize,
+    },
+
+    /// Sigue el progreso de un job hasta que termine
+    Watch {
+        id: String,
+
+        /// Intervalo entre consultas en milisegundos
+        #[arg(long, default_value = "500")]
+        interval_ms: u64,
+
+        /// Tiempo máximo de espera en segundos
+        #[arg(long, default_value = "600")]
+        max_wait_secs: u64,
+    },
+}
+
+fn main() {
+    let cli = Cli::parse();
+    let client = Client::new(cli.server.clone())
+        .with_timeout(Duration::from_secs(cli.timeout_secs.max(1)));
+
+    if let Err(e) = run(&client, cli.command) {
+        eprintln!("❌ {}", e);
+        process::exit(1);
+    }
+}
+
+/// Ejecuta un subcomando
+fn run(client: &Client, command: Command) -> Result<(), String> {
+    match command {
+        Command::Submit { task, params, priority, wait } => {
+            let priority = JobPriority::from_str(&priority)
+                .ok_or_else(|| format!("Invalid priority '{}': use low, normal or high", priority))?;
+            let params = params.iter()
+                .map(String::as_str)
+                .map(parse_param)
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let id = client.submit_job(&task, &params, priority).map_err(describe)?;
+            println!("📨 Job encolado: {}", id);
+
+            if wait {
+                watch(client, &id, Duration::from_millis(500), Duration::from_secs(600))?;
+            }
+            Ok(())
+        }
+        Command::Status { id } => {
+            let info = client.job_status(&id).map_err(describe)?;
+            println!("{}", format_status(&info));
+            Ok(())
+        }
+        Command::Result { id } => {
+            let result = client.job_result(&id).map_err(describe)?;
+            println!("{}", serde_json::to_string_pretty(&result).unwrap_or_default());
+            Ok(())
+        }
+        Command::Cancel { id } => {
+            client.cancel_job(&id).map_err(describe)?;
+            println!("🛑 Job cancelado: {}", id);
+            Ok(())
+        }
+        Command::List { status, limit } => {
+            let status = status.map(|s| parse_status(&s)).transpose()?;
+            let jobs = client.list_jobs(status, limit).map_err(describe)?;
+
+            if jobs.is_empty() {
+                println!("(sin jobs)");
+                return Ok(());
+            }
+            println!("{:<24} {:<12} {:<9} {:<8} {:>5}", "ID", "TASK", "STATUS", "PRIO", "%");
+            for job in jobs {
+                println!(
+                    "{:<24} {:<12} {:<9} {:<8} {:>4}%",
+                    job.id,
+                    job.task,
+                    status_name(job.status),
+                    format!("{:?}", job.priority).to_lowercase(),
+                    job.progress
+                );
+            }
+            Ok(())
+        }
+        Command::Watch { id, interval_ms, max_wait_secs } => {
+            watch(
+                client,
+                &id,
+                Duration::from_millis(interval_ms.max(50)),
+                Duration::from_secs(max_wait_secs),
+            )
+        }
+    }
+}
+
+/// Muestra el progreso de un job hasta que termine e imprime el resultado
+fn watch(client: &Client, id: &str, interval: Duration, max_wait: Duration) -> Result<(), String> {
+    let deadline = Instant::now() + max_wait;
+    let interactive = io::stdout().is_terminal();
+    let mut last_line = String::new();
+
+    let info = loop {
+        let info = client.job_status(id).map_err(describe)?;
+        let line = format_status(&info);
+
+        // En una terminal se redibuja la misma línea; si no, solo los cambios
+        if interactive {
+            print!("\r{:<width$}", line, width = last_line.chars().count());
+            let _ = io::stdout().flush();
+        } else if line != last_line {
+            println!("{}", line);
+        }
+        last_line = line;
+
+        if info.is_finished() {
+            break info;
+        }
+        if Instant::now() >= deadline {
+            if interactive {
+                println!();
+            }
+            return Err(format!("Job {} still {} after {:?}", id, status_name(info.status), max_wait));
+        }
+        thread::sleep(interval);
+    };
+
+    if interactive {
+        println!();
+    }
+
+    match info.status {
+        JobStatus::Done => {
+            let result = client.job_result(id).map_err(describe)?;
+            println!("✅ {}", serde_json::to_string_pretty(&result).unwrap_or_default());
+            Ok(())
+        }
+        status => {
+            let message = match client.job_result(id) {
+                Err(ClientError::Http { message, .. }) => message,
+                _ => String::new(),
+            };
+            Err(format!("Job {} finished as {}: {}", id, status_name(status), message))
+        }
+    }
+}
+
+/// Parsea `clave=valor`
+fn parse_param(param: &str) -> Result<(&str, &str), String> {
+    param.split_once('=')
+        .filter(|(key, _)| !key.is_empty())
+        .ok_or_else(|| format!("Invalid parameter '{}': expected key=value", param))
+}
+
+/// Parsea un estado de job (`running`, `DONE`...)
+fn parse_status(status: &str) -> Result<JobStatus, String> {
+    serde_json::from_value(serde_json::Value::from(status.to_lowercase()))
+        .map_err(|_| format!("Unknown status '{}'", status))
+}
+
+/// Línea de estado con barra de progreso: `[######------]  40% running  eta 3.8s`
+fn format_status(info: &JobStatusInfo) -> String {
+    let progress = match info.status {
+        JobStatus::Done => 100,
+        _ => info.progress.unwrap_or(0).min(100) as usize,
+    };
+    let filled = progress * BAR_WIDTH / 100;
+
+    let mut line = format!(
+        "[{}{}] {:>3}% {}",
+        "#".repeat(filled),
+        "-".repeat(BAR_WIDTH - filled),
+        progress,
+        status_name(info.status)
+    );
+    if let (Some(eta), false) = (info.eta_ms, info.is_finished()) {
+        line.push_str(&format!("  eta {:.1}s", eta as f64 / 1000.0));
+    }
+    line
+}
+
+/// Mensaje legible de un error del cliente
+fn describe(error: ClientError) -> String {
+    match error {
+        ClientError::Io(e) => format!("Cannot reach server: {}", e),
+        ClientError::Http { status: 429 | 503, message, retry_after_secs: Some(secs) } => {
+            format!("{} (retry in {}s)", message, secs)
+        }
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_param() {
+        assert_eq!(parse_param("n=97").unwrap(), ("n", "97"));
+        assert_eq!(parse_param("expr=a=b").unwrap(), ("expr", "a=b"));
+        assert!(parse_param("n").is_err());
+        assert!(parse_param("=97").is_err());
+    }
+
+    #[test]
+    fn test_parse_status() {
+        assert_eq!(parse_status("RUNNING").unwrap(), JobStatus::Running);
+        assert!(parse_status("finished").is_err());
+    }
+
+    #[test]
+    fn test_format_status() {
+        let running = JobStatusInfo { status: JobStatus::Running, progress: Some(40), eta_ms: Some(3800) };
+        assert_eq!(
+            format_status(&running),
+            format!("[{}{}]  40% running  eta 3.8s", "#".repeat(12), "-".repeat(18))
+        );
+
+        let done = JobStatusInfo { status: JobStatus::Done, progress: None, eta_ms: Some(10) };
+        assert_eq!(format_status(&done), format!("[{}] 100% done", "#".repeat(BAR_WIDTH)));
+    }
+
+    #[test]
+    fn test_cli_parses_subcommands() {
+        let cli = Cli::try_parse_from(["redunix-cli", "-s", "host:9", "submit", "pi", "digits=50", "-p", "high", "-w"])
+            .unwrap();
+        assert_eq!(cli.server, "host:9");
+        match cli.command {
+            Command::Submit { task, params, priority, wait } => {
+                assert_eq!((task.as_str(), priority.as_str(), wait), ("pi", "high", true));
+                assert_eq!(params, vec!["digits=50"]);
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+
+        assert!(Cli::try_parse_from(["redunix-cli", "frobnicate"]).is_err());
+    }
+}
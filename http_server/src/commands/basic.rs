@@ -7,7 +7,6 @@
 //! - /reverse: Invertir texto
 //! - /toupper: Convertir a mayúsculas
 //! - /timestamp: Timestamp actual
-//! - /help: Ayuda sobre comandos disponibles
 //! - /random: Generar números aleatorios
 //! - /hash: Hash SHA256 de texto
 //! - /createfile: Crear archivo con contenido
@@ -15,34 +14,50 @@
 //! - /simulate: Simular tarea con trabajo real
 //! - /sleep: Dormir N segundos
 //! - /loadtest: Generar carga de prueba
+//!
+//! `/help` no es un handler de acá: lo genera el router con la descripción
+//! de cada ruta (ver `router::docs`).
 
-use crate::http::{Request, Response, StatusCode};
+use crate::commands::{limits, sandbox};
+use crate::http::{date, Request, Response, StatusCode};
+use crate::metrics::MetricsCollector;
+use serde_json::json;
 use std::time::{SystemTime, UNIX_EPOCH, Duration};
 use std::fs;
-use std::path::Path;
 
 /// Handler para /status
 /// 
-/// Retorna información sobre el estado del servidor.
-/// Por ahora retorna información básica, luego agregaremos métricas.
+/// Retorna información sobre el estado del servidor, con las métricas que
+/// lleva `metrics` desde el arranque. El router lo registra como closure
+/// que captura el colector del servidor.
 /// 
 /// # Ejemplo de response
 /// ```json
 /// {
 ///   "status": "running",
+///   "version": "0.1.0",
+///   "server": "RedUnix HTTP/1.0",
 ///   "uptime_seconds": 123,
-///   "connections_served": 42
+///   "requests_served": 42,
+///   "open_connections": 3,
+///   "active_threads": 1,
+///   "latency_p50_us": 180,
+///   "latency_p99_us": 2400
 /// }
 /// ```
-pub fn status_handler(_req: &Request) -> Response {
-    // TODO: Agregar métricas reales cuando implementemos el sistema de métricas
-    let body = r#"{
-  "status": "running",
-  "version": "0.1.0",
-  "server": "RedUnix HTTP/1.0"
-}"#;
-    
-    Response::json(body)
+pub fn status_handler(_req: &Request, metrics: &MetricsCollector) -> Response {
+    let snapshot = metrics.get_snapshot();
+    Response::json_value(&json!({
+        "status": "running",
+        "version": env!("CARGO_PKG_VERSION"),
+        "server": "RedUnix HTTP/1.0",
+        "uptime_seconds": snapshot.uptime_secs,
+        "requests_served": snapshot.total_requests,
+        "open_connections": metrics.open_connections(),
+        "active_threads": snapshot.active_threads,
+        "latency_p50_us": snapshot.latency_p50_us,
+        "latency_p99_us": snapshot.latency_p99_us,
+    }))
 }
 
 /// Handler para /fibonacci?num=N
@@ -94,12 +109,7 @@ pub fn fibonacci_handler(req: &Request) -> Response {
     let result = calculate_fibonacci(num);
     
     // Construir response
-    let body = format!(
-        r#"{{"num": {}, "result": {}}}"#,
-        num, result
-    );
-    
-    Response::json(&body)
+    Response::json_value(&json!({"num": num, "result": result}))
 }
 
 /// Calcula el N-ésimo número de Fibonacci
@@ -153,12 +163,7 @@ pub fn reverse_handler(req: &Request) -> Response {
     // Invertir el texto (manejando correctamente caracteres UTF-8)
     let reversed: String = text.chars().rev().collect();
     
-    let body = format!(
-        r#"{{"original": "{}", "reversed": "{}"}}"#,
-        text, reversed
-    );
-    
-    Response::json(&body)
+    Response::json_value(&json!({"original": text, "reversed": reversed}))
 }
 
 /// Handler para /toupper?text=TEXT
@@ -188,12 +193,7 @@ pub fn toupper_handler(req: &Request) -> Response {
     
     let upper = text.to_uppercase();
     
-    let body = format!(
-        r#"{{"original": "{}", "upper": "{}"}}"#,
-        text, upper
-    );
-    
-    Response::json(&body)
+    Response::json_value(&json!({"original": text, "upper": upper}))
 }
 
 /// Handler para /timestamp
@@ -208,95 +208,13 @@ pub fn toupper_handler(req: &Request) -> Response {
 /// }
 /// ```
 pub fn timestamp_handler(_req: &Request) -> Response {
-    let now = SystemTime::now()
+    let now = SystemTime::now();
+    let secs = now
         .duration_since(UNIX_EPOCH)
         .unwrap()
         .as_secs();
     
-    // TODO: Agregar formato ISO cuando implementemos manejo de fechas
-    let body = format!(
-        r#"{{"timestamp": {}}}"#,
-        now
-    );
-    
-    Response::json(&body)
-}
-
-/// Handler para /help
-/// 
-/// Retorna la lista de comandos disponibles con su descripción.
-pub fn help_handler(_req: &Request) -> Response {
-    let body = r#"{
-  "commands": [
-    {
-      "path": "/status",
-      "description": "Server status and metrics",
-      "parameters": []
-    },
-    {
-      "path": "/fibonacci",
-      "description": "Calculate Fibonacci number",
-      "parameters": ["num (required): integer <= 90"]
-    },
-    {
-      "path": "/reverse",
-      "description": "Reverse a text string",
-      "parameters": ["text (required): string to reverse"]
-    },
-    {
-      "path": "/toupper",
-      "description": "Convert text to uppercase",
-      "parameters": ["text (required): string to convert"]
-    },
-    {
-      "path": "/timestamp",
-      "description": "Get current Unix timestamp",
-      "parameters": []
-    },
-    {
-      "path": "/random",
-      "description": "Generate random numbers",
-      "parameters": ["count (optional): number of values", "min (optional): minimum value", "max (optional): maximum value"]
-    },
-    {
-      "path": "/hash",
-      "description": "Calculate SHA256 hash of text",
-      "parameters": ["text (required): text to hash"]
-    },
-    {
-      "path": "/createfile",
-      "description": "Create a file with content",
-      "parameters": ["name (required): filename", "content (required): text content", "repeat (optional): repetitions"]
-    },
-    {
-      "path": "/deletefile",
-      "description": "Delete a file",
-      "parameters": ["name (required): filename"]
-    },
-    {
-      "path": "/simulate",
-      "description": "Simulate a task with real work",
-      "parameters": ["seconds (required): duration", "task (optional): task name"]
-    },
-    {
-      "path": "/sleep",
-      "description": "Sleep for N seconds",
-      "parameters": ["seconds (required): duration"]
-    },
-    {
-      "path": "/loadtest",
-      "description": "Generate test load",
-      "parameters": ["tasks (optional): number of tasks", "sleep (optional): sleep per task in ms"]
-    },
-    {
-      "path": "/help",
-      "description": "Show this help message",
-      "parameters": []
-    }
-  ]
-}"#;
-    
-    Response::json(body)
+    Response::json_value(&json!({"timestamp": secs, "iso": date::format_iso8601(now)}))
 }
 
 /// Handler para /random?count=N&min=A&max=B
@@ -304,7 +222,7 @@ pub fn help_handler(_req: &Request) -> Response {
 /// Genera números aleatorios en el rango especificado.
 /// 
 /// # Query parameters
-/// - `count`: Cantidad de números (default: 1, max: 1000)
+/// - `count`: Cantidad de números (default: 1, max: `limits.random_max_count`)
 /// - `min`: Valor mínimo (default: 0)
 /// - `max`: Valor máximo (default: 100)
 pub fn random_handler(req: &Request) -> Response {
@@ -315,7 +233,7 @@ pub fn random_handler(req: &Request) -> Response {
     let count: usize = req.query_param("count")
         .and_then(|s| s.parse().ok())
         .unwrap_or(1)
-        .min(1000); // Máximo 1000 números
+        .min(limits::current().random_max_count);
     
     let min: i32 = req.query_param("min")
         .and_then(|s| s.parse().ok())
@@ -352,12 +270,7 @@ pub fn random_handler(req: &Request) -> Response {
         numbers.push(num);
     }
     
-    let body = format!(
-        r#"{{"count": {}, "min": {}, "max": {}, "values": {:?}}}"#,
-        count, min, max, numbers
-    );
-    
-    Response::json(&body)
+    Response::json_value(&json!({"count": count, "min": min, "max": max, "values": numbers}))
 }
 
 /// Handler para /hash?text=TEXT
@@ -386,12 +299,11 @@ pub fn hash_handler(req: &Request) -> Response {
     text.hash(&mut hasher);
     let hash_value = hasher.finish();
     
-    let body = format!(
-        r#"{{"text": "{}", "hash": "{:016x}", "algorithm": "simple-hash"}}"#,
-        text, hash_value
-    );
-    
-    Response::json(&body)
+    Response::json_value(&json!({
+        "text": text,
+        "hash": format!("{:016x}", hash_value),
+        "algorithm": "simple-hash",
+    }))
 }
 
 /// Handler para /createfile?name=FILE&content=TEXT&repeat=N
@@ -401,7 +313,9 @@ pub fn hash_handler(req: &Request) -> Response {
 /// # Query parameters
 /// - `name`: Nombre del archivo (requerido)
 /// - `content`: Contenido del archivo (requerido)
-/// - `repeat`: Número de repeticiones del contenido (default: 1, max: 10000)
+/// - `repeat`: Número de repeticiones del contenido (default: 1, max: `limits.createfile_max_repeat`)
+/// 
+/// Si el archivo resultante supera `max_upload_bytes` responde 413.
 pub fn createfile_handler(req: &Request) -> Response {
     let name = match req.query_param("name") {
         Some(n) => n,
@@ -426,26 +340,23 @@ pub fn createfile_handler(req: &Request) -> Response {
     let repeat: usize = req.query_param("repeat")
         .and_then(|s| s.parse().ok())
         .unwrap_or(1)
-        .min(10000); // Máximo 10000 repeticiones
+        .min(limits::current().createfile_max_repeat);
     
-    // Validar nombre de archivo (seguridad básica)
-    if name.contains("..") || name.contains('/') || name.contains('\\') {
+    // Validar tamaño antes de construir el contenido en memoria
+    let total_bytes = content.len() as u64 * repeat as u64;
+    let max_upload = limits::current().max_upload_bytes;
+    if total_bytes > max_upload {
         return Response::error(
-            StatusCode::BadRequest,
-            "Invalid filename: cannot contain path separators or .."
+            StatusCode::PayloadTooLarge,
+            &format!("Upload too large: {} bytes (max: {})", total_bytes, max_upload)
         );
     }
     
-    // Crear en el directorio data/
-    let filepath = format!("./data/{}", name);
-    
-    // Crear directorio data/ si no existe
-    if let Err(e) = fs::create_dir_all("./data") {
-        return Response::error(
-            StatusCode::InternalServerError,
-            &format!("Failed to create data directory: {}", e)
-        );
-    }
+    // Resolver dentro del sandbox del directorio de datos
+    let filepath = match sandbox::resolve(name) {
+        Ok(path) => path,
+        Err(e) => return Response::error(StatusCode::BadRequest, &e),
+    };
     
     // Construir contenido repetido
     let full_content = content.repeat(repeat);
@@ -453,11 +364,7 @@ pub fn createfile_handler(req: &Request) -> Response {
     // Escribir archivo
     match fs::write(&filepath, &full_content) {
         Ok(_) => {
-            let body = format!(
-                r#"{{"filename": "{}", "size": {}, "repeat": {}}}"#,
-                name, full_content.len(), repeat
-            );
-            Response::json(&body)
+            Response::json_value(&json!({"filename": name, "size": full_content.len(), "repeat": repeat}))
         }
         Err(e) => {
             Response::error(
@@ -470,7 +377,7 @@ pub fn createfile_handler(req: &Request) -> Response {
 
 /// Handler para /deletefile?name=FILE
 /// 
-/// Elimina un archivo del directorio data/.
+/// Elimina un archivo del directorio de datos.
 /// 
 /// # Query parameters
 /// - `name`: Nombre del archivo (requerido)
@@ -485,18 +392,14 @@ pub fn deletefile_handler(req: &Request) -> Response {
         }
     };
     
-    // Validar nombre de archivo
-    if name.contains("..") || name.contains('/') || name.contains('\\') {
-        return Response::error(
-            StatusCode::BadRequest,
-            "Invalid filename: cannot contain path separators or .."
-        );
-    }
-    
-    let filepath = format!("./data/{}", name);
+    // Resolver dentro del sandbox del directorio de datos
+    let filepath = match sandbox::resolve(name) {
+        Ok(path) => path,
+        Err(e) => return Response::error(StatusCode::BadRequest, &e),
+    };
     
     // Verificar que el archivo existe
-    if !Path::new(&filepath).exists() {
+    if !filepath.is_file() {
         return Response::error(
             StatusCode::NotFound,
             &format!("File not found: {}", name)
@@ -506,8 +409,7 @@ pub fn deletefile_handler(req: &Request) -> Response {
     // Eliminar archivo
     match fs::remove_file(&filepath) {
         Ok(_) => {
-            let body = format!(r#"{{"filename": "{}", "deleted": true}}"#, name);
-            Response::json(&body)
+            Response::json_value(&json!({"filename": name, "deleted": true}))
         }
         Err(e) => {
             Response::error(
@@ -524,16 +426,17 @@ pub fn deletefile_handler(req: &Request) -> Response {
 /// Realiza cálculos para consumir CPU durante el tiempo especificado.
 /// 
 /// # Query parameters
-/// - `seconds`: Duración en segundos (requerido, max: 30)
+/// - `seconds`: Duración en segundos (requerido, max: `limits.simulate_max_secs`)
 /// - `task`: Nombre de la tarea (opcional)
 pub fn simulate_handler(req: &Request) -> Response {
+    let max_secs = limits::current().simulate_max_secs;
     let seconds: u64 = match req.query_param("seconds") {
         Some(s) => match s.parse() {
-            Ok(n) if n > 0 && n <= 30 => n,
+            Ok(n) if n > 0 && n <= max_secs => n,
             _ => {
                 return Response::error(
                     StatusCode::BadRequest,
-                    "Parameter 'seconds' must be between 1 and 30"
+                    &format!("Parameter 'seconds' must be between 1 and {}", max_secs)
                 );
             }
         },
@@ -569,12 +472,12 @@ pub fn simulate_handler(req: &Request) -> Response {
     
     let elapsed = start.elapsed().unwrap().as_secs_f64();
     
-    let body = format!(
-        r#"{{"task": "{}", "seconds": {}, "elapsed": {:.3}, "iterations": {}}}"#,
-        task_name, seconds, elapsed, counter
-    );
-    
-    Response::json(&body)
+    Response::json_value(&json!({
+        "task": task_name,
+        "seconds": seconds,
+        "elapsed": (elapsed * 1000.0).round() / 1000.0,
+        "iterations": counter,
+    }))
 }
 
 /// Handler para /sleep?seconds=S
@@ -582,15 +485,16 @@ pub fn simulate_handler(req: &Request) -> Response {
 /// Duerme durante N segundos.
 /// 
 /// # Query parameters
-/// - `seconds`: Duración en segundos (requerido, max: 10)
+/// - `seconds`: Duración en segundos (requerido, max: `limits.sleep_max_secs`)
 pub fn sleep_handler(req: &Request) -> Response {
+    let max_secs = limits::current().sleep_max_secs;
     let seconds: u64 = match req.query_param("seconds") {
         Some(s) => match s.parse() {
-            Ok(n) if n > 0 && n <= 10 => n,
+            Ok(n) if n > 0 && n <= max_secs => n,
             _ => {
                 return Response::error(
                     StatusCode::BadRequest,
-                    "Parameter 'seconds' must be between 1 and 10"
+                    &format!("Parameter 'seconds' must be between 1 and {}", max_secs)
                 );
             }
         },
@@ -604,8 +508,7 @@ pub fn sleep_handler(req: &Request) -> Response {
     
     std::thread::sleep(Duration::from_secs(seconds));
     
-    let body = format!(r#"{{"slept": {}}}"#, seconds);
-    Response::json(&body)
+    Response::json_value(&json!({"slept": seconds}))
 }
 
 /// Handler para /loadtest?tasks=N&sleep=X
@@ -613,18 +516,19 @@ pub fn sleep_handler(req: &Request) -> Response {
 /// Genera carga de prueba ejecutando múltiples tareas.
 /// 
 /// # Query parameters
-/// - `tasks`: Número de tareas (default: 10, max: 100)
-/// - `sleep`: Sleep por tarea en ms (default: 10, max: 1000)
+/// - `tasks`: Número de tareas (default: 10, max: `limits.loadtest_max_tasks`)
+/// - `sleep`: Sleep por tarea en ms (default: 10, max: `limits.loadtest_max_sleep_ms`)
 pub fn loadtest_handler(req: &Request) -> Response {
+    let limits = limits::current();
     let tasks: usize = req.query_param("tasks")
         .and_then(|s| s.parse().ok())
         .unwrap_or(10)
-        .min(100);
+        .min(limits.loadtest_max_tasks);
     
     let sleep_ms: u64 = req.query_param("sleep")
         .and_then(|s| s.parse().ok())
         .unwrap_or(10)
-        .min(1000);
+        .min(limits.loadtest_max_sleep_ms);
     
     let start = SystemTime::now();
     
@@ -639,12 +543,7 @@ pub fn loadtest_handler(req: &Request) -> Response {
     
     let elapsed = start.elapsed().unwrap().as_millis();
     
-    let body = format!(
-        r#"{{"tasks": {}, "sleep_ms": {}, "total_time_ms": {}}}"#,
-        tasks, sleep_ms, elapsed
-    );
-    
-    Response::json(&body)
+    Response::json_value(&json!({"tasks": tasks, "sleep_ms": sleep_ms, "total_time_ms": elapsed as u64}))
 }
 
 #[cfg(test)]
@@ -680,7 +579,7 @@ mod tests {
         assert_eq!(response.status(), StatusCode::Ok);
         let body = String::from_utf8(response.body().to_vec()).unwrap();
         assert!(body.contains("55"));
-        assert!(body.contains("\"num\": 10"));
+        assert!(body.contains("\"num\":10"));
     }
     
     #[test]
@@ -716,12 +615,12 @@ mod tests {
         let req0 = make_request("/fibonacci?num=0");
         let resp0 = fibonacci_handler(&req0);
         let body0 = String::from_utf8(resp0.body().to_vec()).unwrap();
-        assert!(body0.contains("\"result\": 0"));
+        assert!(body0.contains("\"result\":0"));
         
         let req1 = make_request("/fibonacci?num=1");
         let resp1 = fibonacci_handler(&req1);
         let body1 = String::from_utf8(resp1.body().to_vec()).unwrap();
-        assert!(body1.contains("\"result\": 1"));
+        assert!(body1.contains("\"result\":1"));
     }
     
     // ==================== REVERSE ====================
@@ -743,7 +642,20 @@ mod tests {
         
         assert_eq!(response.status(), StatusCode::Ok);
         let body = String::from_utf8(response.body().to_vec()).unwrap();
-        assert!(body.contains("\"reversed\": \"\""));
+        assert!(body.contains("\"reversed\":\"\""));
+    }
+    
+    #[test]
+    fn test_text_handlers_escape_json() {
+        // Comillas y backslashes del usuario no rompen el JSON
+        for response in [
+            reverse_handler(&make_request("/reverse?text=a\"b\\")),
+            toupper_handler(&make_request("/toupper?text=a\"b\\")),
+            hash_handler(&make_request("/hash?text=a\"b\\")),
+        ] {
+            let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+            assert_eq!(body["original"].as_str().or(body["text"].as_str()), Some("a\"b\\"));
+        }
     }
     
     #[test]
@@ -782,7 +694,7 @@ mod tests {
         
         assert_eq!(response.status(), StatusCode::Ok);
         let body = String::from_utf8(response.body().to_vec()).unwrap();
-        assert!(body.contains("\"upper\": \"HELLO\""));
+        assert!(body.contains("\"upper\":\"HELLO\""));
     }
     
     #[test]
@@ -807,13 +719,17 @@ mod tests {
     
     #[test]
     fn test_status_handler() {
+        let metrics = MetricsCollector::new();
+        metrics.record_request("/fibonacci", 200, Duration::from_millis(2));
         let request = make_request("/status");
-        let response = status_handler(&request);
+        let response = status_handler(&request, &metrics);
         
         assert_eq!(response.status(), StatusCode::Ok);
-        let body = String::from_utf8(response.body().to_vec()).unwrap();
-        assert!(body.contains("running"));
-        assert!(body.contains("version"));
+        let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(body["status"], "running");
+        assert_eq!(body["version"], env!("CARGO_PKG_VERSION"));
+        assert_eq!(body["requests_served"], 1);
+        assert!(body["uptime_seconds"].is_u64());
     }
     
     // ==================== TIMESTAMP ====================
@@ -829,21 +745,12 @@ mod tests {
         
         // Verificar que el timestamp es un número válido
         let timestamp_str = body.split("timestamp\":").nth(1).unwrap().split('}').next().unwrap();
-        let _timestamp: u64 = timestamp_str.trim().parse().expect("Should be valid number");
-    }
-    
-    // ==================== HELP ====================
-    
-    #[test]
-    fn test_help_handler() {
-        let request = make_request("/help");
-        let response = help_handler(&request);
+        let timestamp: u64 = timestamp_str.trim().parse().expect("Should be valid number");
         
-        assert_eq!(response.status(), StatusCode::Ok);
-        let body = String::from_utf8(response.body().to_vec()).unwrap();
-        assert!(body.contains("commands"));
-        assert!(body.contains("fibonacci"));
-        assert!(body.contains("reverse"));
+        // `iso` es el mismo instante en ISO 8601
+        let value: serde_json::Value = serde_json::from_str(&body).unwrap();
+        let iso = value["iso"].as_str().unwrap();
+        assert_eq!(iso, date::format_iso8601(UNIX_EPOCH + Duration::from_secs(timestamp)));
     }
     
     // ==================== RANDOM ====================
@@ -866,9 +773,9 @@ mod tests {
         
         assert_eq!(response.status(), StatusCode::Ok);
         let body = String::from_utf8(response.body().to_vec()).unwrap();
-        assert!(body.contains("\"count\": 5"));
-        assert!(body.contains("\"min\": 10"));
-        assert!(body.contains("\"max\": 20"));
+        assert!(body.contains("\"count\":5"));
+        assert!(body.contains("\"min\":10"));
+        assert!(body.contains("\"max\":20"));
     }
     
     #[test]
@@ -887,7 +794,7 @@ mod tests {
         assert_eq!(response.status(), StatusCode::Ok);
         let body = String::from_utf8(response.body().to_vec()).unwrap();
         // Debe limitar a 1000
-        assert!(body.contains("\"count\": 1000"));
+        assert!(body.contains("\"count\":1000"));
     }
     
     // ==================== HASH ====================
@@ -1010,20 +917,20 @@ mod tests {
         
         assert_eq!(response.status(), StatusCode::Ok);
         let body = String::from_utf8(response.body().to_vec()).unwrap();
-        assert!(body.contains("\"tasks\": 5"));
+        assert!(body.contains("\"tasks\":5"));
     }
     
     // ==================== FILE OPERATIONS ====================
     
     #[test]
     fn test_createfile_handler_success() {
-        let request = make_request("/createfile?name=test.txt&content=hello");
+        let request = make_request("/createfile?name=test_create.txt&content=hello");
         let response = createfile_handler(&request);
         
         assert_eq!(response.status(), StatusCode::Ok);
         
-        // Limpiar
-        let _ = std::fs::remove_file("./data/test.txt");
+        // Limpiar (sin tocar data/test.txt, que está versionado)
+        let _ = std::fs::remove_file("./data/test_create.txt");
     }
     
     #[test]
@@ -1033,7 +940,7 @@ mod tests {
         
         assert_eq!(response.status(), StatusCode::Ok);
         let body = String::from_utf8(response.body().to_vec()).unwrap();
-        assert!(body.contains("\"size\": 100"));
+        assert!(body.contains("\"size\":100"));
         
         // Limpiar
         let _ = std::fs::remove_file("./data/test_repeat.txt");
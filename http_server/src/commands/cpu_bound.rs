@@ -8,7 +8,9 @@
 //! - /mandelbrot: Generación del conjunto de Mandelbrot
 //! - /matrixmul: Multiplicación de matrices
 
+use crate::commands::limits;
 use crate::http::{Request, Response, StatusCode};
+use serde_json::json;
 use std::time::Instant;
 
 /// Handler para /isprime?n=NUM
@@ -45,14 +47,14 @@ pub fn isprime_handler(req: &Request) -> Response {
     
     let start = Instant::now();
     let is_prime = is_prime_miller_rabin(n, 10);
-    let elapsed_ms = start.elapsed().as_millis();
-    
-    let body = format!(
-        r#"{{"n": {}, "is_prime": {}, "method": "miller-rabin", "elapsed_ms": {}}}"#,
-        n, is_prime, elapsed_ms
-    );
-    
-    Response::json(&body)
+    let elapsed_ms = start.elapsed().as_millis() as u64;
+    
+    Response::json_value(&json!({
+        "n": n,
+        "is_prime": is_prime,
+        "method": "miller-rabin",
+        "elapsed_ms": elapsed_ms,
+    }))
 }
 
 /// Verifica si un número es primo usando el test de Miller-Rabin
@@ -67,14 +69,14 @@ fn is_prime_miller_rabin(n: u64, k: usize) -> bool {
     if n == 2 || n == 3 {
         return true;
     }
-    if n % 2 == 0 {
+    if n.is_multiple_of(2) {
         return false;
     }
     
     // Escribir n-1 como 2^r * d
     let mut d = n - 1;
     let mut r = 0;
-    while d % 2 == 0 {
+    while d.is_multiple_of(2) {
         d /= 2;
         r += 1;
     }
@@ -190,20 +192,10 @@ pub fn factor_handler(req: &Request) -> Response {
     
     let start = Instant::now();
     let factors = factorize(n);
-    let elapsed_ms = start.elapsed().as_millis();
-    
-    // Formatear factores como [[primo, exponente], ...]
-    let factors_str = factors.iter()
-        .map(|(p, e)| format!("[{}, {}]", p, e))
-        .collect::<Vec<_>>()
-        .join(", ");
-    
-    let body = format!(
-        r#"{{"n": {}, "factors": [{}], "elapsed_ms": {}}}"#,
-        n, factors_str, elapsed_ms
-    );
+    let elapsed_ms = start.elapsed().as_millis() as u64;
     
-    Response::json(&body)
+    // Factores como [[primo, exponente], ...]
+    Response::json_value(&json!({"n": n, "factors": factors, "elapsed_ms": elapsed_ms}))
 }
 
 /// Factoriza un número en sus factores primos
@@ -213,9 +205,9 @@ fn factorize(mut n: u64) -> Vec<(u64, u32)> {
     let mut factors = Vec::new();
     
     // Manejar factor 2
-    if n % 2 == 0 {
+    if n.is_multiple_of(2) {
         let mut count = 0;
-        while n % 2 == 0 {
+        while n.is_multiple_of(2) {
             n /= 2;
             count += 1;
         }
@@ -225,9 +217,9 @@ fn factorize(mut n: u64) -> Vec<(u64, u32)> {
     // Probar divisores impares hasta √n
     let mut d = 3;
     while d * d <= n {
-        if n % d == 0 {
+        if n.is_multiple_of(d) {
             let mut count = 0;
-            while n % d == 0 {
+            while n.is_multiple_of(d) {
                 n /= d;
                 count += 1;
             }
@@ -249,7 +241,7 @@ fn factorize(mut n: u64) -> Vec<(u64, u32)> {
 /// Calcula dígitos de π usando el algoritmo de Bailey–Borwein–Plouffe.
 /// 
 /// # Query parameters
-/// - `digits`: Número de dígitos decimales (1-1000)
+/// - `digits`: Número de dígitos decimales (1 a `limits.pi_max_digits`)
 /// 
 /// # Ejemplo de response
 /// ```json
@@ -266,26 +258,22 @@ pub fn pi_handler(req: &Request) -> Response {
         }
     };
     
+    let max_digits = limits::current().pi_max_digits;
     let digits: usize = match digits_str.parse() {
-        Ok(d) if d >= 1 && d <= 1000 => d,
+        Ok(d) if d >= 1 && d <= max_digits => d,
         _ => {
             return Response::error(
                 StatusCode::BadRequest,
-                "Parameter 'digits' must be between 1 and 1000"
+                &format!("Parameter 'digits' must be between 1 and {}", max_digits)
             );
         }
     };
     
     let start = Instant::now();
     let pi_value = calculate_pi(digits);
-    let elapsed_ms = start.elapsed().as_millis();
+    let elapsed_ms = start.elapsed().as_millis() as u64;
     
-    let body = format!(
-        r#"{{"digits": {}, "value": "{}", "elapsed_ms": {}}}"#,
-        digits, pi_value, elapsed_ms
-    );
-    
-    Response::json(&body)
+    Response::json_value(&json!({"digits": digits, "value": pi_value, "elapsed_ms": elapsed_ms}))
 }
 
 /// Calcula π con precisión especificada usando serie de Machin
@@ -309,7 +297,6 @@ fn calculate_pi(digits: usize) -> String {
 fn calculate_arctan(x: i32, terms: usize) -> f64 {
     let x_f = x as f64;
     let mut result = 0.0;
-    let x_squared = x_f * x_f;
     
     for n in 0..terms {
         let sign = if n % 2 == 0 { 1.0 } else { -1.0 };
@@ -330,54 +317,48 @@ fn calculate_arctan(x: i32, terms: usize) -> f64 {
 /// Genera el conjunto de Mandelbrot.
 /// 
 /// # Query parameters
-/// - `width`: Ancho (default: 80, max: 500)
-/// - `height`: Alto (default: 40, max: 500)
-/// - `max_iter`: Iteraciones máximas (default: 100, max: 1000)
+/// - `width`: Ancho (default: 80, max: `limits.mandelbrot_max_size`)
+/// - `height`: Alto (default: 40, max: `limits.mandelbrot_max_size`)
+/// - `max_iter`: Iteraciones máximas (default: 100, max: `limits.mandelbrot_max_iter`)
 /// 
 /// # Ejemplo de response
 /// ```json
 /// {"width": 80, "height": 40, "max_iter": 100, "data": [[...]]}
 /// ```
 pub fn mandelbrot_handler(req: &Request) -> Response {
+    let limits = limits::current();
     let width: usize = req.query_param("width")
         .and_then(|s| s.parse().ok())
         .unwrap_or(80)
-        .min(500);
+        .min(limits.mandelbrot_max_size);
     
     let height: usize = req.query_param("height")
         .and_then(|s| s.parse().ok())
         .unwrap_or(40)
-        .min(500);
+        .min(limits.mandelbrot_max_size);
     
     let max_iter: u32 = req.query_param("max_iter")
         .and_then(|s| s.parse().ok())
         .unwrap_or(100)
-        .min(1000);
+        .min(limits.mandelbrot_max_iter);
     
     let start = Instant::now();
     let data = calculate_mandelbrot(width, height, max_iter);
-    let elapsed_ms = start.elapsed().as_millis();
+    let elapsed_ms = start.elapsed().as_millis() as u64;
     
-    // Convertir data a JSON (simplificado - solo primeras filas)
-    let sample_rows = data.iter()
+    // Solo una muestra: primeras 5 filas, 10 valores por fila
+    let sample_rows: Vec<Vec<_>> = data.iter()
         .take(5)
-        .map(|row| {
-            let row_str = row.iter()
-                .take(10)
-                .map(|v| v.to_string())
-                .collect::<Vec<_>>()
-                .join(",");
-            format!("[{}]", row_str)
-        })
-        .collect::<Vec<_>>()
-        .join(",");
-    
-    let body = format!(
-        r#"{{"width": {}, "height": {}, "max_iter": {}, "sample_data": [{}], "elapsed_ms": {}}}"#,
-        width, height, max_iter, sample_rows, elapsed_ms
-    );
-    
-    Response::json(&body)
+        .map(|row| row.iter().take(10).collect())
+        .collect();
+    
+    Response::json_value(&json!({
+        "width": width,
+        "height": height,
+        "max_iter": max_iter,
+        "sample_data": sample_rows,
+        "elapsed_ms": elapsed_ms,
+    }))
 }
 
 /// Calcula el conjunto de Mandelbrot
@@ -420,7 +401,7 @@ fn calculate_mandelbrot(width: usize, height: usize, max_iter: u32) -> Vec<Vec<u
 /// Multiplica dos matrices N×N con valores pseudoaleatorios.
 /// 
 /// # Query parameters
-/// - `size`: Tamaño de la matriz (1-500)
+/// - `size`: Tamaño de la matriz (1 a `limits.matrixmul_max_size`)
 /// - `seed`: Semilla para generación (default: 42)
 /// 
 /// # Ejemplo de response
@@ -428,13 +409,14 @@ fn calculate_mandelbrot(width: usize, height: usize, max_iter: u32) -> Vec<Vec<u
 /// {"size": 100, "seed": 42, "result_hash": "a3f5...", "elapsed_ms": 234}
 /// ```
 pub fn matrixmul_handler(req: &Request) -> Response {
+    let max_size = limits::current().matrixmul_max_size;
     let size: usize = match req.query_param("size") {
         Some(s) => match s.parse() {
-            Ok(n) if n >= 1 && n <= 500 => n,
+            Ok(n) if n >= 1 && n <= max_size => n,
             _ => {
                 return Response::error(
                     StatusCode::BadRequest,
-                    "Parameter 'size' must be between 1 and 500"
+                    &format!("Parameter 'size' must be between 1 and {}", max_size)
                 );
             }
         },
@@ -452,14 +434,14 @@ pub fn matrixmul_handler(req: &Request) -> Response {
     
     let start = Instant::now();
     let hash = matrix_multiply(size, seed);
-    let elapsed_ms = start.elapsed().as_millis();
-    
-    let body = format!(
-        r#"{{"size": {}, "seed": {}, "result_hash": "{:016x}", "elapsed_ms": {}}}"#,
-        size, seed, hash, elapsed_ms
-    );
-    
-    Response::json(&body)
+    let elapsed_ms = start.elapsed().as_millis() as u64;
+    
+    Response::json_value(&json!({
+        "size": size,
+        "seed": seed,
+        "result_hash": format!("{:016x}", hash),
+        "elapsed_ms": elapsed_ms,
+    }))
 }
 
 /// Multiplica dos matrices y retorna hash del resultado
@@ -559,8 +541,8 @@ fn matrix_multiply(size: usize, seed: u64) -> u64 {
             
             assert_eq!(response.status(), StatusCode::Ok);
             let body = String::from_utf8(response.body().to_vec()).unwrap();
-            assert!(body.contains("\"is_prime\": true"));
-            assert!(body.contains("\"n\": 97"));
+            assert!(body.contains("\"is_prime\":true"));
+            assert!(body.contains("\"n\":97"));
         }
         
         #[test]
@@ -570,7 +552,7 @@ fn matrix_multiply(size: usize, seed: u64) -> u64 {
             
             assert_eq!(response.status(), StatusCode::Ok);
             let body = String::from_utf8(response.body().to_vec()).unwrap();
-            assert!(body.contains("\"is_prime\": false"));
+            assert!(body.contains("\"is_prime\":false"));
         }
         
         #[test]
@@ -660,8 +642,8 @@ fn matrix_multiply(size: usize, seed: u64) -> u64 {
             assert_eq!(response.status(), StatusCode::Ok);
             let body = String::from_utf8(response.body().to_vec()).unwrap();
             assert!(body.contains("\"factors\""));
-            assert!(body.contains("[2, 2]"));
-            assert!(body.contains("[3, 1]"));
+            assert!(body.contains("[2,2]"));
+            assert!(body.contains("[3,1]"));
         }
         
         #[test]
@@ -671,7 +653,7 @@ fn matrix_multiply(size: usize, seed: u64) -> u64 {
             
             assert_eq!(response.status(), StatusCode::Ok);
             let body = String::from_utf8(response.body().to_vec()).unwrap();
-            assert!(body.contains("[97, 1]"));
+            assert!(body.contains("[97,1]"));
         }
         
         #[test]
@@ -738,7 +720,7 @@ fn matrix_multiply(size: usize, seed: u64) -> u64 {
             
             assert_eq!(response.status(), StatusCode::Ok);
             let body = String::from_utf8(response.body().to_vec()).unwrap();
-            assert!(body.contains("\"digits\": 10"));
+            assert!(body.contains("\"digits\":10"));
             assert!(body.contains("\"value\""));
             
             // Verificar que el valor de π comienza con 3.14
@@ -801,8 +783,8 @@ fn matrix_multiply(size: usize, seed: u64) -> u64 {
             
             assert_eq!(response.status(), StatusCode::Ok);
             let body = String::from_utf8(response.body().to_vec()).unwrap();
-            assert!(body.contains("\"width\": 80"));
-            assert!(body.contains("\"height\": 40"));
+            assert!(body.contains("\"width\":80"));
+            assert!(body.contains("\"height\":40"));
         }
         
         #[test]
@@ -812,9 +794,9 @@ fn matrix_multiply(size: usize, seed: u64) -> u64 {
             
             assert_eq!(response.status(), StatusCode::Ok);
             let body = String::from_utf8(response.body().to_vec()).unwrap();
-            assert!(body.contains("\"width\": 20"));
-            assert!(body.contains("\"height\": 20"));
-            assert!(body.contains("\"max_iter\": 50"));
+            assert!(body.contains("\"width\":20"));
+            assert!(body.contains("\"height\":20"));
+            assert!(body.contains("\"max_iter\":50"));
         }
         
         #[test]
@@ -825,8 +807,8 @@ fn matrix_multiply(size: usize, seed: u64) -> u64 {
             assert_eq!(response.status(), StatusCode::Ok);
             let body = String::from_utf8(response.body().to_vec()).unwrap();
             // Debe limitar a 500
-            assert!(body.contains("\"width\": 500"));
-            assert!(body.contains("\"height\": 500"));
+            assert!(body.contains("\"width\":500"));
+            assert!(body.contains("\"height\":500"));
         }
         
         // ==================== MATRIXMUL ====================
@@ -854,8 +836,8 @@ fn matrix_multiply(size: usize, seed: u64) -> u64 {
             
             assert_eq!(response.status(), StatusCode::Ok);
             let body = String::from_utf8(response.body().to_vec()).unwrap();
-            assert!(body.contains("\"size\": 10"));
-            assert!(body.contains("\"seed\": 42"));
+            assert!(body.contains("\"size\":10"));
+            assert!(body.contains("\"seed\":42"));
             assert!(body.contains("\"result_hash\""));
         }
         
@@ -866,7 +848,7 @@ fn matrix_multiply(size: usize, seed: u64) -> u64 {
             
             assert_eq!(response.status(), StatusCode::Ok);
             let body = String::from_utf8(response.body().to_vec()).unwrap();
-            assert!(body.contains("\"seed\": 42"));  // Default
+            assert!(body.contains("\"seed\":42"));  // Default
         }
         
         #[test]
@@ -0,0 +1,220 @@
+//! # Simulación de Fábrica
+//! src/commands/factory.rs
+//!
+//! Expone la simulación de Tarea-2 (Corte → Ensamblaje → Empaque, cada
+//! estación en su hilo y planificada con FCFS o Round Robin):
+//! - /factory: Correr la simulación con N productos y retornar las
+//!   estadísticas de espera y turnaround por producto
+//!
+//! Casi todo el tiempo del worker se va en `thread::sleep` de las estaciones,
+//! así que conviene enviarla como job (`/jobs/submit?task=factory`).
+
+use crate::commands::limits;
+use crate::http::{Request, Response, StatusCode};
+use std::thread;
+use std::time::{Duration, Instant};
+use tarea2::factory::{Factory, StationTimes};
+use tarea2::scheduler::SchedulingAlgorithm;
+
+/// Capacidad del canal de entrada a la primera estación
+const INPUT_CAPACITY: usize = 5;
+
+/// Quantum por defecto de Round Robin (el de la simulación original)
+const DEFAULT_QUANTUM_MS: u64 = 750;
+
+/// Parsea un parámetro opcional en `1..=max`
+fn bounded_param(req: &Request, name: &str, default: u64, max: u64) -> Result<u64, String> {
+    match req.query_param(name) {
+        Some(s) => match s.parse() {
+            Ok(n) if n > 0 && n <= max => Ok(n),
+            _ => Err(format!("Parameter '{}' must be between 1 and {}", name, max)),
+        },
+        None => Ok(default),
+    }
+}
+
+/// Parsea `algorithm` (`fcfs` o `rr`/`roundrobin`) y `quantum_ms`
+fn parse_algorithm(req: &Request, max_ms: u64) -> Result<SchedulingAlgorithm, String> {
+    match req.query_param("algorithm").unwrap_or("fcfs").to_lowercase().as_str() {
+        "fcfs" => Ok(SchedulingAlgorithm::FCFS),
+        "rr" | "roundrobin" | "round_robin" => {
+            let quantum_ms = bounded_param(req, "quantum_ms", DEFAULT_QUANTUM_MS.min(max_ms), max_ms)?;
+            Ok(SchedulingAlgorithm::RoundRobin { quantum_ms })
+        }
+        other => Err(format!("Invalid algorithm: {} (use fcfs or rr)", other)),
+    }
+}
+
+/// Parsea los tiempos de cada estación (default: los de Tarea-2)
+fn parse_station_times(req: &Request, max_ms: u64) -> Result<StationTimes, String> {
+    let defaults = StationTimes::default();
+    Ok(StationTimes {
+        cutting_ms: bounded_param(req, "cutting_ms", defaults.cutting_ms.min(max_ms), max_ms)?,
+        assembly_ms: bounded_param(req, "assembly_ms", defaults.assembly_ms.min(max_ms), max_ms)?,
+        packaging_ms: bounded_param(req, "packaging_ms", defaults.packaging_ms.min(max_ms), max_ms)?,
+    })
+}
+
+/// Handler para /factory?products=N&algorithm=fcfs|rr&quantum_ms=Q
+///
+/// Corre la simulación de la fábrica y retorna el resumen de estadísticas.
+///
+/// # Query parameters
+/// - `products`: Cantidad de productos (requerido, max: `limits.factory_max_products`)
+/// - `algorithm`: `fcfs` o `rr` (opcional, default: `fcfs`)
+/// - `quantum_ms`: Quantum de Round Robin (opcional, default: 750)
+/// - `cutting_ms`, `assembly_ms`, `packaging_ms`: Tiempo de cada estación
+///   (opcional, default: 2000/3000/1000)
+/// - `interval_ms`: Tiempo entre llegadas de productos (opcional, default: 0)
+///
+/// Todos los tiempos están acotados por `limits.factory_max_station_ms`.
+pub fn factory_handler(req: &Request) -> Response {
+    let limits = limits::current();
+    let max_ms = limits.factory_max_station_ms;
+
+    let products = match req.query_param("products") {
+        Some(s) => match s.parse::<u32>() {
+            Ok(n) if n > 0 && n as usize <= limits.factory_max_products => n,
+            _ => {
+                return Response::error(
+                    StatusCode::BadRequest,
+                    &format!("Parameter 'products' must be between 1 and {}", limits.factory_max_products)
+                );
+            }
+        },
+        None => {
+            return Response::error(
+                StatusCode::BadRequest,
+                "Missing required parameter: products"
+            );
+        }
+    };
+
+    let algorithm = match parse_algorithm(req, max_ms) {
+        Ok(a) => a,
+        Err(e) => return Response::error(StatusCode::BadRequest, &e),
+    };
+
+    let times = match parse_station_times(req, max_ms) {
+        Ok(t) => t,
+        Err(e) => return Response::error(StatusCode::BadRequest, &e),
+    };
+
+    let interval_ms = match req.query_param("interval_ms") {
+        Some(s) => match s.parse::<u64>() {
+            Ok(n) if n <= max_ms => n,
+            _ => {
+                return Response::error(
+                    StatusCode::BadRequest,
+                    &format!("Parameter 'interval_ms' must be between 0 and {}", max_ms)
+                );
+            }
+        },
+        None => 0,
+    };
+
+    let quantum_ms = match algorithm {
+        SchedulingAlgorithm::RoundRobin { quantum_ms } => Some(quantum_ms),
+        SchedulingAlgorithm::FCFS => None,
+    };
+    let station_times = serde_json::json!({
+        "cutting_ms": times.cutting_ms,
+        "assembly_ms": times.assembly_ms,
+        "packaging_ms": times.packaging_ms,
+    });
+
+    let start = Instant::now();
+    let factory = Factory::new_with_options(INPUT_CAPACITY, algorithm, times, false);
+    for id in 1..=products {
+        if id > 1 && interval_ms > 0 {
+            thread::sleep(Duration::from_millis(interval_ms));
+        }
+        if factory.send_product(id).is_err() {
+            return Response::error(StatusCode::InternalServerError, "Factory stopped unexpectedly");
+        }
+    }
+    let stats = factory.shutdown();
+
+    let product_stats: Vec<_> = stats.product_stats.iter()
+        .map(|p| serde_json::json!({
+            "id": p.id,
+            "waiting_time": p.waiting_time,
+            "turnaround_time": p.turnaround_time,
+        }))
+        .collect();
+
+    let body = serde_json::json!({
+        "algorithm": if quantum_ms.is_some() { "rr" } else { "fcfs" },
+        "quantum_ms": quantum_ms,
+        "products": stats.total_products,
+        "station_times": station_times,
+        "interval_ms": interval_ms,
+        "avg_waiting_time": stats.avg_waiting_time,
+        "avg_turnaround_time": stats.avg_turnaround_time,
+        "completion_order": stats.completion_order,
+        "product_stats": product_stats,
+        "elapsed_ms": start.elapsed().as_millis() as u64,
+    });
+
+    Response::json_value(&body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_request(path: &str) -> Request {
+        let raw = format!("GET {} HTTP/1.0\r\n\r\n", path);
+        Request::parse(raw.as_bytes()).unwrap()
+    }
+
+    fn run(path: &str) -> serde_json::Value {
+        let response = factory_handler(&make_request(path));
+        assert_eq!(response.status(), StatusCode::Ok);
+        serde_json::from_slice(response.body()).unwrap()
+    }
+
+    #[test]
+    fn test_factory_fcfs() {
+        let body = run("/factory?products=3&cutting_ms=5&assembly_ms=5&packaging_ms=5");
+        assert_eq!(body["algorithm"], "fcfs");
+        assert!(body["quantum_ms"].is_null());
+        assert_eq!(body["products"], 3);
+        // FCFS con llegadas simultáneas conserva el orden de llegada
+        assert_eq!(body["completion_order"], serde_json::json!([1, 2, 3]));
+
+        let stats = body["product_stats"].as_array().unwrap();
+        assert_eq!(stats.len(), 3);
+        for p in stats {
+            let waiting = p["waiting_time"].as_f64().unwrap();
+            let turnaround = p["turnaround_time"].as_f64().unwrap();
+            assert!(turnaround >= 0.015 && waiting <= turnaround);
+        }
+    }
+
+    #[test]
+    fn test_factory_round_robin() {
+        let body = run("/factory?products=2&algorithm=rr&quantum_ms=5&cutting_ms=12&assembly_ms=5&packaging_ms=5");
+        assert_eq!(body["algorithm"], "rr");
+        assert_eq!(body["quantum_ms"], 5);
+        assert_eq!(body["products"], 2);
+        assert_eq!(body["station_times"]["cutting_ms"], 12);
+    }
+
+    #[test]
+    fn test_factory_invalid_params() {
+        for path in [
+            "/factory",
+            "/factory?products=0",
+            "/factory?products=abc",
+            "/factory?products=100000",
+            "/factory?products=2&algorithm=sjf",
+            "/factory?products=2&algorithm=rr&quantum_ms=0",
+            "/factory?products=2&cutting_ms=999999",
+            "/factory?products=2&interval_ms=-1",
+        ] {
+            let response = factory_handler(&make_request(path));
+            assert_eq!(response.status(), StatusCode::BadRequest, "{}", path);
+        }
+    }
+}
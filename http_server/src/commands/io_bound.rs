@@ -7,19 +7,27 @@
 //! - /grep: Buscar patrones en archivos
 //! - /compress: Comprimir archivos (gzip)
 //! - /hashfile: Calcular hash SHA256 de archivos
+//! - /download: Descargar archivos con header `Content-Digest` (soporta `Range`)
+//! - /uploadfile: Subir archivos con `multipart/form-data`
 
+use crate::commands::sandbox;
+use crate::http::{multipart, range};
+use crate::http::request::Method;
 use crate::http::{Request, Response, StatusCode};
+use serde_json::json;
 use std::fs::{self, File};
-use std::io::{BufRead, BufReader, BufWriter, Write, Read};
-use std::path::Path;
-use std::time::Instant;
+use std::io::{BufRead, BufReader, BufWriter, Write, Read, Seek, SeekFrom};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Instant, SystemTime};
 
 /// Handler para /sortfile?name=FILE&algo=merge|quick
 /// 
 /// Ordena un archivo que contiene números (uno por línea).
 /// 
 /// # Query parameters
-/// - `name`: Nombre del archivo en data/ (requerido)
+/// - `name`: Nombre del archivo en el directorio de datos (requerido)
 /// - `algo`: Algoritmo (merge o quick, default: merge)
 /// 
 /// # Ejemplo de response
@@ -37,14 +45,6 @@ pub fn sortfile_handler(req: &Request) -> Response {
         }
     };
     
-    // Validar nombre de archivo
-    if name.contains("..") || name.contains('/') || name.contains('\\') {
-        return Response::error(
-            StatusCode::BadRequest,
-            "Invalid filename"
-        );
-    }
-    
     let algo = req.query_param("algo").unwrap_or("merge");
     if algo != "merge" && algo != "quick" {
         return Response::error(
@@ -53,10 +53,13 @@ pub fn sortfile_handler(req: &Request) -> Response {
         );
     }
     
-    let filepath = format!("./data/{}", name);
+    let filepath = match sandbox::resolve(name) {
+        Ok(path) => path,
+        Err(e) => return Response::error(StatusCode::BadRequest, &e),
+    };
     
     // Verificar que existe
-    if !Path::new(&filepath).exists() {
+    if !filepath.exists() {
         return Response::error(
             StatusCode::NotFound,
             &format!("File not found: {}", name)
@@ -86,7 +89,10 @@ pub fn sortfile_handler(req: &Request) -> Response {
     
     // Escribir archivo ordenado
     let output_name = format!("{}.sorted", name);
-    let output_path = format!("./data/{}", output_name);
+    let output_path = match sandbox::resolve(&output_name) {
+        Ok(path) => path,
+        Err(e) => return Response::error(StatusCode::BadRequest, &e),
+    };
     
     if let Err(e) = write_numbers_to_file(&output_path, &sorted) {
         return Response::error(
@@ -95,18 +101,19 @@ pub fn sortfile_handler(req: &Request) -> Response {
         );
     }
     
-    let elapsed_ms = start.elapsed().as_millis();
+    let elapsed_ms = start.elapsed().as_millis() as u64;
     
-    let body = format!(
-        r#"{{"file": "{}", "algo": "{}", "sorted_file": "{}", "lines": {}, "elapsed_ms": {}}}"#,
-        name, algo, output_name, sorted.len(), elapsed_ms
-    );
-    
-    Response::json(&body)
+    Response::json_value(&json!({
+        "file": name,
+        "algo": algo,
+        "sorted_file": output_name,
+        "lines": sorted.len(),
+        "elapsed_ms": elapsed_ms,
+    }))
 }
 
 /// Lee números de un archivo (un número por línea)
-fn read_numbers_from_file(path: &str) -> std::io::Result<Vec<i64>> {
+fn read_numbers_from_file(path: impl AsRef<Path>) -> std::io::Result<Vec<i64>> {
     let file = File::open(path)?;
     let reader = BufReader::new(file);
     let mut numbers = Vec::new();
@@ -122,7 +129,7 @@ fn read_numbers_from_file(path: &str) -> std::io::Result<Vec<i64>> {
 }
 
 /// Escribe números a un archivo (uno por línea)
-fn write_numbers_to_file(path: &str, numbers: &[i64]) -> std::io::Result<()> {
+fn write_numbers_to_file(path: impl AsRef<Path>, numbers: &[i64]) -> std::io::Result<()> {
     let file = File::create(path)?;
     let mut writer = BufWriter::new(file);
     
@@ -184,7 +191,7 @@ fn merge(left: &[i64], right: &[i64], result: &mut [i64]) {
 /// Cuenta líneas, palabras y bytes de un archivo.
 /// 
 /// # Query parameters
-/// - `name`: Nombre del archivo en data/ (requerido)
+/// - `name`: Nombre del archivo en el directorio de datos (requerido)
 /// 
 /// # Ejemplo de response
 /// ```json
@@ -201,17 +208,12 @@ pub fn wordcount_handler(req: &Request) -> Response {
         }
     };
     
-    // Validar nombre
-    if name.contains("..") || name.contains('/') || name.contains('\\') {
-        return Response::error(
-            StatusCode::BadRequest,
-            "Invalid filename"
-        );
-    }
-    
-    let filepath = format!("./data/{}", name);
+    let filepath = match sandbox::resolve(name) {
+        Ok(path) => path,
+        Err(e) => return Response::error(StatusCode::BadRequest, &e),
+    };
     
-    if !Path::new(&filepath).exists() {
+    if !filepath.exists() {
         return Response::error(
             StatusCode::NotFound,
             &format!("File not found: {}", name)
@@ -230,18 +232,19 @@ pub fn wordcount_handler(req: &Request) -> Response {
         }
     };
     
-    let elapsed_ms = start.elapsed().as_millis();
-    
-    let body = format!(
-        r#"{{"file": "{}", "lines": {}, "words": {}, "bytes": {}, "elapsed_ms": {}}}"#,
-        name, lines, words, bytes, elapsed_ms
-    );
+    let elapsed_ms = start.elapsed().as_millis() as u64;
     
-    Response::json(&body)
+    Response::json_value(&json!({
+        "file": name,
+        "lines": lines,
+        "words": words,
+        "bytes": bytes,
+        "elapsed_ms": elapsed_ms,
+    }))
 }
 
 /// Cuenta estadísticas de un archivo
-fn count_file_stats(path: &str) -> std::io::Result<(usize, usize, usize)> {
+fn count_file_stats(path: impl AsRef<Path>) -> std::io::Result<(usize, usize, usize)> {
     let file = File::open(path)?;
     let reader = BufReader::new(file);
     
@@ -264,7 +267,7 @@ fn count_file_stats(path: &str) -> std::io::Result<(usize, usize, usize)> {
 /// Busca líneas que coincidan con un patrón.
 /// 
 /// # Query parameters
-/// - `name`: Nombre del archivo en data/ (requerido)
+/// - `name`: Nombre del archivo en el directorio de datos (requerido)
 /// - `pattern`: Expresión regular (requerido)
 /// 
 /// # Ejemplo de response
@@ -292,17 +295,12 @@ pub fn grep_handler(req: &Request) -> Response {
         }
     };
     
-    // Validar nombre
-    if name.contains("..") || name.contains('/') || name.contains('\\') {
-        return Response::error(
-            StatusCode::BadRequest,
-            "Invalid filename"
-        );
-    }
-    
-    let filepath = format!("./data/{}", name);
+    let filepath = match sandbox::resolve(name) {
+        Ok(path) => path,
+        Err(e) => return Response::error(StatusCode::BadRequest, &e),
+    };
     
-    if !Path::new(&filepath).exists() {
+    if !filepath.exists() {
         return Response::error(
             StatusCode::NotFound,
             &format!("File not found: {}", name)
@@ -321,25 +319,22 @@ pub fn grep_handler(req: &Request) -> Response {
         }
     };
     
-    let elapsed_ms = start.elapsed().as_millis();
-    
-    // Formatear primeras 10 líneas para JSON
-    let lines_json = lines.iter()
-        .take(10)
-        .map(|l| format!(r#""{}""#, l.replace('"', "\\\"")))
-        .collect::<Vec<_>>()
-        .join(", ");
+    let elapsed_ms = start.elapsed().as_millis() as u64;
     
-    let body = format!(
-        r#"{{"file": "{}", "pattern": "{}", "matches": {}, "sample_lines": [{}], "elapsed_ms": {}}}"#,
-        name, pattern, count, lines_json, elapsed_ms
-    );
+    // Solo las primeras 10 líneas
+    let sample_lines: Vec<_> = lines.iter().take(10).collect();
     
-    Response::json(&body)
+    Response::json_value(&json!({
+        "file": name,
+        "pattern": pattern,
+        "matches": count,
+        "sample_lines": sample_lines,
+        "elapsed_ms": elapsed_ms,
+    }))
 }
 
 /// Busca líneas que coincidan con un patrón
-fn grep_file(path: &str, pattern: &str) -> Result<(usize, Vec<String>), Box<dyn std::error::Error>> {
+fn grep_file(path: impl AsRef<Path>, pattern: &str) -> Result<(usize, Vec<String>), Box<dyn std::error::Error>> {
     use regex::Regex;
     
     let re = Regex::new(pattern)?;
@@ -367,7 +362,7 @@ fn grep_file(path: &str, pattern: &str) -> Result<(usize, Vec<String>), Box<dyn
 /// Comprime un archivo usando gzip.
 /// 
 /// # Query parameters
-/// - `name`: Nombre del archivo en data/ (requerido)
+/// - `name`: Nombre del archivo en el directorio de datos (requerido)
 /// - `codec`: Codec de compresión (solo gzip por ahora)
 /// 
 /// # Ejemplo de response
@@ -393,17 +388,12 @@ pub fn compress_handler(req: &Request) -> Response {
         );
     }
     
-    // Validar nombre
-    if name.contains("..") || name.contains('/') || name.contains('\\') {
-        return Response::error(
-            StatusCode::BadRequest,
-            "Invalid filename"
-        );
-    }
-    
-    let filepath = format!("./data/{}", name);
+    let filepath = match sandbox::resolve(name) {
+        Ok(path) => path,
+        Err(e) => return Response::error(StatusCode::BadRequest, &e),
+    };
     
-    if !Path::new(&filepath).exists() {
+    if !filepath.exists() {
         return Response::error(
             StatusCode::NotFound,
             &format!("File not found: {}", name)
@@ -413,7 +403,10 @@ pub fn compress_handler(req: &Request) -> Response {
     let start = Instant::now();
     
     let output_name = format!("{}.gz", name);
-    let output_path = format!("./data/{}", output_name);
+    let output_path = match sandbox::resolve(&output_name) {
+        Ok(path) => path,
+        Err(e) => return Response::error(StatusCode::BadRequest, &e),
+    };
     
     let (original_size, compressed_size) = match compress_file_gzip(&filepath, &output_path) {
         Ok(sizes) => sizes,
@@ -425,24 +418,27 @@ pub fn compress_handler(req: &Request) -> Response {
         }
     };
     
-    let elapsed_ms = start.elapsed().as_millis();
-    
-    let body = format!(
-        r#"{{"file": "{}", "codec": "gzip", "output": "{}", "original_size": {}, "compressed_size": {}, "ratio": {:.2}, "elapsed_ms": {}}}"#,
-        name, output_name, original_size, compressed_size, 
-        (compressed_size as f64 / original_size as f64), elapsed_ms
-    );
-    
-    Response::json(&body)
+    let elapsed_ms = start.elapsed().as_millis() as u64;
+    let ratio = compressed_size as f64 / original_size as f64;
+    
+    Response::json_value(&json!({
+        "file": name,
+        "codec": "gzip",
+        "output": output_name,
+        "original_size": original_size,
+        "compressed_size": compressed_size,
+        "ratio": (ratio * 100.0).round() / 100.0,
+        "elapsed_ms": elapsed_ms,
+    }))
 }
 
 /// Comprime un archivo con gzip
-fn compress_file_gzip(input: &str, output: &str) -> std::io::Result<(u64, u64)> {
+fn compress_file_gzip(input: impl AsRef<Path>, output: impl AsRef<Path>) -> std::io::Result<(u64, u64)> {
     use flate2::Compression;
     use flate2::write::GzEncoder;
     
     let mut input_file = File::open(input)?;
-    let output_file = File::create(output)?;
+    let output_file = File::create(output.as_ref())?;
     
     let original_size = input_file.metadata()?.len();
     
@@ -450,7 +446,7 @@ fn compress_file_gzip(input: &str, output: &str) -> std::io::Result<(u64, u64)>
     std::io::copy(&mut input_file, &mut encoder)?;
     encoder.finish()?;
     
-    let compressed_size = fs::metadata(output)?.len();
+    let compressed_size = fs::metadata(output.as_ref())?.len();
     
     Ok((original_size, compressed_size))
 }
@@ -460,9 +456,12 @@ fn compress_file_gzip(input: &str, output: &str) -> std::io::Result<(u64, u64)>
 /// Calcula el hash SHA256 de un archivo.
 /// 
 /// # Query parameters
-/// - `name`: Nombre del archivo en data/ (requerido)
+/// - `name`: Nombre del archivo en el directorio de datos (requerido)
 /// - `algo`: Algoritmo (solo sha256 por ahora)
 /// 
+/// La respuesta lleva `ETag` y `Last-Modified` del archivo; con
+/// `If-None-Match` o `If-Modified-Since` vigentes responde 304 sin hashear.
+/// 
 /// # Ejemplo de response
 /// ```json
 /// {"file": "text.txt", "algo": "sha256", "hash": "a3f5...", "size": 1024, "elapsed_ms": 23}
@@ -486,23 +485,28 @@ pub fn hashfile_handler(req: &Request) -> Response {
         );
     }
     
-    // Validar nombre
-    if name.contains("..") || name.contains('/') || name.contains('\\') {
-        return Response::error(
-            StatusCode::BadRequest,
-            "Invalid filename"
-        );
-    }
-    
-    let filepath = format!("./data/{}", name);
+    let filepath = match sandbox::resolve(name) {
+        Ok(path) => path,
+        Err(e) => return Response::error(StatusCode::BadRequest, &e),
+    };
     
-    if !Path::new(&filepath).exists() {
+    if !filepath.exists() {
         return Response::error(
             StatusCode::NotFound,
             &format!("File not found: {}", name)
         );
     }
     
+    // Si el cliente ya tiene el resultado para esta versión no se vuelve a hashear
+    let version = file_version(&filepath);
+    let modified = version.map(|(_, mtime)| mtime);
+    let etag = version.map(|(len, mtime)| Response::file_etag(len, Some(mtime)));
+    if let Some(etag) = &etag {
+        if req.is_not_modified(etag, modified) {
+            return Response::not_modified(etag, modified);
+        }
+    }
+    
     let start = Instant::now();
     
     let (hash, size) = match hash_file_sha256(&filepath) {
@@ -515,18 +519,33 @@ pub fn hashfile_handler(req: &Request) -> Response {
         }
     };
     
-    let elapsed_ms = start.elapsed().as_millis();
+    let elapsed_ms = start.elapsed().as_millis() as u64;
     
-    let body = format!(
-        r#"{{"file": "{}", "algo": "sha256", "hash": "{}", "size": {}, "elapsed_ms": {}}}"#,
-        name, hash, size, elapsed_ms
-    );
+    let response = Response::json_value(&json!({
+        "file": name,
+        "algo": "sha256",
+        "hash": hash,
+        "size": size,
+        "elapsed_ms": elapsed_ms,
+    }));
     
-    Response::json(&body)
+    // Solo se publican los validadores si el archivo no cambió mientras se hasheaba
+    match etag {
+        Some(etag) if file_version(&filepath) == version => {
+            response.with_validators(&etag, modified)
+        }
+        _ => response,
+    }
+}
+
+/// Tamaño y fecha de modificación de un archivo (para ETag/Last-Modified)
+fn file_version(path: &Path) -> Option<(u64, SystemTime)> {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.len(), metadata.modified().ok()?))
 }
 
 /// Calcula el hash SHA256 de un archivo
-fn hash_file_sha256(path: &str) -> std::io::Result<(String, u64)> {
+fn hash_file_sha256(path: impl AsRef<Path>) -> std::io::Result<(String, u64)> {
     use sha2::{Sha256, Digest};
     
     let mut file = File::open(path)?;
@@ -549,6 +568,283 @@ fn hash_file_sha256(path: &str) -> std::io::Result<(String, u64)> {
     Ok((hash_string, size))
 }
 
+/// Máximo de archivos con digest en caché (se vacía al llenarse)
+const DIGEST_CACHE_CAPACITY: usize = 256;
+
+/// Digest SHA-256 (base64) por archivo, válido mientras no cambien
+/// su fecha de modificación ni su tamaño
+type DigestCache = HashMap<PathBuf, (SystemTime, u64, String)>;
+
+fn digest_cache() -> &'static Mutex<DigestCache> {
+    static CACHE: OnceLock<Mutex<DigestCache>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Handler para /download?name=FILE
+/// 
+/// Entrega el contenido del archivo con `Content-Digest: sha-256=:...:`
+/// (RFC 9530) para que el cliente pueda verificar la transferencia.
+/// El digest se guarda en caché por archivo + fecha de modificación, así
+/// que descargas repetidas de un archivo sin cambios no lo recalculan.
+/// 
+/// # Query parameters
+/// - `name`: Nombre del archivo en el directorio de datos (requerido)
+/// 
+/// Archivos mayores a `max_download_bytes` responden 413.
+pub fn download_handler(req: &Request) -> Response {
+    let name = match req.query_param("name") {
+        Some(n) => n,
+        None => {
+            return Response::error(
+                StatusCode::BadRequest,
+                "Missing required parameter: name"
+            );
+        }
+    };
+    
+    let filepath = match sandbox::resolve(name) {
+        Ok(path) => path,
+        Err(e) => return Response::error(StatusCode::BadRequest, &e),
+    };
+    
+    file_response(&filepath, name, crate::commands::limits::current().max_download_bytes, req, None)
+}
+
+/// Arma la respuesta de /download para `path` (ya resuelto en el sandbox)
+///
+/// Sin `content_type` se entrega como adjunto (`application/octet-stream`);
+/// con uno, para mostrar en el navegador (lo usan los archivos estáticos).
+/// Con un header `Range` válido responde 206 con esa parte del archivo. En ese
+/// caso el digest del archivo completo va en `Repr-Digest`, porque
+/// `Content-Digest` describe solo los bytes enviados. Si el cliente ya tiene
+/// la versión actual (`If-None-Match`/`If-Modified-Since`) responde 304 sin
+/// leer el archivo.
+pub(crate) fn file_response(
+    path: &Path,
+    name: &str,
+    max_bytes: u64,
+    req: &Request,
+    content_type: Option<&str>,
+) -> Response {
+    let metadata = match fs::metadata(path) {
+        Ok(m) if m.is_file() => m,
+        Ok(_) => {
+            return Response::error(StatusCode::BadRequest, &format!("Not a file: {}", name));
+        }
+        Err(_) => {
+            return Response::error(StatusCode::NotFound, &format!("File not found: {}", name));
+        }
+    };
+    
+    let modified = metadata.modified().ok();
+    let etag = Response::file_etag(metadata.len(), modified);
+    if req.is_not_modified(&etag, modified) {
+        return Response::not_modified(&etag, modified);
+    }
+    
+    if metadata.len() > max_bytes {
+        return Response::error(
+            StatusCode::PayloadTooLarge,
+            &format!("File is {} bytes; max download is {} bytes", metadata.len(), max_bytes)
+        );
+    }
+    
+    let len = metadata.len();
+    let byte_range = match req.header("Range").map(|r| range::parse(r, len)) {
+        Some(Ok(r)) => r,
+        Some(Err(_)) => return Response::range_not_satisfiable(len),
+        None => None,
+    };
+    
+    let opened = content_digest(path, modified, len).and_then(|digest| {
+        let mut file = File::open(path)?;
+        file.seek(SeekFrom::Start(byte_range.map_or(0, |r| r.start)))?;
+        Ok((digest, file))
+    });
+    let (digest, file) = match opened {
+        Ok(opened) => opened,
+        Err(e) => {
+            return Response::error(
+                StatusCode::InternalServerError,
+                &format!("Failed to read file: {}", e)
+            );
+        }
+    };
+    
+    let filename = Path::new(name).file_name()
+        .map(|f| f.to_string_lossy().replace('"', ""))
+        .unwrap_or_default();
+    
+    // El body se copia del archivo al socket al escribir la respuesta (sendfile)
+    let status = if byte_range.is_some() { StatusCode::PartialContent } else { StatusCode::Ok };
+    let response = match content_type {
+        Some(content_type) => Response::new(status).with_header("Content-Type", content_type),
+        None => Response::new(status)
+            .with_header("Content-Type", "application/octet-stream")
+            .with_header("Content-Disposition", &format!("attachment; filename=\"{}\"", filename)),
+    };
+    let response = response
+        .with_header("Accept-Ranges", "bytes")
+        .with_validators(&etag, modified);
+    match byte_range {
+        Some(byte_range) => response
+            .with_header("Repr-Digest", &format!("sha-256=:{}:", digest))
+            .with_header("Content-Range", &byte_range.content_range(len))
+            .with_file(file, byte_range.size()),
+        None => response
+            .with_header("Content-Digest", &format!("sha-256=:{}:", digest))
+            .with_file(file, len),
+    }
+}
+
+/// Tamaño de los bloques al escribir uploads a disco
+const UPLOAD_CHUNK_BYTES: usize = 64 * 1024;
+
+/// Handler para POST /uploadfile (body `multipart/form-data`)
+///
+/// Guarda en el directorio de datos cada parte que trae `filename`, con el
+/// nombre base del archivo. Las demás partes se retornan como campos de texto.
+///
+/// Cada archivo está acotado por `limits.max_upload_bytes` (413) y se escribe
+/// en bloques a `<name>.part`, que se renombra solo si la escritura terminó.
+///
+/// # Ejemplo de response
+/// ```json
+/// {"files": [{"field": "file", "filename": "a.txt", "size": 12, "content_type": "text/plain"}], "fields": {"note": "hola"}}
+/// ```
+pub fn uploadfile_handler(req: &Request) -> Response {
+    if req.method() != Method::POST {
+        return Response::method_not_allowed("POST");
+    }
+
+    let content_type = req.header("Content-Type").unwrap_or("");
+    let boundary = match multipart::boundary(content_type) {
+        Ok(b) => b,
+        Err(e @ multipart::MultipartError::NotMultipart) => {
+            return Response::error(StatusCode::UnsupportedMediaType, &e.to_string());
+        }
+        Err(e) => return Response::error(StatusCode::BadRequest, &e.to_string()),
+    };
+
+    let parts = match multipart::parse(req.body(), &boundary) {
+        Ok(p) => p,
+        Err(e) => return Response::error(StatusCode::BadRequest, &e.to_string()),
+    };
+
+    // Validar todos los archivos antes de escribir cualquiera
+    let max_upload = crate::commands::limits::current().max_upload_bytes;
+    let mut uploads = Vec::new();
+    let mut fields = serde_json::Map::new();
+    for part in &parts {
+        let Some(filename) = &part.filename else {
+            fields.insert(part.name.clone(), String::from_utf8_lossy(part.data).into());
+            continue;
+        };
+
+        let basename = match Path::new(filename).file_name().and_then(|f| f.to_str()) {
+            Some(b) => b,
+            None => {
+                return Response::error(
+                    StatusCode::BadRequest,
+                    &format!("Invalid filename: {}", filename)
+                );
+            }
+        };
+        if part.data.len() as u64 > max_upload {
+            return Response::error(
+                StatusCode::PayloadTooLarge,
+                &format!("Upload too large: {} bytes (max: {})", part.data.len(), max_upload)
+            );
+        }
+        let filepath = match sandbox::resolve(basename) {
+            Ok(path) => path,
+            Err(e) => return Response::error(StatusCode::BadRequest, &e),
+        };
+        uploads.push((part, basename, filepath));
+    }
+
+    if uploads.is_empty() {
+        return Response::error(StatusCode::BadRequest, "No file parts in multipart body");
+    }
+
+    let mut files = Vec::new();
+    for (part, basename, filepath) in uploads {
+        if let Err(e) = write_upload(&filepath, part.data) {
+            return Response::error(
+                StatusCode::InternalServerError,
+                &format!("Failed to write file: {}", e)
+            );
+        }
+        files.push(serde_json::json!({
+            "field": part.name,
+            "filename": basename,
+            "size": part.data.len(),
+            "content_type": part.content_type,
+        }));
+    }
+
+    let body = serde_json::json!({
+        "files": files,
+        "fields": fields,
+    });
+    Response::json_value(&body)
+}
+
+/// Escribe `data` en bloques a `<path>.part` y lo renombra a `path`
+fn write_upload(path: &Path, data: &[u8]) -> std::io::Result<()> {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".part");
+    let tmp = PathBuf::from(tmp);
+
+    let result = (|| {
+        let mut writer = BufWriter::new(File::create(&tmp)?);
+        for chunk in data.chunks(UPLOAD_CHUNK_BYTES) {
+            writer.write_all(chunk)?;
+        }
+        writer.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+        fs::rename(&tmp, path)
+    })();
+
+    if result.is_err() {
+        fs::remove_file(&tmp).ok();
+    }
+    result
+}
+
+/// Digest SHA-256 en base64 del archivo `path`, usando la caché si no cambió
+///
+/// El archivo se lee en bloques, sin cargarlo en memoria. Sin fecha de
+/// modificación (o si el archivo cambió mientras se leía) se calcula sin
+/// guardar.
+fn content_digest(path: &Path, modified: Option<SystemTime>, len: u64) -> std::io::Result<String> {
+    use sha2::{Digest, Sha256};
+    
+    if let Some(mtime) = modified {
+        let cache = digest_cache().lock().unwrap();
+        if let Some((cached_mtime, cached_len, digest)) = cache.get(path) {
+            if *cached_mtime == mtime && *cached_len == len {
+                return Ok(digest.clone());
+            }
+        }
+    }
+    
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut File::open(path)?, &mut hasher)?;
+    let digest = crate::http::base64::encode(&hasher.finalize());
+    
+    let unchanged = fs::metadata(path).ok()
+        .and_then(|m| m.modified().ok())
+        .is_some_and(|now| Some(now) == modified);
+    if let (Some(mtime), true) = (modified, unchanged) {
+        let mut cache = digest_cache().lock().unwrap();
+        if cache.len() >= DIGEST_CACHE_CAPACITY && !cache.contains_key(path) {
+            cache.clear();
+        }
+        cache.insert(path.to_path_buf(), (mtime, len, digest.clone()));
+    }
+    Ok(digest)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -746,6 +1042,26 @@ mod tests {
         assert_eq!(response.status(), StatusCode::BadRequest);
     }
     
+    #[test]
+    fn test_io_handlers_reject_paths_outside_sandbox() {
+        let handlers = [
+            ("/sortfile", sortfile_handler as fn(&Request) -> Response),
+            ("/wordcount", wordcount_handler),
+            ("/grep", grep_handler),
+            ("/compress", compress_handler),
+            ("/hashfile", hashfile_handler),
+            ("/download", download_handler),
+        ];
+        
+        for (path, handler) in handlers {
+            for name in ["../etc/passwd", "/etc/passwd", "..", "a/../../etc/passwd"] {
+                let request = make_request(&format!("{}?name={}&pattern=root", path, name));
+                let response = handler(&request);
+                assert_eq!(response.status(), StatusCode::BadRequest, "{} name={}", path, name);
+            }
+        }
+    }
+    
     // ==================== WORDCOUNT ====================
     
     #[test]
@@ -835,7 +1151,7 @@ mod tests {
         
         assert_eq!(response.status(), StatusCode::Ok);
         let body = String::from_utf8(response.body().to_vec()).unwrap();
-        assert!(body.contains("\"matches\": 0"));
+        assert!(body.contains("\"matches\":0"));
     }
     
     #[test]
@@ -950,7 +1266,7 @@ mod tests {
         let body = String::from_utf8(response.body().to_vec()).unwrap();
         assert!(body.contains("\"hash\""));
         assert!(body.contains("\"size\""));
-        assert!(body.contains("\"algo\": \"sha256\""));
+        assert!(body.contains("\"algo\":\"sha256\""));
     }
     
     #[test]
@@ -970,4 +1286,202 @@ mod tests {
         
         assert_eq!(response.status(), StatusCode::BadRequest);
     }
-}
\ No newline at end of file
+    
+    // ==================== DOWNLOAD ====================
+    
+    #[test]
+    fn test_download_handler_sends_content_digest() {
+        use sha2::{Digest, Sha256};
+        
+        fs::create_dir_all("./data").unwrap();
+        fs::write("./data/test_download.txt", "Hello digest!").unwrap();
+        
+        let request = make_request("/download?name=test_download.txt");
+        let mut response = download_handler(&request);
+        
+        assert_eq!(response.status(), StatusCode::Ok);
+        assert!(response.is_streaming());
+        assert_eq!(response.headers().get("Content-Length"), Some("13"));
+        response.buffer_body().unwrap();
+        assert_eq!(response.body(), b"Hello digest!");
+        let expected = crate::http::base64::encode(&Sha256::digest(b"Hello digest!"));
+        assert_eq!(response.headers().get("Content-Digest"), Some(format!("sha-256=:{}:", expected).as_str()));
+        assert_eq!(
+            response.headers().get("Content-Disposition"),
+            Some("attachment; filename=\"test_download.txt\"")
+        );
+        
+        fs::remove_file("./data/test_download.txt").ok();
+    }
+    
+    #[test]
+    fn test_download_digest_cache_follows_file_changes() {
+        use sha2::{Digest, Sha256};
+        
+        let plain = make_request("/download");
+        let dir = std::env::temp_dir().join(format!("download-digest-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("data.bin");
+        
+        fs::write(&path, "first").unwrap();
+        let first = file_response(&path, "data.bin", 1024, &plain, None);
+        let again = file_response(&path, "data.bin", 1024, &plain, None);
+        assert_eq!(first.headers().get("Content-Digest"), again.headers().get("Content-Digest"));
+        
+        // Otro tamaño invalida la entrada aunque la fecha no cambie
+        fs::write(&path, "second version").unwrap();
+        let changed = file_response(&path, "data.bin", 1024, &plain, None);
+        let expected = crate::http::base64::encode(&Sha256::digest(b"second version"));
+        assert_eq!(changed.headers().get("Content-Digest"), Some(format!("sha-256=:{}:", expected).as_str()));
+        
+        assert_eq!(file_response(&path, "data.bin", 4, &plain, None).status(), StatusCode::PayloadTooLarge);
+        assert_eq!(file_response(&dir, "dir", 1024, &plain, None).status(), StatusCode::BadRequest);
+        assert_eq!(file_response(&dir.join("missing"), "missing", 1024, &plain, None).status(), StatusCode::NotFound);
+        
+        let _ = fs::remove_dir_all(&dir);
+    }
+    
+    #[test]
+    fn test_download_file_ranges() {
+        let dir = std::env::temp_dir().join(format!("download-range-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("data.bin");
+        fs::write(&path, "0123456789").unwrap();
+        let ranged = |range: &str| {
+            let raw = format!("GET /download HTTP/1.0\r\nrange: {}\r\n\r\n", range);
+            Request::parse(raw.as_bytes()).unwrap()
+        };
+        let plain = make_request("/download");
+        
+        let body = |mut response: Response| {
+            response.buffer_body().unwrap();
+            response.body().to_vec()
+        };
+        
+        let partial = file_response(&path, "data.bin", 1024, &ranged("bytes=2-5"), None);
+        assert_eq!(partial.status(), StatusCode::PartialContent);
+        assert_eq!(partial.headers().get("Content-Length"), Some("4"));
+        assert_eq!(body(partial.clone()), b"2345");
+        assert_eq!(partial.headers().get("Content-Range"), Some("bytes 2-5/10"));
+        assert_eq!(partial.headers().get("Accept-Ranges"), Some("bytes"));
+        assert!(partial.headers().get("Content-Digest").is_none());
+        
+        // Repr-Digest es el del archivo completo
+        let full = file_response(&path, "data.bin", 1024, &plain, None);
+        assert_eq!(full.status(), StatusCode::Ok);
+        assert_eq!(full.headers().get("Content-Digest"), partial.headers().get("Repr-Digest"));
+        
+        let suffix = file_response(&path, "data.bin", 1024, &ranged("bytes=-3"), None);
+        assert_eq!(body(suffix), b"789");
+        
+        // Un Range que no se entiende se ignora
+        let ignored = file_response(&path, "data.bin", 1024, &ranged("lines=1-2"), None);
+        assert_eq!(ignored.status(), StatusCode::Ok);
+        assert_eq!(body(ignored), b"0123456789");
+        
+        let unsatisfiable = file_response(&path, "data.bin", 1024, &ranged("bytes=10-"), None);
+        assert_eq!(unsatisfiable.status(), StatusCode::RangeNotSatisfiable);
+        assert_eq!(unsatisfiable.headers().get("Content-Range"), Some("bytes */10"));
+        
+        let _ = fs::remove_dir_all(&dir);
+    }
+    
+    #[test]
+    fn test_conditional_hashfile_and_download() {
+        fs::create_dir_all("./data").ok();
+        fs::write("./data/test_conditional.txt", "conditional").unwrap();
+        let conditional = |path: &str, header: &str| {
+            let raw = format!("GET {} HTTP/1.0\r\n{}\r\n\r\n", path, header);
+            Request::parse(raw.as_bytes()).unwrap()
+        };
+        
+        for (path, handler) in [
+            ("/hashfile?name=test_conditional.txt", hashfile_handler as fn(&Request) -> Response),
+            ("/download?name=test_conditional.txt", download_handler),
+        ] {
+            let first = handler(&make_request(path));
+            assert_eq!(first.status(), StatusCode::Ok);
+            let etag = first.headers().get("ETag").unwrap().to_string();
+            let last_modified = first.headers().get("Last-Modified").unwrap().to_string();
+            
+            let cached = handler(&conditional(path, &format!("If-None-Match: {}", etag)));
+            assert_eq!(cached.status(), StatusCode::NotModified, "{}", path);
+            assert!(cached.body().is_empty());
+            assert_eq!(cached.headers().get("ETag"), Some(etag.as_str()));
+            
+            let cached = handler(&conditional(path, &format!("If-Modified-Since: {}", last_modified)));
+            assert_eq!(cached.status(), StatusCode::NotModified, "{}", path);
+            
+            let stale = handler(&conditional(path, "If-None-Match: \"other\""));
+            assert_eq!(stale.status(), StatusCode::Ok, "{}", path);
+        }
+        
+        fs::remove_file("./data/test_conditional.txt").ok();
+    }
+    
+    fn multipart_request(method: &str, content_type: &str, body: &str) -> Request {
+        let raw = format!(
+            "{} /uploadfile HTTP/1.0\r\ncontent-type: {}\r\nContent-Length: {}\r\n\r\n{}",
+            method, content_type, body.len(), body
+        );
+        Request::parse(raw.as_bytes()).unwrap()
+    }
+    
+    #[test]
+    fn test_uploadfile_handler_writes_files() {
+        fs::create_dir_all("./data").ok();
+        let body = "--b1\r\n\
+            Content-Disposition: form-data; name=\"note\"\r\n\r\nhola\r\n--b1\r\n\
+            Content-Disposition: form-data; name=\"file\"; filename=\"../../test_upload.txt\"\r\n\
+            Content-Type: text/plain\r\n\r\nuploaded\r\ndata\r\n--b1--\r\n";
+        
+        let response = uploadfile_handler(&multipart_request("POST", "multipart/form-data; boundary=b1", body));
+        assert_eq!(response.status(), StatusCode::Ok);
+        
+        let json: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(json["fields"]["note"], "hola");
+        assert_eq!(json["files"][0]["filename"], "test_upload.txt");
+        assert_eq!(json["files"][0]["size"], 14);
+        assert_eq!(json["files"][0]["content_type"], "text/plain");
+        
+        // Solo se usa el nombre base, dentro del directorio de datos
+        assert_eq!(fs::read_to_string("./data/test_upload.txt").unwrap(), "uploaded\r\ndata");
+        assert!(!Path::new("./data/test_upload.txt.part").exists());
+        fs::remove_file("./data/test_upload.txt").ok();
+    }
+    
+    #[test]
+    fn test_uploadfile_handler_rejects_invalid_requests() {
+        let file = "--b1\r\nContent-Disposition: form-data; name=\"f\"; filename=\"x.txt\"\r\n\r\nx\r\n--b1--";
+        let field = "--b1\r\nContent-Disposition: form-data; name=\"f\"\r\n\r\nx\r\n--b1--";
+        let cases = [
+            ("GET", "multipart/form-data; boundary=b1", file, StatusCode::MethodNotAllowed),
+            ("POST", "application/json", file, StatusCode::UnsupportedMediaType),
+            ("POST", "multipart/form-data", file, StatusCode::BadRequest),
+            ("POST", "multipart/form-data; boundary=b1", "garbage", StatusCode::BadRequest),
+            ("POST", "multipart/form-data; boundary=b1", field, StatusCode::BadRequest),
+        ];
+        
+        for (method, content_type, body, status) in cases {
+            let response = uploadfile_handler(&multipart_request(method, content_type, body));
+            assert_eq!(response.status(), status, "{} {} {}", method, content_type, body);
+        }
+    }
+    
+    #[test]
+    fn test_write_upload_in_chunks() {
+        let dir = std::env::temp_dir().join(format!("upload-chunks-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("big.bin");
+        
+        let data: Vec<u8> = (0..UPLOAD_CHUNK_BYTES * 2 + 7).map(|i| i as u8).collect();
+        write_upload(&path, &data).unwrap();
+        assert_eq!(fs::read(&path).unwrap(), data);
+        
+        // Si falla no quedan archivos a medias
+        assert!(write_upload(&dir.join("missing/big.bin"), &data).is_err());
+        assert!(!dir.join("missing/big.bin.part").exists());
+        
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
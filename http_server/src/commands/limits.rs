@@ -0,0 +1,74 @@
+//! # Límites de Comandos
+//! src/commands/limits.rs
+//!
+//! Almacena los `CommandLimits` vigentes para que los handlers (que son
+//! funciones sin estado) puedan consultarlos. El servidor los fija al
+//! iniciar y los actualiza al recargar la configuración.
+
+use crate::config::CommandLimits;
+use std::sync::RwLock;
+
+/// Contenedor thread-safe de límites
+pub struct LimitsStore {
+    inner: RwLock<CommandLimits>,
+}
+
+impl LimitsStore {
+    /// Crea un contenedor con los límites por defecto
+    pub const fn new() -> Self {
+        Self {
+            inner: RwLock::new(CommandLimits::DEFAULT),
+        }
+    }
+
+    /// Obtiene una copia de los límites actuales
+    pub fn get(&self) -> CommandLimits {
+        self.inner.read().unwrap().clone()
+    }
+
+    /// Reemplaza los límites actuales
+    pub fn set(&self, limits: CommandLimits) {
+        *self.inner.write().unwrap() = limits;
+    }
+}
+
+impl Default for LimitsStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Límites globales usados por los handlers
+static LIMITS: LimitsStore = LimitsStore::new();
+
+/// Límites vigentes para los handlers
+pub fn current() -> CommandLimits {
+    LIMITS.get()
+}
+
+/// Fija los límites globales (al iniciar o recargar la configuración)
+pub fn set(limits: CommandLimits) {
+    LIMITS.set(limits);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_store_defaults() {
+        let store = LimitsStore::new();
+        assert_eq!(store.get(), CommandLimits::default());
+    }
+
+    #[test]
+    fn test_store_set() {
+        let store = LimitsStore::new();
+        let limits = CommandLimits {
+            sleep_max_secs: 42,
+            ..CommandLimits::default()
+        };
+        store.set(limits.clone());
+        assert_eq!(store.get(), limits);
+    }
+}
@@ -9,15 +9,25 @@
 //! - **basic**: Comandos básicos (fibonacci, reverse, toupper, etc.)
 //! - **cpu_bound**: Comandos intensivos en CPU (isprime, factor, pi, etc.)
 //! - **io_bound**: Comandos intensivos en I/O (sortfile, compress, etc.)
+//! - **network**: Comandos de red saliente (fetch)
+//! - **factory**: Simulación de la fábrica de Tarea-2 (factory)
+//! - **limits**: Límites configurables de carga de cada comando
+//! - **sandbox**: Resolución segura de rutas dentro de `data_dir`
 //!
 //! Cada comando es una función handler que recibe un Request
 //! y retorna una Response.
 
 pub mod basic;
 pub mod cpu_bound;
+pub mod factory;
 pub mod io_bound;
+pub mod limits;
+pub mod network;
+pub mod sandbox;
 
 // Re-exportar funciones útiles
 pub use basic::*;
 pub use cpu_bound::*;
-pub use io_bound::*;
\ No newline at end of file
+pub use factory::*;
+pub use io_bound::*;
+pub use network::*;
\ No newline at end of file
@@ -0,0 +1,514 @@
+//! # Comandos de Red
+//! src/commands/network.rs
+//!
+//! Comandos que dependen de la red saliente, no del disco:
+//! - /fetch: Descargar una URL de un host permitido al directorio de datos
+//!
+//! Usa un cliente HTTP/1.0 mínimo sobre `TcpStream` (sin TLS ni redirects).
+//! Solo se conecta a los hosts de `--fetch-allow`; sin allowlist el comando
+//! está deshabilitado, así el servidor no puede usarse para llegar a
+//! servicios internos arbitrarios.
+
+use crate::commands::{limits, sandbox};
+use crate::http::{Request, Response, StatusCode};
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// Máximo de bytes de status line + headers de la respuesta remota
+const MAX_RESPONSE_HEAD_BYTES: usize = 16 * 1024;
+
+/// URL `http://` ya separada en partes
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Target {
+    host: String,
+    port: u16,
+    /// Path con query (ej: `/data.csv?v=2`)
+    path: String,
+}
+
+impl Target {
+    /// Valor del header `Host`
+    fn host_header(&self) -> String {
+        if self.port == 80 {
+            self.host.clone()
+        } else {
+            format!("{}:{}", self.host, self.port)
+        }
+    }
+
+    /// Último segmento del path, para nombrar el archivo por defecto
+    fn file_name(&self) -> &str {
+        let path = self.path.split(['?', '#']).next().unwrap_or("");
+        path.rsplit('/').find(|s| !s.is_empty()).unwrap_or("index.html")
+    }
+}
+
+/// Parsea `http://host[:port][/path]`
+fn parse_url(url: &str) -> Result<Target, String> {
+    let rest = match url.split_once("://") {
+        Some((scheme, rest)) if scheme.eq_ignore_ascii_case("http") => rest,
+        Some((scheme, _)) => return Err(format!("Unsupported scheme: {} (only http)", scheme)),
+        None => return Err(format!("Invalid URL: {}", url)),
+    };
+
+    let (authority, path) = match rest.find(['/', '?']) {
+        Some(i) if rest[i..].starts_with('/') => (&rest[..i], rest[i..].to_string()),
+        Some(i) => (&rest[..i], format!("/{}", &rest[i..])),
+        None => (rest, "/".to_string()),
+    };
+    let path = path.split('#').next().unwrap_or("/").to_string();
+
+    if authority.contains('@') {
+        return Err("Credentials in URL are not supported".to_string());
+    }
+    let (host, port) = match authority.rsplit_once(':') {
+        Some((host, port)) => {
+            let port = port.parse::<u16>().ok().filter(|p| *p > 0)
+                .ok_or_else(|| format!("Invalid port: {}", port))?;
+            (host, port)
+        }
+        None => (authority, 80),
+    };
+    if host.is_empty() || host.contains([' ', '[', ']']) {
+        return Err(format!("Invalid host: {}", host));
+    }
+
+    Ok(Target { host: host.to_ascii_lowercase(), port, path })
+}
+
+/// Host permitido por `--fetch-allow`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AllowRule {
+    /// Host en minúsculas; `*.dominio` permite los subdominios
+    host: String,
+    /// Puerto exacto (None = cualquiera)
+    port: Option<u16>,
+}
+
+impl AllowRule {
+    fn matches(&self, target: &Target) -> bool {
+        if self.port.is_some_and(|port| port != target.port) {
+            return false;
+        }
+        match self.host.strip_prefix("*.") {
+            Some(domain) => target.host.strip_suffix(domain).is_some_and(|sub| sub.ends_with('.')),
+            None => self.host == target.host,
+        }
+    }
+}
+
+/// Parsea la allowlist: `host`, `host:puerto` o `*.dominio`, separados por comas
+pub fn parse_allowlist(spec: &str) -> Result<Vec<AllowRule>, String> {
+    spec.split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let (host, port) = match entry.rsplit_once(':') {
+                Some((host, port)) => {
+                    let port = port.parse::<u16>().ok().filter(|p| *p > 0)
+                        .ok_or_else(|| format!("Invalid fetch allow entry: {}", entry))?;
+                    (host, Some(port))
+                }
+                None => (entry, None),
+            };
+            let name = host.strip_prefix("*.").unwrap_or(host);
+            if name.is_empty() || name.contains(['*', '/', ' ']) {
+                return Err(format!("Invalid fetch allow entry: {}", entry));
+            }
+            Ok(AllowRule { host: host.to_ascii_lowercase(), port })
+        })
+        .collect()
+}
+
+/// Resultado de una descarga
+#[derive(Debug)]
+struct Fetched {
+    status: u16,
+    bytes: u64,
+    content_type: Option<String>,
+    connect_ms: u128,
+}
+
+/// Errores de una descarga
+#[derive(Debug)]
+enum FetchError {
+    /// El host remoto no respondió a tiempo
+    Timeout(String),
+    /// Conexión fallida o respuesta inválida/no exitosa
+    Upstream(String),
+    /// El body remoto supera el máximo permitido
+    TooLarge(u64),
+    /// Error escribiendo el archivo local
+    Local(io::Error),
+}
+
+impl FetchError {
+    fn from_network(e: io::Error, timeout: Duration) -> Self {
+        match e.kind() {
+            io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock => {
+                FetchError::Timeout(format!("Upstream did not respond within {:?}", timeout))
+            }
+            _ => FetchError::Upstream(e.to_string()),
+        }
+    }
+
+    fn into_response(self) -> Response {
+        match self {
+            FetchError::Timeout(message) => Response::error(StatusCode::GatewayTimeout, &message),
+            FetchError::Upstream(message) => Response::error(StatusCode::BadGateway, &message),
+            FetchError::TooLarge(max) => Response::error(
+                StatusCode::BadGateway,
+                &format!("Upstream body exceeds max fetch size ({} bytes)", max),
+            ),
+            FetchError::Local(e) => Response::error(
+                StatusCode::InternalServerError,
+                &format!("Failed to write file: {}", e),
+            ),
+        }
+    }
+}
+
+/// Handler para /fetch?url=URL&name=FILE
+///
+/// Descarga `url` (solo `http://`, host en `--fetch-allow`) y guarda el
+/// body en `name` dentro del directorio de datos. La URL debe ir
+/// codificada (`%3F`, `%26`...) si tiene query propia.
+///
+/// # Query parameters
+/// - `url`: URL a descargar (requerido)
+/// - `name`: Archivo destino (default: último segmento del path)
+///
+/// # Ejemplo de response
+/// ```json
+/// {"url": "http://example.com/data.csv", "file": "data.csv", "status": 200,
+///  "bytes": 1024, "content_type": "text/csv", "connect_ms": 3, "elapsed_ms": 41}
+/// ```
+pub fn fetch_handler(req: &Request) -> Response {
+    let url = match req.query_param("url") {
+        Some(u) => u,
+        None => {
+            return Response::error(
+                StatusCode::BadRequest,
+                "Missing required parameter: url"
+            );
+        }
+    };
+
+    let target = match parse_url(url) {
+        Ok(target) => target,
+        Err(e) => return Response::error(StatusCode::BadRequest, &e),
+    };
+
+    let limits = limits::current();
+    let allowlist = match parse_allowlist(&limits.fetch_allow) {
+        Ok(rules) => rules,
+        Err(e) => return Response::error(StatusCode::InternalServerError, &e),
+    };
+    if allowlist.is_empty() {
+        return Response::error(StatusCode::Forbidden, "Fetch is disabled (no --fetch-allow hosts)");
+    }
+    if !allowlist.iter().any(|rule| rule.matches(&target)) {
+        return Response::error(
+            StatusCode::Forbidden,
+            &format!("Host not allowed: {}", target.host_header())
+        );
+    }
+
+    let name = req.query_param("name").unwrap_or_else(|| target.file_name());
+    let filepath = match sandbox::resolve(name) {
+        Ok(path) => path,
+        Err(e) => return Response::error(StatusCode::BadRequest, &e),
+    };
+
+    let start = Instant::now();
+    let timeout = Duration::from_millis(limits.fetch_timeout_ms);
+    let fetched = match fetch_to_file(&target, timeout, limits.max_fetch_bytes, &filepath) {
+        Ok(fetched) => fetched,
+        Err(e) => return e.into_response(),
+    };
+
+    let body = serde_json::json!({
+        "url": url,
+        "file": name,
+        "status": fetched.status,
+        "bytes": fetched.bytes,
+        "content_type": fetched.content_type,
+        "connect_ms": fetched.connect_ms,
+        "elapsed_ms": start.elapsed().as_millis(),
+    });
+    Response::json_value(&body)
+}
+
+/// Descarga `target` a `path` pasando por un `.part`
+///
+/// El archivo final solo se reemplaza si la descarga terminó completa.
+fn fetch_to_file(target: &Target, timeout: Duration, max_bytes: u64, path: &Path) -> Result<Fetched, FetchError> {
+    let mut part = path.as_os_str().to_owned();
+    part.push(".part");
+    let part = Path::new(&part);
+
+    let result = File::create(part)
+        .map_err(FetchError::Local)
+        .and_then(|file| {
+            let mut out = BufWriter::new(file);
+            let fetched = fetch(target, timeout, max_bytes, &mut out)?;
+            out.flush().map_err(FetchError::Local)?;
+            Ok(fetched)
+        })
+        .and_then(|fetched| fs::rename(part, path).map(|_| fetched).map_err(FetchError::Local));
+
+    if result.is_err() {
+        let _ = fs::remove_file(part);
+    }
+    result
+}
+
+/// Hace `GET` a `target` y escribe el body en `out`
+///
+/// `timeout` se aplica a la conexión y al total de la descarga. Solo
+/// acepta respuestas 2xx; no sigue redirects.
+fn fetch(target: &Target, timeout: Duration, max_bytes: u64, out: &mut impl Write) -> Result<Fetched, FetchError> {
+    let start = Instant::now();
+    let addrs = (target.host.as_str(), target.port).to_socket_addrs()
+        .map_err(|e| FetchError::Upstream(format!("Cannot resolve {}: {}", target.host, e)))?;
+
+    let mut last_error = None;
+    let mut stream = None;
+    for addr in addrs {
+        match TcpStream::connect_timeout(&addr, timeout) {
+            Ok(s) => {
+                stream = Some(s);
+                break;
+            }
+            Err(e) => last_error = Some(e),
+        }
+    }
+    let mut stream = match (stream, last_error) {
+        (Some(stream), _) => stream,
+        (None, Some(e)) => return Err(FetchError::from_network(e, timeout)),
+        (None, None) => return Err(FetchError::Upstream(format!("{} resolves to nothing", target.host))),
+    };
+    let connect_ms = start.elapsed().as_millis();
+
+    let network = |e| FetchError::from_network(e, timeout);
+    stream.set_read_timeout(Some(timeout)).map_err(network)?;
+    stream.set_write_timeout(Some(timeout)).map_err(network)?;
+    let request = format!(
+        "GET {} HTTP/1.0\r\nHost: {}\r\nUser-Agent: RedUnix-HTTP/1.0\r\nAccept-Encoding: identity\r\n\r\n",
+        target.path, target.host_header()
+    );
+    stream.write_all(request.as_bytes()).map_err(network)?;
+
+    // Status line y headers
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 8192];
+    let head_end = loop {
+        if let Some(i) = buffer.windows(4).position(|w| w == b"\r\n\r\n") {
+            break i + 4;
+        }
+        if buffer.len() > MAX_RESPONSE_HEAD_BYTES {
+            return Err(FetchError::Upstream("Upstream response headers too large".to_string()));
+        }
+        let n = stream.read(&mut chunk).map_err(network)?;
+        if n == 0 {
+            return Err(FetchError::Upstream("Upstream closed before sending headers".to_string()));
+        }
+        buffer.extend_from_slice(&chunk[..n]);
+    };
+
+    let head = String::from_utf8_lossy(&buffer[..head_end]).to_string();
+    let mut lines = head.split("\r\n");
+    let status = lines.next()
+        .filter(|line| line.starts_with("HTTP/1."))
+        .and_then(|line| line.split_whitespace().nth(1))
+        .and_then(|code| code.parse::<u16>().ok())
+        .ok_or_else(|| FetchError::Upstream("Invalid upstream status line".to_string()))?;
+    let header = |name: &str| {
+        head.split("\r\n").skip(1)
+            .filter_map(|line| line.split_once(':'))
+            .find(|(key, _)| key.trim().eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.trim().to_string())
+    };
+
+    if !(200..300).contains(&status) {
+        let location = header("location").map(|l| format!(" (Location: {})", l)).unwrap_or_default();
+        return Err(FetchError::Upstream(format!("Upstream responded {}{}", status, location)));
+    }
+    if header("transfer-encoding").is_some_and(|te| !te.eq_ignore_ascii_case("identity")) {
+        return Err(FetchError::Upstream("Upstream used an unsupported Transfer-Encoding".to_string()));
+    }
+    let content_length = match header("content-length") {
+        Some(value) => Some(value.parse::<u64>()
+            .map_err(|_| FetchError::Upstream(format!("Invalid upstream Content-Length: {}", value)))?),
+        None => None,
+    };
+    if content_length.is_some_and(|len| len > max_bytes) {
+        return Err(FetchError::TooLarge(max_bytes));
+    }
+
+    // Body: hasta Content-Length o hasta que el servidor cierre
+    let expected = content_length.unwrap_or(u64::MAX);
+    let mut bytes = 0u64;
+    let mut pending = buffer.split_off(head_end);
+    loop {
+        let take = pending.len().min((expected - bytes).min(usize::MAX as u64) as usize);
+        if bytes + take as u64 > max_bytes {
+            return Err(FetchError::TooLarge(max_bytes));
+        }
+        out.write_all(&pending[..take]).map_err(FetchError::Local)?;
+        bytes += take as u64;
+
+        if bytes == expected {
+            break;
+        }
+        if start.elapsed() > timeout {
+            return Err(FetchError::Timeout(format!("Download did not finish within {:?}", timeout)));
+        }
+        let n = stream.read(&mut chunk).map_err(network)?;
+        if n == 0 {
+            if content_length.is_some() {
+                return Err(FetchError::Upstream(format!(
+                    "Upstream body truncated: {} of {} bytes", bytes, expected
+                )));
+            }
+            break;
+        }
+        pending = chunk[..n].to_vec();
+    }
+
+    Ok(Fetched { status, bytes, content_type: header("content-type"), connect_ms })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+    use std::thread;
+
+    /// Servidor que responde `response` a una conexión y retorna el request recibido
+    fn serve_once(response: Vec<u8>) -> (u16, thread::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let handle = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buf = [0u8; 1024];
+            let n = stream.read(&mut buf).unwrap();
+            stream.write_all(&response).unwrap();
+            String::from_utf8_lossy(&buf[..n]).to_string()
+        });
+        (port, handle)
+    }
+
+    fn target(port: u16, path: &str) -> Target {
+        Target { host: "127.0.0.1".to_string(), port, path: path.to_string() }
+    }
+
+    #[test]
+    fn test_parse_url() {
+        assert_eq!(
+            parse_url("http://Example.com:8080/a/b.csv?v=2#top").unwrap(),
+            Target { host: "example.com".to_string(), port: 8080, path: "/a/b.csv?v=2".to_string() }
+        );
+        assert_eq!(parse_url("http://example.com").unwrap().path, "/");
+        assert_eq!(parse_url("http://example.com?x=1").unwrap().path, "/?x=1");
+        assert_eq!(parse_url("http://example.com/data/").unwrap().file_name(), "data");
+        assert_eq!(parse_url("http://example.com/").unwrap().file_name(), "index.html");
+
+        assert!(parse_url("https://example.com/").is_err());
+        assert!(parse_url("example.com/").is_err());
+        assert!(parse_url("http://user:pw@example.com/").is_err());
+        assert!(parse_url("http://example.com:99999/").is_err());
+        assert!(parse_url("http:///path").is_err());
+    }
+
+    #[test]
+    fn test_allowlist_matching() {
+        let rules = parse_allowlist("example.com, *.data.org, 127.0.0.1:9000").unwrap();
+        let allowed = |url: &str| rules.iter().any(|r| r.matches(&parse_url(url).unwrap()));
+
+        assert!(allowed("http://example.com/x"));
+        assert!(allowed("http://EXAMPLE.com:81/x"));
+        assert!(allowed("http://cdn.data.org/x"));
+        assert!(allowed("http://127.0.0.1:9000/"));
+        assert!(!allowed("http://data.org/x"));
+        assert!(!allowed("http://evildata.org/x"));
+        assert!(!allowed("http://example.com.evil.net/x"));
+        assert!(!allowed("http://127.0.0.1:9001/"));
+
+        assert!(parse_allowlist("").unwrap().is_empty());
+        assert!(parse_allowlist("host:0").is_err());
+        assert!(parse_allowlist("*").is_err());
+        assert!(parse_allowlist("a/b").is_err());
+    }
+
+    #[test]
+    fn test_fetch_writes_body() {
+        let (port, server) = serve_once(
+            b"HTTP/1.0 200 OK\r\nContent-Type: text/plain\r\nContent-Length: 5\r\n\r\nhello".to_vec()
+        );
+        let mut out = Vec::new();
+        let fetched = fetch(&target(port, "/greeting.txt"), Duration::from_secs(5), 1024, &mut out).unwrap();
+
+        assert_eq!(out, b"hello");
+        assert_eq!(fetched.status, 200);
+        assert_eq!(fetched.bytes, 5);
+        assert_eq!(fetched.content_type.as_deref(), Some("text/plain"));
+        let request = server.join().unwrap();
+        assert!(request.starts_with("GET /greeting.txt HTTP/1.0\r\n"));
+        assert!(request.contains(&format!("Host: 127.0.0.1:{}\r\n", port)));
+    }
+
+    #[test]
+    fn test_fetch_rejects_bad_responses() {
+        let timeout = Duration::from_secs(5);
+
+        // Sin Content-Length se lee hasta el cierre, con el mismo límite
+        let (port, _) = serve_once(b"HTTP/1.0 200 OK\r\n\r\n0123456789".to_vec());
+        let result = fetch(&target(port, "/"), timeout, 4, &mut Vec::new());
+        assert!(matches!(result, Err(FetchError::TooLarge(4))));
+
+        let (port, _) = serve_once(b"HTTP/1.0 200 OK\r\nContent-Length: 100\r\n\r\n".to_vec());
+        let result = fetch(&target(port, "/"), timeout, 4, &mut Vec::new());
+        assert!(matches!(result, Err(FetchError::TooLarge(4))));
+
+        let (port, _) = serve_once(b"HTTP/1.0 200 OK\r\nContent-Length: 10\r\n\r\nshort".to_vec());
+        let result = fetch(&target(port, "/"), timeout, 1024, &mut Vec::new());
+        assert!(matches!(result, Err(FetchError::Upstream(m)) if m.contains("truncated")));
+
+        let (port, _) = serve_once(b"HTTP/1.0 302 Found\r\nLocation: /other\r\n\r\n".to_vec());
+        let result = fetch(&target(port, "/"), timeout, 1024, &mut Vec::new());
+        assert!(matches!(result, Err(FetchError::Upstream(m)) if m.contains("302") && m.contains("/other")));
+    }
+
+    #[test]
+    fn test_fetch_to_file_keeps_previous_file_on_error() {
+        let dir = std::env::temp_dir().join(format!("fetch-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("out.txt");
+        fs::write(&path, "previous").unwrap();
+
+        let (port, _) = serve_once(b"HTTP/1.0 200 OK\r\nContent-Length: 10\r\n\r\nshort".to_vec());
+        assert!(fetch_to_file(&target(port, "/"), Duration::from_secs(5), 1024, &path).is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "previous");
+        assert!(!dir.join("out.txt.part").exists());
+
+        let (port, _) = serve_once(b"HTTP/1.0 200 OK\r\nContent-Length: 3\r\n\r\nnew".to_vec());
+        fetch_to_file(&target(port, "/"), Duration::from_secs(5), 1024, &path).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_fetch_handler_requires_allowlist() {
+        let request = Request::parse(b"GET /fetch?url=http://127.0.0.1:1/x HTTP/1.0\r\n\r\n").unwrap();
+        // Límites por defecto: sin allowlist
+        assert_eq!(fetch_handler(&request).status(), StatusCode::Forbidden);
+
+        let request = Request::parse(b"GET /fetch HTTP/1.0\r\n\r\n").unwrap();
+        assert_eq!(fetch_handler(&request).status(), StatusCode::BadRequest);
+    }
+}
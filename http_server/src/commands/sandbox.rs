@@ -0,0 +1,208 @@
+//! # Sandbox del Directorio de Datos
+//! src/commands/sandbox.rs
+//!
+//! Todos los comandos de archivos resuelven sus rutas dentro de
+//! `config.data_dir`. Las rutas se canonicalizan (resolviendo `..` y
+//! symlinks) y se verifica que el resultado siga dentro del sandbox,
+//! así que ni `../etc/passwd` ni un symlink hacia afuera son accesibles.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::RwLock;
+
+/// Directorio de datos por defecto (igual al default de `Config`)
+const DEFAULT_DATA_DIR: &str = "./data";
+
+/// Raíz canonicalizada del sandbox (None = aún no inicializada)
+static ROOT: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+/// Contador para nombres únicos de archivos de prueba de escritura
+static PROBE_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Crea y valida el directorio de datos, y lo fija como raíz del sandbox
+///
+/// Se llama al iniciar el servidor; retorna la ruta canonicalizada.
+pub fn init(data_dir: &str) -> Result<PathBuf, String> {
+    let root = prepare_root(data_dir)?;
+    *ROOT.write().unwrap() = Some(root.clone());
+    Ok(root)
+}
+
+/// Raíz actual del sandbox
+///
+/// Si no se llamó a `init`, usa `./data` (útil en tests de handlers).
+pub fn root() -> Result<PathBuf, String> {
+    if let Some(root) = ROOT.read().unwrap().as_ref() {
+        return Ok(root.clone());
+    }
+    init(DEFAULT_DATA_DIR)
+}
+
+/// Resuelve un nombre de archivo dentro del sandbox actual
+pub fn resolve(name: &str) -> Result<PathBuf, String> {
+    resolve_in(&root()?, name)
+}
+
+/// Crea el directorio si no existe y verifica que sea un directorio escribible
+fn prepare_root(data_dir: &str) -> Result<PathBuf, String> {
+    if data_dir.trim().is_empty() {
+        return Err("Data directory cannot be empty".to_string());
+    }
+
+    fs::create_dir_all(data_dir)
+        .map_err(|e| format!("Cannot create data directory {}: {}", data_dir, e))?;
+
+    let root = fs::canonicalize(data_dir)
+        .map_err(|e| format!("Cannot resolve data directory {}: {}", data_dir, e))?;
+
+    let metadata = fs::metadata(&root)
+        .map_err(|e| format!("Cannot access data directory {}: {}", data_dir, e))?;
+    if !metadata.is_dir() {
+        return Err(format!("Data directory {} is not a directory", data_dir));
+    }
+    check_writable(&root)?;
+
+    Ok(root)
+}
+
+/// Verifica que se pueda escribir en `dir` creando y borrando un archivo
+///
+/// Los bits de permisos no alcanzan (root, directorios de otro usuario,
+/// ACLs), así que se prueba una escritura real.
+pub fn check_writable(dir: &Path) -> Result<(), String> {
+    let probe = dir.join(format!(
+        ".write_probe_{}_{}",
+        std::process::id(),
+        PROBE_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+
+    fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&probe)
+        .map_err(|e| format!("Data directory {} is not writable: {}", dir.display(), e))?;
+    fs::remove_file(&probe)
+        .map_err(|e| format!("Data directory {} is not writable: {}", dir.display(), e))?;
+
+    Ok(())
+}
+
+/// Resuelve `name` relativo a `root` y verifica que no escape del sandbox
+///
+/// El archivo puede no existir todavía (ej: `/createfile`); en ese caso se
+/// canonicaliza el directorio padre. Un symlink roto se rechaza: escribir
+/// en él seguiría el enlace hacia afuera del sandbox.
+pub fn resolve_in(root: &Path, name: &str) -> Result<PathBuf, String> {
+    if name.is_empty() || name.contains('\0') || name.contains('\\') {
+        return Err("Invalid filename".to_string());
+    }
+    if Path::new(name).is_absolute() {
+        return Err("Invalid filename: absolute paths are not allowed".to_string());
+    }
+
+    let joined = root.join(name);
+    let resolved = match fs::canonicalize(&joined) {
+        Ok(path) => path,
+        Err(_) => {
+            if fs::symlink_metadata(&joined).is_ok_and(|m| m.file_type().is_symlink()) {
+                return Err("Invalid filename: dangling symlink".to_string());
+            }
+            let file_name = joined.file_name()
+                .ok_or_else(|| "Invalid filename".to_string())?;
+            let parent = joined.parent()
+                .and_then(|p| fs::canonicalize(p).ok())
+                .ok_or_else(|| "Invalid filename: directory does not exist".to_string())?;
+            parent.join(file_name)
+        }
+    };
+
+    if resolved == root || !resolved.starts_with(root) {
+        return Err("Invalid filename: path escapes data directory".to_string());
+    }
+
+    Ok(resolved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_root(name: &str) -> PathBuf {
+        let mut dir = std::env::temp_dir();
+        dir.push(format!("http_server_sandbox_{}_{}", name, std::process::id()));
+        prepare_root(dir.to_str().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_prepare_root_creates_directory() {
+        let root = temp_root("create");
+        assert!(root.is_dir());
+        assert!(root.is_absolute());
+    }
+
+    #[test]
+    fn test_prepare_root_rejects_file() {
+        let mut path = std::env::temp_dir();
+        path.push(format!("http_server_sandbox_file_{}", std::process::id()));
+        fs::write(&path, "x").unwrap();
+
+        let result = prepare_root(path.to_str().unwrap());
+        assert!(result.is_err());
+
+        fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_check_writable() {
+        let root = temp_root("writable");
+        assert!(check_writable(&root).is_ok());
+        assert_eq!(fs::read_dir(&root).unwrap().count(), 0);
+
+        assert!(check_writable(&root.join("missing")).unwrap_err().contains("not writable"));
+    }
+
+    #[test]
+    fn test_resolve_inside_sandbox() {
+        let root = temp_root("inside");
+        fs::write(root.join("a.txt"), "x").unwrap();
+
+        assert_eq!(resolve_in(&root, "a.txt").unwrap(), root.join("a.txt"));
+        assert_eq!(resolve_in(&root, "new.txt").unwrap(), root.join("new.txt"));
+    }
+
+    #[test]
+    fn test_resolve_rejects_escapes() {
+        let root = temp_root("escape");
+
+        assert!(resolve_in(&root, "../etc/passwd").is_err());
+        assert!(resolve_in(&root, "..").is_err());
+        assert!(resolve_in(&root, "/etc/passwd").is_err());
+        assert!(resolve_in(&root, "").is_err());
+        assert!(resolve_in(&root, "..\\x").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_resolve_rejects_symlink_escape() {
+        let root = temp_root("symlink");
+        let link = root.join("outside");
+        let _ = fs::remove_file(&link);
+        std::os::unix::fs::symlink("/etc", &link).unwrap();
+
+        assert!(resolve_in(&root, "outside/passwd").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_resolve_rejects_dangling_symlink() {
+        let root = temp_root("dangling");
+        let link = root.join("dangling");
+        let _ = fs::remove_file(&link);
+        let target = std::env::temp_dir().join(format!("http_server_outside_{}", std::process::id()));
+        let _ = fs::remove_file(&target);
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+
+        assert!(resolve_in(&root, "dangling").unwrap_err().contains("symlink"));
+    }
+}
@@ -0,0 +1,285 @@
+//! # Bus de Eventos Interno
+//! src/events.rs
+//!
+//! Pub/sub liviano sobre `mpsc` para notificaciones entre módulos. Los
+//! productores (conexiones, job manager, pools) publican un `Event` sin
+//! conocer a sus consumidores; métricas, logging y `/ws` se suscriben.
+//!
+//! - Cada suscriptor tiene su propio canal acotado (`SUBSCRIBER_CAPACITY`).
+//!   `publish` nunca bloquea: si un suscriptor se atrasa, el evento se
+//!   descarta para él y se cuenta en `dropped`.
+//! - Los suscriptores cuyo `Receiver` se soltó se eliminan en el siguiente
+//!   `publish`.
+//! - Hay un bus por servidor (lo crea el `JobManager`), así dos servidores en
+//!   el mismo proceso no mezclan sus métricas.
+
+use crate::http::redact;
+use crate::jobs::{JobMetadata, JobStatus, JobType};
+use serde::Serialize;
+use std::io;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// Eventos en el canal de cada suscriptor antes de empezar a descartar
+pub const SUBSCRIBER_CAPACITY: usize = 8192;
+
+/// Notificación publicada en el bus
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    /// Se escribió la respuesta de un request
+    RequestCompleted {
+        path: String,
+        status: u16,
+        latency: Duration,
+    },
+
+    /// Un job se creó o cambió de estado o de progreso
+    JobStateChanged {
+        id: String,
+        job_type: JobType,
+        status: JobStatus,
+        progress: u8,
+        eta_ms: Option<u64>,
+    },
+
+    /// Una cola rechazó (o desplazó) trabajo por estar llena
+    QueueSaturated {
+        queue: String,
+        len: usize,
+        capacity: usize,
+    },
+
+    /// Una tarea hizo panic; `job_id` indica si era un job asíncrono
+    WorkerPanicked {
+        pool: String,
+        message: String,
+        job_id: Option<String>,
+    },
+
+    /// El handler de una ruta (`path` tal como se registró) superó su timeout
+    RouteTimedOut {
+        path: String,
+        timeout: Duration,
+    },
+}
+
+impl Event {
+    /// Evento con el estado actual de un job
+    pub fn job_state(job: &JobMetadata) -> Self {
+        Event::JobStateChanged {
+            id: job.id.clone(),
+            job_type: job.job_type,
+            status: job.status,
+            progress: job.progress,
+            eta_ms: job.eta_ms,
+        }
+    }
+
+    /// Nivel de log y línea que imprime el consumidor de logging
+    ///
+    /// `None` para los eventos que ya se registran en otro lado (los
+    /// requests se imprimen en el thread de conexión).
+    pub fn log_line(&self) -> Option<(&'static str, String)> {
+        match self {
+            Event::RequestCompleted { .. } => None,
+            Event::JobStateChanged { id, job_type, status, progress, .. } => Some((
+                "debug",
+                format!("📋 Job {} ({:?}): {:?} {}%", id, job_type, status, progress),
+            )),
+            Event::QueueSaturated { queue, len, capacity } => Some((
+                "warn",
+                format!("🚦 Cola {} saturada ({}/{})", queue, len, capacity),
+            )),
+            // El mensaje del panic puede incluir parámetros del request
+            Event::WorkerPanicked { pool, message, job_id: Some(id) } => Some((
+                "error",
+                format!("💥 Job {} hizo panic en el pool {}: {}", id, pool, redact::redact_text(message)),
+            )),
+            Event::WorkerPanicked { pool, message, job_id: None } => Some((
+                "error",
+                format!("💥 Panic en el pool {}: {}", pool, redact::redact_text(message)),
+            )),
+            Event::RouteTimedOut { path, timeout } => Some((
+                "warn",
+                format!("⏱️  La ruta {} superó su timeout de {} ms", path, timeout.as_millis()),
+            )),
+        }
+    }
+}
+
+/// Contadores del bus (sección `events` de `/metrics`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct BusStats {
+    pub published: u64,
+
+    /// Entregas descartadas porque el canal del suscriptor estaba lleno
+    pub dropped: u64,
+    pub subscribers: usize,
+}
+
+struct Subscriber {
+    name: String,
+    sender: SyncSender<Event>,
+}
+
+struct BusInner {
+    subscribers: Mutex<Vec<Subscriber>>,
+    published: AtomicU64,
+    dropped: AtomicU64,
+}
+
+/// Bus de eventos con fan-out a todos los suscriptores
+///
+/// Clonar el bus comparte los mismos suscriptores.
+///
+/// # Ejemplo
+/// ```
+/// use http_server::events::{Event, EventBus};
+/// use std::time::Duration;
+///
+/// let bus = EventBus::new();
+/// let events = bus.subscribe("demo");
+/// bus.publish(Event::RequestCompleted {
+///     path: "/status".to_string(),
+///     status: 200,
+///     latency: Duration::from_millis(3),
+/// });
+/// assert!(matches!(events.recv().unwrap(), Event::RequestCompleted { status: 200, .. }));
+/// ```
+#[derive(Clone)]
+pub struct EventBus {
+    inner: Arc<BusInner>,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        Self {
+            inner: Arc::new(BusInner {
+                subscribers: Mutex::new(Vec::new()),
+                published: AtomicU64::new(0),
+                dropped: AtomicU64::new(0),
+            }),
+        }
+    }
+
+    /// Registra un suscriptor que recibe los eventos publicados desde ahora
+    pub fn subscribe(&self, name: &str) -> Receiver<Event> {
+        let (sender, receiver) = mpsc::sync_channel(SUBSCRIBER_CAPACITY);
+        self.inner.subscribers.lock().unwrap().push(Subscriber { name: name.to_string(), sender });
+        receiver
+    }
+
+    /// Entrega `event` a cada suscriptor sin bloquear
+    pub fn publish(&self, event: Event) {
+        self.inner.published.fetch_add(1, Ordering::Relaxed);
+        let mut subscribers = self.inner.subscribers.lock().unwrap();
+        subscribers.retain(|subscriber| match subscriber.sender.try_send(event.clone()) {
+            Ok(()) => true,
+            Err(TrySendError::Full(_)) => {
+                self.inner.dropped.fetch_add(1, Ordering::Relaxed);
+                true
+            }
+            Err(TrySendError::Disconnected(_)) => false,
+        });
+    }
+
+    /// Lanza un thread `events-<name>` que pasa cada evento a `handler`
+    ///
+    /// El thread termina cuando se suelta el último clon del bus.
+    pub fn spawn_consumer<F>(&self, name: &str, mut handler: F) -> io::Result<JoinHandle<()>>
+    where
+        F: FnMut(Event) + Send + 'static,
+    {
+        let events = self.subscribe(name);
+        thread::Builder::new()
+            .name(format!("events-{}", name))
+            .spawn(move || {
+                for event in events {
+                    handler(event);
+                }
+            })
+    }
+
+    /// Nombres de los suscriptores vigentes
+    pub fn subscriber_names(&self) -> Vec<String> {
+        let subscribers = self.inner.subscribers.lock().unwrap();
+        subscribers.iter().map(|subscriber| subscriber.name.clone()).collect()
+    }
+
+    pub fn stats(&self) -> BusStats {
+        BusStats {
+            published: self.inner.published.load(Ordering::Relaxed),
+            dropped: self.inner.dropped.load(Ordering::Relaxed),
+            subscribers: self.inner.subscribers.lock().unwrap().len(),
+        }
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn completed(status: u16) -> Event {
+        Event::RequestCompleted { path: "/status".to_string(), status, latency: Duration::from_millis(1) }
+    }
+
+    #[test]
+    fn test_fan_out_to_every_subscriber() {
+        let bus = EventBus::new();
+        let first = bus.subscribe("first");
+        let second = bus.clone().subscribe("second");
+
+        bus.publish(completed(200));
+        bus.publish(completed(404));
+
+        for events in [first, second] {
+            let statuses: Vec<Event> = events.try_iter().collect();
+            assert_eq!(statuses, vec![completed(200), completed(404)]);
+        }
+        assert_eq!(bus.stats(), BusStats { published: 2, dropped: 0, subscribers: 2 });
+    }
+
+    #[test]
+    fn test_slow_subscriber_drops_instead_of_blocking() {
+        let bus = EventBus::new();
+        let slow = bus.subscribe("slow");
+
+        for _ in 0..SUBSCRIBER_CAPACITY + 5 {
+            bus.publish(completed(200));
+        }
+
+        assert_eq!(slow.try_iter().count(), SUBSCRIBER_CAPACITY);
+        assert_eq!(bus.stats().dropped, 5);
+    }
+
+    #[test]
+    fn test_disconnected_subscribers_are_pruned() {
+        let bus = EventBus::new();
+        let kept = bus.subscribe("kept");
+        drop(bus.subscribe("gone"));
+        assert_eq!(bus.subscriber_names(), vec!["kept", "gone"]);
+
+        bus.publish(completed(200));
+        assert_eq!(bus.subscriber_names(), vec!["kept"]);
+        assert_eq!(kept.try_iter().count(), 1);
+    }
+
+    #[test]
+    fn test_consumer_thread_receives_events() {
+        let bus = EventBus::new();
+        let (tx, rx) = mpsc::channel();
+        bus.spawn_consumer("test", move |event| tx.send(event).unwrap()).unwrap();
+
+        bus.publish(completed(201));
+        assert_eq!(rx.recv_timeout(Duration::from_secs(2)).unwrap(), completed(201));
+    }
+}
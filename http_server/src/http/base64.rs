@@ -0,0 +1,76 @@
+//! # Base64
+//! src/http/base64.rs
+//!
+//! Codificación base64 estándar (RFC 4648, alfabeto `+/`), usada por
+//! `Authorization: Basic` y el handshake de WebSocket.
+
+/// Alfabeto estándar
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Codifica `data` con padding `=`
+pub fn encode(data: &[u8]) -> String {
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let bytes = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let n = u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+/// Decodifica base64 estándar (con o sin padding)
+pub fn decode(input: &str) -> Option<Vec<u8>> {
+    fn value(c: u8) -> Option<u32> {
+        match c {
+            b'A'..=b'Z' => Some((c - b'A') as u32),
+            b'a'..=b'z' => Some((c - b'a' + 26) as u32),
+            b'0'..=b'9' => Some((c - b'0' + 52) as u32),
+            b'+' => Some(62),
+            b'/' => Some(63),
+            _ => None,
+        }
+    }
+
+    let data = input.trim_end_matches('=').as_bytes();
+    if data.len() % 4 == 1 {
+        return None;
+    }
+
+    let mut out = Vec::with_capacity(data.len() * 3 / 4);
+    for chunk in data.chunks(4) {
+        let mut acc = 0u32;
+        for (i, &c) in chunk.iter().enumerate() {
+            acc |= value(c)? << (18 - 6 * i);
+        }
+        let bytes = acc.to_be_bytes();
+        out.extend_from_slice(&bytes[1..chunk.len()]);
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode() {
+        assert_eq!(encode(b"admin:hunter2"), "YWRtaW46aHVudGVyMg==");
+        assert_eq!(encode(b"a"), "YQ==");
+        assert_eq!(encode(b"abc"), "YWJj");
+        assert_eq!(encode(b""), "");
+    }
+
+    #[test]
+    fn test_decode() {
+        assert_eq!(decode("YWRtaW46aHVudGVyMg==").unwrap(), b"admin:hunter2");
+        assert_eq!(decode("YQ").unwrap(), b"a");
+        assert!(decode("a$b=").is_none());
+        assert_eq!(decode(&encode(b"\x00\xff round trip")).unwrap(), b"\x00\xff round trip");
+    }
+}
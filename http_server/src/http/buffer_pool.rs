@@ -0,0 +1,177 @@
+//! # Pool de Buffers de Lectura
+//! src/http/buffer_pool.rs
+//!
+//! Cada conexión necesita un buffer donde el `RequestParser` acumula los
+//! bytes del request. Pedirlo al allocator en cada conexión (y soltarlo al
+//! cerrarla) genera mucho movimiento bajo carga; el pool guarda los buffers
+//! devueltos y los reparte a las conexiones siguientes.
+//!
+//! - Un buffer se devuelve vacío pero conserva su capacidad.
+//! - Los que crecieron más allá de `MAX_RETAINED_CAPACITY` (un body grande)
+//!   se liberan en vez de guardarse, para no retener memoria.
+//! - El pool guarda como máximo `max_pooled` buffers (`--buffer-pool-size`);
+//!   con 0 no guarda ninguno y cada conexión crea el suyo.
+//!
+//! Las estadísticas se publican en `/metrics` (`buffer_pool`).
+
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+/// Capacidad con la que se crea cada buffer
+pub const BUFFER_CAPACITY: usize = 8192;
+
+/// Capacidad máxima de un buffer que vuelve al pool
+pub const MAX_RETAINED_CAPACITY: usize = 64 * 1024;
+
+/// Estadísticas del pool
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct BufferPoolStats {
+    /// Buffers guardados ahora mismo
+    pub pooled: usize,
+
+    /// Máximo de buffers que se guardan
+    pub max_pooled: usize,
+
+    /// Capacidad inicial de cada buffer
+    pub buffer_bytes: usize,
+
+    /// Pedidos atendidos con un buffer guardado
+    pub hits: u64,
+
+    /// Pedidos que tuvieron que crear un buffer nuevo
+    pub misses: u64,
+
+    /// Buffers devueltos que quedaron guardados
+    pub returned: u64,
+
+    /// Buffers devueltos que se liberaron (pool lleno o demasiado grandes)
+    pub discarded: u64,
+}
+
+/// Pool de buffers compartido por los workers de conexión
+///
+/// # Ejemplo
+/// ```
+/// use http_server::http::buffer_pool::BufferPool;
+///
+/// let pool = BufferPool::new(4);
+/// let mut buffer = pool.take();
+/// buffer.extend_from_slice(b"GET / HTTP/1.0\r\n\r\n");
+/// pool.give_back(buffer);
+///
+/// // El siguiente pedido reusa el mismo buffer, ya vacío
+/// assert!(pool.take().is_empty());
+/// assert_eq!(pool.stats().hits, 1);
+/// ```
+#[derive(Debug)]
+pub struct BufferPool {
+    buffers: Mutex<Vec<Vec<u8>>>,
+    max_pooled: usize,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    returned: AtomicU64,
+    discarded: AtomicU64,
+}
+
+impl BufferPool {
+    /// Pool que guarda como máximo `max_pooled` buffers
+    pub fn new(max_pooled: usize) -> Self {
+        Self {
+            buffers: Mutex::new(Vec::new()),
+            max_pooled,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            returned: AtomicU64::new(0),
+            discarded: AtomicU64::new(0),
+        }
+    }
+
+    /// Buffer vacío: uno guardado si hay, si no uno nuevo de `BUFFER_CAPACITY`
+    pub fn take(&self) -> Vec<u8> {
+        if let Some(buffer) = self.buffers.lock().unwrap().pop() {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return buffer;
+        }
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        Vec::with_capacity(BUFFER_CAPACITY)
+    }
+
+    /// Devuelve un buffer para que lo use otra conexión
+    pub fn give_back(&self, mut buffer: Vec<u8>) {
+        // Un buffer sin capacidad (ya se lo llevó `mem::take`) no aporta nada
+        if buffer.capacity() == 0 {
+            return;
+        }
+        if buffer.capacity() <= MAX_RETAINED_CAPACITY {
+            let mut buffers = self.buffers.lock().unwrap();
+            if buffers.len() < self.max_pooled {
+                buffer.clear();
+                buffers.push(buffer);
+                self.returned.fetch_add(1, Ordering::Relaxed);
+                return;
+            }
+        }
+        self.discarded.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Estadísticas actuales (para `/metrics`)
+    pub fn stats(&self) -> BufferPoolStats {
+        BufferPoolStats {
+            pooled: self.buffers.lock().unwrap().len(),
+            max_pooled: self.max_pooled,
+            buffer_bytes: BUFFER_CAPACITY,
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            returned: self.returned.load(Ordering::Relaxed),
+            discarded: self.discarded.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl Default for BufferPool {
+    fn default() -> Self {
+        Self::new(256)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_buffers_are_reused_up_to_the_limit() {
+        let pool = BufferPool::new(1);
+        let first = pool.take();
+        let second = pool.take();
+        pool.give_back(first);
+        pool.give_back(second);
+
+        let stats = pool.stats();
+        assert_eq!((stats.misses, stats.returned, stats.discarded, stats.pooled), (2, 1, 1, 1));
+
+        assert!(pool.take().capacity() >= BUFFER_CAPACITY);
+        assert_eq!(pool.stats().hits, 1);
+        assert_eq!(pool.stats().pooled, 0);
+    }
+
+    #[test]
+    fn test_grown_buffers_are_released() {
+        let pool = BufferPool::new(8);
+        let mut buffer = pool.take();
+        buffer.resize(MAX_RETAINED_CAPACITY + 1, 0);
+        pool.give_back(buffer);
+        pool.give_back(Vec::new());
+
+        let stats = pool.stats();
+        assert_eq!((stats.pooled, stats.discarded), (0, 1));
+    }
+
+    #[test]
+    fn test_zero_sized_pool_keeps_nothing() {
+        let pool = BufferPool::new(0);
+        pool.give_back(pool.take());
+        assert_eq!(pool.stats().pooled, 0);
+        assert_eq!(pool.stats().misses, 1);
+    }
+}
@@ -0,0 +1,109 @@
+//! # Políticas de caché
+//! src/http/cache.rs
+//!
+//! Valores de `Cache-Control` (y su `Expires` equivalente para clientes
+//! HTTP/1.0) que usa el servidor:
+//!
+//! ```text
+//! NoStore          → Cache-Control: no-store          Expires: Thu, 01 Jan 1970 00:00:00 GMT
+//! NoCache          → Cache-Control: no-cache          Expires: Thu, 01 Jan 1970 00:00:00 GMT
+//! Public(1h)       → Cache-Control: public, max-age=3600   Expires: <ahora + 1h>
+//! Private(60s)     → Cache-Control: private, max-age=60    Expires: <ahora + 60s>
+//! ```
+//!
+//! Las respuestas que no fijan una política reciben la de su ruta
+//! (`for_path`): la ayuda es cacheable, los archivos se revalidan con sus
+//! validadores y todo lo demás (métricas, jobs, comandos) no se guarda.
+
+use super::date;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Cuánto tiempo se cachean `/help` y `/routes.json`
+pub const HELP_MAX_AGE: Duration = Duration::from_secs(3600);
+
+/// Política de caché de una respuesta
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheControl {
+    /// No guardar la respuesta en ningún caché
+    NoStore,
+
+    /// Se puede guardar, pero hay que revalidarla antes de usarla
+    NoCache,
+
+    /// Cacheable por cualquier caché (proxies incluidos) durante el tiempo dado
+    Public(Duration),
+
+    /// Cacheable solo por el cliente durante el tiempo dado
+    Private(Duration),
+}
+
+impl CacheControl {
+    /// Valor del header `Cache-Control`
+    ///
+    /// # Ejemplo
+    /// ```
+    /// use http_server::http::cache::CacheControl;
+    /// use std::time::Duration;
+    ///
+    /// assert_eq!(CacheControl::NoStore.header_value(), "no-store");
+    /// assert_eq!(CacheControl::Public(Duration::from_secs(60)).header_value(), "public, max-age=60");
+    /// ```
+    pub fn header_value(&self) -> String {
+        match self {
+            CacheControl::NoStore => "no-store".to_string(),
+            CacheControl::NoCache => "no-cache".to_string(),
+            CacheControl::Public(max_age) => format!("public, max-age={}", max_age.as_secs()),
+            CacheControl::Private(max_age) => format!("private, max-age={}", max_age.as_secs()),
+        }
+    }
+
+    /// Valor del header `Expires` para una respuesta generada en `now`
+    ///
+    /// Sin `max-age` la respuesta ya está vencida (fecha en el pasado).
+    pub fn expires(&self, now: SystemTime) -> String {
+        match self {
+            CacheControl::NoStore | CacheControl::NoCache => date::format(UNIX_EPOCH),
+            CacheControl::Public(max_age) | CacheControl::Private(max_age) => date::format(now + *max_age),
+        }
+    }
+}
+
+/// Política por defecto de una ruta
+///
+/// # Ejemplo
+/// ```
+/// use http_server::http::cache::{self, CacheControl};
+///
+/// assert_eq!(cache::for_path("/help"), CacheControl::Public(cache::HELP_MAX_AGE));
+/// assert_eq!(cache::for_path("/metrics"), CacheControl::NoStore);
+/// ```
+pub fn for_path(path: &str) -> CacheControl {
+    match path {
+        "/help" | "/routes.json" => CacheControl::Public(HELP_MAX_AGE),
+        // Llevan ETag/Last-Modified: se revalidan con 304
+        "/download" | "/hashfile" => CacheControl::NoCache,
+        _ => CacheControl::NoStore,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expires() {
+        let now = UNIX_EPOCH + Duration::from_secs(784111777);
+        assert_eq!(CacheControl::Private(Duration::from_secs(60)).expires(now), "Sun, 06 Nov 1994 08:50:37 GMT");
+        assert_eq!(CacheControl::NoStore.expires(now), "Thu, 01 Jan 1970 00:00:00 GMT");
+        assert_eq!(CacheControl::NoCache.header_value(), "no-cache");
+    }
+
+    #[test]
+    fn test_route_defaults() {
+        for path in ["/metrics", "/metrics/summary", "/jobs/status", "/status", "/fibonacci"] {
+            assert_eq!(for_path(path), CacheControl::NoStore, "{}", path);
+        }
+        assert_eq!(for_path("/download"), CacheControl::NoCache);
+        assert!(matches!(for_path("/help"), CacheControl::Public(_)));
+    }
+}
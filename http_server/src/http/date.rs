@@ -0,0 +1,231 @@
+//! # Fechas HTTP
+//! src/http/date.rs
+//!
+//! Formato y parsing de fechas HTTP (IMF-fixdate, RFC 9110 sección 5.6.7),
+//! usadas por `Date`, `Last-Modified` e `If-Modified-Since`:
+//!
+//! ```text
+//! Sun, 06 Nov 1994 08:49:37 GMT
+//! ```
+//!
+//! También se formatea ISO 8601 en UTC (`1994-11-06T08:49:37Z`) para los
+//! bodies JSON, como el campo `iso` de `/timestamp`, y la fecha del
+//! Common Log Format (`06/Nov/1994:08:49:37 +0000`) para el access log.
+//!
+//! Se implementa a mano (sin chrono) con la conversión de días a fecha civil
+//! del calendario gregoriano. Solo se manejan fechas desde 1970.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const DAYS: [&str; 7] = ["Thu", "Fri", "Sat", "Sun", "Mon", "Tue", "Wed"];
+const MONTHS: [&str; 12] = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+
+/// Formatea `time` como IMF-fixdate (se truncan las fracciones de segundo)
+///
+/// # Ejemplo
+/// ```
+/// use http_server::http::date;
+/// use std::time::{Duration, UNIX_EPOCH};
+///
+/// let time = UNIX_EPOCH + Duration::from_secs(784111777);
+/// assert_eq!(date::format(time), "Sun, 06 Nov 1994 08:49:37 GMT");
+/// ```
+pub fn format(time: SystemTime) -> String {
+    let (days, rem) = days_and_seconds(time);
+    let (year, month, day) = civil_from_days(days);
+
+    format!(
+        "{}, {:02} {} {} {:02}:{:02}:{:02} GMT",
+        DAYS[(days % 7) as usize], day, MONTHS[month as usize - 1], year,
+        rem / 3600, rem % 3600 / 60, rem % 60
+    )
+}
+
+/// Fecha actual como IMF-fixdate, para el header `Date`
+pub fn now() -> String {
+    format(SystemTime::now())
+}
+
+/// Formatea `time` como ISO 8601 en UTC (se truncan las fracciones de segundo)
+///
+/// # Ejemplo
+/// ```
+/// use http_server::http::date;
+/// use std::time::{Duration, UNIX_EPOCH};
+///
+/// let time = UNIX_EPOCH + Duration::from_secs(784111777);
+/// assert_eq!(date::format_iso8601(time), "1994-11-06T08:49:37Z");
+/// ```
+pub fn format_iso8601(time: SystemTime) -> String {
+    let (days, rem) = days_and_seconds(time);
+    let (year, month, day) = civil_from_days(days);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year, month, day, rem / 3600, rem % 3600 / 60, rem % 60
+    )
+}
+
+/// Formatea `time` como en el Common Log Format, siempre en UTC
+///
+/// # Ejemplo
+/// ```
+/// use http_server::http::date;
+/// use std::time::{Duration, UNIX_EPOCH};
+///
+/// let time = UNIX_EPOCH + Duration::from_secs(784111777);
+/// assert_eq!(date::format_clf(time), "06/Nov/1994:08:49:37 +0000");
+/// ```
+pub fn format_clf(time: SystemTime) -> String {
+    let (days, rem) = days_and_seconds(time);
+    let (year, month, day) = civil_from_days(days);
+
+    format!(
+        "{:02}/{}/{}:{:02}:{:02}:{:02} +0000",
+        day, MONTHS[month as usize - 1], year, rem / 3600, rem % 3600 / 60, rem % 60
+    )
+}
+
+/// Días desde 1970-01-01 y segundos dentro del día (fechas previas cuentan como 1970)
+fn days_and_seconds(time: SystemTime) -> (u64, u64) {
+    let secs = time.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    (secs / 86_400, secs % 86_400)
+}
+
+/// Parsea una fecha IMF-fixdate; retorna `None` si no es válida
+///
+/// # Ejemplo
+/// ```
+/// use http_server::http::date;
+/// use std::time::{Duration, UNIX_EPOCH};
+///
+/// let time = date::parse("Sun, 06 Nov 1994 08:49:37 GMT").unwrap();
+/// assert_eq!(time, UNIX_EPOCH + Duration::from_secs(784111777));
+/// assert!(date::parse("yesterday").is_none());
+/// ```
+pub fn parse(value: &str) -> Option<SystemTime> {
+    let (_, rest) = value.trim().split_once(", ")?;
+    let fields: Vec<&str> = rest.split(' ').collect();
+    let [day, month, year, time, "GMT"] = fields[..] else {
+        return None;
+    };
+
+    let number = |s: &str, digits: usize| -> Option<u64> {
+        if s.len() != digits || !s.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        s.parse().ok()
+    };
+    let day = number(day, 2)?;
+    let month = MONTHS.iter().position(|m| *m == month)? as u64 + 1;
+    let year = number(year, 4)?;
+
+    let mut hms = time.split(':').map(|part| number(part, 2));
+    let (hour, minute, second) = (hms.next()??, hms.next()??, hms.next()??);
+    if hms.next().is_some() || hour > 23 || minute > 59 || second > 60 {
+        return None;
+    }
+
+    if year < 1970 || day == 0 || day > days_in_month(year, month) {
+        return None;
+    }
+
+    let secs = days_from_civil(year, month, day) * 86_400 + hour * 3600 + minute * 60 + second;
+    Some(UNIX_EPOCH + Duration::from_secs(secs))
+}
+
+fn days_in_month(year: u64, month: u64) -> u64 {
+    match month {
+        2 if year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400)) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Días desde 1970-01-01 a (año, mes, día)
+fn civil_from_days(days: u64) -> (u64, u64, u64) {
+    // Eras de 400 años contadas desde el 0000-03-01
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z % 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+    (year, month, day)
+}
+
+/// (año, mes, día) a días desde 1970-01-01
+fn days_from_civil(year: u64, month: u64, day: u64) -> u64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year / 400;
+    let yoe = year % 400;
+    let mp = if month > 2 { month - 3 } else { month + 9 };
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_and_parse_roundtrip() {
+        for (secs, text) in [
+            (0, "Thu, 01 Jan 1970 00:00:00 GMT"),
+            (951_782_400, "Tue, 29 Feb 2000 00:00:00 GMT"),
+            (1_709_251_199, "Thu, 29 Feb 2024 23:59:59 GMT"),
+            (4_102_444_800, "Fri, 01 Jan 2100 00:00:00 GMT"),
+        ] {
+            let time = UNIX_EPOCH + Duration::from_secs(secs);
+            assert_eq!(format(time), text);
+            assert_eq!(parse(text), Some(time), "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_format_truncates_subseconds() {
+        let time = UNIX_EPOCH + Duration::from_millis(1_500);
+        assert_eq!(format(time), "Thu, 01 Jan 1970 00:00:01 GMT");
+        assert_eq!(format_iso8601(time), "1970-01-01T00:00:01Z");
+    }
+
+    #[test]
+    fn test_format_iso8601() {
+        for (secs, text) in [
+            (0, "1970-01-01T00:00:00Z"),
+            (951_782_400, "2000-02-29T00:00:00Z"),
+            (1_709_251_199, "2024-02-29T23:59:59Z"),
+        ] {
+            assert_eq!(format_iso8601(UNIX_EPOCH + Duration::from_secs(secs)), text);
+        }
+    }
+
+    #[test]
+    fn test_now_is_parseable() {
+        let parsed = parse(&now()).unwrap();
+        let drift = SystemTime::now().duration_since(parsed).unwrap();
+        assert!(drift < Duration::from_secs(2));
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_dates() {
+        for value in [
+            "",
+            "Sunday, 06-Nov-94 08:49:37 GMT",
+            "Sun Nov  6 08:49:37 1994",
+            "Sun, 06 Nov 1994 08:49:37 UTC",
+            "Sun, 6 Nov 1994 08:49:37 GMT",
+            "Sun, 06 Nov 1994 24:00:00 GMT",
+            "Sun, 31 Apr 1994 08:49:37 GMT",
+            "Sun, 29 Feb 1900 08:49:37 GMT",
+            "Sun, 06 Nov 1969 08:49:37 GMT",
+        ] {
+            assert_eq!(parse(value), None, "{}", value);
+        }
+    }
+}
@@ -0,0 +1,142 @@
+//! # Bodies Comprimidos
+//! src/http/encoding.rs
+//!
+//! Descompresión de bodies enviados con `Content-Encoding: gzip`. El
+//! tamaño descomprimido se limita mientras se descomprime: un archivo
+//! pequeño puede expandirse a gigas ("zip bomb"), así que nunca se
+//! bufferea más de `max_bytes` + 1 bytes.
+//!
+//! También negocia la compresión de respuestas según `Accept-Encoding`.
+
+use flate2::read::MultiGzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::io::{Read, Write};
+
+/// Errores al decodificar un body
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodeError {
+    /// `Content-Encoding` distinto de gzip/identity
+    Unsupported(String),
+
+    /// El body no es gzip válido
+    Invalid(String),
+
+    /// El body descomprimido supera el máximo permitido
+    TooLarge(u64),
+}
+
+impl std::fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            DecodeError::Unsupported(encoding) => write!(f, "Unsupported Content-Encoding: {}", encoding),
+            DecodeError::Invalid(e) => write!(f, "Invalid gzip body: {}", e),
+            DecodeError::TooLarge(max) => write!(f, "Decompressed body too large (max: {} bytes)", max),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+/// Decodifica `body` según el valor de `Content-Encoding`
+///
+/// Acepta `gzip` (y su alias `x-gzip`) e `identity`, sin distinguir
+/// mayúsculas. Retorna `None` si no hay nada que decodificar.
+pub fn decode(encoding: &str, body: &[u8], max_bytes: u64) -> Result<Option<Vec<u8>>, DecodeError> {
+    match encoding.trim().to_ascii_lowercase().as_str() {
+        "" | "identity" => Ok(None),
+        "gzip" | "x-gzip" => gunzip(body, max_bytes).map(Some),
+        other => Err(DecodeError::Unsupported(other.to_string())),
+    }
+}
+
+/// Descomprime `data` (uno o más miembros gzip) hasta `max_bytes`
+pub fn gunzip(data: &[u8], max_bytes: u64) -> Result<Vec<u8>, DecodeError> {
+    let mut out = Vec::new();
+    MultiGzDecoder::new(data)
+        .take(max_bytes.saturating_add(1))
+        .read_to_end(&mut out)
+        .map_err(|e| DecodeError::Invalid(e.to_string()))?;
+
+    if out.len() as u64 > max_bytes {
+        return Err(DecodeError::TooLarge(max_bytes));
+    }
+    Ok(out)
+}
+
+/// Comprime `data` como un miembro gzip
+pub fn gzip(data: &[u8]) -> Vec<u8> {
+    let mut encoder = GzEncoder::new(Vec::with_capacity(data.len() / 2), Compression::default());
+    // Escribir a un Vec no falla
+    encoder.write_all(data).expect("gzip en memoria");
+    encoder.finish().expect("gzip en memoria")
+}
+
+/// Indica si un header `Accept-Encoding` acepta gzip
+///
+/// Respeta los valores `q` (`gzip;q=0` lo rechaza) y el comodín `*`; una
+/// mención explícita de gzip gana sobre `*`.
+pub fn accepts_gzip(accept_encoding: &str) -> bool {
+    let mut wildcard = false;
+    for item in accept_encoding.split(',') {
+        let mut parts = item.split(';');
+        let coding = parts.next().unwrap_or("").trim().to_ascii_lowercase();
+        let q = parts
+            .filter_map(|p| p.trim().strip_prefix("q=").or_else(|| p.trim().strip_prefix("Q=")))
+            .find_map(|q| q.trim().parse::<f32>().ok())
+            .unwrap_or(1.0);
+        match coding.as_str() {
+            "gzip" | "x-gzip" => return q > 0.0,
+            "*" => wildcard = q > 0.0,
+            _ => {}
+        }
+    }
+    wildcard
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gunzip_roundtrip() {
+        let data = b"{\"task\": \"isprime\", \"params\": {\"n\": 97}}".repeat(10);
+        assert_eq!(gunzip(&gzip(&data), 4096).unwrap(), data);
+
+        // Varios miembros concatenados se leen completos
+        let mut members = gzip(b"hola ");
+        members.extend(gzip(b"mundo"));
+        assert_eq!(gunzip(&members, 100).unwrap(), b"hola mundo");
+    }
+
+    #[test]
+    fn test_gunzip_stops_at_limit() {
+        // 10 MiB de ceros comprimen a unos pocos KiB
+        let bomb = gzip(&vec![0u8; 10 * 1024 * 1024]);
+        assert!(bomb.len() < 64 * 1024);
+        assert_eq!(gunzip(&bomb, 1024), Err(DecodeError::TooLarge(1024)));
+        assert_eq!(gunzip(&gzip(&[7u8; 1024]), 1024).unwrap().len(), 1024);
+    }
+
+    #[test]
+    fn test_decode_by_encoding() {
+        assert_eq!(decode("identity", b"raw", 10), Ok(None));
+        assert_eq!(decode("", b"raw", 10), Ok(None));
+        assert_eq!(decode("GZIP", &gzip(b"abc"), 10), Ok(Some(b"abc".to_vec())));
+        assert_eq!(decode("x-gzip", &gzip(b"abc"), 10), Ok(Some(b"abc".to_vec())));
+        assert_eq!(decode("br", b"raw", 10), Err(DecodeError::Unsupported("br".to_string())));
+        assert!(matches!(decode("gzip", b"not gzip", 10), Err(DecodeError::Invalid(_))));
+    }
+
+    #[test]
+    fn test_accepts_gzip() {
+        assert!(accepts_gzip("gzip"));
+        assert!(accepts_gzip("deflate, GZIP;q=0.5, br"));
+        assert!(accepts_gzip("*"));
+        assert!(!accepts_gzip(""));
+        assert!(!accepts_gzip("br, deflate"));
+        assert!(!accepts_gzip("gzip;q=0"));
+        assert!(!accepts_gzip("*, gzip;q=0"));
+        assert!(!accepts_gzip("identity, *;q=0"));
+    }
+}
@@ -0,0 +1,168 @@
+//! # Headers HTTP
+//! src/http/headers.rs
+//!
+//! Colección de headers que usan tanto `Request` como `Response`:
+//!
+//! - Los nombres se comparan sin distinguir mayúsculas (`content-type` y
+//!   `Content-Type` son el mismo header), pero se conservan tal como
+//!   llegaron o se agregaron.
+//! - Un mismo header puede repetirse (ej: varios `X-Forwarded-For` o
+//!   `Set-Cookie`); `get` retorna el primero y `get_all` todos, en orden.
+//! - Se escriben en el orden en que se agregaron.
+
+use std::ops::Index;
+
+/// Lista ordenada de headers `(nombre, valor)`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Headers {
+    entries: Vec<(String, String)>,
+}
+
+impl Headers {
+    /// Crea una colección vacía
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Primer valor del header `name`, sin distinguir mayúsculas
+    ///
+    /// # Ejemplo
+    /// ```
+    /// use http_server::http::Headers;
+    ///
+    /// let mut headers = Headers::new();
+    /// headers.insert("Content-Type", "text/plain");
+    /// assert_eq!(headers.get("content-type"), Some("text/plain"));
+    /// assert_eq!(headers.get("Accept"), None);
+    /// ```
+    pub fn get<'a>(&'a self, name: &str) -> Option<&'a str> {
+        self.get_all(name).next()
+    }
+
+    /// Todos los valores del header `name`, en el orden en que llegaron
+    ///
+    /// # Ejemplo
+    /// ```
+    /// use http_server::http::Headers;
+    ///
+    /// let mut headers = Headers::new();
+    /// headers.append("X-Forwarded-For", "10.0.0.1");
+    /// headers.append("x-forwarded-for", "10.0.0.2");
+    /// let all: Vec<&str> = headers.get_all("X-Forwarded-For").collect();
+    /// assert_eq!(all, ["10.0.0.1", "10.0.0.2"]);
+    /// ```
+    pub fn get_all<'a, 'n>(&'a self, name: &'n str) -> impl Iterator<Item = &'a str> + use<'a, 'n> {
+        self.entries.iter()
+            .filter(move |(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Indica si el header `name` está presente
+    pub fn contains(&self, name: &str) -> bool {
+        self.get(name).is_some()
+    }
+
+    /// Reemplaza todos los valores de `name` por `value`
+    ///
+    /// Si el header ya existía conserva su posición (y su nombre original).
+    pub fn insert(&mut self, name: &str, value: &str) {
+        match self.entries.iter().position(|(key, _)| key.eq_ignore_ascii_case(name)) {
+            Some(i) => {
+                self.entries[i].1 = value.to_string();
+                let mut index = 0;
+                self.entries.retain(|(key, _)| {
+                    index += 1;
+                    index - 1 == i || !key.eq_ignore_ascii_case(name)
+                });
+            }
+            None => self.append(name, value),
+        }
+    }
+
+    /// Agrega un valor más para `name` sin quitar los anteriores
+    pub fn append(&mut self, name: &str, value: &str) {
+        self.entries.push((name.to_string(), value.to_string()));
+    }
+
+    /// Quita todos los valores de `name` y retorna el primero
+    pub fn remove(&mut self, name: &str) -> Option<String> {
+        let first = self.get(name).map(str::to_string);
+        self.entries.retain(|(key, _)| !key.eq_ignore_ascii_case(name));
+        first
+    }
+
+    /// Recorre los headers como `(nombre, valor)`, en orden
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.entries.iter().map(|(name, value)| (name.as_str(), value.as_str()))
+    }
+
+    /// Cantidad de headers (cada repetición cuenta)
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Indica si no hay headers
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl Index<&str> for Headers {
+    type Output = str;
+
+    /// Primer valor de `name`; entra en pánico si el header no existe
+    fn index(&self, name: &str) -> &str {
+        self.get(name).unwrap_or_else(|| panic!("Missing header: {}", name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_case_insensitive_lookup() {
+        let mut headers = Headers::new();
+        headers.insert("Content-Length", "10");
+
+        assert_eq!(headers.get("content-length"), Some("10"));
+        assert_eq!(&headers["CONTENT-LENGTH"], "10");
+        assert!(headers.contains("Content-length"));
+        assert!(!headers.contains("Content-Type"));
+    }
+
+    #[test]
+    fn test_multiple_values() {
+        let mut headers = Headers::new();
+        headers.append("Set-Cookie", "a=1");
+        headers.append("Vary", "Accept");
+        headers.append("set-cookie", "b=2");
+
+        assert_eq!(headers.get("Set-Cookie"), Some("a=1"));
+        assert_eq!(headers.get_all("SET-COOKIE").collect::<Vec<_>>(), ["a=1", "b=2"]);
+        assert_eq!(headers.len(), 3);
+    }
+
+    #[test]
+    fn test_insert_replaces_all_values_in_place() {
+        let mut headers = Headers::new();
+        headers.append("X-A", "1");
+        headers.append("Vary", "Accept");
+        headers.append("x-a", "2");
+        headers.insert("X-a", "3");
+
+        let entries: Vec<_> = headers.iter().collect();
+        assert_eq!(entries, [("X-A", "3"), ("Vary", "Accept")]);
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut headers = Headers::new();
+        headers.append("X-A", "1");
+        headers.append("x-a", "2");
+
+        assert_eq!(headers.remove("x-A"), Some("1".to_string()));
+        assert!(headers.is_empty());
+        assert_eq!(headers.remove("X-A"), None);
+    }
+}
@@ -0,0 +1,198 @@
+//! # Content-Type y charset
+//! src/http/mime.rs
+//!
+//! Parsing de valores `Content-Type` (`tipo/subtipo; param=valor`) para
+//! saber si un body es texto y en qué charset viene. El servidor solo
+//! entiende UTF-8: un body de texto en otro charset, o con bytes que no
+//! son UTF-8 válido, se rechaza con 415.
+//!
+//! ```text
+//! application/json; charset=utf-8   → texto, UTF-8
+//! text/plain; charset="ISO-8859-1"  → texto, charset no soportado
+//! application/octet-stream          → binario (no se valida)
+//! ```
+
+/// Charset de las respuestas de texto
+pub const UTF_8: &str = "utf-8";
+
+/// `Content-Type` de las respuestas JSON
+pub const APPLICATION_JSON: &str = "application/json; charset=utf-8";
+
+/// `Content-Type` de las respuestas de texto plano
+pub const TEXT_PLAIN: &str = "text/plain; charset=utf-8";
+
+/// Un `Content-Type` parseado
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MediaType {
+    /// `tipo/subtipo` en minúsculas (ej: `application/json`)
+    pub essence: String,
+
+    /// Parámetro `charset` en minúsculas, sin comillas
+    pub charset: Option<String>,
+}
+
+/// Errores de charset de un body
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CharsetError {
+    /// El `Content-Type` declara un charset distinto de UTF-8
+    Unsupported(String),
+
+    /// El body no es UTF-8 válido
+    InvalidUtf8,
+}
+
+impl std::fmt::Display for CharsetError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            CharsetError::Unsupported(charset) => write!(f, "Unsupported charset: {} (use utf-8)", charset),
+            CharsetError::InvalidUtf8 => write!(f, "Request body is not valid UTF-8"),
+        }
+    }
+}
+
+impl std::error::Error for CharsetError {}
+
+impl MediaType {
+    /// Parsea un valor de `Content-Type`; `None` si no es `tipo/subtipo`
+    ///
+    /// # Ejemplo
+    /// ```
+    /// use http_server::http::mime::MediaType;
+    ///
+    /// let media = MediaType::parse("Application/JSON; Charset=\"UTF-8\"").unwrap();
+    /// assert_eq!(media.essence, "application/json");
+    /// assert_eq!(media.charset.as_deref(), Some("utf-8"));
+    /// assert!(MediaType::parse("json").is_none());
+    /// ```
+    pub fn parse(value: &str) -> Option<Self> {
+        let mut parts = value.split(';');
+        let essence = parts.next()?.trim().to_ascii_lowercase();
+        let (kind, subtype) = essence.split_once('/')?;
+        if kind.is_empty() || subtype.is_empty() {
+            return None;
+        }
+
+        let charset = parts
+            .filter_map(|param| param.split_once('='))
+            .find(|(name, _)| name.trim().eq_ignore_ascii_case("charset"))
+            .map(|(_, value)| value.trim().trim_matches('"').to_ascii_lowercase());
+
+        Some(Self { essence, charset })
+    }
+
+    /// Indica si el body es texto (y por lo tanto debe ser UTF-8)
+    ///
+    /// `text/*`, JSON (incluido `+json`) y formularios urlencoded. Los
+    /// multipart y los binarios se dejan tal cual.
+    pub fn is_text(&self) -> bool {
+        self.essence.starts_with("text/")
+            || self.essence == "application/json"
+            || self.essence.ends_with("+json")
+            || self.essence == "application/x-www-form-urlencoded"
+    }
+
+    /// Verifica que un body de texto venga en UTF-8
+    ///
+    /// Se acepta `charset=utf-8` (o `utf8`), `us-ascii` (subconjunto) o sin
+    /// charset. Los tipos que no son texto no se validan.
+    ///
+    /// # Ejemplo
+    /// ```
+    /// use http_server::http::mime::{CharsetError, MediaType};
+    ///
+    /// let json = MediaType::parse("application/json").unwrap();
+    /// assert!(json.check_body("ñandú".as_bytes()).is_ok());
+    /// assert_eq!(json.check_body(b"\xF1and\xFA"), Err(CharsetError::InvalidUtf8));
+    ///
+    /// let latin1 = MediaType::parse("text/plain; charset=iso-8859-1").unwrap();
+    /// assert!(latin1.check_body(b"abc").is_err());
+    /// ```
+    pub fn check_body(&self, body: &[u8]) -> Result<(), CharsetError> {
+        if !self.is_text() {
+            return Ok(());
+        }
+        match self.charset.as_deref() {
+            None | Some("utf-8") | Some("utf8") | Some("us-ascii") => {}
+            Some(other) => return Err(CharsetError::Unsupported(other.to_string())),
+        }
+        std::str::from_utf8(body).map(|_| ()).map_err(|_| CharsetError::InvalidUtf8)
+    }
+}
+
+/// `Content-Type` de un archivo según su extensión
+///
+/// Las extensiones desconocidas (o la falta de extensión) son
+/// `application/octet-stream`. Los tipos de texto llevan `charset=utf-8`.
+///
+/// # Ejemplo
+/// ```
+/// use http_server::http::mime;
+/// use std::path::Path;
+///
+/// assert_eq!(mime::for_path(Path::new("data/index.HTML")), "text/html; charset=utf-8");
+/// assert_eq!(mime::for_path(Path::new("logo.png")), "image/png");
+/// assert_eq!(mime::for_path(Path::new("README")), "application/octet-stream");
+/// ```
+pub fn for_path(path: &std::path::Path) -> &'static str {
+    let extension = path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase)
+        .unwrap_or_default();
+    match extension.as_str() {
+        "html" | "htm" => "text/html; charset=utf-8",
+        "css" => "text/css; charset=utf-8",
+        "js" | "mjs" => "text/javascript; charset=utf-8",
+        "json" => APPLICATION_JSON,
+        "txt" | "log" | "md" => TEXT_PLAIN,
+        "csv" => "text/csv; charset=utf-8",
+        "xml" => "application/xml",
+        "svg" => "image/svg+xml",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "ico" => "image/x-icon",
+        "pdf" => "application/pdf",
+        "wasm" => "application/wasm",
+        "gz" => "application/gzip",
+        "zip" => "application/zip",
+        "tar" => "application/x-tar",
+        _ => "application/octet-stream",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_media_types() {
+        let media = MediaType::parse(" text/html ;charset=ISO-8859-1; q=1").unwrap();
+        assert_eq!(media.essence, "text/html");
+        assert_eq!(media.charset.as_deref(), Some("iso-8859-1"));
+
+        let plain = MediaType::parse("application/octet-stream").unwrap();
+        assert_eq!(plain.charset, None);
+        assert!(!plain.is_text());
+
+        assert!(MediaType::parse("").is_none());
+        assert!(MediaType::parse("text/").is_none());
+        assert!(MediaType::parse("application/problem+json").unwrap().is_text());
+    }
+
+    #[test]
+    fn test_check_body() {
+        let invalid = b"caf\xE9";
+        let json = MediaType::parse("application/json; charset=utf8").unwrap();
+        assert!(json.check_body("café".as_bytes()).is_ok());
+        assert_eq!(json.check_body(invalid), Err(CharsetError::InvalidUtf8));
+
+        // Binarios y multipart no se validan
+        for binary in ["application/octet-stream", "multipart/form-data; boundary=x"] {
+            assert!(MediaType::parse(binary).unwrap().check_body(invalid).is_ok());
+        }
+
+        let utf16 = MediaType::parse("application/json; charset=utf-16").unwrap();
+        assert_eq!(utf16.check_body(b"{}"), Err(CharsetError::Unsupported("utf-16".to_string())));
+    }
+}
@@ -39,11 +39,24 @@
 // Vamos a implementarlos uno por uno
 
 pub mod request;   // Parsing de HTTP requests
+pub mod parser;    // Parser incremental (requests que llegan en pedazos)
+pub mod buffer_pool; // Buffers de lectura reusados entre conexiones
 pub mod response;  // Construcción de HTTP responses
+pub mod headers;   // Headers sin distinguir mayúsculas y con valores repetidos
 pub mod status;    // Códigos de estado HTTP
+pub mod redact;    // Redacción de secretos en logs y errores
+pub mod base64;    // Codificación base64 (Basic auth, WebSocket)
+pub mod encoding;  // Bodies comprimidos (Content-Encoding: gzip)
+pub mod multipart; // Bodies multipart/form-data (uploads)
+pub mod range;     // Header Range y respuestas 206 Partial Content
+pub mod sendfile;  // Bodies de archivo con sendfile(2)
+pub mod date;      // Fechas HTTP (Date, Last-Modified, If-Modified-Since)
+pub mod cache;     // Cache-Control / Expires por ruta
+pub mod mime;      // Content-Type con charset (bodies de texto en UTF-8)
 
 // Re-exportamos los tipos principales para facilitar su uso
 // Esto permite usar `http::Request` en vez de `http::request::Request`
 pub use request::Request;
-pub use response::Response;
+pub use response::{Response, ResponseBuilder};
+pub use headers::Headers;
 pub use status::StatusCode;
\ No newline at end of file
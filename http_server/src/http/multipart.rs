@@ -0,0 +1,243 @@
+//! # Bodies multipart/form-data
+//! src/http/multipart.rs
+//!
+//! Parser de `multipart/form-data` (RFC 7578) para subir archivos. Trabaja
+//! sobre el body ya leído (acotado por `--max-body-bytes`) y no copia los
+//! datos: cada `Part` apunta a su rango dentro del body, así el handler
+//! puede escribirlo a disco directamente.
+//!
+//! ```text
+//! --BOUNDARY\r\n
+//! Content-Disposition: form-data; name="file"; filename="a.txt"\r\n
+//! Content-Type: text/plain\r\n
+//! \r\n
+//! ...datos...\r\n
+//! --BOUNDARY--\r\n
+//! ```
+
+/// Máximo de partes en un body
+pub const MAX_PARTS: usize = 64;
+
+/// Máximo de bytes de los headers de una parte
+const MAX_PART_HEADER_BYTES: usize = 8192;
+
+/// Máximo largo de un boundary (RFC 2046)
+const MAX_BOUNDARY_LEN: usize = 70;
+
+/// Errores al parsear un body multipart
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MultipartError {
+    /// `Content-Type` no es `multipart/form-data`
+    NotMultipart,
+
+    /// Falta el parámetro `boundary` o es inválido
+    InvalidBoundary,
+
+    /// El body no respeta el formato
+    Malformed(String),
+
+    /// Más partes que `MAX_PARTS`
+    TooManyParts,
+}
+
+impl std::fmt::Display for MultipartError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            MultipartError::NotMultipart => write!(f, "Content-Type must be multipart/form-data"),
+            MultipartError::InvalidBoundary => write!(f, "Missing or invalid multipart boundary"),
+            MultipartError::Malformed(e) => write!(f, "Malformed multipart body: {}", e),
+            MultipartError::TooManyParts => write!(f, "Too many multipart parts (max: {})", MAX_PARTS),
+        }
+    }
+}
+
+impl std::error::Error for MultipartError {}
+
+/// Una parte del body
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Part<'a> {
+    /// Nombre del campo (`name=` de `Content-Disposition`)
+    pub name: String,
+
+    /// Nombre del archivo, solo en partes que son archivos
+    pub filename: Option<String>,
+
+    /// `Content-Type` de la parte, si lo trae
+    pub content_type: Option<String>,
+
+    /// Contenido de la parte
+    pub data: &'a [u8],
+}
+
+/// Extrae el boundary de un header `Content-Type: multipart/form-data; boundary=...`
+pub fn boundary(content_type: &str) -> Result<String, MultipartError> {
+    let mut params = content_type.split(';');
+    let media = params.next().unwrap_or("").trim();
+    if !media.eq_ignore_ascii_case("multipart/form-data") {
+        return Err(MultipartError::NotMultipart);
+    }
+
+    let boundary = params
+        .filter_map(|p| p.split_once('='))
+        .find(|(key, _)| key.trim().eq_ignore_ascii_case("boundary"))
+        .map(|(_, value)| value.trim().trim_matches('"').to_string())
+        .ok_or(MultipartError::InvalidBoundary)?;
+
+    if boundary.is_empty() || boundary.len() > MAX_BOUNDARY_LEN || boundary.ends_with(' ') {
+        return Err(MultipartError::InvalidBoundary);
+    }
+    Ok(boundary)
+}
+
+/// Separa `body` en sus partes
+pub fn parse<'a>(body: &'a [u8], boundary: &str) -> Result<Vec<Part<'a>>, MultipartError> {
+    let delimiter = format!("--{}", boundary).into_bytes();
+    let malformed = |message: &str| MultipartError::Malformed(message.to_string());
+
+    // El primer delimitador puede venir tras un preámbulo (que se ignora)
+    let first = find(body, &delimiter, 0).ok_or_else(|| malformed("missing opening boundary"))?;
+    if first > 0 && !body[..first].ends_with(b"\r\n") {
+        return Err(malformed("missing opening boundary"));
+    }
+
+    let mut next_delimiter = b"\r\n".to_vec();
+    next_delimiter.extend_from_slice(&delimiter);
+
+    let mut parts = Vec::new();
+    let mut pos = first + delimiter.len();
+    loop {
+        if body[pos..].starts_with(b"--") {
+            return Ok(parts);
+        }
+        if !body[pos..].starts_with(b"\r\n") {
+            return Err(malformed("expected CRLF after boundary"));
+        }
+        pos += 2;
+
+        if parts.len() == MAX_PARTS {
+            return Err(MultipartError::TooManyParts);
+        }
+
+        let header_end = find(&body[..body.len().min(pos + MAX_PART_HEADER_BYTES)], b"\r\n\r\n", pos)
+            .map(|i| i + 4)
+            .or_else(|| body[pos..].starts_with(b"\r\n").then_some(pos + 2))
+            .ok_or_else(|| malformed("part headers too large or incomplete"))?;
+        let data_end = find(body, &next_delimiter, header_end)
+            .ok_or_else(|| malformed("missing closing boundary"))?;
+
+        parts.push(part(&body[pos..header_end], &body[header_end..data_end])?);
+        pos = data_end + next_delimiter.len();
+    }
+}
+
+/// Arma una parte a partir de su bloque de headers y sus datos
+fn part<'a>(headers: &[u8], data: &'a [u8]) -> Result<Part<'a>, MultipartError> {
+    let headers = std::str::from_utf8(headers)
+        .map_err(|_| MultipartError::Malformed("part headers are not UTF-8".to_string()))?;
+
+    let mut disposition = None;
+    let mut content_type = None;
+    for line in headers.split("\r\n").filter(|l| !l.is_empty()) {
+        let (name, value) = line.split_once(':')
+            .ok_or_else(|| MultipartError::Malformed(format!("invalid part header: {}", line)))?;
+        if name.trim().eq_ignore_ascii_case("content-disposition") {
+            disposition = Some(value.trim());
+        } else if name.trim().eq_ignore_ascii_case("content-type") {
+            content_type = Some(value.trim().to_string());
+        }
+    }
+
+    let disposition = disposition
+        .ok_or_else(|| MultipartError::Malformed("part without Content-Disposition".to_string()))?;
+    let name = disposition_param(disposition, "name")
+        .ok_or_else(|| MultipartError::Malformed("part without name".to_string()))?;
+
+    Ok(Part {
+        name,
+        filename: disposition_param(disposition, "filename"),
+        content_type,
+        data,
+    })
+}
+
+/// Valor de un parámetro de `Content-Disposition` (con o sin comillas)
+fn disposition_param(disposition: &str, key: &str) -> Option<String> {
+    disposition.split(';').skip(1).find_map(|param| {
+        let (name, value) = param.split_once('=')?;
+        if !name.trim().eq_ignore_ascii_case(key) {
+            return None;
+        }
+        let value = value.trim();
+        let value = value.strip_prefix('"').and_then(|v| v.strip_suffix('"')).unwrap_or(value);
+        Some(value.replace("\\\"", "\""))
+    })
+}
+
+/// Posición de `needle` en `haystack` a partir de `from`
+fn find(haystack: &[u8], needle: &[u8], from: usize) -> Option<usize> {
+    if from > haystack.len() {
+        return None;
+    }
+    haystack[from..].windows(needle.len()).position(|w| w == needle).map(|i| i + from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BODY: &[u8] = b"preamble\r\n--XyZ\r\n\
+Content-Disposition: form-data; name=\"note\"\r\n\r\n\
+hola\r\n--XyZ\r\n\
+Content-Disposition: form-data; name=\"file\"; filename=\"a b.txt\"\r\n\
+Content-Type: text/plain\r\n\r\n\
+line 1\r\nline 2 --XyZ\r\n\r\n--XyZ--\r\n";
+
+    #[test]
+    fn test_boundary() {
+        assert_eq!(boundary("multipart/form-data; boundary=XyZ").unwrap(), "XyZ");
+        assert_eq!(boundary("Multipart/Form-Data; charset=utf-8; Boundary=\"a b\"").unwrap(), "a b");
+        assert_eq!(boundary("application/json"), Err(MultipartError::NotMultipart));
+        assert_eq!(boundary("multipart/form-data"), Err(MultipartError::InvalidBoundary));
+        assert_eq!(boundary("multipart/form-data; boundary="), Err(MultipartError::InvalidBoundary));
+    }
+
+    #[test]
+    fn test_parse_fields_and_files() {
+        let parts = parse(BODY, "XyZ").unwrap();
+        assert_eq!(parts.len(), 2);
+
+        assert_eq!(parts[0].name, "note");
+        assert_eq!(parts[0].filename, None);
+        assert_eq!(parts[0].data, b"hola");
+
+        assert_eq!(parts[1].name, "file");
+        assert_eq!(parts[1].filename.as_deref(), Some("a b.txt"));
+        assert_eq!(parts[1].content_type.as_deref(), Some("text/plain"));
+        // El boundary sin CRLF delante es parte de los datos
+        assert_eq!(parts[1].data, b"line 1\r\nline 2 --XyZ\r\n");
+    }
+
+    #[test]
+    fn test_parse_empty_and_malformed() {
+        assert_eq!(parse(b"--XyZ--\r\n", "XyZ").unwrap(), vec![]);
+        assert!(matches!(parse(b"no boundary here", "XyZ"), Err(MultipartError::Malformed(_))));
+        assert!(matches!(
+            parse(b"--XyZ\r\nContent-Disposition: form-data; name=\"a\"\r\n\r\nunterminated", "XyZ"),
+            Err(MultipartError::Malformed(_))
+        ));
+        assert!(matches!(
+            parse(b"--XyZ\r\nContent-Type: text/plain\r\n\r\nx\r\n--XyZ--", "XyZ"),
+            Err(MultipartError::Malformed(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_limits_part_count() {
+        let mut body = Vec::new();
+        for i in 0..=MAX_PARTS {
+            body.extend(format!("--b\r\nContent-Disposition: form-data; name=\"f{}\"\r\n\r\nx\r\n", i).bytes());
+        }
+        body.extend(b"--b--");
+        assert_eq!(parse(&body, "b"), Err(MultipartError::TooManyParts));
+    }
+}
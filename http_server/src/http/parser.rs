@@ -0,0 +1,377 @@
+//! # Parser incremental de requests
+//! src/http/parser.rs
+//!
+//! `Request::parse` necesita el request completo en un solo buffer. Este
+//! parser en cambio recibe los bytes a medida que llegan del socket:
+//!
+//! ```text
+//! feed(b"GET / HT")          → NeedMoreData
+//! feed(b"TP/1.0\r\n\r\n")    → Complete(request)
+//! ```
+//!
+//! - Los límites (`ParseLimits`) se aplican mientras se lee: una request
+//!   line, un header o un bloque de headers demasiado largos, o demasiados
+//!   headers, se rechazan sin esperar el resto.
+//! - El body se lee según `Content-Length`; sin ese header el request no
+//!   tiene body.
+//! - Los bytes que sobran tras un request quedan en el buffer: son el
+//!   siguiente request de una conexión con pipelining (`next_request`).
+//! - Con `RequestParser::pooled` el buffer sale de un `BufferPool` y vuelve
+//!   a él cuando el parser se descarta.
+
+use super::buffer_pool::BufferPool;
+use super::request::{Method, ParseError, Request};
+use std::sync::Arc;
+
+/// Límites de tamaño de un request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseLimits {
+    /// Máximo de bytes de la request line
+    pub request_line: usize,
+
+    /// Máximo de bytes de los headers, sin la request line
+    pub headers: usize,
+
+    /// Máximo de headers (líneas) por request
+    pub header_count: usize,
+
+    /// Máximo de bytes de una línea de header (`Nombre: valor`)
+    pub header_line: usize,
+
+    /// Máximo de bytes del body según `Content-Length`
+    pub body: u64,
+}
+
+impl Default for ParseLimits {
+    fn default() -> Self {
+        Self {
+            request_line: 8192,
+            headers: 8192,
+            header_count: 100,
+            header_line: 4096,
+            body: 1024 * 1024,
+        }
+    }
+}
+
+/// Resultado de alimentar el parser
+#[derive(Debug)]
+pub enum ParseStatus {
+    /// Falta recibir parte del request
+    NeedMoreData,
+
+    /// Request completo (headers + body según `Content-Length`)
+    Complete(Box<Request>),
+}
+
+/// Request con los headers ya parseados, esperando su body
+#[derive(Debug)]
+struct PendingBody {
+    request: Request,
+    head_len: usize,
+    content_length: usize,
+}
+
+/// Parser incremental de requests HTTP/1.0
+///
+/// # Ejemplo
+/// ```
+/// use http_server::http::parser::{ParseLimits, ParseStatus, RequestParser};
+///
+/// let mut parser = RequestParser::new(ParseLimits::default());
+/// assert!(matches!(parser.feed(b"POST /echo HTTP/1.0\r\nContent-Le").unwrap(), ParseStatus::NeedMoreData));
+/// assert!(matches!(parser.feed(b"ngth: 2\r\n\r\no").unwrap(), ParseStatus::NeedMoreData));
+///
+/// let ParseStatus::Complete(request) = parser.feed(b"k").unwrap() else { panic!() };
+/// assert_eq!(request.body(), b"ok");
+/// ```
+#[derive(Debug)]
+pub struct RequestParser {
+    limits: ParseLimits,
+    buffer: Vec<u8>,
+    pending: Option<PendingBody>,
+    pool: Option<Arc<BufferPool>>,
+}
+
+impl RequestParser {
+    /// Crea un parser vacío con los límites dados
+    pub fn new(limits: ParseLimits) -> Self {
+        Self {
+            limits,
+            buffer: Vec::with_capacity(8192),
+            pending: None,
+            pool: None,
+        }
+    }
+
+    /// Parser cuyo buffer se pide a `pool` y se devuelve al descartarlo
+    ///
+    /// # Ejemplo
+    /// ```
+    /// use http_server::http::buffer_pool::BufferPool;
+    /// use http_server::http::parser::{ParseLimits, RequestParser};
+    /// use std::sync::Arc;
+    ///
+    /// let pool = Arc::new(BufferPool::new(4));
+    /// let mut parser = RequestParser::pooled(ParseLimits::default(), &pool);
+    /// parser.feed(b"GET / HTTP/1.0\r\n\r\n").unwrap();
+    /// drop(parser);
+    /// assert_eq!(pool.stats().pooled, 1);
+    /// ```
+    pub fn pooled(limits: ParseLimits, pool: &Arc<BufferPool>) -> Self {
+        Self {
+            limits,
+            buffer: pool.take(),
+            pending: None,
+            pool: Some(Arc::clone(pool)),
+        }
+    }
+
+    /// Agrega bytes recibidos e intenta completar el request actual
+    ///
+    /// Tras un error el parser queda en un estado indefinido: la conexión
+    /// debe responder el error y cerrarse.
+    pub fn feed(&mut self, data: &[u8]) -> Result<ParseStatus, ParseError> {
+        self.buffer.extend_from_slice(data);
+        self.next_request()
+    }
+
+    /// Intenta completar un request con los bytes que ya están en el buffer
+    ///
+    /// Después de un `Complete`, permite obtener el siguiente request
+    /// pipelined sin leer más del socket.
+    pub fn next_request(&mut self) -> Result<ParseStatus, ParseError> {
+        if self.pending.is_none() {
+            let Some(pending) = self.parse_head()? else {
+                return Ok(ParseStatus::NeedMoreData);
+            };
+            self.pending = Some(pending);
+        }
+
+        let Some(pending) = self.pending.take_if(|p| self.buffer.len() - p.head_len >= p.content_length) else {
+            return Ok(ParseStatus::NeedMoreData);
+        };
+        let PendingBody { mut request, head_len, content_length } = pending;
+        let end = head_len + content_length;
+        request.set_body(&self.buffer[head_len..end]);
+        self.buffer.drain(..end);
+        Ok(ParseStatus::Complete(Box::new(request)))
+    }
+
+    /// Termina el request actual cuando el cliente cierra la conexión
+    ///
+    /// Retorna `Ok(None)` si no quedó nada por parsear. Un request sin la
+    /// línea vacía final se parsea igual (como `Request::parse`); uno con el
+    /// body incompleto es un error.
+    pub fn finish(&mut self) -> Result<Option<Request>, ParseError> {
+        if self.pending.take().is_some() {
+            return Err(ParseError::BodyTooShort);
+        }
+        if self.buffer.is_empty() {
+            return Ok(None);
+        }
+        let parsed = Request::parse(&self.buffer);
+        self.buffer.clear();
+        parsed.map(Some)
+    }
+
+    /// Indica si ya llegaron la request line y los headers del request actual
+    pub fn head_complete(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    /// Bytes recibidos que todavía no forman parte de un request completo
+    pub fn buffered(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Parsea la request line y los headers si ya llegaron completos
+    fn parse_head(&self) -> Result<Option<PendingBody>, ParseError> {
+        let buffer = &self.buffer;
+        let line_end = buffer.windows(2).position(|w| w == b"\r\n");
+        if line_end.unwrap_or(buffer.len()) > self.limits.request_line {
+            return Err(ParseError::RequestLineTooLong(self.limits.request_line));
+        }
+        let Some(line_end) = line_end else {
+            return Ok(None);
+        };
+
+        // Headers: desde el fin de la request line hasta la línea vacía (sin
+        // contarla). Si aún no llegó, lo leído puede incluir su primer `\r`
+        let head_end = buffer.windows(4).position(|w| w == b"\r\n\r\n").map(|i| i + 4);
+        let header_bytes = match head_end {
+            Some(end) => end - line_end - 4,
+            None => (buffer.len() - line_end - 2).saturating_sub(1),
+        };
+        if header_bytes > self.limits.headers {
+            return Err(ParseError::HeadersTooLarge(self.limits.headers));
+        }
+        let headers_start = line_end + 2;
+        let headers_end = head_end.map_or(buffer.len(), |end| (end - 4).max(headers_start));
+        self.check_header_lines(&buffer[headers_start..headers_end])?;
+        let Some(head_len) = head_end else {
+            return Ok(None);
+        };
+
+        let request = Request::parse_head(&buffer[..head_len])?;
+        let content_length = match Self::content_length(&request)? {
+            Some(length) if length > self.limits.body => {
+                return Err(ParseError::BodyTooLarge { length, max: self.limits.body });
+            }
+            Some(length) => length as usize,
+            // Sin Content-Length, lo que sigue a un GET es el próximo request;
+            // en un POST sería un body sin largo conocido
+            None if request.method() == Method::POST && buffer.len() > head_len => {
+                return Err(ParseError::MissingContentLength);
+            }
+            None => 0,
+        };
+
+        Ok(Some(PendingBody { request, head_len, content_length }))
+    }
+
+    /// Cuenta los headers recibidos (incluido uno a medias) y mide cada línea
+    fn check_header_lines(&self, headers: &[u8]) -> Result<(), ParseError> {
+        let mut count = 0;
+        for line in headers.split(|&b| b == b'\n') {
+            let line = line.strip_suffix(b"\r").unwrap_or(line);
+            if line.len() > self.limits.header_line {
+                return Err(ParseError::HeaderLineTooLong(self.limits.header_line));
+            }
+            if !line.is_empty() {
+                count += 1;
+            }
+            if count > self.limits.header_count {
+                return Err(ParseError::TooManyHeaders(self.limits.header_count));
+            }
+        }
+        Ok(())
+    }
+
+    /// `Content-Length` declarado; si se repite, todos deben coincidir
+    fn content_length(request: &Request) -> Result<Option<u64>, ParseError> {
+        let mut declared = None;
+        for value in request.header_values("Content-Length") {
+            let length = value.parse::<u64>()
+                .map_err(|_| ParseError::InvalidContentLength(value.to_string()))?;
+            if declared.is_some_and(|previous| previous != length) {
+                return Err(ParseError::InvalidContentLength(value.to_string()));
+            }
+            declared = Some(length);
+        }
+        Ok(declared)
+    }
+}
+
+impl Drop for RequestParser {
+    fn drop(&mut self) {
+        if let Some(pool) = &self.pool {
+            pool.give_back(std::mem::take(&mut self.buffer));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Alimenta `raw` de a `step` bytes hasta obtener un request o un error
+    fn feed_in_steps(raw: &[u8], step: usize, limits: ParseLimits) -> Result<Option<Request>, ParseError> {
+        let mut parser = RequestParser::new(limits);
+        for chunk in raw.chunks(step) {
+            if let ParseStatus::Complete(request) = parser.feed(chunk)? {
+                return Ok(Some(*request));
+            }
+        }
+        parser.finish()
+    }
+
+    #[test]
+    fn test_request_split_across_feeds() {
+        let raw = b"POST /echo HTTP/1.0\r\nContent-Length: 10\r\n\r\n0123456789";
+        for step in [1, 3, 7, 64] {
+            let request = feed_in_steps(raw, step, ParseLimits::default()).unwrap().unwrap();
+            assert_eq!(request.path(), "/echo");
+            assert_eq!(request.body(), b"0123456789", "step {}", step);
+        }
+    }
+
+    #[test]
+    fn test_pipelined_requests() {
+        let mut parser = RequestParser::new(ParseLimits::default());
+        let raw = b"GET /a HTTP/1.0\r\n\r\nPOST /b HTTP/1.0\r\nContent-Length: 2\r\n\r\nhiGET /c HT";
+
+        let ParseStatus::Complete(first) = parser.feed(raw).unwrap() else { panic!("GET /a") };
+        assert_eq!(first.path(), "/a");
+        let ParseStatus::Complete(second) = parser.next_request().unwrap() else { panic!("POST /b") };
+        assert_eq!((second.path(), second.body()), ("/b", &b"hi"[..]));
+        assert!(matches!(parser.next_request().unwrap(), ParseStatus::NeedMoreData));
+        assert_eq!(parser.buffered(), 9);
+
+        let ParseStatus::Complete(third) = parser.feed(b"TP/1.0\r\n\r\n").unwrap() else { panic!("GET /c") };
+        assert_eq!(third.path(), "/c");
+        assert!(matches!(parser.finish(), Ok(None)));
+    }
+
+    #[test]
+    fn test_limits_are_enforced_before_the_request_ends() {
+        let limits = ParseLimits { request_line: 32, headers: 64, body: 1024, ..ParseLimits::default() };
+        let long_line = format!("GET /{} HTTP/1.0\r\n\r\n", "a".repeat(40));
+        let long_headers = format!("GET / HTTP/1.0\r\nX-Fill: {}\r\n\r\n", "b".repeat(60));
+        let at_limit = format!("GET /{} HTTP/1.0\r\nX-Fill: {}\r\n\r\n", "a".repeat(17), "b".repeat(54));
+
+        for step in [1, 5, 4096] {
+            assert_eq!(feed_in_steps(&long_line.as_bytes()[..36], step, limits).unwrap_err(), ParseError::RequestLineTooLong(32));
+            assert_eq!(feed_in_steps(&long_headers.as_bytes()[..84], step, limits).unwrap_err(), ParseError::HeadersTooLarge(64));
+            assert!(feed_in_steps(at_limit.as_bytes(), step, limits).is_ok(), "step {}", step);
+        }
+
+        let big_body = b"POST / HTTP/1.0\r\nContent-Length: 2048\r\n\r\n";
+        assert_eq!(
+            feed_in_steps(big_body, 4096, limits).unwrap_err(),
+            ParseError::BodyTooLarge { length: 2048, max: 1024 }
+        );
+        assert_eq!(ParseError::BodyTooLarge { length: 2048, max: 1024 }.status(), crate::http::StatusCode::PayloadTooLarge);
+    }
+
+    #[test]
+    fn test_header_count_and_line_limits() {
+        let limits = ParseLimits { header_count: 3, header_line: 24, ..ParseLimits::default() };
+        let many = format!("GET / HTTP/1.0\r\n{}\r\n", "A: 1\r\n".repeat(4));
+        let long_line = format!("GET / HTTP/1.0\r\nX-Long: {}\r\n\r\n", "x".repeat(20));
+        let at_limit = format!("GET / HTTP/1.0\r\n{}X-Long: {}\r\n\r\n", "A: 1\r\n".repeat(2), "x".repeat(16));
+
+        for step in [1, 5, 4096] {
+            // El cuarto header se rechaza apenas empieza a llegar
+            assert_eq!(feed_in_steps(&many.as_bytes()[..35], step, limits).unwrap_err(), ParseError::TooManyHeaders(3));
+            assert_eq!(feed_in_steps(&long_line.as_bytes()[..42], step, limits).unwrap_err(), ParseError::HeaderLineTooLong(24));
+            assert!(feed_in_steps(at_limit.as_bytes(), step, limits).is_ok(), "step {}", step);
+        }
+        assert_eq!(ParseError::TooManyHeaders(3).status(), crate::http::StatusCode::RequestHeaderFieldsTooLarge);
+        assert_eq!(ParseError::RequestLineTooLong(32).status(), crate::http::StatusCode::UriTooLong);
+    }
+
+    #[test]
+    fn test_body_framing_errors() {
+        let limits = ParseLimits::default();
+        let framing = |raw: &[u8]| feed_in_steps(raw, 4096, limits).unwrap_err();
+
+        assert_eq!(framing(b"POST / HTTP/1.0\r\nContent-Length: 10\r\n\r\nabc"), ParseError::BodyTooShort);
+        assert_eq!(framing(b"POST / HTTP/1.0\r\n\r\nabcd"), ParseError::MissingContentLength);
+        assert_eq!(
+            framing(b"POST / HTTP/1.0\r\nContent-Length: lots\r\n\r\n"),
+            ParseError::InvalidContentLength("lots".to_string())
+        );
+        assert_eq!(
+            framing(b"POST / HTTP/1.0\r\nContent-Length: 1\r\ncontent-length: 2\r\n\r\nab"),
+            ParseError::InvalidContentLength("2".to_string())
+        );
+    }
+
+    #[test]
+    fn test_finish_parses_request_without_blank_line() {
+        let mut parser = RequestParser::new(ParseLimits::default());
+        assert!(matches!(parser.feed(b"GET /status HTTP/1.0\r\n").unwrap(), ParseStatus::NeedMoreData));
+        assert_eq!(parser.finish().unwrap().unwrap().path(), "/status");
+    }
+}
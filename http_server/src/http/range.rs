@@ -0,0 +1,157 @@
+//! # Requests con Range
+//! src/http/range.rs
+//!
+//! Parser del header `Range` (RFC 9110, sección 14) para responder una parte
+//! de un archivo con `206 Partial Content`. Solo se soporta un rango de bytes
+//! por request:
+//!
+//! ```text
+//! Range: bytes=0-499     → los primeros 500 bytes
+//! Range: bytes=500-      → desde el byte 500 hasta el final
+//! Range: bytes=-500      → los últimos 500 bytes
+//! ```
+//!
+//! Un header con otra unidad, mal formado o con varios rangos se ignora y se
+//! responde el archivo completo, como permite el RFC. Un rango que no se
+//! solapa con el archivo es `416 Range Not Satisfiable`.
+
+/// Rango de bytes a responder (ambos extremos inclusive)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ByteRange {
+    /// Primer byte del rango
+    pub start: u64,
+
+    /// Último byte del rango
+    pub end: u64,
+}
+
+impl ByteRange {
+    /// Cantidad de bytes del rango
+    pub fn size(&self) -> u64 {
+        self.end - self.start + 1
+    }
+
+    /// Valor del header `Content-Range` para un archivo de `total` bytes
+    ///
+    /// # Ejemplo
+    /// ```
+    /// use http_server::http::range::ByteRange;
+    ///
+    /// let range = ByteRange { start: 0, end: 9 };
+    /// assert_eq!(range.content_range(100), "bytes 0-9/100");
+    /// ```
+    pub fn content_range(&self, total: u64) -> String {
+        format!("bytes {}-{}/{}", self.start, self.end, total)
+    }
+}
+
+/// El rango pedido no se solapa con el archivo (416)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Unsatisfiable;
+
+impl std::fmt::Display for Unsatisfiable {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "Requested range not satisfiable")
+    }
+}
+
+impl std::error::Error for Unsatisfiable {}
+
+/// Interpreta un header `Range` para un archivo de `total` bytes
+///
+/// Retorna `Ok(None)` si el header debe ignorarse (se responde completo).
+/// El final del rango se recorta al tamaño del archivo.
+///
+/// # Ejemplo
+/// ```
+/// use http_server::http::range::{self, ByteRange};
+///
+/// assert_eq!(range::parse("bytes=-10", 100), Ok(Some(ByteRange { start: 90, end: 99 })));
+/// assert_eq!(range::parse("items=0-1", 100), Ok(None));
+/// assert!(range::parse("bytes=200-", 100).is_err());
+/// ```
+pub fn parse(header: &str, total: u64) -> Result<Option<ByteRange>, Unsatisfiable> {
+    let Some((unit, spec)) = header.trim().split_once('=') else {
+        return Ok(None);
+    };
+    if !unit.trim().eq_ignore_ascii_case("bytes") || spec.contains(',') {
+        return Ok(None);
+    }
+    let Some((first, last)) = spec.trim().split_once('-') else {
+        return Ok(None);
+    };
+
+    let parse_pos = |s: &str| -> Option<u64> {
+        if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        s.parse().ok()
+    };
+
+    let range = match (first.trim(), last.trim()) {
+        // bytes=-N: sufijo de N bytes
+        ("", suffix) => {
+            let Some(n) = parse_pos(suffix) else { return Ok(None) };
+            if n == 0 || total == 0 {
+                return Err(Unsatisfiable);
+            }
+            ByteRange { start: total.saturating_sub(n), end: total - 1 }
+        }
+        // bytes=N-: desde N hasta el final
+        (start, "") => {
+            let Some(start) = parse_pos(start) else { return Ok(None) };
+            if start >= total {
+                return Err(Unsatisfiable);
+            }
+            ByteRange { start, end: total - 1 }
+        }
+        // bytes=N-M
+        (start, end) => {
+            let (Some(start), Some(end)) = (parse_pos(start), parse_pos(end)) else {
+                return Ok(None);
+            };
+            if start > end {
+                return Ok(None);
+            }
+            if start >= total {
+                return Err(Unsatisfiable);
+            }
+            ByteRange { start, end: end.min(total - 1) }
+        }
+    };
+    Ok(Some(range))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ranges() {
+        assert_eq!(parse("bytes=0-499", 1000), Ok(Some(ByteRange { start: 0, end: 499 })));
+        assert_eq!(parse("bytes=500-", 1000), Ok(Some(ByteRange { start: 500, end: 999 })));
+        assert_eq!(parse("bytes=-200", 1000), Ok(Some(ByteRange { start: 800, end: 999 })));
+        assert_eq!(parse(" Bytes = 10 - 19 ", 1000), Ok(Some(ByteRange { start: 10, end: 19 })));
+
+        // El final y los sufijos se recortan al tamaño del archivo
+        assert_eq!(parse("bytes=900-5000", 1000), Ok(Some(ByteRange { start: 900, end: 999 })));
+        assert_eq!(parse("bytes=-5000", 1000), Ok(Some(ByteRange { start: 0, end: 999 })));
+        assert_eq!(parse("bytes=0-0", 1000).unwrap().unwrap().size(), 1);
+    }
+
+    #[test]
+    fn test_parse_ignored_headers() {
+        for header in ["", "bytes", "items=0-1", "bytes=0-1,5-6", "bytes=abc", "bytes=5-1", "bytes=-", "bytes=+1-2"] {
+            assert_eq!(parse(header, 1000), Ok(None), "{}", header);
+        }
+    }
+
+    #[test]
+    fn test_parse_unsatisfiable() {
+        assert_eq!(parse("bytes=1000-", 1000), Err(Unsatisfiable));
+        assert_eq!(parse("bytes=1000-2000", 1000), Err(Unsatisfiable));
+        assert_eq!(parse("bytes=-0", 1000), Err(Unsatisfiable));
+        assert_eq!(parse("bytes=0-", 0), Err(Unsatisfiable));
+        assert_eq!(parse("bytes=-10", 0), Err(Unsatisfiable));
+    }
+}
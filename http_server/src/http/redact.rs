@@ -0,0 +1,139 @@
+//! # Redacción de Secretos
+//! src/http/redact.rs
+//!
+//! Evita que credenciales, llaves y contenidos de archivos terminen en los
+//! logs o en los mensajes de error. Un parámetro es sensible si su nombre
+//! contiene alguno de los marcadores (`password`, `token`, `key`...) o si
+//! lleva contenido del usuario (`content`, `text`, `data`); su valor se
+//! reemplaza por `***`.
+//!
+//! ```
+//! use http_server::http::redact;
+//! assert_eq!(redact::redact_query("n=7&api_key=abc"), "n=7&api_key=***");
+//! ```
+
+use regex::Regex;
+use std::panic;
+use std::sync::OnceLock;
+
+/// Texto que reemplaza los valores redactados
+pub const REDACTED: &str = "***";
+
+/// Fragmentos de nombre que marcan un parámetro como credencial o llave
+const SECRET_MARKERS: &[&str] = &["password", "passwd", "secret", "token", "key", "auth", "credential"];
+
+/// Parámetros cuyo valor es contenido del usuario (ej: `/createfile?content=`)
+const CONTENT_PARAMS: &[&str] = &["content", "text", "data", "body"];
+
+/// Indica si el valor del parámetro `name` no debe mostrarse
+pub fn is_sensitive_param(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    SECRET_MARKERS.iter().any(|marker| name.contains(marker))
+        || CONTENT_PARAMS.contains(&name.as_str())
+}
+
+/// Redacta los valores sensibles de un query string (`a=1&token=x`)
+pub fn redact_query(query: &str) -> String {
+    query.split('&')
+        .map(|pair| match pair.split_once('=') {
+            Some((name, _)) if is_sensitive_param(name) => format!("{}={}", name, REDACTED),
+            _ => pair.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+/// Redacta los valores sensibles que aparezcan en un texto libre
+///
+/// Reconoce `nombre=valor`, `nombre: valor` y `"nombre": "valor"` (JSON),
+/// además del header `Authorization`. Se usa para mensajes de error y de
+/// panic, que pueden incluir parámetros del request.
+pub fn redact_text(text: &str) -> String {
+    static PAIRS: OnceLock<Regex> = OnceLock::new();
+    static AUTHORIZATION: OnceLock<Regex> = OnceLock::new();
+
+    let authorization = AUTHORIZATION.get_or_init(|| {
+        Regex::new(r"(?i)(authorization\b\s*:?\s*)[^\r\n]+").unwrap()
+    });
+    let pairs = PAIRS.get_or_init(|| {
+        Regex::new(r#"([A-Za-z_][A-Za-z0-9_\-]*)("?\s*[=:]\s*"?)([^&\s",}]+)"#).unwrap()
+    });
+
+    let text = authorization.replace_all(text, format!("${{1}}{}", REDACTED));
+    pairs.replace_all(&text, |caps: &regex::Captures| {
+        if is_sensitive_param(&caps[1]) {
+            format!("{}{}{}", &caps[1], &caps[2], REDACTED)
+        } else {
+            caps[0].to_string()
+        }
+    }).into_owned()
+}
+
+/// Instala un panic hook que redacta el mensaje antes de imprimirlo
+///
+/// El hook por defecto escribe el mensaje tal cual en stderr, y los
+/// handlers pueden hacer panic con parámetros del request en el mensaje.
+pub fn install_panic_hook() {
+    panic::set_hook(Box::new(|info| {
+        let thread = std::thread::current();
+        let location = info.location()
+            .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column()))
+            .unwrap_or_else(|| "unknown location".to_string());
+        let message = crate::workers::panic_message(info.payload());
+        eprintln!(
+            "thread '{}' panicked at {}:\n{}",
+            thread.name().unwrap_or("<unnamed>"),
+            location,
+            redact_text(&message)
+        );
+    }));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_sensitive_param() {
+        assert!(is_sensitive_param("password"));
+        assert!(is_sensitive_param("API_KEY"));
+        assert!(is_sensitive_param("encryption_key"));
+        assert!(is_sensitive_param("access_token"));
+        assert!(is_sensitive_param("content"));
+        assert!(!is_sensitive_param("name"));
+        assert!(!is_sensitive_param("n"));
+        assert!(!is_sensitive_param("contents_dir"));
+    }
+
+    #[test]
+    fn test_redact_query() {
+        assert_eq!(
+            redact_query("name=a.txt&content=hello&repeat=2"),
+            "name=a.txt&content=***&repeat=2"
+        );
+        assert_eq!(redact_query("token=abc&flag"), "token=***&flag");
+        assert_eq!(redact_query(""), "");
+    }
+
+    #[test]
+    fn test_redact_text() {
+        assert_eq!(
+            redact_text("bad request /createfile?name=x&content=top-secret"),
+            "bad request /createfile?name=x&content=***"
+        );
+        assert_eq!(
+            redact_text(r#"invalid params {"api_key": "sk-123", "n": 7}"#),
+            r#"invalid params {"api_key": "***", "n": 7}"#
+        );
+        assert_eq!(redact_text("key: hunter2 at line 3"), "key: *** at line 3");
+        assert_eq!(
+            redact_text("Invalid header: Authorization: Basic YWRtaW46eA=="),
+            "Invalid header: Authorization: ***"
+        );
+        assert_eq!(
+            redact_text("Invalid header: Authorization Basic YWRtaW46eA=="),
+            "Invalid header: Authorization ***"
+        );
+        assert_eq!(redact_text("n=abc is not a number"), "n=abc is not a number");
+    }
+}
@@ -19,7 +19,12 @@
 //! 3. **Empty Line**: `\r\n` que separa headers del body
 //! 4. **Body**: (Opcional, no usado en GET)
 
+use crate::http::date;
+use crate::http::{Headers, StatusCode};
+use crate::http::encoding::{self, DecodeError};
+use crate::http::mime::{CharsetError, MediaType};
 use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 /// Métodos HTTP soportados
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -27,11 +32,14 @@ pub enum Method {
     /// GET - Obtener un recurso
     GET,
     
-    /// HEAD - Como GET pero solo retorna headers (opcional en el proyecto)
+    /// HEAD - Como GET pero solo retorna headers (el servidor quita el body al escribir)
     HEAD,
 
     /// POST - Enviar datos a un recurso
     POST,
+
+    /// OPTIONS - Métodos que admite un recurso (y preflight de CORS)
+    OPTIONS,
 }
 
 impl Method {
@@ -45,6 +53,7 @@ impl Method {
             "GET" => Ok(Method::GET),
             "HEAD" => Ok(Method::HEAD),
             "POST" => Ok(Method::POST),
+            "OPTIONS" => Ok(Method::OPTIONS),
             _ => Err(ParseError::UnsupportedMethod(s.to_string())),
         }
     }
@@ -55,6 +64,7 @@ impl Method {
             Method::GET => "GET",
             Method::HEAD => "HEAD",
             Method::POST => "POST",
+            Method::OPTIONS => "OPTIONS",
         }
     }
 }
@@ -62,23 +72,32 @@ impl Method {
 /// Representa un request HTTP/1.0 parseado
 #[derive(Debug, Clone)]
 pub struct Request {
-    /// Método HTTP (GET, HEAD, POST)
+    /// Método HTTP (GET, HEAD, POST, OPTIONS)
     method: Method,
     
+    /// Target tal como llegó en la request line (ej: "/fibonacci?num=10")
+    target: String,
+    
     /// Path de la petición (ej: "/fibonacci")
     path: String,
     
     /// Query parameters parseados (ej: {"num": "10"})
     query_params: HashMap<String, String>,
     
-    /// Headers HTTP (ej: {"Host": "localhost:8080"})
-    headers: HashMap<String, String>,
+    /// Headers HTTP (ej: `Host: localhost:8080`), en el orden en que llegaron
+    headers: Headers,
     
     /// Versión HTTP (debe ser "HTTP/1.0")
     version: String,
     
     /// Body del request para métodos POST
     body: Vec<u8>,
+    
+    /// Id que le asignó el servidor (el mismo de `X-Request-Id`)
+    id: Option<String>,
+    
+    /// Segmentos variables del path según la ruta (ej: `/jobs/:id` → id)
+    path_params: Vec<(String, String)>,
 }
 
 /// Errores que pueden ocurrir durante el parsing
@@ -101,6 +120,30 @@ pub enum ParseError {
     
     /// Request vacío
     EmptyRequest,
+    
+    /// Request line más larga que el máximo en bytes
+    RequestLineTooLong(usize),
+    
+    /// Headers más grandes que el máximo en bytes (sin la request line)
+    HeadersTooLarge(usize),
+    
+    /// Más headers que el máximo permitido
+    TooManyHeaders(usize),
+    
+    /// Una línea de header más larga que el máximo en bytes
+    HeaderLineTooLong(usize),
+    
+    /// `Content-Length` mayor que el máximo de body
+    BodyTooLarge { length: u64, max: u64 },
+    
+    /// `Content-Length` que no es un número (o repetido con valores distintos)
+    InvalidContentLength(String),
+    
+    /// POST con body pero sin `Content-Length`
+    MissingContentLength,
+    
+    /// La conexión se cerró antes de recibir `Content-Length` bytes de body
+    BodyTooShort,
 }
 
 impl std::fmt::Display for ParseError {
@@ -112,12 +155,53 @@ impl std::fmt::Display for ParseError {
             ParseError::InvalidHttpVersion(v) => write!(f, "Invalid HTTP version: {}", v),
             ParseError::InvalidHeader(h) => write!(f, "Invalid header: {}", h),
             ParseError::EmptyRequest => write!(f, "Empty request"),
+            ParseError::RequestLineTooLong(max) => write!(f, "Request line too long (max: {} bytes)", max),
+            ParseError::HeadersTooLarge(max) => write!(f, "Request headers too large (max: {} bytes)", max),
+            ParseError::TooManyHeaders(max) => write!(f, "Too many request headers (max: {})", max),
+            ParseError::HeaderLineTooLong(max) => write!(f, "Request header line too long (max: {} bytes)", max),
+            ParseError::BodyTooLarge { length, max } => {
+                write!(f, "Request body too large: {} bytes (max: {})", length, max)
+            }
+            ParseError::InvalidContentLength(v) => write!(f, "Invalid Content-Length: {}", v),
+            ParseError::MissingContentLength => write!(f, "Request body requires a Content-Length header"),
+            ParseError::BodyTooShort => write!(f, "Request body shorter than Content-Length"),
         }
     }
 }
 
 impl std::error::Error for ParseError {}
 
+impl ParseError {
+    /// Código con el que se responde el error
+    /// 
+    /// Los límites de tamaño son 414 (request line), 431 (headers) o 413
+    /// (body); el resto son requests malformados (400).
+    pub fn status(&self) -> StatusCode {
+        match self {
+            ParseError::RequestLineTooLong(_) => StatusCode::UriTooLong,
+            ParseError::HeadersTooLarge(_)
+                | ParseError::TooManyHeaders(_)
+                | ParseError::HeaderLineTooLong(_) => StatusCode::RequestHeaderFieldsTooLarge,
+            ParseError::BodyTooLarge { .. } => StatusCode::PayloadTooLarge,
+            _ => StatusCode::BadRequest,
+        }
+    }
+    
+    /// Indica si el error es de sintaxis (request line o headers inválidos)
+    /// y no de tamaño o de framing del body
+    pub fn is_malformed(&self) -> bool {
+        matches!(
+            self,
+            ParseError::IncompleteRequest
+                | ParseError::InvalidRequestLine
+                | ParseError::UnsupportedMethod(_)
+                | ParseError::InvalidHttpVersion(_)
+                | ParseError::InvalidHeader(_)
+                | ParseError::EmptyRequest
+        )
+    }
+}
+
 impl Request {
     /// Parsea un request HTTP/1.0 desde bytes
     /// 
@@ -142,8 +226,25 @@ impl Request {
     /// assert_eq!(request.query_param("num"), Some("10"));
     /// ```
     pub fn parse(buffer: &[u8]) -> Result<Self, ParseError> {
+        // Solo la request line y los headers deben ser texto: el body se
+        // conserva como bytes (puede ser binario, ej: gzip)
+        let (head, body) = match buffer.windows(4).position(|w| w == b"\r\n\r\n") {
+            Some(i) => (&buffer[..i + 4], &buffer[i + 4..]),
+            None => (buffer, &[][..]),
+        };
+        
+        let mut request = Self::parse_head(head)?;
+        request.set_body(body);
+        Ok(request)
+    }
+    
+    /// Parsea la request line y los headers (sin body)
+    /// 
+    /// Lo usa `RequestParser` apenas llega la línea vacía, para conocer
+    /// `Content-Length` antes de leer el body.
+    pub(crate) fn parse_head(head: &[u8]) -> Result<Self, ParseError> {
         // Convertir a string (validando que sea UTF-8 válido)
-        let request_str = std::str::from_utf8(buffer)
+        let request_str = std::str::from_utf8(head)
             .map_err(|_| ParseError::InvalidRequestLine)?;
         
         if request_str.trim().is_empty() {
@@ -158,28 +259,41 @@ impl Request {
         }
         
         // 1. Parsear la request line (primera línea)
-        let (method, path, query_params, version) = Self::parse_request_line(lines[0])?;
+        let (method, target, version) = Self::parse_request_line(lines[0])?;
+        let (path, query_params) = Self::parse_path_and_query(&target);
         
         // 2. Parsear headers (resto de líneas hasta encontrar línea vacía)
         let headers = Self::parse_headers(&lines[1..])?;
 
-        // 3. Parsear body
-        let body = Self::parse_body(&lines, method);
-
         Ok(Request {
             method,
+            target,
             path,
             query_params,
             headers,
             version,
-            body,
+            body: Vec::new(),
+            id: None,
+            path_params: Vec::new(),
         })
     }
     
+    /// Asigna el body (solo se usa en POST; en otros métodos se descarta)
+    pub(crate) fn set_body(&mut self, body: &[u8]) {
+        if self.method == Method::POST {
+            self.body = body.to_vec();
+        }
+    }
+    
+    /// Asigna el id del request (lo hace el servidor antes de despacharlo)
+    pub(crate) fn set_id(&mut self, id: &str) {
+        self.id = Some(id.to_string());
+    }
+    
     /// Parsea la request line (primera línea del request)
     /// 
     /// Formato: `GET /path?query HTTP/1.0`
-    fn parse_request_line(line: &str) -> Result<(Method, String, HashMap<String, String>, String), ParseError> {
+    fn parse_request_line(line: &str) -> Result<(Method, String, String), ParseError> {
         let parts: Vec<&str> = line.split_whitespace().collect();
         
         // Debe tener exactamente 3 partes: METHOD PATH VERSION
@@ -190,16 +304,13 @@ impl Request {
         // Parsear método
         let method = Method::from_str(parts[0])?;
         
-        // Parsear path y query
-        let (path, query_params) = Self::parse_path_and_query(parts[1]);
-        
         // Validar versión HTTP
         let version = parts[2].to_string();
         if version != "HTTP/1.0" && version != "HTTP/1.1" {
             return Err(ParseError::InvalidHttpVersion(version));
         }
         
-        Ok((method, path, query_params, version))
+        Ok((method, parts[1].to_string(), version))
     }
     
     /// Parsea el path y extrae los query parameters
@@ -262,9 +373,10 @@ impl Request {
 
     /// Parsea los headers HTTP
     /// 
-    /// Cada header tiene formato: "Name: Value"
-    fn parse_headers(lines: &[&str]) -> Result<HashMap<String, String>, ParseError> {
-        let mut headers = HashMap::new();
+    /// Cada header tiene formato: "Name: Value". Un header repetido
+    /// conserva todos sus valores (ver `header_values`).
+    fn parse_headers(lines: &[&str]) -> Result<Headers, ParseError> {
+        let mut headers = Headers::new();
         
         for line in lines {
             // La línea vacía marca el fin de los headers
@@ -274,9 +386,9 @@ impl Request {
             
             // Buscar el separador ':'
             if let Some(colon_pos) = line.find(':') {
-                let name = line[..colon_pos].trim().to_string();
-                let value = line[colon_pos + 1..].trim().to_string();
-                headers.insert(name, value);
+                let name = line[..colon_pos].trim();
+                let value = line[colon_pos + 1..].trim();
+                headers.append(name, value);
             } else {
                 // Header sin ':' es inválido
                 return Err(ParseError::InvalidHeader(line.to_string()));
@@ -286,28 +398,6 @@ impl Request {
         Ok(headers)
     }
 
-    /// Parsea el cuerpo del request
-    fn parse_body(lines: &[&str], method: Method) -> Vec<u8> {
-        if method != Method::POST {
-            return Vec::new();
-        }
-        
-        let mut body_start = 0;
-        for (i, line) in lines.iter().enumerate() {
-            if line.trim().is_empty() {
-                body_start = i + 1;
-                break;
-            }
-        }
-        
-        if body_start < lines.len() {
-            let body_str = lines[body_start..].join("\r\n");
-            body_str.as_bytes().to_vec()
-        } else {
-            Vec::new()
-        }
-    }
-    
     // === Métodos públicos para acceder a los campos ===
     
     /// Obtiene el método HTTP del request
@@ -320,6 +410,11 @@ impl Request {
         &self.path
     }
     
+    /// Obtiene el target de la request line, con la query sin decodificar
+    pub fn target(&self) -> &str {
+        &self.target
+    }
+    
     /// Obtiene todos los query parameters
     pub fn query_params(&self) -> &HashMap<String, String> {
         &self.query_params
@@ -341,14 +436,91 @@ impl Request {
         self.query_params.get(name).map(|s| s.as_str())
     }
     
+    /// Obtiene un parámetro del path de una ruta con `:nombre`
+    /// 
+    /// # Ejemplo
+    /// ```
+    /// use http_server::http::{Request, Response};
+    /// use http_server::router::Router;
+    /// 
+    /// let mut router = Router::new();
+    /// router.register("/users/:id", |req: &Request| {
+    ///     Response::text(req.path_param("id").unwrap())
+    /// });
+    /// 
+    /// let request = Request::parse(b"GET /users/42 HTTP/1.0\r\n\r\n").unwrap();
+    /// assert_eq!(router.route(&request).body(), b"42");
+    /// assert_eq!(request.path_param("id"), None);
+    /// ```
+    pub fn path_param(&self, name: &str) -> Option<&str> {
+        self.path_params.iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+    
     /// Obtiene todos los headers
-    pub fn headers(&self) -> &HashMap<String, String> {
+    pub fn headers(&self) -> &Headers {
         &self.headers
     }
     
-    /// Obtiene un header específico
-    pub fn header(&self, name: &str) -> Option<&str> {
-        self.headers.get(name).map(|s| s.as_str())
+    /// Obtiene un header específico, sin distinguir mayúsculas
+    /// 
+    /// Si el header vino repetido retorna el primer valor.
+    /// 
+    /// # Ejemplo
+    /// ```
+    /// use http_server::http::Request;
+    /// 
+    /// let raw = b"GET / HTTP/1.0\r\ncontent-type: text/plain\r\n\r\n";
+    /// let request = Request::parse(raw).unwrap();
+    /// assert_eq!(request.header("Content-Type"), Some("text/plain"));
+    /// ```
+    pub fn header<'a>(&'a self, name: &str) -> Option<&'a str> {
+        self.headers.get(name)
+    }
+    
+    /// Todos los valores de un header repetido, en orden de llegada
+    /// 
+    /// # Ejemplo
+    /// ```
+    /// use http_server::http::Request;
+    /// 
+    /// let raw = b"GET / HTTP/1.0\r\nX-Forwarded-For: 10.0.0.1\r\nx-forwarded-for: 10.0.0.2\r\n\r\n";
+    /// let request = Request::parse(raw).unwrap();
+    /// assert_eq!(request.header_values("X-Forwarded-For"), ["10.0.0.1", "10.0.0.2"]);
+    /// ```
+    pub fn header_values<'a>(&'a self, name: &str) -> Vec<&'a str> {
+        self.headers.get_all(name).collect()
+    }
+    
+    /// `Content-Type` del request, parseado (ver `http::mime`)
+    pub fn content_type(&self) -> Option<MediaType> {
+        MediaType::parse(self.headers.get("Content-Type")?)
+    }
+    
+    /// Asigna los parámetros del path (lo hace el router al elegir la ruta)
+    pub(crate) fn set_path_params(&mut self, params: Vec<(String, String)>) {
+        self.path_params = params;
+    }
+    
+    /// Verifica que un body de texto (JSON, `text/*`, formularios) sea UTF-8
+    /// 
+    /// Los bodies binarios o sin `Content-Type` no se validan. El error se
+    /// responde con 415.
+    /// 
+    /// # Ejemplo
+    /// ```
+    /// use http_server::http::Request;
+    /// 
+    /// let raw = b"POST /jobs/submit HTTP/1.0\r\nContent-Type: application/json; charset=latin1\r\nContent-Length: 2\r\n\r\n{}";
+    /// let request = Request::parse(raw).unwrap();
+    /// assert!(request.check_charset().is_err());
+    /// ```
+    pub fn check_charset(&self) -> Result<(), CharsetError> {
+        match self.content_type() {
+            Some(media) if !self.body.is_empty() => media.check_body(&self.body),
+            _ => Ok(()),
+        }
     }
     
     /// Obtiene la versión HTTP
@@ -356,6 +528,11 @@ impl Request {
         &self.version
     }
     
+    /// Id que le asignó el servidor (`None` si no pasó por el servidor)
+    pub fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+    
     /// Obtiene el body del request
     pub fn body(&self) -> &[u8] {
         &self.body
@@ -365,6 +542,100 @@ impl Request {
     pub fn body_string(&self) -> Option<String> {
         String::from_utf8(self.body.clone()).ok()
     }
+    
+    /// Deserializa el body como JSON
+    /// 
+    /// El error es un mensaje listo para responder 400.
+    /// 
+    /// # Ejemplo
+    /// ```
+    /// use http_server::http::Request;
+    /// 
+    /// let raw = b"POST /jobs/submit HTTP/1.0\r\nContent-Length: 16\r\n\r\n{\"task\": \"pi\"}  ";
+    /// let request = Request::parse(raw).unwrap();
+    /// let body: serde_json::Value = request.json_body().unwrap();
+    /// assert_eq!(body["task"], "pi");
+    /// ```
+    pub fn json_body<T: serde::de::DeserializeOwned>(&self) -> Result<T, String> {
+        serde_json::from_slice(&self.body).map_err(|e| format!("Invalid JSON in request body: {}", e))
+    }
+    
+    /// Descomprime el body según `Content-Encoding` (ver `http::encoding`)
+    /// 
+    /// Tras decodificar, los handlers ven el body original: se quita
+    /// `Content-Encoding` y `Content-Length` pasa a ser el tamaño
+    /// descomprimido, que no puede superar `max_bytes`.
+    pub fn decode_body(&mut self, max_bytes: u64) -> Result<(), DecodeError> {
+        let Some(content_encoding) = self.headers.get("Content-Encoding") else {
+            return Ok(());
+        };
+        let Some(decoded) = encoding::decode(content_encoding, &self.body, max_bytes)? else {
+            return Ok(());
+        };
+        
+        self.headers.remove("Content-Encoding");
+        self.headers.insert("Content-Length", &decoded.len().to_string());
+        self.body = decoded;
+        Ok(())
+    }
+    
+    /// Entity tags de `If-None-Match` (`*` incluido tal cual)
+    /// 
+    /// # Ejemplo
+    /// ```
+    /// use http_server::http::Request;
+    /// 
+    /// let raw = b"GET / HTTP/1.0\r\nif-none-match: \"a\", W/\"b\"\r\n\r\n";
+    /// let request = Request::parse(raw).unwrap();
+    /// assert_eq!(request.if_none_match(), Some(vec!["\"a\"", "W/\"b\""]));
+    /// ```
+    pub fn if_none_match(&self) -> Option<Vec<&str>> {
+        let tags = self.headers.get("If-None-Match")?.split(',')
+            .map(str::trim)
+            .filter(|tag| !tag.is_empty())
+            .collect();
+        Some(tags)
+    }
+    
+    /// Fecha de `If-Modified-Since`, si es una fecha HTTP válida
+    pub fn if_modified_since(&self) -> Option<SystemTime> {
+        date::parse(self.headers.get("If-Modified-Since")?)
+    }
+    
+    /// Indica si el cliente ya tiene la versión actual del recurso (304)
+    /// 
+    /// Sigue el RFC 9110 (sección 13.2.2): solo aplica a GET y HEAD, y si
+    /// viene `If-None-Match` se ignora `If-Modified-Since`. Los ETags se
+    /// comparan en forma débil (`W/"x"` coincide con `"x"`).
+    /// 
+    /// # Ejemplo
+    /// ```
+    /// use http_server::http::Request;
+    /// 
+    /// let raw = b"GET / HTTP/1.0\r\nIf-None-Match: \"v1\"\r\n\r\n";
+    /// let request = Request::parse(raw).unwrap();
+    /// assert!(request.is_not_modified("W/\"v1\"", None));
+    /// assert!(!request.is_not_modified("\"v2\"", None));
+    /// ```
+    pub fn is_not_modified(&self, etag: &str, last_modified: Option<SystemTime>) -> bool {
+        if !matches!(self.method, Method::GET | Method::HEAD) {
+            return false;
+        }
+        
+        if let Some(tags) = self.if_none_match() {
+            let opaque = |tag: &str| tag.strip_prefix("W/").unwrap_or(tag).to_string();
+            return tags.iter().any(|tag| *tag == "*" || opaque(tag) == opaque(etag));
+        }
+        
+        match (self.if_modified_since(), last_modified) {
+            // Last-Modified tiene resolución de segundos
+            (Some(since), Some(modified)) => {
+                let secs = |t: SystemTime| t.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+                secs(modified) <= secs(since)
+            }
+            _ => false,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -416,7 +687,18 @@ mod tests {
         assert_eq!(request.header("Host"), Some("localhost:8080"));
         assert_eq!(request.header("User-Agent"), Some("test"));
     }
-    
+
+    #[test]
+    fn test_headers_are_case_insensitive_and_repeatable() {
+        let raw = b"GET / HTTP/1.0\r\nhost: localhost\r\nX-Forwarded-For: 10.0.0.1\r\nX-FORWARDED-FOR: 10.0.0.2\r\n\r\n";
+        let request = Request::parse(raw).unwrap();
+
+        assert_eq!(request.header("Host"), Some("localhost"));
+        assert_eq!(request.header("x-forwarded-for"), Some("10.0.0.1"));
+        assert_eq!(request.header_values("X-Forwarded-For"), ["10.0.0.1", "10.0.0.2"]);
+        assert!(request.header_values("Accept").is_empty());
+    }
+
     #[test]
     fn test_url_decode() {
         let raw = b"GET /reverse?text=hello%20world HTTP/1.0\r\n\r\n";
@@ -457,4 +739,70 @@ mod tests {
         
         assert!(matches!(result, Err(ParseError::InvalidRequestLine)));
     }
+    
+    #[test]
+    fn test_parse_binary_post_body() {
+        let mut raw = b"POST /jobs/submit HTTP/1.0\r\nContent-Length: 4\r\n\r\n".to_vec();
+        raw.extend_from_slice(&[0x1f, 0x8b, 0xff, 0x00]);
+        let request = Request::parse(&raw).unwrap();
+        
+        assert_eq!(request.body(), &[0x1f, 0x8b, 0xff, 0x00]);
+    }
+    
+    #[test]
+    fn test_decode_gzip_body() {
+        use flate2::write::GzEncoder;
+        use std::io::Write;
+        
+        let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(b"{\"task\": \"isprime\"}").unwrap();
+        let gz = encoder.finish().unwrap();
+        
+        let mut raw = format!(
+            "POST /jobs/submit HTTP/1.0\r\ncontent-encoding: gzip\r\ncontent-length: {}\r\n\r\n",
+            gz.len()
+        ).into_bytes();
+        raw.extend_from_slice(&gz);
+        
+        let mut request = Request::parse(&raw).unwrap();
+        assert_eq!(request.decode_body(5), Err(DecodeError::TooLarge(5)));
+        
+        request.decode_body(1024).unwrap();
+        assert_eq!(request.body_string().unwrap(), "{\"task\": \"isprime\"}");
+        assert_eq!(request.header("content-length"), Some("19"));
+        assert_eq!(request.header("content-encoding"), None);
+        
+        // Sin Content-Encoding el body queda igual
+        let mut plain = Request::parse(b"POST /x HTTP/1.0\r\nContent-Length: 2\r\n\r\nok").unwrap();
+        plain.decode_body(1).unwrap();
+        assert_eq!(plain.body(), b"ok");
+    }
+    
+    #[test]
+    fn test_conditional_headers() {
+        let modified = UNIX_EPOCH + std::time::Duration::from_millis(784_111_777_500);
+        let request = |headers: &str| {
+            Request::parse(format!("GET /x HTTP/1.0\r\n{}\r\n", headers).as_bytes()).unwrap()
+        };
+        
+        assert!(request("If-None-Match: *\r\n").is_not_modified("\"a\"", None));
+        assert!(request("If-None-Match: \"b\", W/\"a\"\r\n").is_not_modified("\"a\"", None));
+        assert!(!request("If-None-Match: \"b\"\r\n").is_not_modified("\"a\"", Some(modified)));
+        
+        // If-Modified-Since se compara en segundos
+        let since = "If-Modified-Since: Sun, 06 Nov 1994 08:49:37 GMT\r\n";
+        assert!(request(since).is_not_modified("\"a\"", Some(modified)));
+        assert!(!request(since).is_not_modified("\"a\"", Some(modified + std::time::Duration::from_secs(1))));
+        assert!(!request(since).is_not_modified("\"a\"", None));
+        assert!(!request("If-Modified-Since: yesterday\r\n").is_not_modified("\"a\"", Some(modified)));
+        
+        // If-None-Match tiene prioridad sobre If-Modified-Since
+        let both = format!("{}If-None-Match: \"b\"\r\n", since);
+        assert!(!request(&both).is_not_modified("\"a\"", Some(modified)));
+        
+        // Solo GET y HEAD
+        let post = Request::parse(b"POST /x HTTP/1.0\r\nIf-None-Match: *\r\n\r\n").unwrap();
+        assert!(!post.is_not_modified("\"a\"", None));
+        assert_eq!(request("").if_none_match(), None);
+    }
 }
\ No newline at end of file
@@ -27,9 +27,62 @@
 //! let bytes = response.to_bytes();
 //! // Ahora puedes enviar `bytes` por el socket
 //! ```
+//!
+//! Para los headers comunes (`Content-Type`, `Cache-Control`, `Location`,
+//! `Retry-After`) está `Response::builder`, que los arma con el formato
+//! correcto.
+//!
+//! ## Bodies en streaming
+//!
+//! Para archivos grandes el body puede venir de un `Read` (`with_stream`):
+//! se copia al socket en bloques al escribir la respuesta con `write_to`,
+//! sin cargarlo completo en memoria. Si viene de un archivo (`with_file`),
+//! `send_to` lo envía a un socket con `sendfile(2)` (ver `sendfile`).
 
-use super::StatusCode;
-use std::collections::HashMap;
+use super::cache::CacheControl;
+use super::range::{ByteRange, Unsatisfiable};
+use super::{mime, sendfile, Headers, StatusCode};
+use std::fs::File;
+use std::io::{self, BufWriter, IoSlice, Read, Write};
+use std::net::TcpStream;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Tamaño de los bloques al copiar un body en streaming al socket
+const STREAM_CHUNK_BYTES: usize = 64 * 1024;
+
+/// Body que se lee de un `Read` al escribir la respuesta
+/// 
+/// Los clones de la respuesta comparten el reader: el body se puede
+/// escribir una sola vez.
+#[derive(Clone)]
+struct BodyStream {
+    reader: Arc<Mutex<Option<BodySource>>>,
+    len: u64,
+}
+
+/// De dónde sale un body en streaming
+enum BodySource {
+    Reader(Box<dyn Read + Send>),
+
+    /// Archivo abierto, desde su posición actual (admite `sendfile`)
+    File(File),
+}
+
+impl Read for BodySource {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            BodySource::Reader(reader) => reader.read(buf),
+            BodySource::File(file) => file.read(buf),
+        }
+    }
+}
+
+impl std::fmt::Debug for BodyStream {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("BodyStream").field("len", &self.len).finish()
+    }
+}
 
 /// Representa una respuesta HTTP/1.0 completa
 #[derive(Debug, Clone)]
@@ -38,11 +91,14 @@ pub struct Response {
     status: StatusCode,
     
     /// Headers HTTP (Content-Type, Content-Length, etc.)
-    /// Usamos HashMap para evitar duplicados
-    headers: HashMap<String, String>,
+    /// Se escriben en el orden en que se agregaron
+    headers: Headers,
     
     /// Cuerpo de la respuesta (puede ser vacío)
     body: Vec<u8>,
+    
+    /// Body en streaming; si existe, reemplaza a `body`
+    stream: Option<BodyStream>,
 }
 
 impl Response {
@@ -59,14 +115,15 @@ impl Response {
     pub fn new(status: StatusCode) -> Self {
         Self {
             status,
-            headers: HashMap::new(),
+            headers: Headers::new(),
             body: Vec::new(),
+            stream: None,
         }
     }
     
     /// Agrega un header a la respuesta
     /// 
-    /// Si el header ya existe, se sobrescribe.
+    /// Si el header ya existe (sin distinguir mayúsculas), se sobrescribe.
     /// 
     /// # Ejemplo
     /// ```
@@ -76,7 +133,25 @@ impl Response {
     ///     .with_header("Content-Type", "application/json");
     /// ```
     pub fn with_header(mut self, name: &str, value: &str) -> Self {
-        self.headers.insert(name.to_string(), value.to_string());
+        self.headers.insert(name, value);
+        self
+    }
+    
+    /// Agrega otro valor para un header que puede repetirse (ej: `Set-Cookie`)
+    /// 
+    /// A diferencia de `with_header`, no reemplaza los valores anteriores.
+    /// 
+    /// # Ejemplo
+    /// ```
+    /// use http_server::http::{Response, StatusCode};
+    /// 
+    /// let response = Response::new(StatusCode::Ok)
+    ///     .with_appended_header("Set-Cookie", "a=1")
+    ///     .with_appended_header("Set-Cookie", "b=2");
+    /// assert_eq!(response.headers().get_all("set-cookie").count(), 2);
+    /// ```
+    pub fn with_appended_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.append(name, value);
         self
     }
     
@@ -90,7 +165,7 @@ impl Response {
     /// response.add_header("Content-Type", "application/json");
     /// ```
     pub fn add_header(&mut self, name: &str, value: &str) {
-        self.headers.insert(name.to_string(), value.to_string());
+        self.headers.insert(name, value);
     }
     
     /// Establece el cuerpo de la respuesta desde un string
@@ -106,10 +181,8 @@ impl Response {
     /// ```
     pub fn with_body(mut self, body: &str) -> Self {
         self.body = body.as_bytes().to_vec();
-        self.headers.insert(
-            "Content-Length".to_string(),
-            self.body.len().to_string()
-        );
+        self.stream = None;
+        self.headers.insert("Content-Length", &self.body.len().to_string());
         self
     }
     
@@ -127,32 +200,119 @@ impl Response {
     /// ```
     pub fn with_body_bytes(mut self, body: Vec<u8>) -> Self {
         self.body = body;
-        self.headers.insert(
-            "Content-Length".to_string(),
-            self.body.len().to_string()
-        );
+        self.stream = None;
+        self.headers.insert("Content-Length", &self.body.len().to_string());
+        self
+    }
+    
+    /// Establece un body de `len` bytes que se lee de `reader` al enviarlo
+    /// 
+    /// El body no se carga en memoria: `write_to` lo copia al socket en
+    /// bloques. `Content-Length` es `len`; si `reader` trae más bytes se
+    /// ignoran y si trae menos la escritura falla.
+    /// 
+    /// # Ejemplo
+    /// ```
+    /// use http_server::http::{Response, StatusCode};
+    /// use std::io::Cursor;
+    /// 
+    /// let mut response = Response::new(StatusCode::Ok)
+    ///     .with_stream(Cursor::new(b"Hello World".to_vec()), 5);
+    /// assert!(response.is_streaming());
+    /// 
+    /// let mut out = Vec::new();
+    /// response.write_to(&mut out).unwrap();
+    /// assert!(out.ends_with(b"Content-Length: 5\r\n\r\nHello"));
+    /// ```
+    pub fn with_stream(self, reader: impl Read + Send + 'static, len: u64) -> Self {
+        self.with_source(BodySource::Reader(Box::new(reader)), len)
+    }
+    
+    /// Como `with_stream`, con `len` bytes de `file` desde su posición actual
+    /// 
+    /// `write_to` lo copia como cualquier stream; `send_to` lo envía con
+    /// `sendfile(2)` sin pasar los bytes por el proceso.
+    /// 
+    /// # Ejemplo
+    /// ```
+    /// use http_server::http::{Response, StatusCode};
+    /// use std::fs::File;
+    /// 
+    /// let file = File::open("Cargo.toml").unwrap();
+    /// let mut response = Response::new(StatusCode::Ok).with_file(file, 9);
+    /// 
+    /// let mut out = Vec::new();
+    /// response.write_to(&mut out).unwrap();
+    /// assert!(out.ends_with(b"Content-Length: 9\r\n\r\n[package]"));
+    /// ```
+    pub fn with_file(self, file: File, len: u64) -> Self {
+        self.with_source(BodySource::File(file), len)
+    }
+    
+    fn with_source(mut self, source: BodySource, len: u64) -> Self {
+        self.body = Vec::new();
+        self.stream = Some(BodyStream {
+            reader: Arc::new(Mutex::new(Some(source))),
+            len,
+        });
+        self.headers.insert("Content-Length", &len.to_string());
         self
     }
     
     /// Crea una respuesta JSON exitosa (200 OK)
     /// 
-    /// Automáticamente establece `Content-Type: application/json`.
+    /// Automáticamente establece `Content-Type: application/json; charset=utf-8`.
     /// 
     /// # Ejemplo
     /// ```
     /// use http_server::http::Response;
     /// 
     /// let response = Response::json(r#"{"status": "ok"}"#);
+    /// assert_eq!(response.headers().get("Content-Type").unwrap(), "application/json; charset=utf-8");
     /// ```
     pub fn json(body: &str) -> Self {
         Self::new(StatusCode::Ok)
-            .with_header("Content-Type", "application/json")
+            .with_header("Content-Type", mime::APPLICATION_JSON)
+            .with_body(body)
+    }
+    
+    /// Crea una respuesta de texto plano exitosa (200 OK)
+    /// 
+    /// Automáticamente establece `Content-Type: text/plain; charset=utf-8`.
+    /// 
+    /// # Ejemplo
+    /// ```
+    /// use http_server::http::Response;
+    /// 
+    /// let response = Response::text("ok\n");
+    /// assert_eq!(response.headers().get("Content-Type").unwrap(), "text/plain; charset=utf-8");
+    /// ```
+    pub fn text(body: &str) -> Self {
+        Self::new(StatusCode::Ok)
+            .with_header("Content-Type", mime::TEXT_PLAIN)
             .with_body(body)
     }
     
+    /// Crea una respuesta JSON exitosa (200 OK) a partir de un `serde_json::Value`
+    /// 
+    /// A diferencia de armar el body con `format!`, los strings se escapan,
+    /// así que el body es JSON válido aunque traiga comillas o saltos de línea.
+    /// 
+    /// # Ejemplo
+    /// ```
+    /// use http_server::http::Response;
+    /// use serde_json::json;
+    /// 
+    /// let response = Response::json_value(&json!({"text": "say \"hi\""}));
+    /// assert_eq!(response.body(), br#"{"text":"say \"hi\""}"#);
+    /// ```
+    pub fn json_value(value: &serde_json::Value) -> Self {
+        Self::json(&value.to_string())
+    }
+    
     /// Crea una respuesta de error con mensaje JSON
     /// 
-    /// Formato del JSON: `{"error": "mensaje"}`
+    /// Formato del JSON: `{"error": "mensaje"}` (el mensaje se escapa)
     /// 
     /// # Ejemplo
     /// ```
@@ -164,12 +324,181 @@ impl Response {
     /// );
     /// ```
     pub fn error(status: StatusCode, message: &str) -> Self {
-        let body = format!(r#"{{"error": "{}"}}"#, message);
+        let body = serde_json::json!({"error": message}).to_string();
         Self::new(status)
-            .with_header("Content-Type", "application/json")
+            .with_header("Content-Type", mime::APPLICATION_JSON)
             .with_body(&body)
     }
     
+    /// Crea una redirección (301, 302, 303, 307 o 308) a `location`
+    /// 
+    /// Lleva el header `Location` y un body JSON con la misma URL para
+    /// clientes que no siguen redirecciones.
+    /// 
+    /// # Ejemplo
+    /// ```
+    /// use http_server::http::{Response, StatusCode};
+    /// 
+    /// let response = Response::redirect(StatusCode::SeeOther, "/jobs/status?id=job-1");
+    /// assert_eq!(response.status(), StatusCode::SeeOther);
+    /// assert_eq!(response.headers().get("Location").unwrap(), "/jobs/status?id=job-1");
+    /// ```
+    pub fn redirect(status: StatusCode, location: &str) -> Self {
+        debug_assert!(status.is_redirect(), "{} is not a redirect status", status);
+        let mut response = Self::json_value(&serde_json::json!({"location": location}))
+            .with_header("Location", location);
+        response.status = status;
+        response
+    }
+    
+    /// Crea una respuesta 405 con el header `Allow` (ej: `"GET, POST"`)
+    /// 
+    /// # Ejemplo
+    /// ```
+    /// use http_server::http::{Response, StatusCode};
+    /// 
+    /// let response = Response::method_not_allowed("POST");
+    /// assert_eq!(response.status(), StatusCode::MethodNotAllowed);
+    /// assert_eq!(response.headers().get("Allow").unwrap(), "POST");
+    /// ```
+    pub fn method_not_allowed(allow: &str) -> Self {
+        Self::error(StatusCode::MethodNotAllowed, &format!("Method not allowed. Use {}", allow))
+            .with_header("Allow", allow)
+    }
+    
+    /// Recorta el body al rango `range` y responde 206 Partial Content
+    /// 
+    /// El body debe ser el contenido completo: su largo es el total que se
+    /// informa en `Content-Range`.
+    /// 
+    /// # Ejemplo
+    /// ```
+    /// use http_server::http::{Response, StatusCode};
+    /// use http_server::http::range::ByteRange;
+    /// 
+    /// let response = Response::new(StatusCode::Ok)
+    ///     .with_body("Hello World")
+    ///     .with_range(ByteRange { start: 6, end: 10 });
+    /// assert_eq!(response.status(), StatusCode::PartialContent);
+    /// assert_eq!(response.body(), b"World");
+    /// assert_eq!(response.headers().get("Content-Range").unwrap(), "bytes 6-10/11");
+    /// ```
+    pub fn with_range(mut self, range: ByteRange) -> Self {
+        let total = self.body.len() as u64;
+        let end = range.end.min(total.saturating_sub(1));
+        let body = self.body.get(range.start as usize..=end as usize).unwrap_or_default().to_vec();
+        
+        self.status = StatusCode::PartialContent;
+        self.headers.insert("Content-Range", &ByteRange { start: range.start, end }.content_range(total));
+        self.with_body_bytes(body)
+    }
+    
+    /// Crea una respuesta 416 para un archivo de `total` bytes
+    /// 
+    /// Incluye `Content-Range: bytes */total` para que el cliente sepa el
+    /// tamaño real.
+    /// 
+    /// # Ejemplo
+    /// ```
+    /// use http_server::http::{Response, StatusCode};
+    /// 
+    /// let response = Response::range_not_satisfiable(100);
+    /// assert_eq!(response.status(), StatusCode::RangeNotSatisfiable);
+    /// assert_eq!(response.headers().get("Content-Range").unwrap(), "bytes */100");
+    /// ```
+    pub fn range_not_satisfiable(total: u64) -> Self {
+        Self::error(StatusCode::RangeNotSatisfiable, &Unsatisfiable.to_string())
+            .with_header("Content-Range", &format!("bytes */{}", total))
+    }
+    
+    /// ETag débil de un archivo a partir de su tamaño y fecha de modificación
+    /// 
+    /// No requiere leer el archivo, así que sirve para responder 304 sin
+    /// recalcular nada. Cambia si el archivo cambia de tamaño o de fecha.
+    /// 
+    /// # Ejemplo
+    /// ```
+    /// use http_server::http::Response;
+    /// use std::time::{Duration, UNIX_EPOCH};
+    /// 
+    /// let etag = Response::file_etag(1024, Some(UNIX_EPOCH + Duration::from_secs(60)));
+    /// assert_eq!(etag, r#"W/"400-3c""#);
+    /// ```
+    pub fn file_etag(len: u64, modified: Option<SystemTime>) -> String {
+        let mtime = modified
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        format!("W/\"{:x}-{:x}\"", len, mtime)
+    }
+    
+    /// ETag fuerte del contenido `data` (primeros 16 bytes de su SHA-256)
+    /// 
+    /// # Ejemplo
+    /// ```
+    /// use http_server::http::Response;
+    /// 
+    /// assert_eq!(Response::content_etag(b"abc"), Response::content_etag(b"abc"));
+    /// assert_ne!(Response::content_etag(b"abc"), Response::content_etag(b"abd"));
+    /// ```
+    pub fn content_etag(data: &[u8]) -> String {
+        use sha2::{Digest, Sha256};
+        
+        let hash = Sha256::digest(data);
+        let hex: String = hash[..16].iter().map(|b| format!("{:02x}", b)).collect();
+        format!("\"{}\"", hex)
+    }
+    
+    /// Agrega los validadores `ETag` y (si se conoce) `Last-Modified`
+    /// 
+    /// # Ejemplo
+    /// ```
+    /// use http_server::http::Response;
+    /// use std::time::UNIX_EPOCH;
+    /// 
+    /// let response = Response::json("{}").with_validators("\"v1\"", Some(UNIX_EPOCH));
+    /// assert_eq!(response.headers().get("ETag").unwrap(), "\"v1\"");
+    /// assert_eq!(response.headers().get("Last-Modified").unwrap(), "Thu, 01 Jan 1970 00:00:00 GMT");
+    /// ```
+    pub fn with_validators(mut self, etag: &str, last_modified: Option<SystemTime>) -> Self {
+        self.headers.insert("ETag", etag);
+        if let Some(time) = last_modified {
+            self.headers.insert("Last-Modified", &super::date::format(time));
+        }
+        self
+    }
+    
+    /// Fija la política de caché: `Cache-Control` y su `Expires` equivalente
+    /// 
+    /// # Ejemplo
+    /// ```
+    /// use http_server::http::Response;
+    /// use http_server::http::cache::CacheControl;
+    /// 
+    /// let response = Response::json("{}").with_cache(CacheControl::NoStore);
+    /// assert_eq!(response.headers().get("Cache-Control").unwrap(), "no-store");
+    /// assert_eq!(response.headers().get("Expires").unwrap(), "Thu, 01 Jan 1970 00:00:00 GMT");
+    /// ```
+    pub fn with_cache(mut self, policy: CacheControl) -> Self {
+        self.headers.insert("Cache-Control", &policy.header_value());
+        self.headers.insert("Expires", &policy.expires(SystemTime::now()));
+        self
+    }
+    
+    /// Crea una respuesta 304 Not Modified (sin body) con sus validadores
+    /// 
+    /// # Ejemplo
+    /// ```
+    /// use http_server::http::{Response, StatusCode};
+    /// 
+    /// let response = Response::not_modified("\"v1\"", None);
+    /// assert_eq!(response.status(), StatusCode::NotModified);
+    /// assert!(response.body().is_empty());
+    /// ```
+    pub fn not_modified(etag: &str, last_modified: Option<SystemTime>) -> Self {
+        Self::new(StatusCode::NotModified).with_validators(etag, last_modified)
+    }
+    
     /// Convierte la respuesta a bytes listos para enviar por el socket
     /// 
     /// Genera el formato completo HTTP/1.0:
@@ -188,31 +517,180 @@ impl Response {
     /// let bytes = response.to_bytes();
     /// // bytes contiene: "HTTP/1.0 200 OK\r\n...\r\n\r\nHello"
     /// ```
+    /// 
+    /// Un body en streaming no se incluye (solo los headers); para enviarlo
+    /// se usa `write_to`.
     pub fn to_bytes(&self) -> Vec<u8> {
-        let mut result = Vec::new();
-        
-        // 1. Status line
-        // Formato: HTTP/1.0 200 OK\r\n
-        let status_line = format!(
-            "HTTP/1.0 {}\r\n",
-            self.status
-        );
-        result.extend_from_slice(status_line.as_bytes());
-        
-        // 2. Headers
-        // Formato: Header-Name: Value\r\n
-        for (name, value) in &self.headers {
-            let header_line = format!("{}: {}\r\n", name, value);
-            result.extend_from_slice(header_line.as_bytes());
+        let mut result = self.head_bytes();
+        result.extend_from_slice(&self.body);
+        result
+    }
+    
+    /// Status line, headers y la línea vacía que los separa del body
+    /// 
+    /// # Ejemplo
+    /// ```
+    /// use http_server::http::{Response, StatusCode};
+    /// 
+    /// let response = Response::new(StatusCode::NotFound).with_body("nada");
+    /// let head = String::from_utf8(response.head_bytes()).unwrap();
+    /// assert!(head.starts_with("HTTP/1.0 404 Not Found\r\n"));
+    /// assert!(head.ends_with("Content-Length: 4\r\n\r\n"));
+    /// ```
+    pub fn head_bytes(&self) -> Vec<u8> {
+        // El upgrade a WebSocket solo existe en HTTP/1.1 (RFC 6455)
+        let version = if self.status == StatusCode::SwitchingProtocols { "HTTP/1.1" } else { "HTTP/1.0" };
+        let estimate = 32 + self.headers.iter().map(|(name, value)| name.len() + value.len() + 4).sum::<usize>();
+        let mut head = Vec::with_capacity(estimate);
+        
+        // Escribir en un Vec no falla
+        let _ = write!(head, "{} {}\r\n", version, self.status);
+        for (name, value) in self.headers.iter() {
+            let _ = write!(head, "{}: {}\r\n", name, value);
         }
+        head.extend_from_slice(b"\r\n");
+        head
+    }
+    
+    /// Bytes que ocupa la respuesta en el socket (headers + body)
+    pub fn wire_len(&self) -> u64 {
+        let body = self.stream.as_ref().map_or(self.body.len() as u64, |stream| stream.len);
+        self.head_bytes().len() as u64 + body
+    }
+    
+    /// Escribe la respuesta completa en `out`, incluido un body en streaming
+    /// 
+    /// Un body en memoria se envía junto con los headers en una escritura
+    /// vectorizada (`write_vectored`, `writev(2)` en un socket), sin copiarlo
+    /// a un buffer intermedio. Un body en streaming pasa por un buffer, así
+    /// el socket recibe bloques de `STREAM_CHUNK_BYTES` aunque sea grande.
+    /// Falla con `UnexpectedEof` si el reader trae menos de lo anunciado.
+    pub fn write_to(&mut self, out: &mut impl Write) -> io::Result<()> {
+        let head = self.head_bytes();
+        let Some(stream) = self.stream.take() else {
+            return write_all_vectored(out, &mut [IoSlice::new(&head), IoSlice::new(&self.body)]);
+        };
         
-        // 3. Línea vacía que separa headers del body
-        result.extend_from_slice(b"\r\n");
+        let mut out = BufWriter::with_capacity(STREAM_CHUNK_BYTES, out);
+        out.write_all(&head)?;
         
-        // 4. Body (si existe)
-        result.extend_from_slice(&self.body);
+        let reader = stream.reader.lock().unwrap().take();
+        let Some(reader) = reader else {
+            return Err(io::Error::other("Streamed body already consumed"));
+        };
+        let copied = io::copy(&mut reader.take(stream.len), &mut out)?;
+        if copied < stream.len {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                format!("Streamed body ended after {} of {} bytes", copied, stream.len)
+            ));
+        }
+        out.flush()
+    }
+    
+    /// Como `write_to`, directo a un socket
+    /// 
+    /// Un body de `with_file` se envía con `sendfile(2)` después de los
+    /// headers; cualquier otro body se escribe igual que con `write_to`.
+    pub fn send_to(&mut self, mut socket: &TcpStream) -> io::Result<()> {
+        let is_file = self.stream.as_ref().is_some_and(|stream| {
+            matches!(*stream.reader.lock().unwrap(), Some(BodySource::File(_)))
+        });
+        if !is_file {
+            return self.write_to(&mut socket);
+        }
         
-        result
+        let stream = self.stream.take().expect("checked above");
+        let Some(BodySource::File(mut file)) = stream.reader.lock().unwrap().take() else {
+            return Err(io::Error::other("Streamed body already consumed"));
+        };
+        socket.write_all(&self.head_bytes())?;
+        let copied = sendfile::copy(&mut file, socket, stream.len)?;
+        if copied < stream.len {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                format!("Streamed body ended after {} of {} bytes", copied, stream.len)
+            ));
+        }
+        Ok(())
+    }
+    
+    /// Lee un body en streaming a memoria (después `body()` lo retorna)
+    /// 
+    /// Para consumidores que necesitan el body completo, como los jobs o los
+    /// tests. No hace nada si el body ya está en memoria.
+    pub fn buffer_body(&mut self) -> io::Result<()> {
+        let Some(stream) = self.stream.take() else {
+            return Ok(());
+        };
+        
+        let reader = stream.reader.lock().unwrap().take();
+        let Some(reader) = reader else {
+            return Err(io::Error::other("Streamed body already consumed"));
+        };
+        let mut body = Vec::new();
+        reader.take(stream.len).read_to_end(&mut body)?;
+        if (body.len() as u64) < stream.len {
+            return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "Streamed body ended early"));
+        }
+        self.body = body;
+        Ok(())
+    }
+    
+    /// Comprime el body con gzip si es JSON de al menos `min_bytes`
+    /// 
+    /// Agrega `Content-Encoding: gzip` y `Vary: Accept-Encoding` y actualiza
+    /// `Content-Length`. No hace nada si el body ya tiene `Content-Encoding`
+    /// o si comprimido no es más chico. Retorna si lo comprimió.
+    /// 
+    /// # Ejemplo
+    /// ```
+    /// use http_server::http::Response;
+    /// 
+    /// let mut response = Response::json(&format!("[{}]", "1,".repeat(500) + "1"));
+    /// assert!(response.compress_gzip(256));
+    /// assert_eq!(response.headers().get("Content-Encoding").unwrap(), "gzip");
+    /// ```
+    pub fn compress_gzip(&mut self, min_bytes: usize) -> bool {
+        let is_json = self.headers.get("Content-Type")
+            .is_some_and(|value| value.to_ascii_lowercase().starts_with("application/json"));
+        let encoded = self.headers.contains("Content-Encoding");
+        if !is_json || encoded || self.stream.is_some() || self.body.len() < min_bytes {
+            return false;
+        }
+        
+        let compressed = super::encoding::gzip(&self.body);
+        if compressed.len() >= self.body.len() {
+            return false;
+        }
+        
+        self.body = compressed;
+        self.headers.insert("Content-Length", &self.body.len().to_string());
+        self.headers.insert("Content-Encoding", "gzip");
+        self.headers.insert("Vary", "Accept-Encoding");
+        true
+    }
+    
+    /// Quita el body conservando `Content-Length` (respuestas a HEAD)
+    /// 
+    /// El cliente recibe los mismos headers que con GET, incluido el tamaño
+    /// que tendría el body.
+    /// 
+    /// # Ejemplo
+    /// ```
+    /// use http_server::http::Response;
+    /// 
+    /// let mut response = Response::json(r#"{"ok": true}"#);
+    /// response.strip_body();
+    /// assert!(response.body().is_empty());
+    /// assert_eq!(response.headers().get("Content-Length").unwrap(), "12");
+    /// ```
+    pub fn strip_body(&mut self) {
+        if !self.body.is_empty() && !self.headers.contains("Content-Length") {
+            self.headers.insert("Content-Length", &self.body.len().to_string());
+        }
+        self.body = Vec::new();
+        self.stream = None;
     }
     
     /// Obtiene el código de estado de la respuesta
@@ -221,14 +699,130 @@ impl Response {
     }
     
     /// Obtiene una referencia a los headers
-    pub fn headers(&self) -> &HashMap<String, String> {
+    pub fn headers(&self) -> &Headers {
         &self.headers
     }
     
     /// Obtiene una referencia al body
+    /// 
+    /// Con un body en streaming retorna un slice vacío hasta `buffer_body`.
     pub fn body(&self) -> &[u8] {
         &self.body
     }
+    
+    /// Indica si el body se envía en streaming
+    pub fn is_streaming(&self) -> bool {
+        self.stream.is_some()
+    }
+
+    /// Crea un `ResponseBuilder` con el código de estado especificado
+    ///
+    /// # Ejemplo
+    /// ```
+    /// use http_server::http::{Response, StatusCode};
+    /// use std::time::Duration;
+    ///
+    /// let response = Response::builder(StatusCode::ServiceUnavailable)
+    ///     .retry_after(Duration::from_millis(1500))
+    ///     .json(&serde_json::json!({"error": "busy"}));
+    /// assert_eq!(response.headers().get("Retry-After").unwrap(), "2");
+    /// ```
+    pub fn builder(status: StatusCode) -> ResponseBuilder {
+        ResponseBuilder::new(status)
+    }
+}
+
+/// Constructor fluido de respuestas con helpers tipados para los headers comunes
+///
+/// Evita escribir a mano los nombres y el formato de `Content-Type`,
+/// `Cache-Control`, `Location` y `Retry-After`. Termina con un método que
+/// fija el body (`body`, `body_bytes`, `json`) o con `build` si no lleva.
+#[derive(Debug, Clone)]
+pub struct ResponseBuilder {
+    response: Response,
+}
+
+impl ResponseBuilder {
+    /// Crea un builder para una respuesta sin headers ni body
+    pub fn new(status: StatusCode) -> Self {
+        Self { response: Response::new(status) }
+    }
+
+    /// Agrega un header arbitrario (reemplaza valores anteriores)
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.response.headers.insert(name, value);
+        self
+    }
+
+    /// Establece `Content-Type` (ej: `"text/plain; charset=utf-8"`)
+    pub fn content_type(self, value: &str) -> Self {
+        self.header("Content-Type", value)
+    }
+
+    /// Establece `Cache-Control` (ej: `"no-cache"`, `"max-age=60"`)
+    pub fn cache_control(self, value: &str) -> Self {
+        self.header("Cache-Control", value)
+    }
+
+    /// Establece `Location` (redirecciones y recursos creados)
+    pub fn location(self, url: &str) -> Self {
+        self.header("Location", url)
+    }
+
+    /// Establece `Retry-After` en segundos enteros, redondeando hacia arriba
+    ///
+    /// # Ejemplo
+    /// ```
+    /// use http_server::http::{Response, StatusCode};
+    /// use std::time::Duration;
+    ///
+    /// let response = Response::builder(StatusCode::TooManyRequests)
+    ///     .retry_after(Duration::from_millis(1001))
+    ///     .build();
+    /// assert_eq!(response.headers().get("Retry-After").unwrap(), "2");
+    /// ```
+    pub fn retry_after(self, delay: Duration) -> Self {
+        let secs = delay.as_millis().div_ceil(1000);
+        self.header("Retry-After", &secs.to_string())
+    }
+
+    /// Termina la respuesta con un body de texto
+    pub fn body(self, body: &str) -> Response {
+        self.response.with_body(body)
+    }
+
+    /// Termina la respuesta con un body binario
+    pub fn body_bytes(self, body: Vec<u8>) -> Response {
+        self.response.with_body_bytes(body)
+    }
+
+    /// Termina la respuesta con un body JSON (fija `Content-Type` si falta)
+    pub fn json(mut self, value: &serde_json::Value) -> Response {
+        if !self.response.headers.contains("Content-Type") {
+            self = self.content_type(mime::APPLICATION_JSON);
+        }
+        self.body(&value.to_string())
+    }
+
+    /// Termina la respuesta sin body
+    pub fn build(self) -> Response {
+        self.response
+    }
+}
+
+/// Escribe todos los `bufs` con `write_vectored`, siguiendo tras las
+/// escrituras parciales (el equivalente estable de `write_all_vectored`)
+fn write_all_vectored(out: &mut impl Write, mut bufs: &mut [IoSlice<'_>]) -> io::Result<()> {
+    IoSlice::advance_slices(&mut bufs, 0);
+    while !bufs.is_empty() {
+        match out.write_vectored(bufs) {
+            Ok(0) => return Err(io::Error::new(io::ErrorKind::WriteZero, "failed to write whole response")),
+            Ok(written) => IoSlice::advance_slices(&mut bufs, written),
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(())
 }
 
 #[cfg(test)]
@@ -249,8 +843,8 @@ mod tests {
             .with_header("Content-Type", "text/plain")
             .with_header("X-Custom", "value");
         
-        assert_eq!(response.headers().get("Content-Type"), Some(&"text/plain".to_string()));
-        assert_eq!(response.headers().get("X-Custom"), Some(&"value".to_string()));
+        assert_eq!(response.headers().get("Content-Type"), Some("text/plain"));
+        assert_eq!(response.headers().get("X-Custom"), Some("value"));
     }
     
     #[test]
@@ -259,7 +853,7 @@ mod tests {
             .with_body("Hello World");
         
         assert_eq!(response.body(), b"Hello World");
-        assert_eq!(response.headers().get("Content-Length"), Some(&"11".to_string()));
+        assert_eq!(response.headers().get("Content-Length"), Some("11"));
     }
     
     #[test]
@@ -267,7 +861,7 @@ mod tests {
         let response = Response::json(r#"{"status": "ok"}"#);
         
         assert_eq!(response.status(), StatusCode::Ok);
-        assert_eq!(response.headers().get("Content-Type"), Some(&"application/json".to_string()));
+        assert_eq!(response.headers().get("Content-Type"), Some(mime::APPLICATION_JSON));
         assert_eq!(response.body(), br#"{"status": "ok"}"#);
     }
     
@@ -276,12 +870,69 @@ mod tests {
         let response = Response::error(StatusCode::BadRequest, "Invalid input");
         
         assert_eq!(response.status(), StatusCode::BadRequest);
-        assert_eq!(response.headers().get("Content-Type"), Some(&"application/json".to_string()));
+        assert_eq!(response.headers().get("Content-Type"), Some(mime::APPLICATION_JSON));
         
         let body_str = String::from_utf8(response.body().to_vec()).unwrap();
         assert!(body_str.contains("Invalid input"));
     }
     
+    #[test]
+    fn test_redirect_and_method_not_allowed() {
+        for status in [StatusCode::MovedPermanently, StatusCode::Found, StatusCode::SeeOther, StatusCode::TemporaryRedirect] {
+            let response = Response::redirect(status, "https://example.com/a?b=\"c\"");
+            let text = String::from_utf8(response.to_bytes()).unwrap();
+            assert!(text.starts_with(&format!("HTTP/1.0 {}\r\n", status)));
+            assert!(text.contains("Location: https://example.com/a?b=\"c\"\r\n"));
+            let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+            assert_eq!(body["location"], "https://example.com/a?b=\"c\"");
+        }
+        
+        let response = Response::method_not_allowed("GET, POST");
+        assert!(response.to_bytes().starts_with(b"HTTP/1.0 405 Method Not Allowed\r\n"));
+        assert_eq!(response.headers().get("Allow"), Some("GET, POST"));
+    }
+    
+    #[test]
+    fn test_json_bodies_are_escaped() {
+        let message = "bad \"quote\" and \\ back\nslash";
+        let error = Response::error(StatusCode::BadRequest, message);
+        let parsed: serde_json::Value = serde_json::from_slice(error.body()).unwrap();
+        assert_eq!(parsed["error"], message);
+        
+        let value = serde_json::json!({"text": message, "n": 1});
+        let response = Response::json_value(&value);
+        assert_eq!(response.headers().get("Content-Type"), Some(mime::APPLICATION_JSON));
+        assert_eq!(serde_json::from_slice::<serde_json::Value>(response.body()).unwrap(), value);
+    }
+    
+    #[test]
+    fn test_builder() {
+        let response = Response::builder(StatusCode::ServiceUnavailable)
+            .content_type("text/plain; charset=utf-8")
+            .cache_control("no-store")
+            .retry_after(Duration::from_millis(2000))
+            .body("busy");
+        assert_eq!(response.status(), StatusCode::ServiceUnavailable);
+        assert_eq!(response.headers().get("Content-Type"), Some("text/plain; charset=utf-8"));
+        assert_eq!(response.headers().get("Cache-Control"), Some("no-store"));
+        assert_eq!(response.headers().get("Retry-After"), Some("2"));
+        assert_eq!(response.headers().get("Content-Length"), Some("4"));
+        
+        // Sin demora se anuncia 0; json no pisa un Content-Type explícito
+        let json = Response::builder(StatusCode::SeeOther)
+            .location("/jobs/status?id=1")
+            .retry_after(Duration::ZERO)
+            .json(&serde_json::json!({"ok": true}));
+        assert_eq!(json.headers().get("Content-Type"), Some(mime::APPLICATION_JSON));
+        assert_eq!(json.headers().get("Location"), Some("/jobs/status?id=1"));
+        assert_eq!(json.headers().get("Retry-After"), Some("0"));
+        let custom = Response::builder(StatusCode::Ok)
+            .content_type("application/problem+json")
+            .json(&serde_json::json!({}));
+        assert_eq!(custom.headers().get("Content-Type"), Some("application/problem+json"));
+        assert!(Response::builder(StatusCode::NoContent).build().body().is_empty());
+    }
+    
     #[test]
     fn test_to_bytes() {
         let response = Response::new(StatusCode::Ok)
@@ -298,6 +949,60 @@ mod tests {
         assert!(text.ends_with("\r\n\r\nTest"));
     }
     
+    /// Writer que acepta pocos bytes por llamada y cuenta las escrituras
+    struct Trickle {
+        out: Vec<u8>,
+        max: usize,
+        vectored_calls: usize,
+    }
+    
+    impl Write for Trickle {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            let n = buf.len().min(self.max);
+            self.out.extend_from_slice(&buf[..n]);
+            Ok(n)
+        }
+        
+        fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+            self.vectored_calls += 1;
+            let mut budget = self.max;
+            for buf in bufs {
+                let n = buf.len().min(budget);
+                self.out.extend_from_slice(&buf[..n]);
+                budget -= n;
+            }
+            Ok(self.max - budget)
+        }
+        
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+    
+    #[test]
+    fn test_write_to_uses_vectored_writes() {
+        let body: Vec<u8> = (0..10_000u32).map(|i| (i % 256) as u8).collect();
+        let mut response = Response::new(StatusCode::Ok).with_body_bytes(body.clone());
+        let expected = response.to_bytes();
+        
+        // Un writer sin límite recibe headers y body en una sola llamada
+        let mut whole = Trickle { out: Vec::new(), max: usize::MAX, vectored_calls: 0 };
+        response.write_to(&mut whole).unwrap();
+        assert_eq!(whole.out, expected);
+        assert_eq!(whole.vectored_calls, 1);
+        
+        // Las escrituras parciales siguen donde quedaron, incluso a mitad de un slice
+        let mut partial = Trickle { out: Vec::new(), max: 777, vectored_calls: 0 };
+        response.write_to(&mut partial).unwrap();
+        assert_eq!(partial.out, expected);
+        assert_eq!(partial.vectored_calls, expected.len().div_ceil(777));
+        
+        // Sin body alcanzan los headers
+        let mut empty = Trickle { out: Vec::new(), max: usize::MAX, vectored_calls: 0 };
+        Response::new(StatusCode::NoContent).write_to(&mut empty).unwrap();
+        assert!(empty.out.ends_with(b"\r\n\r\n"));
+    }
+    
     #[test]
     fn test_empty_body_response() {
         let response = Response::new(StatusCode::NoContent);
@@ -315,6 +1020,167 @@ mod tests {
             .with_body_bytes(binary_data.clone());
         
         assert_eq!(response.body(), &binary_data[..]);
-        assert_eq!(response.headers().get("Content-Length"), Some(&"4".to_string()));
+        assert_eq!(response.headers().get("Content-Length"), Some("4"));
+    }
+    
+    #[test]
+    fn test_compress_gzip() {
+        let body = format!(r#"{{"items": [{}]}}"#, "\"value\",".repeat(200));
+        let mut response = Response::json(&body);
+        assert!(response.compress_gzip(1024));
+        assert_eq!(response.headers().get("Content-Encoding"), Some("gzip"));
+        assert_eq!(response.headers().get("Vary"), Some("Accept-Encoding"));
+        assert_eq!(response.headers().get("Content-Length"), Some(response.body().len().to_string().as_str()));
+        assert_eq!(super::super::encoding::gunzip(response.body(), 1 << 20).unwrap(), body.as_bytes());
+        
+        // Ya comprimido, chico o no JSON: se deja igual
+        assert!(!response.compress_gzip(0));
+        assert!(!Response::json(r#"{"ok": true}"#).compress_gzip(1024));
+        assert!(!Response::new(StatusCode::Ok).with_body(&body).compress_gzip(0));
+    }
+    
+    #[test]
+    fn test_with_range() {
+        let response = Response::new(StatusCode::Ok).with_body("0123456789");
+        
+        let partial = response.clone().with_range(ByteRange { start: 2, end: 4 });
+        assert_eq!(partial.status(), StatusCode::PartialContent);
+        assert_eq!(partial.body(), b"234");
+        assert_eq!(partial.headers().get("Content-Length"), Some("3"));
+        assert_eq!(partial.headers().get("Content-Range"), Some("bytes 2-4/10"));
+        
+        // El final se recorta al body
+        let tail = response.with_range(ByteRange { start: 8, end: 100 });
+        assert_eq!(tail.body(), b"89");
+        assert_eq!(tail.headers().get("Content-Range"), Some("bytes 8-9/10"));
+        
+        let unsatisfiable = Response::range_not_satisfiable(10);
+        assert_eq!(unsatisfiable.status(), StatusCode::RangeNotSatisfiable);
+        assert!(unsatisfiable.to_bytes().starts_with(b"HTTP/1.0 416 Range Not Satisfiable\r\n"));
+    }
+    
+    #[test]
+    fn test_validators() {
+        let modified = UNIX_EPOCH + std::time::Duration::from_millis(1_500);
+        assert_eq!(Response::file_etag(10, Some(modified)), "W/\"a-1\"");
+        assert_eq!(Response::file_etag(10, None), "W/\"a-0\"");
+        assert_eq!(Response::content_etag(b"").len(), 34);
+        
+        let response = Response::not_modified("W/\"a-1\"", Some(modified));
+        let text = String::from_utf8(response.to_bytes()).unwrap();
+        assert!(text.starts_with("HTTP/1.0 304 Not Modified\r\n"));
+        assert!(text.contains("ETag: W/\"a-1\"\r\n"));
+        assert!(text.contains("Last-Modified: Thu, 01 Jan 1970 00:00:01 GMT\r\n"));
+        assert!(text.ends_with("\r\n\r\n"));
+    }
+    
+    #[test]
+    fn test_with_cache() {
+        let response = Response::json("{}").with_cache(CacheControl::Public(Duration::from_secs(300)));
+        assert_eq!(response.headers().get("Cache-Control"), Some("public, max-age=300"));
+        let expires = super::super::date::parse(response.headers().get("Expires").unwrap()).unwrap();
+        assert!(expires > SystemTime::now() + Duration::from_secs(298));
+        
+        // Reemplaza una política anterior
+        let response = response.with_cache(CacheControl::NoCache);
+        assert_eq!(response.headers().get_all("Cache-Control").collect::<Vec<_>>(), ["no-cache"]);
+    }
+    
+    #[test]
+    fn test_strip_body() {
+        let mut response = Response::json(r#"{"ok": true}"#);
+        response.strip_body();
+        let text = String::from_utf8(response.to_bytes()).unwrap();
+        assert!(text.contains("Content-Length: 12\r\n"));
+        assert!(text.ends_with("\r\n\r\n"));
+        
+        // Sin Content-Length previo se agrega antes de quitar el body
+        let mut raw = Response::new(StatusCode::Ok);
+        raw.body = b"abc".to_vec();
+        raw.strip_body();
+        assert_eq!(raw.headers().get("Content-Length"), Some("3"));
+        assert!(raw.body().is_empty());
+    }
+    
+    #[test]
+    fn test_stream_body() {
+        let data: Vec<u8> = (0..200_000u32).map(|i| (i % 251) as u8).collect();
+        let mut response = Response::new(StatusCode::Ok)
+            .with_header("Content-Type", "application/octet-stream")
+            .with_stream(std::io::Cursor::new(data.clone()), data.len() as u64);
+        assert!(response.is_streaming());
+        assert!(response.body().is_empty());
+        assert!(!response.to_bytes().ends_with(&data[..10]));
+        
+        let mut out = Vec::new();
+        response.write_to(&mut out).unwrap();
+        let split = out.windows(4).position(|w| w == b"\r\n\r\n").unwrap() + 4;
+        let head = String::from_utf8_lossy(&out[..split]);
+        assert!(head.contains("Content-Length: 200000\r\n"));
+        assert_eq!(&out[split..], &data[..]);
+        
+        // Los clones comparten el reader, que se consume una sola vez
+        let mut consumed = Response::new(StatusCode::Ok).with_stream(std::io::empty(), 0);
+        let mut clone = consumed.clone();
+        consumed.write_to(&mut Vec::new()).unwrap();
+        assert!(clone.write_to(&mut Vec::new()).is_err());
+    }
+    
+    #[test]
+    fn test_send_to_socket_with_file_body() {
+        use std::net::TcpListener;
+
+        let path = std::env::temp_dir().join(format!("send-to-{}.bin", std::process::id()));
+        let data: Vec<u8> = (0..100_000u32).map(|i| (i % 251) as u8).collect();
+        std::fs::write(&path, &data).unwrap();
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (server, _) = listener.accept().unwrap();
+        let reader = std::thread::spawn(move || {
+            let mut received = Vec::new();
+            client.read_to_end(&mut received).unwrap();
+            received
+        });
+
+        let file = File::open(&path).unwrap();
+        let mut response = Response::new(StatusCode::Ok).with_file(file, data.len() as u64);
+        let wire_len = response.wire_len();
+        response.send_to(&server).unwrap();
+        // Un body en memoria va por `write_to`
+        Response::new(StatusCode::Ok).with_body("fin").send_to(&server).unwrap();
+        // Un archivo más corto que lo anunciado es un error
+        let file = File::open(&path).unwrap();
+        let err = Response::new(StatusCode::Ok).with_file(file, data.len() as u64 + 1).send_to(&server).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+        drop(server);
+
+        let received = reader.join().unwrap();
+        let split = received.windows(4).position(|w| w == b"\r\n\r\n").unwrap() + 4;
+        assert!(String::from_utf8_lossy(&received[..split]).contains("Content-Length: 100000\r\n"));
+        assert_eq!(&received[split..split + data.len()], &data[..]);
+        assert_eq!(wire_len, (split + data.len()) as u64);
+        assert!(String::from_utf8_lossy(&received[split + data.len()..]).contains("\r\n\r\nfin"));
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_stream_body_length_mismatch() {
+        // Bytes de más se ignoran; de menos es un error
+        let mut longer = Response::new(StatusCode::Ok).with_stream(&b"abcdef"[..], 3);
+        longer.buffer_body().unwrap();
+        assert_eq!(longer.body(), b"abc");
+        assert!(!longer.is_streaming());
+        
+        let mut shorter = Response::new(StatusCode::Ok).with_stream(&b"ab"[..], 3);
+        let err = shorter.write_to(&mut Vec::new()).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+        
+        // HEAD y gzip no tocan el reader
+        let mut head = Response::json("").with_stream(&b"{}"[..], 2);
+        assert!(!head.compress_gzip(0));
+        head.strip_body();
+        assert!(!head.is_streaming());
+        assert_eq!(head.headers().get("Content-Length"), Some("2"));
     }
 }
\ No newline at end of file
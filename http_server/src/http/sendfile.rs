@@ -0,0 +1,111 @@
+//! # Copia de Archivos al Socket sin Pasar por el Proceso
+//! src/http/sendfile.rs
+//!
+//! Un body que sale de un archivo (`Response::with_file`) se puede enviar
+//! con `sendfile(2)`: el kernel copia de la page cache al socket, sin leer
+//! los bytes a un buffer del proceso para después escribirlos.
+//!
+//! - Solo en Linux; en otros sistemas se usa `io::copy`.
+//! - Si el archivo no admite `sendfile` (`EINVAL`, `ENOSYS`: algunos
+//!   sistemas de archivos o un pipe) se sigue con `io::copy` desde donde
+//!   quedó. `copy_file_range(2)` no sirve acá: el destino es un socket.
+//! - Se envía desde la posición actual del archivo, que avanza con lo
+//!   enviado (un `Range` ya hizo el `seek`).
+
+use std::fs::File;
+use std::io::{self, Read};
+use std::net::TcpStream;
+
+#[cfg(target_os = "linux")]
+mod ffi {
+    extern "C" {
+        /// `ssize_t sendfile(int out_fd, int in_fd, off_t *offset, size_t count)`
+        pub fn sendfile(out_fd: i32, in_fd: i32, offset: *mut i64, count: usize) -> isize;
+    }
+}
+
+/// Máximo por llamada (Linux transfiere como mucho ~2 GB por vez)
+#[cfg(target_os = "linux")]
+const MAX_CHUNK: u64 = 1 << 30;
+
+/// Copia hasta `len` bytes de `file` a `socket` y retorna cuántos copió
+///
+/// Retorna menos de `len` solo si el archivo terminó antes.
+#[cfg(target_os = "linux")]
+pub fn copy(file: &mut File, socket: &TcpStream, len: u64) -> io::Result<u64> {
+    use std::os::unix::io::AsRawFd;
+
+    let mut sent = 0;
+    while sent < len {
+        let count = (len - sent).min(MAX_CHUNK) as usize;
+        let n = unsafe { ffi::sendfile(socket.as_raw_fd(), file.as_raw_fd(), std::ptr::null_mut(), count) };
+        if n > 0 {
+            sent += n as u64;
+            continue;
+        }
+        if n == 0 {
+            break;
+        }
+
+        let err = io::Error::last_os_error();
+        match err.raw_os_error() {
+            Some(code) if code == EINTR => continue,
+            Some(code) if code == EINVAL || code == ENOSYS => {
+                return Ok(sent + portable_copy(file, socket, len - sent)?);
+            }
+            _ => return Err(err),
+        }
+    }
+    Ok(sent)
+}
+
+#[cfg(target_os = "linux")]
+const EINTR: i32 = 4;
+#[cfg(target_os = "linux")]
+const EINVAL: i32 = 22;
+#[cfg(target_os = "linux")]
+const ENOSYS: i32 = 38;
+
+#[cfg(not(target_os = "linux"))]
+pub fn copy(file: &mut File, socket: &TcpStream, len: u64) -> io::Result<u64> {
+    portable_copy(file, socket, len)
+}
+
+/// Copia pasando por un buffer del proceso
+fn portable_copy(file: &mut File, mut socket: &TcpStream, len: u64) -> io::Result<u64> {
+    io::copy(&mut file.take(len), &mut socket)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Seek, SeekFrom, Write};
+    use std::net::TcpListener;
+
+    #[test]
+    fn test_copies_from_the_current_position() {
+        let path = std::env::temp_dir().join(format!("sendfile-{}.bin", std::process::id()));
+        let data: Vec<u8> = (0..200_000u32).map(|i| (i % 251) as u8).collect();
+        File::create(&path).unwrap().write_all(&data).unwrap();
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (server, _) = listener.accept().unwrap();
+        let reader = std::thread::spawn(move || {
+            let mut received = Vec::new();
+            client.read_to_end(&mut received).unwrap();
+            received
+        });
+
+        let mut file = File::open(&path).unwrap();
+        file.seek(SeekFrom::Start(10)).unwrap();
+        assert_eq!(copy(&mut file, &server, 150_000).unwrap(), 150_000);
+        // Pedir más de lo que queda copia solo hasta el final
+        assert_eq!(copy(&mut file, &server, 100_000).unwrap(), 49_990);
+        drop(server);
+
+        let received = reader.join().unwrap();
+        assert_eq!(received, &data[10..]);
+        let _ = std::fs::remove_file(&path);
+    }
+}
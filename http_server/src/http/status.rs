@@ -4,38 +4,98 @@
 //! Este módulo define los códigos de estado HTTP/1.0 que usará el servidor.
 //! Según el RFC 1945, HTTP/1.0 define códigos en 5 categorías:
 //!
-//! - **1xx**: Informacional (no se usan en HTTP/1.0)
-//! - **2xx**: Éxito (200 OK)
-//! - **3xx**: Redirección (no implementadas por ahora)
-//! - **4xx**: Error del cliente (400, 404, 409, 429)
-//! - **5xx**: Error del servidor (500, 503)
+//! - **1xx**: Informacional (solo 101, para el upgrade a WebSocket)
+//! - **2xx**: Éxito (200, 202, 204, 206)
+//! - **3xx**: Redirección (301, 302, 303, y 304 para requests condicionales)
+//! - **4xx**: Error del cliente (400, 401, 403, 404, 405, 409, 413, 415, 416, 429, 431)
+//! - **5xx**: Error del servidor (500, 502, 503, 504)
 
 /// Representa los códigos de estado HTTP que soporta nuestro servidor
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum StatusCode {
+    /// 101 Switching Protocols - Upgrade aceptado (WebSocket)
+    SwitchingProtocols = 101,
+    
     /// 200 OK - La petición fue exitosa
     Ok = 200,
     
+    /// 202 Accepted - Aceptada, se completa después (ej: el shutdown drena)
+    Accepted = 202,
+    
     /// 204 No Content - Petición exitosa sin contenido en el body
     NoContent = 204,
     
+    /// 206 Partial Content - Se responde solo el rango pedido con `Range`
+    PartialContent = 206,
+    
+    /// 301 Moved Permanently - El recurso está en otra URL (ej: HTTP → HTTPS)
+    MovedPermanently = 301,
+    
+    /// 302 Found - El recurso está temporalmente en otra URL
+    Found = 302,
+    
+    /// 303 See Other - El resultado se obtiene con GET en otra URL (ej: tras un POST)
+    SeeOther = 303,
+    
+    /// 304 Not Modified - El recurso no cambió desde la versión que tiene el cliente
+    NotModified = 304,
+    
+    /// 307 Temporary Redirect - Como 302, pero el cliente repite el mismo método y body
+    TemporaryRedirect = 307,
+    
+    /// 308 Permanent Redirect - Como 301, pero el cliente repite el mismo método y body
+    PermanentRedirect = 308,
+    
     /// 400 Bad Request - Parámetros inválidos o malformados
     BadRequest = 400,
     
+    /// 401 Unauthorized - Faltan credenciales o son inválidas
+    Unauthorized = 401,
+    
+    /// 403 Forbidden - Recurso no permitido (ej: host fuera de la allowlist de /fetch)
+    Forbidden = 403,
+    
     /// 404 Not Found - Ruta o recurso no encontrado
     NotFound = 404,
     
+    /// 405 Method Not Allowed - La ruta existe pero no acepta ese método
+    MethodNotAllowed = 405,
+    
+    /// 408 Request Timeout - El cliente dejó de enviar el request
+    RequestTimeout = 408,
+    
     /// 409 Conflict - Conflicto en el estado del recurso (ej: job no disponible aún)
     Conflict = 409,
     
+    /// 413 Payload Too Large - El body o el archivo excede el tamaño máximo
+    PayloadTooLarge = 413,
+    
+    /// 414 URI Too Long - La request line excede el tamaño máximo
+    UriTooLong = 414,
+    
+    /// 415 Unsupported Media Type - `Content-Encoding` no soportado
+    UnsupportedMediaType = 415,
+    
+    /// 416 Range Not Satisfiable - El `Range` pedido está fuera del archivo
+    RangeNotSatisfiable = 416,
+    
     /// 429 Too Many Requests - Rate limiting activado
     TooManyRequests = 429,
     
+    /// 431 Request Header Fields Too Large - Headers más grandes que el límite
+    RequestHeaderFieldsTooLarge = 431,
+    
     /// 500 Internal Server Error - Error interno del servidor
     InternalServerError = 500,
     
+    /// 502 Bad Gateway - El servidor remoto de /fetch falló o respondió mal
+    BadGateway = 502,
+    
     /// 503 Service Unavailable - Colas llenas o servidor sobrecargado
     ServiceUnavailable = 503,
+    
+    /// 504 Gateway Timeout - El servidor remoto de /fetch no respondió a tiempo
+    GatewayTimeout = 504,
 }
 
 impl StatusCode {
@@ -62,14 +122,34 @@ impl StatusCode {
     /// ```
     pub fn reason_phrase(&self) -> &'static str {
         match self {
+            StatusCode::SwitchingProtocols => "Switching Protocols",
             StatusCode::Ok => "OK",
+            StatusCode::Accepted => "Accepted",
             StatusCode::NoContent => "No Content",
+            StatusCode::PartialContent => "Partial Content",
+            StatusCode::MovedPermanently => "Moved Permanently",
+            StatusCode::Found => "Found",
+            StatusCode::SeeOther => "See Other",
+            StatusCode::TemporaryRedirect => "Temporary Redirect",
+            StatusCode::PermanentRedirect => "Permanent Redirect",
+            StatusCode::NotModified => "Not Modified",
             StatusCode::BadRequest => "Bad Request",
+            StatusCode::Unauthorized => "Unauthorized",
+            StatusCode::Forbidden => "Forbidden",
             StatusCode::NotFound => "Not Found",
+            StatusCode::MethodNotAllowed => "Method Not Allowed",
+            StatusCode::RequestTimeout => "Request Timeout",
             StatusCode::Conflict => "Conflict",
+            StatusCode::PayloadTooLarge => "Payload Too Large",
+            StatusCode::UriTooLong => "URI Too Long",
+            StatusCode::UnsupportedMediaType => "Unsupported Media Type",
+            StatusCode::RangeNotSatisfiable => "Range Not Satisfiable",
             StatusCode::TooManyRequests => "Too Many Requests",
+            StatusCode::RequestHeaderFieldsTooLarge => "Request Header Fields Too Large",
             StatusCode::InternalServerError => "Internal Server Error",
+            StatusCode::BadGateway => "Bad Gateway",
             StatusCode::ServiceUnavailable => "Service Unavailable",
+            StatusCode::GatewayTimeout => "Gateway Timeout",
         }
     }
     
@@ -83,7 +163,26 @@ impl StatusCode {
     /// assert!(!StatusCode::NotFound.is_success());
     /// ```
     pub fn is_success(&self) -> bool {
-        matches!(self, StatusCode::Ok | StatusCode::NoContent)
+        matches!(self, StatusCode::Ok | StatusCode::Accepted | StatusCode::NoContent | StatusCode::PartialContent)
+    }
+    
+    /// Verifica si el código es una redirección con `Location` (301, 302, 303, 307, 308)
+    /// 
+    /// # Ejemplo
+    /// ```
+    /// use http_server::http::StatusCode;
+    /// assert!(StatusCode::SeeOther.is_redirect());
+    /// assert!(!StatusCode::NotModified.is_redirect());
+    /// ```
+    pub fn is_redirect(&self) -> bool {
+        matches!(
+            self,
+            StatusCode::MovedPermanently
+                | StatusCode::Found
+                | StatusCode::SeeOther
+                | StatusCode::TemporaryRedirect
+                | StatusCode::PermanentRedirect
+        )
     }
     
     /// Verifica si el código indica error del cliente (4xx)
@@ -128,9 +227,17 @@ mod tests {
     
     #[test]
     fn test_status_code_values() {
+        assert_eq!(StatusCode::SwitchingProtocols.as_u16(), 101);
         assert_eq!(StatusCode::Ok.as_u16(), 200);
+        assert_eq!(StatusCode::Accepted.as_u16(), 202);
+        assert_eq!(StatusCode::PartialContent.as_u16(), 206);
+        assert_eq!(StatusCode::NotModified.as_u16(), 304);
         assert_eq!(StatusCode::BadRequest.as_u16(), 400);
+        assert_eq!(StatusCode::Unauthorized.as_u16(), 401);
         assert_eq!(StatusCode::NotFound.as_u16(), 404);
+        assert_eq!(StatusCode::MethodNotAllowed.as_u16(), 405);
+        assert_eq!(StatusCode::RangeNotSatisfiable.as_u16(), 416);
+        assert_eq!(StatusCode::RequestHeaderFieldsTooLarge.as_u16(), 431);
         assert_eq!(StatusCode::InternalServerError.as_u16(), 500);
     }
     
@@ -139,11 +246,21 @@ mod tests {
         assert_eq!(StatusCode::Ok.reason_phrase(), "OK");
         assert_eq!(StatusCode::BadRequest.reason_phrase(), "Bad Request");
         assert_eq!(StatusCode::ServiceUnavailable.reason_phrase(), "Service Unavailable");
+        assert_eq!(StatusCode::PayloadTooLarge.to_string(), "413 Payload Too Large");
+        assert_eq!(StatusCode::UriTooLong.to_string(), "414 URI Too Long");
+        assert_eq!(StatusCode::Found.to_string(), "302 Found");
+        assert_eq!(StatusCode::SeeOther.to_string(), "303 See Other");
+        assert_eq!(StatusCode::TemporaryRedirect.to_string(), "307 Temporary Redirect");
+        assert_eq!(StatusCode::PermanentRedirect.to_string(), "308 Permanent Redirect");
+        assert_eq!(StatusCode::MethodNotAllowed.to_string(), "405 Method Not Allowed");
+        assert_eq!(StatusCode::RequestTimeout.to_string(), "408 Request Timeout");
     }
     
     #[test]
     fn test_is_success() {
         assert!(StatusCode::Ok.is_success());
+        assert!(StatusCode::PartialContent.is_success());
+        assert!(StatusCode::Accepted.is_success());
         assert!(!StatusCode::BadRequest.is_success());
         assert!(!StatusCode::InternalServerError.is_success());
     }
@@ -6,10 +6,14 @@
 //! - /jobs/status
 //! - /jobs/result
 //! - /jobs/cancel
+//! - /jobs/list
+//! - /workers
 
 use crate::http::{Request, Response, StatusCode};
 use crate::jobs::manager::JobManager;
-use crate::jobs::types::{JobType, JobPriority};
+use crate::jobs::types::{JobType, JobPriority, JobStatus};
+use serde_json::json;
+use std::time::Duration;
 
 /// Handler para /jobs/submit?task=TASK&<params>&prio=low|normal|high
 /// 
@@ -41,7 +45,7 @@ pub fn submit_handler(req: &Request, job_manager: &JobManager) -> Response {
             };
             
             let priority = req.query_param("prio")
-                .and_then(|p| JobPriority::from_str(p))
+                .and_then(JobPriority::from_str)
                 .unwrap_or(JobPriority::Normal);
             
             let mut params_map = std::collections::HashMap::new();
@@ -58,24 +62,9 @@ pub fn submit_handler(req: &Request, job_manager: &JobManager) -> Response {
         }
         Method::POST => {
             // POST: parsear JSON del body
-            let body_str = match req.body_string() {
-                Some(s) => s,
-                None => {
-                    return Response::error(
-                        StatusCode::BadRequest,
-                        "Invalid UTF-8 in request body"
-                    );
-                }
-            };
-            
-            let json: serde_json::Value = match serde_json::from_str(&body_str) {
+            let json: serde_json::Value = match req.json_body() {
                 Ok(v) => v,
-                Err(_) => {
-                    return Response::error(
-                        StatusCode::BadRequest,
-                        "Invalid JSON in request body"
-                    );
-                }
+                Err(e) => return Response::error(StatusCode::BadRequest, &e),
             };
             
             let task = match json.get("command").or_else(|| json.get("task")) {
@@ -90,7 +79,7 @@ pub fn submit_handler(req: &Request, job_manager: &JobManager) -> Response {
             
             let priority = json.get("priority").or_else(|| json.get("prio"))
                 .and_then(|v| v.as_str())
-                .and_then(|s| JobPriority::from_str(s))
+                .and_then(JobPriority::from_str)
                 .unwrap_or(JobPriority::Normal);
             
             let params_json = match json.get("params") {
@@ -102,10 +91,7 @@ pub fn submit_handler(req: &Request, job_manager: &JobManager) -> Response {
             (task, priority, params_json)
         }
         _ => {
-            return Response::error(
-                StatusCode::BadRequest,
-                "Method not allowed. Use GET or POST"
-            );
+            return Response::method_not_allowed("GET, POST");
         }
     };
     
@@ -121,21 +107,24 @@ pub fn submit_handler(req: &Request, job_manager: &JobManager) -> Response {
     };
     
     match job_manager.submit_job(job_type, params_json, priority) {
-        Ok(job_id) => {
-            let body = format!(
-                r#"{{"job_id": "{}", "status": "queued"}}"#,
-                job_id
-            );
-            Response::json(&body)
-        }
+        Ok(job_id) => Response::json_value(&json!({"job_id": job_id, "status": "queued"})),
         Err(error) => {
             if error.contains("full") {
-                let mut response = Response::error(
-                    StatusCode::ServiceUnavailable,
-                    &error
-                );
-                response.add_header("Retry-After", "5");
-                response
+                // Retry-After según la profundidad y el drenado de la cola
+                let retry_ms = job_manager.estimate_retry_after_ms(job_type);
+                let now_ms = std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map(|d| d.as_millis() as u64)
+                    .unwrap_or(0);
+                let body = serde_json::json!({
+                    "error": error,
+                    "retry_after_ms": retry_ms,
+                    "estimated_accept_at_ms": now_ms + retry_ms,
+                });
+                
+                Response::builder(StatusCode::ServiceUnavailable)
+                    .retry_after(Duration::from_millis(retry_ms))
+                    .json(&body)
             } else {
                 Response::error(StatusCode::InternalServerError, &error)
             }
@@ -171,27 +160,16 @@ pub fn status_handler(req: &Request, job_manager: &JobManager) -> Response {
     
     match job_manager.get_job_status(job_id) {
         Some(metadata) => {
-            // Construir response JSON
-            let progress_field = if metadata.progress > 0 {
-                format!(r#","progress":{}"#, metadata.progress)
-            } else {
-                String::new()
-            };
-            
-            let eta_field = if let Some(eta) = metadata.eta_ms {
-                format!(r#","eta_ms":{}"#, eta)
-            } else {
-                String::new()
-            };
-            
-            let body = format!(
-                r#"{{"status":"{}"{}{}}}"#,
-                serde_json::to_string(&metadata.status).unwrap().trim_matches('"'),
-                progress_field,
-                eta_field
-            );
+            // progress y eta_ms solo si se conocen
+            let mut body = json!({"status": metadata.status});
+            if metadata.progress > 0 {
+                body["progress"] = json!(metadata.progress);
+            }
+            if let Some(eta) = metadata.eta_ms {
+                body["eta_ms"] = json!(eta);
+            }
             
-            Response::json(&body)
+            Response::json_value(&body)
         }
         None => {
             Response::error(
@@ -237,9 +215,7 @@ pub fn result_handler(req: &Request, job_manager: &JobManager) -> Response {
                 crate::jobs::types::JobStatus::Done => {
                     // Retornar el resultado
                     if let Some(result) = metadata.result {
-                        Response::new(StatusCode::Ok)
-                            .with_header("Content-Type", "application/json")
-                            .with_body(&result)
+                        Response::json(&result)
                     } else {
                         Response::error(
                             StatusCode::InternalServerError,
@@ -296,10 +272,7 @@ pub fn cancel_handler(req: &Request, job_manager: &JobManager) -> Response {
     };
     
     match job_manager.cancel_job(job_id) {
-        Ok(()) => {
-            let body = r#"{"status": "canceled"}"#;
-            Response::json(body)
-        }
+        Ok(()) => Response::json_value(&json!({"status": "canceled"})),
         Err(error) => {
             if error.contains("not found") {
                 Response::error(StatusCode::NotFound, &error)
@@ -312,6 +285,83 @@ pub fn cancel_handler(req: &Request, job_manager: &JobManager) -> Response {
     }
 }
 
+/// Máximo de jobs que retorna /jobs/list
+pub const MAX_LIST_LIMIT: usize = 500;
+
+/// Handler para /jobs/list?status=STATUS&limit=N
+/// 
+/// Lista los jobs más recientes primero. No incluye los parámetros ni el
+/// resultado (pueden tener datos del usuario); para eso están
+/// /jobs/status y /jobs/result.
+/// 
+/// # Query parameters
+/// - `status`: solo jobs en ese estado (opcional)
+/// - `limit`: máximo de jobs (default 50, máximo 500)
+/// 
+/// # Ejemplo de response
+/// ```json
+/// {"count": 1, "jobs": [{"id": "job-abc123", "task": "isprime",
+///   "status": "running", "priority": "normal", "progress": 40,
+///   "created_at": 1730000000}]}
+/// ```
+pub fn list_handler(req: &Request, job_manager: &JobManager) -> Response {
+    let status = match req.query_param("status") {
+        Some(s) => match serde_json::from_value::<JobStatus>(serde_json::Value::from(s.to_lowercase())) {
+            Ok(status) => Some(status),
+            Err(_) => {
+                return Response::error(
+                    StatusCode::BadRequest,
+                    &format!("Unknown status: {}", s)
+                );
+            }
+        },
+        None => None,
+    };
+    
+    let limit = match req.query_param("limit") {
+        Some(l) => match l.parse::<usize>() {
+            Ok(n) if (1..=MAX_LIST_LIMIT).contains(&n) => n,
+            _ => {
+                return Response::error(
+                    StatusCode::BadRequest,
+                    &format!("limit must be between 1 and {}", MAX_LIST_LIMIT)
+                );
+            }
+        },
+        None => 50,
+    };
+    
+    let jobs: Vec<serde_json::Value> = job_manager.list_jobs(status, limit)
+        .into_iter()
+        .map(|job| serde_json::json!({
+            "id": job.id,
+            "task": job.job_type,
+            "status": job.status,
+            "priority": job.priority,
+            "progress": job.progress,
+            "created_at": job.created_at,
+        }))
+        .collect();
+    
+    let body = serde_json::json!({ "count": jobs.len(), "jobs": jobs });
+    Response::json_value(&body)
+}
+
+/// Handler para /workers
+/// 
+/// Tabla con las estadísticas de cada worker de jobs, para detectar carga
+/// desigual entre workers.
+/// 
+/// # Ejemplo de response
+/// ```json
+/// {"workers": [{"name": "CPU-0", "pool": "cpu", "jobs_processed": 12,
+///   "busy_ms": 5400, "idle_ms": 60200, "last_job_id": "job-abc123"}]}
+/// ```
+pub fn workers_handler(_req: &Request, job_manager: &JobManager) -> Response {
+    let body = serde_json::json!({ "workers": job_manager.worker_stats() });
+    Response::json_value(&body)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -494,4 +544,45 @@ mod tests {
             assert!(body.contains("Missing required parameter: task"));
         }
     
-}
\ No newline at end of file
+    #[test]
+    fn test_workers_handler_lists_all_workers() {
+        let raw = b"GET /workers HTTP/1.0\r\n\r\n";
+        let request = Request::parse(raw).unwrap();
+        
+        let manager = JobManager::new(JobManagerConfig::default());
+        
+        let response = workers_handler(&request, &manager);
+        assert_eq!(response.status(), StatusCode::Ok);
+        
+        let json: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        let workers = json["workers"].as_array().unwrap();
+        assert_eq!(workers.len(), manager.total_workers());
+        assert_eq!(workers[0]["name"], "CPU-0");
+        assert_eq!(workers[0]["jobs_processed"], 0);
+        assert!(workers[0]["last_job_id"].is_null());
+    }
+    
+    #[test]
+    fn test_list_handler_validates_filters() {
+        let manager = JobManager::new(JobManagerConfig::default());
+        
+        for raw in [
+            &b"GET /jobs/list?status=finished HTTP/1.0\r\n\r\n"[..],
+            &b"GET /jobs/list?limit=0 HTTP/1.0\r\n\r\n"[..],
+            &b"GET /jobs/list?limit=many HTTP/1.0\r\n\r\n"[..],
+        ] {
+            let response = list_handler(&Request::parse(raw).unwrap(), &manager);
+            assert_eq!(response.status(), StatusCode::BadRequest);
+        }
+        
+        let raw = b"GET /jobs/list?status=QUEUED&limit=5 HTTP/1.0\r\n\r\n";
+        let response = list_handler(&Request::parse(raw).unwrap(), &manager);
+        assert_eq!(response.status(), StatusCode::Ok);
+        
+        let json: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        let jobs = json["jobs"].as_array().unwrap();
+        assert!(jobs.len() <= 5);
+        assert_eq!(json["count"], jobs.len());
+        assert!(jobs.iter().all(|job| job["status"] == "queued" && job.get("params").is_none()));
+    }
+}
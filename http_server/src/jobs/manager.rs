@@ -2,16 +2,49 @@
 //! src/jobs/manager.rs
 //!
 //! Coordina la ejecución de jobs: encolado, workers, timeouts, cancelación.
+//! Cada tipo de cola tiene su propio `ThreadPool` (ver `crate::workers`).
+//! Con work stealing activo, los workers CPU e IO ociosos toman jobs de la
+//! otra cola; la cola básica no participa.
+//!
+//! Antes de cada job, los workers ceden el paso (hasta `batch_yield_ms`) a
+//! los requests interactivos en curso, registrados en el `PriorityGate`.
+//!
+//! Los valores recargables en caliente (timeouts, backpressure, Retry-After,
+//! limpieza de jobs y nivel de log) se guardan en atómicos compartidos con
+//! los workers; `apply_live_config` los actualiza tras una recarga.
 
-use crate::jobs::types::{JobMetadata, JobPriority, JobType};
-use crate::jobs::queue::JobQueue;
+use crate::config::{Config, LOG_LEVELS};
+use crate::events::{Event, EventBus};
+use crate::jobs::types::{JobMetadata, JobPriority, JobStatus, JobType};
+use crate::jobs::queue::{JobQueue, QueueFullPolicy};
 use crate::jobs::storage::JobStorage;
-use crate::http::{Request, Response};
+use crate::http::{redact, Request, Response};
 use crate::commands;
+use crate::workers::{
+    affinity, panic_message, DispatchPolicy, Elasticity, PoolStats, PriorityGate, SubmitError, ThreadPool,
+};
+use serde::Serialize;
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+
+/// Prefijo del error de un job cuyo comando hizo panic
+const PANIC_ERROR_PREFIX: &str = "Job panicked";
+
+/// Cada cuánto corre la limpieza de jobs terminados
+const CLEANUP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Posición de `level` en `LOG_LEVELS` (un nivel desconocido muestra todo)
+fn log_rank(level: &str) -> usize {
+    LOG_LEVELS.iter().position(|l| *l == level).unwrap_or(LOG_LEVELS.len())
+}
+
+/// Indica si el nivel vigente permite emitir logs de `level`
+fn log_enabled(current: &AtomicUsize, level: &str) -> bool {
+    log_rank(level) <= current.load(Ordering::Relaxed)
+}
 
 /// Configuración del Job Manager
 #[derive(Clone)]
@@ -43,8 +76,46 @@ pub struct JobManagerConfig {
     /// Número de workers para básicos
     pub basic_workers: usize,
     
+    /// Máximo de workers creados a demanda por pool (0 = tamaño fijo)
+    pub cpu_max_workers: usize,
+    pub io_max_workers: usize,
+    pub basic_max_workers: usize,
+    
+    /// Segundos ociosos tras los que se retira un worker extra
+    pub worker_idle_secs: u64,
+    
     /// Ruta del archivo de persistencia
     pub storage_path: String,
+    
+    /// Política de cola llena para la cola CPU
+    pub cpu_queue_policy: QueueFullPolicy,
+    
+    /// Política de cola llena para la cola IO
+    pub io_queue_policy: QueueFullPolicy,
+    
+    /// Política de cola llena para la cola básica
+    pub basic_queue_policy: QueueFullPolicy,
+    
+    /// Porcentaje de ocupación a partir del cual se rechazan jobs (colas `reject`)
+    pub backpressure_threshold: u8,
+    
+    /// Retry-After sugerido al rechazar por cola llena (milisegundos)
+    pub retry_after_ms: u64,
+    
+    /// Edad en segundos a partir de la cual se borran jobs terminados (0 = nunca)
+    pub cleanup_age_secs: u64,
+    
+    /// Nivel de log de los workers
+    pub log_level: String,
+    
+    /// Permite que los workers CPU/IO ociosos tomen jobs de la otra cola
+    pub work_stealing: bool,
+    
+    /// Máximo que un worker cede el paso a requests interactivos (ms)
+    pub batch_yield_ms: u64,
+    
+    /// Cores a los que se fijan los workers CPU (vacío = sin afinidad)
+    pub cpu_affinity: Vec<usize>,
 }
 
 impl Default for JobManagerConfig {
@@ -59,14 +130,35 @@ impl Default for JobManagerConfig {
             cpu_workers: 4,
             io_workers: 4,
             basic_workers: 2,
+            cpu_max_workers: 0,
+            io_max_workers: 0,
+            basic_max_workers: 0,
+            worker_idle_secs: 60,
             storage_path: "./data/jobs.json".to_string(),
+            cpu_queue_policy: QueueFullPolicy::Reject,
+            io_queue_policy: QueueFullPolicy::Reject,
+            basic_queue_policy: QueueFullPolicy::Reject,
+            backpressure_threshold: 90,
+            retry_after_ms: 5_000,
+            cleanup_age_secs: 3600,
+            log_level: "info".to_string(),
+            work_stealing: false,
+            batch_yield_ms: 50,
+            cpu_affinity: Vec::new(),
         }
     }
 }
 
 impl JobManagerConfig {
     /// Crea una configuración desde el Config principal
-    pub fn from_config(config: &crate::config::Config) -> Self {
+    /// 
+    /// Las políticas inválidas caen en Reject y una afinidad inválida en
+    /// ninguna (`Config::validate` ya las rechaza).
+    pub fn from_config(config: &Config) -> Self {
+        let policy = |name: &str| {
+            QueueFullPolicy::parse(name, config.queue_block_timeout_ms).unwrap_or_default()
+        };
+        
         Self {
             cpu_queue_capacity: config.cpu_queue_capacity,
             io_queue_capacity: config.io_queue_capacity,
@@ -77,7 +169,21 @@ impl JobManagerConfig {
             cpu_workers: config.cpu_workers,
             io_workers: config.io_workers,
             basic_workers: config.basic_workers,
+            cpu_max_workers: config.max_cpu_workers,
+            io_max_workers: config.max_io_workers,
+            basic_max_workers: config.max_basic_workers,
+            worker_idle_secs: config.worker_idle_secs,
             storage_path: config.jobs_storage_path.clone(),
+            cpu_queue_policy: policy(&config.cpu_queue_policy),
+            io_queue_policy: policy(&config.io_queue_policy),
+            basic_queue_policy: policy(&config.basic_queue_policy),
+            backpressure_threshold: config.backpressure_threshold,
+            retry_after_ms: config.retry_after_ms,
+            cleanup_age_secs: config.jobs_cleanup_age_secs,
+            log_level: config.log_level.clone(),
+            work_stealing: config.work_stealing,
+            batch_yield_ms: config.batch_yield_ms,
+            cpu_affinity: config.affinity_plan().cpu,
         }
     }
 }
@@ -92,11 +198,121 @@ pub struct JobManager {
     io_queue: JobQueue,
     basic_queue: JobQueue,
     
+    /// Pools de workers por tipo de job
+    cpu_pool: Arc<ThreadPool>,
+    io_pool: Arc<ThreadPool>,
+    basic_pool: Arc<ThreadPool>,
+    
     /// Storage persistente
     storage: JobStorage,
     
+    /// Bus de eventos del servidor (cambios de jobs, colas llenas, panics)
+    events: EventBus,
+    
     /// Jobs actualmente en ejecución (job_id -> thread_handle)
     running_jobs: Arc<Mutex<HashMap<String, ()>>>,
+    
+    /// Timeouts vigentes (ms), compartidos con los workers para poder
+    /// cambiarlos en caliente
+    cpu_timeout_ms: Arc<AtomicU64>,
+    io_timeout_ms: Arc<AtomicU64>,
+    basic_timeout_ms: Arc<AtomicU64>,
+    
+    /// Umbral de backpressure (%), Retry-After (ms) y edad de limpieza (s)
+    backpressure_threshold: Arc<AtomicU64>,
+    retry_after_ms: Arc<AtomicU64>,
+    cleanup_age_secs: Arc<AtomicU64>,
+    
+    /// Nivel de log vigente (posición en `LOG_LEVELS`)
+    log_level: Arc<AtomicUsize>,
+    
+    /// Work stealing activo entre los pools CPU e IO
+    work_stealing: Arc<AtomicBool>,
+    
+    /// Jobs que los workers CPU tomaron de la cola IO, y viceversa
+    cpu_steals: Arc<AtomicU64>,
+    io_steals: Arc<AtomicU64>,
+    
+    /// Contadores por worker, en el orden en que se crearon
+    worker_stats: Arc<Mutex<Vec<WorkerRecord>>>,
+    
+    /// Requests interactivos en curso, a los que los workers ceden el paso
+    priority_gate: Arc<PriorityGate>,
+    batch_yield_ms: Arc<AtomicU64>,
+    
+    /// Jobs cuyo comando hizo panic
+    job_panics: Arc<AtomicU64>,
+    
+    /// Workers detenidos para traspasar los jobs a otro proceso
+    paused: Arc<AtomicBool>,
+}
+
+/// Resultado de `JobManager::prepare_handoff`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HandoffSummary {
+    /// Jobs en cola que el nuevo proceso retomará
+    pub queued: usize,
+    
+    /// De esos, los que estaban corriendo y no terminaron a tiempo
+    pub requeued: usize,
+}
+
+/// Cola de un tipo de job junto con su timeout vigente
+#[derive(Clone)]
+struct Lane {
+    /// Nombre del pool dueño de la cola ("cpu", "io", "basic")
+    pool: &'static str,
+    queue: JobQueue,
+    timeout_ms: Arc<AtomicU64>,
+}
+
+/// Cola de la que puede robar un pool y su contador de robos
+type StealSource = (Lane, Arc<AtomicU64>);
+
+/// Estado compartido que reciben las tareas de los workers
+#[derive(Clone)]
+struct WorkerContext {
+    storage: JobStorage,
+    running_jobs: Arc<Mutex<HashMap<String, ()>>>,
+    log_level: Arc<AtomicUsize>,
+    work_stealing: Arc<AtomicBool>,
+    worker_stats: Arc<Mutex<Vec<WorkerRecord>>>,
+    priority_gate: Arc<PriorityGate>,
+    batch_yield_ms: Arc<AtomicU64>,
+    job_panics: Arc<AtomicU64>,
+    paused: Arc<AtomicBool>,
+    events: EventBus,
+}
+
+/// Contadores de un worker de jobs
+struct WorkerRecord {
+    name: String,
+    pool: &'static str,
+    started: Instant,
+    jobs_processed: u64,
+    busy: Duration,
+    last_job_id: Option<String>,
+    core: Option<usize>,
+}
+
+impl WorkerRecord {
+    fn new(name: String, pool: &'static str, started: Instant) -> Self {
+        Self { name, pool, started, jobs_processed: 0, busy: Duration::ZERO, last_job_id: None, core: None }
+    }
+}
+
+/// Estadísticas de un worker de jobs (endpoint `/workers`)
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkerStats {
+    pub name: String,
+    pub pool: &'static str,
+    pub jobs_processed: u64,
+    pub busy_ms: u64,
+    pub idle_ms: u64,
+    pub last_job_id: Option<String>,
+    
+    /// Core al que está fijado (`--cpu-affinity`), visto en su último job
+    pub core: Option<usize>,
 }
 
 impl JobManager {
@@ -105,133 +321,276 @@ impl JobManager {
         // Crear directorio data/ si no existe
         let _ = std::fs::create_dir_all("./data");
         
+        let events = EventBus::new();
         let storage = JobStorage::new(&config.storage_path)
-            .expect("Failed to initialize job storage");
+            .expect("Failed to initialize job storage")
+            .with_events(events.clone());
+        
+        let pool = |name: &str, min: usize, max: usize, capacity: usize| {
+            let elasticity = Elasticity::new(
+                min,
+                max,
+                Duration::from_secs(config.worker_idle_secs),
+                log_rank("info") <= log_rank(&config.log_level),
+            );
+            Arc::new(ThreadPool::elastic(name, min, capacity, DispatchPolicy::Fifo, elasticity))
+        };
         
         let manager = Self {
             config: config.clone(),
-            cpu_queue: JobQueue::new(config.cpu_queue_capacity),
-            io_queue: JobQueue::new(config.io_queue_capacity),
-            basic_queue: JobQueue::new(config.basic_queue_capacity),
+            cpu_queue: JobQueue::with_policy(config.cpu_queue_capacity, config.cpu_queue_policy),
+            io_queue: JobQueue::with_policy(config.io_queue_capacity, config.io_queue_policy),
+            basic_queue: JobQueue::with_policy(config.basic_queue_capacity, config.basic_queue_policy),
+            cpu_pool: pool("CPU", config.cpu_workers, config.cpu_max_workers, config.cpu_queue_capacity),
+            io_pool: pool("IO", config.io_workers, config.io_max_workers, config.io_queue_capacity),
+            basic_pool: pool("Basic", config.basic_workers, config.basic_max_workers, config.basic_queue_capacity),
             storage,
+            events,
             running_jobs: Arc::new(Mutex::new(HashMap::new())),
+            cpu_timeout_ms: Arc::new(AtomicU64::new(config.cpu_timeout_ms)),
+            io_timeout_ms: Arc::new(AtomicU64::new(config.io_timeout_ms)),
+            basic_timeout_ms: Arc::new(AtomicU64::new(config.basic_timeout_ms)),
+            backpressure_threshold: Arc::new(AtomicU64::new(config.backpressure_threshold as u64)),
+            retry_after_ms: Arc::new(AtomicU64::new(config.retry_after_ms)),
+            cleanup_age_secs: Arc::new(AtomicU64::new(config.cleanup_age_secs)),
+            log_level: Arc::new(AtomicUsize::new(log_rank(&config.log_level))),
+            work_stealing: Arc::new(AtomicBool::new(config.work_stealing)),
+            cpu_steals: Arc::new(AtomicU64::new(0)),
+            io_steals: Arc::new(AtomicU64::new(0)),
+            worker_stats: Arc::new(Mutex::new(Vec::new())),
+            priority_gate: Arc::new(PriorityGate::new()),
+            batch_yield_ms: Arc::new(AtomicU64::new(config.batch_yield_ms)),
+            job_panics: Arc::new(AtomicU64::new(0)),
+            paused: Arc::new(AtomicBool::new(false)),
         };
         
-        // Iniciar workers
-        manager.spawn_workers();
+        for pool in [&manager.cpu_pool, &manager.io_pool, &manager.basic_pool] {
+            pool.set_events(manager.events.clone());
+        }
+        manager.cpu_pool.set_affinity(config.cpu_affinity.clone());
+        
+        {
+            let now = Instant::now();
+            let mut workers = manager.worker_stats.lock().unwrap();
+            for (pool_name, pool) in [
+                ("cpu", &manager.cpu_pool),
+                ("io", &manager.io_pool),
+                ("basic", &manager.basic_pool),
+            ] {
+                workers.extend(pool.worker_names().into_iter()
+                    .map(|name| WorkerRecord::new(name, pool_name, now)));
+            }
+        }
+        
+        if log_enabled(&manager.log_level, "info") {
+            println!("🔧 Job workers started: CPU={}, IO={}, Basic={}",
+                config.cpu_workers, config.io_workers, config.basic_workers);
+        }
+        manager.spawn_cleanup();
         
         manager
     }
     
-    /// Inicia los workers para procesar jobs
-    fn spawn_workers(&self) {
-        // Workers CPU-bound
-        for i in 0..self.config.cpu_workers {
-            let queue = self.cpu_queue.clone();
-            let storage = self.storage.clone();
-            let running = Arc::clone(&self.running_jobs);
-            let timeout_ms = self.config.cpu_timeout_ms;
-            
-            thread::spawn(move || {
-                Self::worker_loop(
-                    format!("CPU-{}", i),
-                    queue,
-                    storage,
-                    running,
-                    timeout_ms,
-                )
-            });
-        }
+    /// Lanza el thread que borra periódicamente los jobs terminados viejos
+    fn spawn_cleanup(&self) {
+        let storage = self.storage.clone();
+        let max_age_secs = Arc::clone(&self.cleanup_age_secs);
+        let log_level = Arc::clone(&self.log_level);
         
-        // Workers IO-bound
-        for i in 0..self.config.io_workers {
-            let queue = self.io_queue.clone();
-            let storage = self.storage.clone();
-            let running = Arc::clone(&self.running_jobs);
-            let timeout_ms = self.config.io_timeout_ms;
-            
-            thread::spawn(move || {
-                Self::worker_loop(
-                    format!("IO-{}", i),
-                    queue,
-                    storage,
-                    running,
-                    timeout_ms,
-                )
+        let spawned = thread::Builder::new()
+            .name("jobs-cleanup".to_string())
+            .spawn(move || loop {
+                thread::sleep(CLEANUP_INTERVAL);
+                
+                // Se lee en cada vuelta para respetar las recargas
+                let age = max_age_secs.load(Ordering::Relaxed);
+                if age == 0 {
+                    continue;
+                }
+                
+                match storage.cleanup_old(age) {
+                    Ok(removed) if removed > 0 && log_enabled(&log_level, "info") => {
+                        println!("🧹 Cleanup removed {} finished jobs older than {}s", removed, age);
+                    }
+                    Err(e) if log_enabled(&log_level, "warn") => {
+                        eprintln!("   ❌ Jobs cleanup failed: {}", e);
+                    }
+                    _ => {}
+                }
             });
-        }
         
-        // Workers básicos
-        for i in 0..self.config.basic_workers {
-            let queue = self.basic_queue.clone();
-            let storage = self.storage.clone();
-            let running = Arc::clone(&self.running_jobs);
-            let timeout_ms = self.config.basic_timeout_ms;
-            
-            thread::spawn(move || {
-                Self::worker_loop(
-                    format!("Basic-{}", i),
-                    queue,
-                    storage,
-                    running,
-                    timeout_ms,
-                )
-            });
+        if let Err(e) = spawned {
+            eprintln!("   ❌ No se pudo iniciar el thread de limpieza: {}", e);
         }
     }
     
-    /// Loop principal del worker
-    fn worker_loop(
-        name: String,
-        queue: JobQueue,
-        storage: JobStorage,
-        running_jobs: Arc<Mutex<HashMap<String, ()>>>,
-        timeout_ms: u64,
-    ) {
-        println!("🔧 Worker {} started", name);
+    /// Tarea de un worker del pool: ejecuta jobs hasta vaciar la cola
+    /// 
+    /// Cada job encolado agenda una de estas tareas. Como cada tarea vacía la
+    /// cola completa, si el pool rechaza una tarea por estar lleno las que ya
+    /// están pendientes se encargan del job. Con work stealing activo, al
+    /// vaciar su cola sigue con la de `steal_from`.
+    fn drain_queue(own: Lane, steal_from: Option<StealSource>, ctx: WorkerContext) {
+        let name = thread::current().name().unwrap_or("worker").to_string();
         
         loop {
-            // Esperar por un job
-            let mut job = queue.dequeue();
-            
-            println!("🔨 Worker {} picked up job: {}", name, job.id);
+            // Handoff en curso: los jobs quedan en la cola para el nuevo proceso
+            if ctx.paused.load(Ordering::SeqCst) {
+                break;
+            }
             
-            // Marcar como running
-            job.mark_running();
-            {
-                let mut running = running_jobs.lock().unwrap();
-                running.insert(job.id.clone(), ());
+            // Prioridad interactiva: el job espera en la cola (cancelable)
+            let max_yield = Duration::from_millis(ctx.batch_yield_ms.load(Ordering::Relaxed));
+            if ctx.priority_gate.yield_to_interactive(max_yield) && log_enabled(&ctx.log_level, "debug") {
+                println!("⏸️  Worker {} yielded to interactive requests", name);
             }
-            let _ = storage.save(&job);
             
-            // Ejecutar el job
-            let result = Self::execute_job(&job, timeout_ms);
+            let (job, timeout_ms) = if let Some(job) = own.queue.try_dequeue() {
+                (job, own.timeout_ms.load(Ordering::Relaxed))
+            } else {
+                let stolen = steal_from.as_ref()
+                    .filter(|_| ctx.work_stealing.load(Ordering::Relaxed))
+                    .and_then(|(lane, steals)| {
+                        let job = lane.queue.try_dequeue()?;
+                        steals.fetch_add(1, Ordering::Relaxed);
+                        Some((job, lane.timeout_ms.load(Ordering::Relaxed)))
+                    });
+                
+                match stolen {
+                    Some((job, timeout_ms)) => {
+                        if log_enabled(&ctx.log_level, "debug") {
+                            println!("🥷 Worker {} stole job: {}", name, job.id);
+                        }
+                        (job, timeout_ms)
+                    }
+                    None => break,
+                }
+            };
             
-            // Actualizar con el resultado
-            match result {
-                Ok(response_body) => {
-                    job.mark_done(response_body);
+            Self::run_job(&name, own.pool, job, timeout_ms, &ctx);
+        }
+    }
+    
+    /// Ejecuta un job desencolado, guarda su estado final y lo anota en las
+    /// estadísticas del worker
+    fn run_job(name: &str, pool: &'static str, mut job: JobMetadata, timeout_ms: u64, ctx: &WorkerContext) {
+        let log_level = &ctx.log_level;
+        if log_enabled(log_level, "debug") {
+            println!("🔨 Worker {} picked up job: {}", name, job.id);
+        }
+        
+        // Marcar como running
+        job.mark_running();
+        {
+            let mut running = ctx.running_jobs.lock().unwrap();
+            running.insert(job.id.clone(), ());
+        }
+        let _ = ctx.storage.save(&job);
+        
+        // Ejecutar el job
+        let started = Instant::now();
+        let result = Self::execute_job(&job, timeout_ms);
+        
+        // Actualizar con el resultado
+        match result {
+            Ok(response_body) => {
+                job.mark_done(response_body);
+                if log_enabled(log_level, "info") {
                     println!("✅ Worker {} completed job: {}", name, job.id);
                 }
-                Err(error) => {
-                    if error.contains("timeout") {
-                        job.mark_timeout();
+            }
+            Err(error) => {
+                if error.contains("timeout") {
+                    job.mark_timeout();
+                    if log_enabled(log_level, "warn") {
                         println!("⏱️  Worker {} timeout job: {}", name, job.id);
-                    } else {
-                        job.mark_error(error.clone());
+                    }
+                } else {
+                    // El error queda visible en /jobs/status y en el log
+                    let error = redact::redact_text(&error);
+                    if let Some(message) = error.strip_prefix(PANIC_ERROR_PREFIX) {
+                        ctx.job_panics.fetch_add(1, Ordering::Relaxed);
+                        ctx.events.publish(Event::WorkerPanicked {
+                            pool: pool.to_string(),
+                            message: message.trim_start_matches(": ").to_string(),
+                            job_id: Some(job.id.clone()),
+                        });
+                    }
+                    job.mark_error(error.clone());
+                    if log_enabled(log_level, "warn") {
                         println!("❌ Worker {} failed job: {} - {}", name, job.id, error);
                     }
                 }
             }
-            
-            // Remover de running
-            {
-                let mut running = running_jobs.lock().unwrap();
-                running.remove(&job.id);
+        }
+        
+        // Remover de running
+        {
+            let mut running = ctx.running_jobs.lock().unwrap();
+            running.remove(&job.id);
+        }
+        
+        // Estadísticas del worker (los extras de un pool elástico se
+        // registran con su primer job)
+        {
+            let mut workers = ctx.worker_stats.lock().unwrap();
+            let position = workers.iter().position(|r| r.name == name).unwrap_or_else(|| {
+                workers.push(WorkerRecord::new(name.to_string(), pool, started));
+                workers.len() - 1
+            });
+            let record = &mut workers[position];
+            record.jobs_processed += 1;
+            record.busy += started.elapsed();
+            record.last_job_id = Some(job.id.clone());
+            record.core = affinity::current_core();
+        }
+        
+        // Guardar estado final
+        let _ = ctx.storage.save(&job);
+    }
+    
+    /// Agenda en el pool del tipo una tarea que procese su cola
+    /// 
+    /// Con work stealing activo y el pool hermano (CPU ↔ IO) ocioso, agenda
+    /// además una tarea allí para que tome el job.
+    fn schedule(&self, job_type: JobType) {
+        let mut targets = vec![
+            (self.pool_for(job_type), self.lane_for(job_type), self.steal_source_for(job_type)),
+        ];
+        
+        if self.work_stealing.load(Ordering::Relaxed) {
+            if let Some((sibling, lane, steal_from)) = self.sibling_of(job_type) {
+                let stats = sibling.stats();
+                if stats.queued == 0 && stats.active < stats.max_workers {
+                    targets.push((sibling, lane, steal_from));
+                }
             }
+        }
+        
+        for (pool, lane, steal_from) in targets {
+            let ctx = self.worker_context();
+            let submitted = pool.try_execute(move || Self::drain_queue(lane, steal_from, ctx));
             
-            // Guardar estado final
-            let _ = storage.save(&job);
+            // Full: las tareas pendientes vaciarán la cola, incluido este job
+            if submitted == Err(SubmitError::ShutDown) && log_enabled(&self.log_level, "warn") {
+                eprintln!("   ❌ Worker pool for {:?} is shut down", job_type);
+            }
+        }
+    }
+    
+    /// Estado compartido que necesita una tarea de worker
+    fn worker_context(&self) -> WorkerContext {
+        WorkerContext {
+            storage: self.storage.clone(),
+            running_jobs: Arc::clone(&self.running_jobs),
+            log_level: Arc::clone(&self.log_level),
+            work_stealing: Arc::clone(&self.work_stealing),
+            worker_stats: Arc::clone(&self.worker_stats),
+            priority_gate: Arc::clone(&self.priority_gate),
+            batch_yield_ms: Arc::clone(&self.batch_yield_ms),
+            job_panics: Arc::clone(&self.job_panics),
+            paused: Arc::clone(&self.paused),
+            events: self.events.clone(),
         }
     }
     
@@ -253,18 +612,22 @@ impl JobManager {
             .map_err(|e| format!("Failed to parse request: {}", e))?;
         
         // Clonar job_type para moverlo al thread
-        let job_type = job.job_type.clone();
+        let job_type = job.job_type;
         
         // Ejecutar con timeout
         let result = Arc::new(Mutex::new(None));
         let result_clone = Arc::clone(&result);
         
-        let handle = thread::spawn(move || {
-            let response = Self::dispatch_command(&job_type, &request);
-            let body = String::from_utf8_lossy(response.body()).to_string();
-            let mut res = result_clone.lock().unwrap();
-            *res = Some(body);
-        });
+        // El nombre del thread identifica al job en el mensaje de un panic
+        let handle = thread::Builder::new()
+            .name(job.id.clone())
+            .spawn(move || {
+                let response = Self::dispatch_command(&job_type, &request);
+                let body = String::from_utf8_lossy(response.body()).to_string();
+                let mut res = result_clone.lock().unwrap();
+                *res = Some(body);
+            })
+            .map_err(|e| format!("Cannot start job thread: {}", e))?;
         
         // Esperar con timeout
         let timeout_duration = Duration::from_millis(timeout_ms);
@@ -272,7 +635,9 @@ impl JobManager {
         
         while start.elapsed() < timeout_duration {
             if handle.is_finished() {
-                let _ = handle.join();
+                if let Err(payload) = handle.join() {
+                    return Err(format!("{}: {}", PANIC_ERROR_PREFIX, panic_message(payload.as_ref())));
+                }
                 let res = result.lock().unwrap();
                 return res.clone().ok_or_else(|| "No result".to_string());
             }
@@ -315,8 +680,10 @@ impl JobManager {
             JobType::Grep => "grep",
             JobType::Compress => "compress",
             JobType::HashFile => "hashfile",
+            JobType::Fetch => "fetch",
             JobType::Fibonacci => "fibonacci",
             JobType::Simulate => "simulate",
+            JobType::Factory => "factory",
         }
     }
     
@@ -333,8 +700,10 @@ impl JobManager {
             JobType::Grep => commands::grep_handler(request),
             JobType::Compress => commands::compress_handler(request),
             JobType::HashFile => commands::hashfile_handler(request),
+            JobType::Fetch => commands::fetch_handler(request),
             JobType::Fibonacci => commands::fibonacci_handler(request),
             JobType::Simulate => commands::simulate_handler(request),
+            JobType::Factory => commands::factory_handler(request),
         }
     }
     
@@ -351,30 +720,152 @@ impl JobManager {
         // Crear metadata
         let metadata = JobMetadata::new(job_id.clone(), job_type, params, priority);
         
-        // Seleccionar cola
-        let queue = if job_type.is_cpu_bound() {
-            &self.cpu_queue
-        } else if job_type.is_io_bound() {
-            &self.io_queue
-        } else {
-            &self.basic_queue
-        };
+        let queue = self.queue_for(job_type);
         
-        // Encolar
-        queue.enqueue(metadata.clone())?;
+        // Backpressure: las colas `reject` rechazan antes de llenarse; las
+        // demás políticas deciden por su cuenta qué hacer al llenarse
+        if queue.policy() == QueueFullPolicy::Reject {
+            let threshold = self.backpressure_threshold.load(Ordering::Relaxed) as usize;
+            if queue.len() * 100 >= queue.max_capacity() * threshold {
+                self.publish_saturated(job_type);
+                return Err(format!(
+                    "Queue is {}% full (backpressure threshold: {}%)",
+                    queue.len() * 100 / queue.max_capacity().max(1),
+                    threshold
+                ));
+            }
+        }
+        
+        // Encolar (con DropOldest puede desplazar al job más antiguo)
+        let displaced = queue.enqueue(metadata.clone()).inspect_err(|_| self.publish_saturated(job_type))?;
+        if let Some(mut dropped) = displaced {
+            self.publish_saturated(job_type);
+            dropped.mark_error("Dropped: queue full (drop-oldest policy)".to_string());
+            if log_enabled(&self.log_level, "warn") {
+                println!("🗑️  Job {} dropped to make room for {}", dropped.id, job_id);
+            }
+            let _ = self.storage.save(&dropped);
+        }
         
         // Guardar en storage
         self.storage.save(&metadata)
             .map_err(|e| format!("Storage error: {}", e))?;
         
+        self.schedule(job_type);
+        
         Ok(job_id)
     }
     
+    /// Pool de workers que corresponde a un tipo de job
+    fn pool_for(&self, job_type: JobType) -> &ThreadPool {
+        if job_type.is_cpu_bound() {
+            &self.cpu_pool
+        } else if job_type.is_io_bound() {
+            &self.io_pool
+        } else {
+            &self.basic_pool
+        }
+    }
+    
+    /// Cola y timeout que corresponden a un tipo de job
+    fn lane_for(&self, job_type: JobType) -> Lane {
+        if job_type.is_cpu_bound() {
+            self.cpu_lane()
+        } else if job_type.is_io_bound() {
+            self.io_lane()
+        } else {
+            Lane {
+                pool: "basic",
+                queue: self.basic_queue.clone(),
+                timeout_ms: Arc::clone(&self.basic_timeout_ms),
+            }
+        }
+    }
+    
+    fn cpu_lane(&self) -> Lane {
+        Lane { pool: "cpu", queue: self.cpu_queue.clone(), timeout_ms: Arc::clone(&self.cpu_timeout_ms) }
+    }
+    
+    fn io_lane(&self) -> Lane {
+        Lane { pool: "io", queue: self.io_queue.clone(), timeout_ms: Arc::clone(&self.io_timeout_ms) }
+    }
+    
+    /// Cola de la que pueden robar los workers del pool de `job_type`
+    fn steal_source_for(&self, job_type: JobType) -> Option<StealSource> {
+        if job_type.is_cpu_bound() {
+            Some((self.io_lane(), Arc::clone(&self.cpu_steals)))
+        } else if job_type.is_io_bound() {
+            Some((self.cpu_lane(), Arc::clone(&self.io_steals)))
+        } else {
+            None
+        }
+    }
+    
+    /// Pool hermano de `job_type` (CPU ↔ IO), con su cola y la de `job_type`
+    /// como fuente de robo
+    fn sibling_of(&self, job_type: JobType) -> Option<(&ThreadPool, Lane, Option<StealSource>)> {
+        if job_type.is_cpu_bound() {
+            Some((&self.io_pool, self.io_lane(), Some((self.cpu_lane(), Arc::clone(&self.io_steals)))))
+        } else if job_type.is_io_bound() {
+            Some((&self.cpu_pool, self.cpu_lane(), Some((self.io_lane(), Arc::clone(&self.cpu_steals)))))
+        } else {
+            None
+        }
+    }
+    
+    /// Cola que corresponde a un tipo de job
+    fn queue_for(&self, job_type: JobType) -> &JobQueue {
+        if job_type.is_cpu_bound() {
+            &self.cpu_queue
+        } else if job_type.is_io_bound() {
+            &self.io_queue
+        } else {
+            &self.basic_queue
+        }
+    }
+    
+    /// Estima en cuántos ms la cola de `job_type` volverá a aceptar jobs
+    /// 
+    /// Usa la profundidad actual y la tasa de drenado reciente; sin datos
+    /// de drenado, o si la estimación es mayor, retorna `retry_after_ms`.
+    pub fn estimate_retry_after_ms(&self, job_type: JobType) -> u64 {
+        let queue = self.queue_for(job_type);
+        let max_ms = self.retry_after_ms();
+        
+        // Ocupación a partir de la cual se rechaza
+        let limit = if queue.policy() == QueueFullPolicy::Reject {
+            let threshold = self.backpressure_threshold.load(Ordering::Relaxed) as usize;
+            queue.max_capacity() * threshold / 100
+        } else {
+            queue.max_capacity()
+        };
+        let excess = (queue.len() + 1).saturating_sub(limit).max(1);
+        
+        match queue.drain_rate_per_sec() {
+            Some(rate) if rate > 0.0 => {
+                let estimate = (excess as f64 / rate * 1000.0).ceil() as u64;
+                estimate.min(max_ms)
+            }
+            _ => max_ms,
+        }
+    }
+    
     /// Obtiene el estado de un job
     pub fn get_job_status(&self, job_id: &str) -> Option<JobMetadata> {
         self.storage.get(job_id)
     }
     
+    /// Lista los jobs más recientes primero, opcionalmente filtrados por estado
+    pub fn list_jobs(&self, status: Option<JobStatus>, limit: usize) -> Vec<JobMetadata> {
+        let mut jobs: Vec<JobMetadata> = self.storage.get_all()
+            .into_iter()
+            .filter(|job| status.is_none_or(|s| job.status == s))
+            .collect();
+        jobs.sort_by(|a, b| b.created_at.cmp(&a.created_at).then_with(|| b.id.cmp(&a.id)));
+        jobs.truncate(limit);
+        jobs
+    }
+    
     /// Cancela un job
     pub fn cancel_job(&self, job_id: &str) -> Result<(), String> {
         // Buscar en las colas primero
@@ -458,8 +949,213 @@ impl JobManager {
                 "capacity": basic_stats.capacity,
             },
             "running_jobs": running_count,
+            "panics": self.job_panics.load(Ordering::Relaxed),
+            "pools": self.pool_stats(),
+            "priority": {
+                "interactive_in_flight": self.priority_gate.interactive(),
+                "batch_yields": self.priority_gate.yields(),
+                "batch_yield_ms": self.batch_yield_ms.load(Ordering::Relaxed),
+            },
+            "work_stealing": {
+                "enabled": self.work_stealing.load(Ordering::Relaxed),
+                "cpu_stole_from_io": self.cpu_steals.load(Ordering::Relaxed),
+                "io_stole_from_cpu": self.io_steals.load(Ordering::Relaxed),
+            },
         })
     }
+
+    /// Obtiene (nombre, jobs en cola, capacidad) de cada cola
+    pub fn queue_depths(&self) -> Vec<(&'static str, usize, usize)> {
+        vec![
+            ("cpu", self.cpu_queue.len(), self.cpu_queue.max_capacity()),
+            ("io", self.io_queue.len(), self.io_queue.max_capacity()),
+            ("basic", self.basic_queue.len(), self.basic_queue.max_capacity()),
+        ]
+    }
+
+    /// Bus de eventos del servidor
+    /// 
+    /// Lo comparten el storage, los pools de jobs y el servidor HTTP.
+    pub fn events(&self) -> &EventBus {
+        &self.events
+    }
+    
+    /// Publica que la cola de `job_type` rechazó o desplazó un job
+    fn publish_saturated(&self, job_type: JobType) {
+        let queue = self.queue_for(job_type);
+        self.events.publish(Event::QueueSaturated {
+            queue: self.lane_for(job_type).pool.to_string(),
+            len: queue.len(),
+            capacity: queue.max_capacity(),
+        });
+    }
+    
+    /// Número de jobs ejecutándose actualmente
+    pub fn running_count(&self) -> usize {
+        let running = self.running_jobs.lock().unwrap();
+        running.len()
+    }
+
+    /// Actualiza los timeouts de los workers (recarga de configuración)
+    /// 
+    /// Aplica a los jobs que comiencen después del cambio.
+    pub fn update_timeouts(&self, cpu_ms: u64, io_ms: u64, basic_ms: u64) {
+        self.cpu_timeout_ms.store(cpu_ms, Ordering::Relaxed);
+        self.io_timeout_ms.store(io_ms, Ordering::Relaxed);
+        self.basic_timeout_ms.store(basic_ms, Ordering::Relaxed);
+    }
+    
+    /// Aplica los campos recargables de `config` (tras una recarga en caliente)
+    pub fn apply_live_config(&self, config: &Config) {
+        self.update_timeouts(config.cpu_timeout_ms, config.io_timeout_ms, config.basic_timeout_ms);
+        self.backpressure_threshold.store(config.backpressure_threshold as u64, Ordering::Relaxed);
+        self.retry_after_ms.store(config.retry_after_ms, Ordering::Relaxed);
+        self.cleanup_age_secs.store(config.jobs_cleanup_age_secs, Ordering::Relaxed);
+        self.log_level.store(log_rank(&config.log_level), Ordering::Relaxed);
+        self.work_stealing.store(config.work_stealing, Ordering::Relaxed);
+        self.batch_yield_ms.store(config.batch_yield_ms, Ordering::Relaxed);
+    }
+    
+    /// Gate con el que el servidor registra los requests interactivos
+    pub fn priority_gate(&self) -> Arc<PriorityGate> {
+        Arc::clone(&self.priority_gate)
+    }
+    
+    /// Retry-After sugerido cuando se rechaza un job (milisegundos)
+    pub fn retry_after_ms(&self) -> u64 {
+        self.retry_after_ms.load(Ordering::Relaxed)
+    }
+    
+    /// Timeouts vigentes (cpu, io, basic) en milisegundos
+    pub fn timeouts(&self) -> (u64, u64, u64) {
+        (
+            self.cpu_timeout_ms.load(Ordering::Relaxed),
+            self.io_timeout_ms.load(Ordering::Relaxed),
+            self.basic_timeout_ms.load(Ordering::Relaxed),
+        )
+    }
+    
+    /// Estadísticas de los pools de workers (cpu, io, basic)
+    pub fn pool_stats(&self) -> Vec<PoolStats> {
+        vec![self.cpu_pool.stats(), self.io_pool.stats(), self.basic_pool.stats()]
+    }
+    
+    /// Estadísticas de cada worker de jobs
+    /// 
+    /// El tiempo ocioso es el transcurrido desde que arrancó el worker menos
+    /// el que pasó ejecutando jobs.
+    pub fn worker_stats(&self) -> Vec<WorkerStats> {
+        let workers = self.worker_stats.lock().unwrap();
+        workers.iter()
+            .map(|record| WorkerStats {
+                name: record.name.clone(),
+                pool: record.pool,
+                jobs_processed: record.jobs_processed,
+                busy_ms: record.busy.as_millis() as u64,
+                idle_ms: record.started.elapsed().saturating_sub(record.busy).as_millis() as u64,
+                last_job_id: record.last_job_id.clone(),
+                core: record.core,
+            })
+            .collect()
+    }
+    
+    /// Vuelve a encolar los jobs que el storage tiene como `queued`
+    /// 
+    /// Se llama al arrancar el servidor: así se retoman los jobs que dejó
+    /// un proceso anterior (handoff o reinicio). Los que figuran `running`
+    /// quedaron a medias porque el proceso murió y se marcan como error.
+    /// Retorna cuántos jobs se encolaron.
+    pub fn recover_queued(&self) -> usize {
+        let mut jobs = self.storage.get_all();
+        jobs.sort_by(|a, b| a.created_at.cmp(&b.created_at).then_with(|| a.id.cmp(&b.id)));
+        
+        let mut recovered = 0;
+        for mut job in jobs {
+            match job.status {
+                JobStatus::Queued => {
+                    let job_type = job.job_type;
+                    match self.queue_for(job_type).enqueue(job.clone()) {
+                        Ok(dropped) => {
+                            if let Some(mut dropped) = dropped {
+                                dropped.mark_error("Dropped: queue full (drop-oldest policy)".to_string());
+                                let _ = self.storage.save(&dropped);
+                            }
+                            recovered += 1;
+                            self.schedule(job_type);
+                        }
+                        Err(e) => {
+                            job.mark_error(format!("Not recovered after restart: {}", e));
+                            let _ = self.storage.save(&job);
+                        }
+                    }
+                }
+                JobStatus::Running => {
+                    job.mark_error("Interrupted: server stopped while the job was running".to_string());
+                    let _ = self.storage.save(&job);
+                }
+                _ => {}
+            }
+        }
+        recovered
+    }
+    
+    /// Prepara el traspaso de los jobs a otro proceso
+    /// 
+    /// Los workers dejan de tomar jobs (los de las colas ya están en el
+    /// storage como `queued`) y se espera hasta `timeout` a los que están
+    /// corriendo; los que no terminan vuelven a `queued` para que el nuevo
+    /// proceso los ejecute de nuevo. Al final el storage queda congelado:
+    /// el archivo pasa a ser del nuevo proceso.
+    pub fn prepare_handoff(&self, timeout: Duration) -> Result<HandoffSummary, String> {
+        self.paused.store(true, Ordering::SeqCst);
+        
+        let deadline = Instant::now() + timeout;
+        while self.running_count() > 0 && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(20));
+        }
+        
+        let unfinished: Vec<String> = self.running_jobs.lock().unwrap().keys().cloned().collect();
+        for id in &unfinished {
+            if let Some(mut job) = self.storage.get(id) {
+                job.mark_requeued();
+                self.storage.save(&job).map_err(|e| format!("Storage error: {}", e))?;
+            }
+        }
+        self.storage.freeze().map_err(|e| format!("Storage error: {}", e))?;
+        
+        let queued = self.storage.get_all()
+            .iter()
+            .filter(|job| job.status == JobStatus::Queued)
+            .count();
+        Ok(HandoffSummary { queued, requeued: unfinished.len() })
+    }
+    
+    /// Deshace `prepare_handoff` cuando el nuevo proceso no arrancó
+    /// 
+    /// Los jobs que se habían devuelto a `queued` siguen corriendo en este
+    /// proceso y guardan su estado final al terminar.
+    pub fn resume_after_handoff(&self) {
+        if let Err(e) = self.storage.unfreeze() {
+            eprintln!("   ❌ Jobs storage: {}", e);
+        }
+        self.paused.store(false, Ordering::SeqCst);
+        
+        for job_type in [JobType::IsPrime, JobType::SortFile, JobType::Fibonacci] {
+            if !self.queue_for(job_type).is_empty() {
+                self.schedule(job_type);
+            }
+        }
+    }
+    
+    /// Verifica que el storage de jobs cargue (ver `JobStorage::check`)
+    pub fn check_storage(&self) -> Result<usize, String> {
+        self.storage.check()
+    }
+    
+    /// Número total de workers de jobs (todas las colas)
+    pub fn total_workers(&self) -> usize {
+        self.config.cpu_workers + self.config.io_workers + self.config.basic_workers
+    }
 }
 
 impl Clone for JobManager {
@@ -469,8 +1165,27 @@ impl Clone for JobManager {
             cpu_queue: self.cpu_queue.clone(),
             io_queue: self.io_queue.clone(),
             basic_queue: self.basic_queue.clone(),
+            cpu_pool: Arc::clone(&self.cpu_pool),
+            io_pool: Arc::clone(&self.io_pool),
+            basic_pool: Arc::clone(&self.basic_pool),
             storage: self.storage.clone(),
+            events: self.events.clone(),
             running_jobs: Arc::clone(&self.running_jobs),
+            cpu_timeout_ms: Arc::clone(&self.cpu_timeout_ms),
+            io_timeout_ms: Arc::clone(&self.io_timeout_ms),
+            basic_timeout_ms: Arc::clone(&self.basic_timeout_ms),
+            backpressure_threshold: Arc::clone(&self.backpressure_threshold),
+            retry_after_ms: Arc::clone(&self.retry_after_ms),
+            cleanup_age_secs: Arc::clone(&self.cleanup_age_secs),
+            log_level: Arc::clone(&self.log_level),
+            work_stealing: Arc::clone(&self.work_stealing),
+            cpu_steals: Arc::clone(&self.cpu_steals),
+            io_steals: Arc::clone(&self.io_steals),
+            worker_stats: Arc::clone(&self.worker_stats),
+            priority_gate: Arc::clone(&self.priority_gate),
+            batch_yield_ms: Arc::clone(&self.batch_yield_ms),
+            job_panics: Arc::clone(&self.job_panics),
+            paused: Arc::clone(&self.paused),
         }
     }
 }
@@ -485,10 +1200,12 @@ mod tests {
     /// Crea un JobManager SIN workers y con storage en un path temporal,
     /// asegurando que el directorio padre exista para evitar "No such file or directory".
     fn manager_with_zero_workers() -> JobManager {
-        let mut cfg = JobManagerConfig::default();
-        cfg.cpu_workers = 0;
-        cfg.io_workers = 0;
-        cfg.basic_workers = 0;
+        let mut cfg = JobManagerConfig {
+            cpu_workers: 0,
+            io_workers: 0,
+            basic_workers: 0,
+            ..JobManagerConfig::default()
+        };
 
         // Construir ruta: <tmp>/http_server_tests/<pid>/jobs.json
         let mut base = std::env::temp_dir();
@@ -524,8 +1241,10 @@ mod tests {
         assert_eq!(JobManager::job_type_to_path(&JobType::Grep), "grep");
         assert_eq!(JobManager::job_type_to_path(&JobType::Compress), "compress");
         assert_eq!(JobManager::job_type_to_path(&JobType::HashFile), "hashfile");
+        assert_eq!(JobManager::job_type_to_path(&JobType::Fetch), "fetch");
         assert_eq!(JobManager::job_type_to_path(&JobType::Fibonacci), "fibonacci");
         assert_eq!(JobManager::job_type_to_path(&JobType::Simulate), "simulate");
+        assert_eq!(JobManager::job_type_to_path(&JobType::Factory), "factory");
     }
 
     #[test]
@@ -567,6 +1286,81 @@ mod tests {
         assert_eq!(md.status, JobStatus::Canceled);
     }
 
+    #[test]
+    fn test_submit_drop_oldest_marks_dropped_job() {
+        let mut mgr = manager_with_zero_workers();
+        mgr.cpu_queue = JobQueue::with_policy(1, QueueFullPolicy::DropOldest);
+
+        let params = serde_json::json!({"n":97}).to_string();
+        let first = mgr.submit_job(JobType::IsPrime, params.clone(), JobPriority::Normal)
+            .expect("submit ok");
+        let second = mgr.submit_job(JobType::IsPrime, params, JobPriority::Normal)
+            .expect("drop-oldest never rejects");
+
+        let dropped = mgr.get_job_status(&first).expect("exists");
+        assert_eq!(dropped.status, JobStatus::Error);
+        assert_eq!(mgr.get_job_status(&second).unwrap().status, JobStatus::Queued);
+    }
+
+    #[test]
+    fn test_list_jobs_filters_by_status() {
+        let mgr = manager_with_zero_workers();
+
+        let params = serde_json::json!({"n":97}).to_string();
+        let queued = mgr.submit_job(JobType::IsPrime, params.clone(), JobPriority::Normal).unwrap();
+        let canceled = mgr.submit_job(JobType::IsPrime, params, JobPriority::Normal).unwrap();
+        mgr.cancel_job(&canceled).unwrap();
+
+        let ids = |jobs: Vec<JobMetadata>| jobs.into_iter().map(|j| j.id).collect::<Vec<_>>();
+        let all = ids(mgr.list_jobs(None, 100));
+        assert!(all.contains(&queued) && all.contains(&canceled));
+
+        let only_canceled = ids(mgr.list_jobs(Some(JobStatus::Canceled), 100));
+        assert!(only_canceled.contains(&canceled));
+        assert!(!only_canceled.contains(&queued));
+
+        assert_eq!(mgr.list_jobs(None, 1).len(), 1);
+    }
+
+    #[test]
+    fn test_handoff_and_recover_through_storage() {
+        let dir = std::env::temp_dir().join(format!("http_server_handoff_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let cfg = JobManagerConfig {
+            cpu_workers: 0,
+            io_workers: 0,
+            basic_workers: 0,
+            storage_path: dir.join("jobs.json").to_string_lossy().to_string(),
+            ..JobManagerConfig::default()
+        };
+
+        // Proceso viejo: dos jobs en cola y uno que quedó "corriendo"
+        let old = JobManager::new(cfg.clone());
+        let params = serde_json::json!({"n":97}).to_string();
+        let first = old.submit_job(JobType::IsPrime, params.clone(), JobPriority::Normal).unwrap();
+        let second = old.submit_job(JobType::SortFile, params.clone(), JobPriority::Normal).unwrap();
+        let mut stale = JobMetadata::new("job-stale".to_string(), JobType::Pi, params, JobPriority::Low);
+        stale.mark_running();
+        old.storage.save(&stale).unwrap();
+
+        let summary = old.prepare_handoff(Duration::from_millis(10)).unwrap();
+        assert_eq!(summary, HandoffSummary { queued: 2, requeued: 0 });
+
+        // Con el storage congelado, el proceso viejo ya no escribe el archivo
+        old.cancel_job(&first).unwrap();
+
+        // Proceso nuevo: retoma la cola desde el mismo archivo
+        let new = JobManager::new(cfg);
+        assert_eq!(new.recover_queued(), 2);
+        assert_eq!(new.queue_depths()[0].1, 1);
+        assert_eq!(new.queue_depths()[1].1, 1);
+        assert_eq!(new.get_job_status(&first).unwrap().status, JobStatus::Queued);
+        assert_eq!(new.get_job_status(&second).unwrap().status, JobStatus::Queued);
+        assert_eq!(new.get_job_status("job-stale").unwrap().status, JobStatus::Error);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
     #[test]
     fn test_cancel_job_not_found() {
         let mgr = manager_with_zero_workers();
@@ -631,6 +1425,138 @@ mod tests {
         assert_ne!(a, b);
     }
 
+    #[test]
+    fn test_submitted_job_runs_on_pool() {
+        let mut cfg = JobManagerConfig::default();
+        let mut base = std::env::temp_dir();
+        base.push("http_server_tests");
+        base.push(format!("pool-{}", std::process::id()));
+        fs::create_dir_all(&base).unwrap();
+        cfg.storage_path = base.join("jobs.json").to_string_lossy().to_string();
+        let mgr = JobManager::new(cfg);
+
+        let id = mgr
+            .submit_job(JobType::Fibonacci, r#"{"num":10}"#.to_string(), JobPriority::Normal)
+            .unwrap();
+
+        let deadline = std::time::Instant::now() + Duration::from_secs(5);
+        loop {
+            let status = mgr.get_job_status(&id).unwrap().status;
+            if status == JobStatus::Done {
+                break;
+            }
+            assert!(std::time::Instant::now() < deadline, "job stuck in {:?}", status);
+            thread::sleep(Duration::from_millis(20));
+        }
+
+        let basic = &mgr.pool_stats()[2];
+        assert_eq!(basic.name, "Basic");
+        assert_eq!(basic.workers, 2);
+    }
+
+    /// Manager con un solo worker IO y ninguno CPU
+    fn manager_io_only(work_stealing: bool, name: &str) -> JobManager {
+        let mut base = std::env::temp_dir();
+        base.push("http_server_tests");
+        base.push(format!("{}-{}", name, std::process::id()));
+        fs::create_dir_all(&base).unwrap();
+
+        JobManager::new(JobManagerConfig {
+            cpu_workers: 0,
+            io_workers: 1,
+            basic_workers: 0,
+            work_stealing,
+            storage_path: base.join("jobs.json").to_string_lossy().to_string(),
+            ..JobManagerConfig::default()
+        })
+    }
+
+    fn wait_for_status(mgr: &JobManager, id: &str, expected: JobStatus) -> bool {
+        let deadline = std::time::Instant::now() + Duration::from_secs(5);
+        while std::time::Instant::now() < deadline {
+            if mgr.get_job_status(id).unwrap().status == expected {
+                return true;
+            }
+            thread::sleep(Duration::from_millis(20));
+        }
+        false
+    }
+
+    #[test]
+    fn test_idle_io_worker_steals_cpu_job() {
+        let mgr = manager_io_only(true, "steal-on");
+        let id = mgr
+            .submit_job(JobType::IsPrime, r#"{"n":97}"#.to_string(), JobPriority::Normal)
+            .unwrap();
+
+        assert!(wait_for_status(&mgr, &id, JobStatus::Done));
+        let stats = mgr.get_queue_stats();
+        assert_eq!(stats["work_stealing"]["io_stole_from_cpu"], 1);
+        assert_eq!(stats["work_stealing"]["cpu_stole_from_io"], 0);
+    }
+
+    #[test]
+    fn test_no_stealing_when_disabled() {
+        let mgr = manager_io_only(false, "steal-off");
+        let id = mgr
+            .submit_job(JobType::IsPrime, r#"{"n":97}"#.to_string(), JobPriority::Normal)
+            .unwrap();
+
+        thread::sleep(Duration::from_millis(200));
+        assert_eq!(mgr.get_job_status(&id).unwrap().status, JobStatus::Queued);
+        assert_eq!(mgr.get_queue_stats()["work_stealing"]["io_stole_from_cpu"], 0);
+
+        // Activarlo en caliente: el siguiente job IO despierta al worker, que
+        // al vaciar su cola roba el job CPU pendiente
+        mgr.apply_live_config(&Config { work_stealing: true, ..Config::default() });
+        mgr.submit_job(JobType::WordCount, r#"{"name":"none.txt"}"#.to_string(), JobPriority::Normal)
+            .unwrap();
+        assert!(wait_for_status(&mgr, &id, JobStatus::Done));
+    }
+
+    #[test]
+    fn test_worker_stats_track_jobs() {
+        let mgr = manager_io_only(false, "worker-stats");
+        let stats = mgr.worker_stats();
+        assert_eq!(stats.len(), 1);
+        assert_eq!(stats[0].name, "IO-0");
+        assert_eq!(stats[0].pool, "io");
+        assert_eq!(stats[0].jobs_processed, 0);
+
+        let id = mgr
+            .submit_job(JobType::WordCount, r#"{"name":"none.txt"}"#.to_string(), JobPriority::Normal)
+            .unwrap();
+        assert!(wait_for_status(&mgr, &id, JobStatus::Done));
+
+        // El registro se actualiza justo antes de guardar el estado final
+        thread::sleep(Duration::from_millis(50));
+        let stats = mgr.worker_stats();
+        assert_eq!(stats[0].jobs_processed, 1);
+        assert_eq!(stats[0].last_job_id.as_deref(), Some(id.as_str()));
+        // Sin --cpu-affinity el worker no está fijado a ningún core
+        assert_eq!(stats[0].core, None);
+    }
+
+    #[test]
+    fn test_jobs_yield_to_interactive_requests() {
+        let mgr = manager_io_only(false, "yield");
+        mgr.apply_live_config(&Config { batch_yield_ms: 5_000, ..Config::default() });
+
+        // Con un request interactivo en curso, el job no arranca
+        let guard = mgr.priority_gate().enter();
+        let id = mgr
+            .submit_job(JobType::WordCount, r#"{"name":"none.txt"}"#.to_string(), JobPriority::Normal)
+            .unwrap();
+        thread::sleep(Duration::from_millis(150));
+        assert_eq!(mgr.get_job_status(&id).unwrap().status, JobStatus::Queued);
+
+        drop(guard);
+        assert!(wait_for_status(&mgr, &id, JobStatus::Done));
+        let stats = mgr.get_queue_stats();
+        assert_eq!(stats["priority"]["batch_yields"], 1);
+        assert_eq!(stats["priority"]["interactive_in_flight"], 0);
+    }
+
     #[test]
     fn test_get_queue_stats_json_shape() {
         let mgr = manager_with_zero_workers();
@@ -640,4 +1566,81 @@ mod tests {
         assert!(v.get("basic_queue").is_some());
         assert!(v.get("running_jobs").is_some());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_queue_depths_and_workers() {
+        let mgr = manager_with_zero_workers();
+        let depths = mgr.queue_depths();
+        assert_eq!(depths.len(), 3);
+        assert_eq!(depths[0], ("cpu", 0, 1000));
+        assert_eq!(depths[2], ("basic", 0, 500));
+        assert_eq!(mgr.running_count(), 0);
+        assert_eq!(mgr.total_workers(), 0);
+    }
+
+    #[test]
+    fn test_update_timeouts() {
+        let mgr = manager_with_zero_workers();
+        assert_eq!(mgr.timeouts(), (60_000, 120_000, 30_000));
+
+        let clone = mgr.clone();
+        mgr.update_timeouts(1_000, 2_000, 3_000);
+        assert_eq!(clone.timeouts(), (1_000, 2_000, 3_000));
+    }
+
+    #[test]
+    fn test_backpressure_threshold_rejects_before_full() {
+        let mut mgr = manager_with_zero_workers();
+        mgr.cpu_queue = JobQueue::new(4);
+        mgr.backpressure_threshold.store(50, Ordering::Relaxed);
+
+        let params = serde_json::json!({"n":97}).to_string();
+        for _ in 0..2 {
+            mgr.submit_job(JobType::IsPrime, params.clone(), JobPriority::Normal).unwrap();
+        }
+        let err = mgr.submit_job(JobType::IsPrime, params.clone(), JobPriority::Normal).unwrap_err();
+        assert!(err.contains("full"));
+
+        // Subir el umbral en caliente vuelve a aceptar
+        let config = Config {
+            backpressure_threshold: 100,
+            retry_after_ms: 1234,
+            ..Config::default()
+        };
+        mgr.apply_live_config(&config);
+        assert!(mgr.submit_job(JobType::IsPrime, params, JobPriority::Normal).is_ok());
+        assert_eq!(mgr.retry_after_ms(), 1234);
+    }
+
+    #[test]
+    fn test_from_config_carries_profile_settings() {
+        let mut config = Config::default();
+        config.apply_profile("prod").unwrap();
+
+        let cfg = JobManagerConfig::from_config(&config);
+        assert_eq!(cfg.backpressure_threshold, 80);
+        assert_eq!(cfg.log_level, "warn");
+    }
+
+    #[test]
+    fn test_estimate_retry_after_uses_drain_rate() {
+        let mut mgr = manager_with_zero_workers();
+        mgr.cpu_queue = JobQueue::new(4);
+        let params = serde_json::json!({"n":97}).to_string();
+
+        // Sin drenado reciente: el máximo configurado
+        assert_eq!(mgr.estimate_retry_after_ms(JobType::IsPrime), 5_000);
+
+        for _ in 0..4 {
+            mgr.cpu_queue.enqueue(JobMetadata::new(
+                mgr.generate_job_id(), JobType::IsPrime, params.clone(), JobPriority::Normal,
+            )).unwrap();
+        }
+        mgr.cpu_queue.try_dequeue();
+        mgr.cpu_queue.try_dequeue();
+
+        // 2 jobs/s de drenado: la estimación queda por debajo del máximo
+        let estimate = mgr.estimate_retry_after_ms(JobType::IsPrime);
+        assert!(estimate > 0 && estimate < 5_000, "{}", estimate);
+    }
+}
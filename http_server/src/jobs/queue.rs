@@ -2,21 +2,79 @@
 //! src/jobs/queue.rs
 //!
 //! Implementa una cola thread-safe que ordena jobs por prioridad.
+//!
+//! Cuando la cola está llena, el comportamiento lo define su
+//! `QueueFullPolicy`: rechazar, bloquear con timeout o descartar el
+//! job más antiguo de menor prioridad.
 
 use crate::jobs::types::JobMetadata;
-use std::collections::BinaryHeap;
+use std::collections::{BinaryHeap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering as AtomicOrdering};
 use std::sync::{Arc, Mutex, Condvar};
 use std::cmp::Ordering;
+use std::time::{Duration, Instant};
+
+/// Ventana usada para medir la tasa de desencolado
+const DRAIN_WINDOW: Duration = Duration::from_secs(30);
+
+/// Máximo de desencolados recordados para medir la tasa
+const MAX_DRAIN_SAMPLES: usize = 256;
+
+/// Política a aplicar cuando se encola en una cola llena
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum QueueFullPolicy {
+    /// Rechazar el job nuevo (el handler responde 503)
+    #[default]
+    Reject,
+    
+    /// Esperar hasta `timeout_ms` a que se libere espacio
+    Block { timeout_ms: u64 },
+    
+    /// Descartar el job más antiguo de menor prioridad para hacer espacio
+    /// (nunca uno de prioridad mayor que el job nuevo)
+    DropOldest,
+}
+
+impl QueueFullPolicy {
+    /// Nombres válidos en la configuración
+    pub const NAMES: &'static [&'static str] = &["reject", "block", "drop-oldest"];
+    
+    /// Parsea la política desde su nombre en la configuración
+    pub fn parse(name: &str, block_timeout_ms: u64) -> Result<Self, String> {
+        match name.to_lowercase().as_str() {
+            "reject" => Ok(QueueFullPolicy::Reject),
+            "block" => Ok(QueueFullPolicy::Block { timeout_ms: block_timeout_ms }),
+            "drop-oldest" | "drop_oldest" => Ok(QueueFullPolicy::DropOldest),
+            _ => Err(format!(
+                "Invalid queue policy: {} (expected one of: {})",
+                name,
+                Self::NAMES.join(", ")
+            )),
+        }
+    }
+    
+    /// Nombre de la política
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            QueueFullPolicy::Reject => "reject",
+            QueueFullPolicy::Block { .. } => "block",
+            QueueFullPolicy::DropOldest => "drop-oldest",
+        }
+    }
+}
 
 /// Wrapper para ordenar jobs en el heap
 #[derive(Clone)]
 struct QueuedJob {
     metadata: JobMetadata,
+    
+    /// Orden de llegada (created_at tiene resolución de segundos)
+    seq: u64,
 }
 
 impl QueuedJob {
-    fn new(metadata: JobMetadata) -> Self {
-        Self { metadata }
+    fn new(metadata: JobMetadata, seq: u64) -> Self {
+        Self { metadata, seq }
     }
 }
 
@@ -58,41 +116,105 @@ pub struct JobQueue {
     /// Condvar para notificar cuando hay nuevos jobs
     condvar: Arc<Condvar>,
     
+    /// Condvar para notificar cuando se libera espacio (política Block)
+    not_full: Arc<Condvar>,
+    
+    /// Contador de llegada para identificar el job más antiguo
+    next_seq: Arc<AtomicU64>,
+    
     /// Capacidad máxima de la cola
     max_capacity: usize,
+    
+    /// Política cuando la cola está llena
+    policy: QueueFullPolicy,
+    
+    /// Instantes de los desencolados recientes (para estimar Retry-After)
+    drained: Arc<Mutex<VecDeque<Instant>>>,
 }
 
 impl JobQueue {
-    /// Crea una nueva cola con capacidad máxima
+    /// Crea una nueva cola con capacidad máxima (política Reject)
     pub fn new(max_capacity: usize) -> Self {
+        Self::with_policy(max_capacity, QueueFullPolicy::Reject)
+    }
+    
+    /// Crea una nueva cola con capacidad máxima y política de cola llena
+    pub fn with_policy(max_capacity: usize, policy: QueueFullPolicy) -> Self {
         Self {
             heap: Arc::new(Mutex::new(BinaryHeap::new())),
             condvar: Arc::new(Condvar::new()),
+            not_full: Arc::new(Condvar::new()),
+            next_seq: Arc::new(AtomicU64::new(0)),
             max_capacity,
+            policy,
+            drained: Arc::new(Mutex::new(VecDeque::new())),
         }
     }
     
     /// Encola un job
     /// 
-    /// Retorna Ok(()) si se encoló exitosamente,
-    /// Err si la cola está llena
-    pub fn enqueue(&self, metadata: JobMetadata) -> Result<(), String> {
+    /// Retorna Ok(None) si se encoló exitosamente, Ok(Some(job)) si se
+    /// descartó `job` para hacer espacio (política DropOldest), y Err si
+    /// la cola está llena (Reject, o Block tras agotar el timeout).
+    pub fn enqueue(&self, metadata: JobMetadata) -> Result<Option<JobMetadata>, String> {
         let mut heap = self.heap.lock().unwrap();
+        let mut dropped = None;
         
         // Verificar capacidad
         if heap.len() >= self.max_capacity {
-            return Err(format!(
-                "Queue is full (max capacity: {})",
-                self.max_capacity
-            ));
+            match self.policy {
+                QueueFullPolicy::Reject => {
+                    return Err(format!(
+                        "Queue is full (max capacity: {})",
+                        self.max_capacity
+                    ));
+                }
+                QueueFullPolicy::Block { timeout_ms } => {
+                    let deadline = Instant::now() + Duration::from_millis(timeout_ms);
+                    
+                    while heap.len() >= self.max_capacity {
+                        let now = Instant::now();
+                        if now >= deadline {
+                            return Err(format!(
+                                "Queue is full (max capacity: {}), waited {} ms",
+                                self.max_capacity, timeout_ms
+                            ));
+                        }
+                        heap = self.not_full.wait_timeout(heap, deadline - now).unwrap().0;
+                    }
+                }
+                QueueFullPolicy::DropOldest => {
+                    // Víctima: el más antiguo entre los de menor prioridad
+                    let victim = heap.iter()
+                        .min_by_key(|job| (job.metadata.priority, job.seq))
+                        .map(|job| (job.metadata.priority, job.seq));
+                    
+                    match victim {
+                        Some((priority, seq)) if priority <= metadata.priority => {
+                            let mut jobs: Vec<QueuedJob> = heap.drain().collect();
+                            if let Some(idx) = jobs.iter().position(|job| job.seq == seq) {
+                                dropped = Some(jobs.remove(idx).metadata);
+                            }
+                            *heap = jobs.into_iter().collect();
+                        }
+                        _ => {
+                            return Err(format!(
+                                "Queue is full (max capacity: {}) of higher-priority jobs",
+                                self.max_capacity
+                            ));
+                        }
+                    }
+                }
+            }
         }
         
-        heap.push(QueuedJob::new(metadata));
+        let seq = self.next_seq.fetch_add(1, AtomicOrdering::Relaxed);
+        heap.push(QueuedJob::new(metadata, seq));
         
         // Notificar a workers esperando
         self.condvar.notify_one();
         
-        Ok(())
+        Ok(dropped)
     }
     
     /// Desencola el job de mayor prioridad
@@ -103,6 +225,8 @@ impl JobQueue {
         
         loop {
             if let Some(job) = heap.pop() {
+                self.not_full.notify_one();
+                self.record_drain();
                 return job.metadata;
             }
             
@@ -116,7 +240,36 @@ impl JobQueue {
     /// Retorna Some(metadata) si hay un job, None si la cola está vacía
     pub fn try_dequeue(&self) -> Option<JobMetadata> {
         let mut heap = self.heap.lock().unwrap();
-        heap.pop().map(|job| job.metadata)
+        let job = heap.pop().map(|job| job.metadata);
+        if job.is_some() {
+            self.not_full.notify_one();
+            self.record_drain();
+        }
+        job
+    }
+    
+    /// Registra un desencolado para la tasa de drenado
+    fn record_drain(&self) {
+        let mut drained = self.drained.lock().unwrap();
+        if drained.len() >= MAX_DRAIN_SAMPLES {
+            drained.pop_front();
+        }
+        drained.push_back(Instant::now());
+    }
+    
+    /// Jobs desencolados por segundo en la ventana reciente
+    /// 
+    /// Retorna None si no hubo desencolados en los últimos 30 segundos.
+    pub fn drain_rate_per_sec(&self) -> Option<f64> {
+        let now = Instant::now();
+        let mut drained = self.drained.lock().unwrap();
+        while drained.front().is_some_and(|t| now.duration_since(*t) > DRAIN_WINDOW) {
+            drained.pop_front();
+        }
+        
+        let oldest = drained.front()?;
+        let elapsed = now.duration_since(*oldest).max(Duration::from_secs(1));
+        Some(drained.len() as f64 / elapsed.as_secs_f64())
     }
     
     /// Retorna el tamaño actual de la cola
@@ -135,6 +288,11 @@ impl JobQueue {
         self.max_capacity
     }
     
+    /// Retorna la política de cola llena
+    pub fn policy(&self) -> QueueFullPolicy {
+        self.policy
+    }
+    
     /// Verifica si la cola está llena
     pub fn is_full(&self) -> bool {
         self.len() >= self.max_capacity
@@ -162,6 +320,10 @@ impl JobQueue {
         // Reconstruir heap con los jobs restantes
         *heap = jobs.into_iter().collect();
         
+        if removed.is_some() {
+            self.not_full.notify_one();
+        }
+        
         removed
     }
     
@@ -201,7 +363,11 @@ impl Clone for JobQueue {
         Self {
             heap: Arc::clone(&self.heap),
             condvar: Arc::clone(&self.condvar),
+            not_full: Arc::clone(&self.not_full),
+            next_seq: Arc::clone(&self.next_seq),
             max_capacity: self.max_capacity,
+            policy: self.policy,
+            drained: Arc::clone(&self.drained),
         }
     }
 }
@@ -403,7 +569,7 @@ mod tests {
         let queue = JobQueue::new(10);
         
         // Mezclar prioridades
-        let priorities = vec![
+        let priorities = [
             JobPriority::Low,
             JobPriority::High,
             JobPriority::Normal,
@@ -428,4 +594,102 @@ mod tests {
         let second = queue.try_dequeue().unwrap();
         assert_eq!(second.priority, JobPriority::High);
     }
+    
+    // ==================== Queue-full policies ====================
+    
+    fn normal_job(id: &str) -> JobMetadata {
+        JobMetadata::new(
+            id.to_string(),
+            JobType::IsPrime,
+            "{}".to_string(),
+            JobPriority::Normal,
+        )
+    }
+    
+    #[test]
+    fn test_policy_parse() {
+        assert_eq!(QueueFullPolicy::parse("reject", 0).unwrap(), QueueFullPolicy::Reject);
+        assert_eq!(
+            QueueFullPolicy::parse("block", 250).unwrap(),
+            QueueFullPolicy::Block { timeout_ms: 250 }
+        );
+        assert_eq!(QueueFullPolicy::parse("Drop-Oldest", 0).unwrap(), QueueFullPolicy::DropOldest);
+        assert!(QueueFullPolicy::parse("random", 0).is_err());
+    }
+    
+    #[test]
+    fn test_policy_drop_oldest() {
+        let queue = JobQueue::with_policy(2, QueueFullPolicy::DropOldest);
+        
+        assert!(queue.enqueue(normal_job("job-0")).unwrap().is_none());
+        assert!(queue.enqueue(normal_job("job-1")).unwrap().is_none());
+        
+        let dropped = queue.enqueue(normal_job("job-2")).unwrap();
+        assert_eq!(dropped.unwrap().id, "job-0");
+        assert_eq!(queue.len(), 2);
+        assert!(queue.find_by_id("job-2").is_some());
+    }
+    
+    #[test]
+    fn test_policy_drop_oldest_respects_priority() {
+        let job = |id: &str, priority| {
+            JobMetadata::new(id.to_string(), JobType::IsPrime, "{}".to_string(), priority)
+        };
+        let queue = JobQueue::with_policy(2, QueueFullPolicy::DropOldest);
+        
+        queue.enqueue(job("high-0", JobPriority::High)).unwrap();
+        queue.enqueue(job("low-1", JobPriority::Low)).unwrap();
+        
+        // Se descarta el de menor prioridad aunque no sea el más antiguo
+        let dropped = queue.enqueue(job("normal-2", JobPriority::Normal)).unwrap();
+        assert_eq!(dropped.unwrap().id, "low-1");
+        
+        // Un job Low no desplaza a jobs de mayor prioridad
+        let err = queue.enqueue(job("low-3", JobPriority::Low)).unwrap_err();
+        assert!(err.contains("full"));
+        assert!(queue.find_by_id("high-0").is_some());
+    }
+    
+    #[test]
+    fn test_drain_rate() {
+        let queue = JobQueue::new(10);
+        assert_eq!(queue.drain_rate_per_sec(), None);
+        
+        for i in 0..3 {
+            queue.enqueue(normal_job(&format!("job-{}", i))).unwrap();
+        }
+        queue.try_dequeue();
+        queue.dequeue();
+        
+        // Dos desencolados en menos de un segundo: 2 jobs/s
+        assert_eq!(queue.drain_rate_per_sec(), Some(2.0));
+    }
+    
+    #[test]
+    fn test_policy_block_times_out() {
+        let queue = JobQueue::with_policy(1, QueueFullPolicy::Block { timeout_ms: 50 });
+        queue.enqueue(normal_job("job-0")).unwrap();
+        
+        let start = Instant::now();
+        let result = queue.enqueue(normal_job("job-1"));
+        
+        assert!(result.unwrap_err().contains("full"));
+        assert!(start.elapsed() >= Duration::from_millis(50));
+    }
+    
+    #[test]
+    fn test_policy_block_waits_for_space() {
+        let queue = JobQueue::with_policy(1, QueueFullPolicy::Block { timeout_ms: 2000 });
+        queue.enqueue(normal_job("job-0")).unwrap();
+        
+        let consumer = queue.clone();
+        let handle = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(50));
+            consumer.try_dequeue()
+        });
+        
+        assert!(queue.enqueue(normal_job("job-1")).is_ok());
+        assert_eq!(handle.join().unwrap().unwrap().id, "job-0");
+        assert!(queue.find_by_id("job-1").is_some());
+    }
 }
\ No newline at end of file
@@ -4,11 +4,13 @@
 //! Permite que los metadatos de jobs sobrevivan a un graceful restart.
 //! Usa un archivo JSON simple en disco.
 
+use crate::events::{Event, EventBus};
 use crate::jobs::types::JobMetadata;
 use std::collections::HashMap;
 use std::fs::{self, File};
 use std::io::{BufReader, BufWriter, Write};
 use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 
 /// Storage para persistir metadatos de jobs
@@ -18,6 +20,13 @@ pub struct JobStorage {
     
     /// Cache en memoria de los jobs
     jobs: Arc<Mutex<HashMap<String, JobMetadata>>>,
+    
+    /// Congelado: los cambios quedan solo en memoria (el archivo ya es
+    /// del proceso que recibe el handoff)
+    frozen: Arc<AtomicBool>,
+    
+    /// Bus donde se publica cada cambio de estado o de progreso
+    events: EventBus,
 }
 
 impl JobStorage {
@@ -32,9 +41,17 @@ impl JobStorage {
         Ok(Self {
             path: path.to_string(),
             jobs: Arc::new(Mutex::new(jobs)),
+            frozen: Arc::new(AtomicBool::new(false)),
+            events: EventBus::new(),
         })
     }
     
+    /// Publica los cambios de los jobs en `events`
+    pub fn with_events(mut self, events: EventBus) -> Self {
+        self.events = events;
+        self
+    }
+    
     /// Carga jobs desde el archivo
     fn load_from_file(path: &str) -> std::io::Result<HashMap<String, JobMetadata>> {
         let file = File::open(path)?;
@@ -49,8 +66,27 @@ impl JobStorage {
         }
     }
     
+    /// Verifica que el archivo (si existe) se pueda leer y parsear
+    /// 
+    /// Al cargar, un archivo corrupto se descarta en silencio; el chequeo
+    /// de arranque (`/readyz`) lo reporta. Retorna cuántos jobs tiene.
+    pub fn check(&self) -> Result<usize, String> {
+        if !Path::new(&self.path).exists() {
+            return Ok(0);
+        }
+        let file = File::open(&self.path)
+            .map_err(|e| format!("Cannot read job storage {}: {}", self.path, e))?;
+        let jobs: HashMap<String, JobMetadata> = serde_json::from_reader(BufReader::new(file))
+            .map_err(|e| format!("Job storage {} is corrupt: {}", self.path, e))?;
+        Ok(jobs.len())
+    }
+    
     /// Guarda todos los jobs al archivo
     fn save_to_file(&self) -> std::io::Result<()> {
+        if self.frozen.load(Ordering::SeqCst) {
+            return Ok(());
+        }
+        
         let jobs = self.jobs.lock().unwrap();
         
         // Crear archivo temporal primero (atomic write)
@@ -59,7 +95,7 @@ impl JobStorage {
         let mut writer = BufWriter::new(file);
         
         serde_json::to_writer_pretty(&mut writer, &*jobs)
-            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+            .map_err(std::io::Error::other)?;
         
         writer.flush()?;
         
@@ -70,10 +106,17 @@ impl JobStorage {
     }
     
     /// Guarda o actualiza un job
+    /// 
+    /// Publica `JobStateChanged` si el job es nuevo o cambió su estado o
+    /// su progreso.
     pub fn save(&self, metadata: &JobMetadata) -> std::io::Result<()> {
-        {
+        let changed = {
             let mut jobs = self.jobs.lock().unwrap();
-            jobs.insert(metadata.id.clone(), metadata.clone());
+            let previous = jobs.insert(metadata.id.clone(), metadata.clone());
+            previous.is_none_or(|job| job.status != metadata.status || job.progress != metadata.progress)
+        };
+        if changed {
+            self.events.publish(Event::job_state(metadata));
         }
         
         self.save_to_file()
@@ -105,6 +148,19 @@ impl JobStorage {
         Ok(removed)
     }
     
+    /// Escribe el archivo y deja de escribirlo hasta `unfreeze`
+    pub fn freeze(&self) -> std::io::Result<()> {
+        self.save_to_file()?;
+        self.frozen.store(true, Ordering::SeqCst);
+        Ok(())
+    }
+    
+    /// Vuelve a persistir los cambios (y escribe los que quedaron en memoria)
+    pub fn unfreeze(&self) -> std::io::Result<()> {
+        self.frozen.store(false, Ordering::SeqCst);
+        self.save_to_file()
+    }
+    
     /// Obtiene el número de jobs almacenados
     pub fn count(&self) -> usize {
         let jobs = self.jobs.lock().unwrap();
@@ -151,6 +207,8 @@ impl Clone for JobStorage {
         Self {
             path: self.path.clone(),
             jobs: Arc::clone(&self.jobs),
+            frozen: Arc::clone(&self.frozen),
+            events: self.events.clone(),
         }
     }
 }
@@ -185,6 +243,57 @@ mod tests {
         let _ = fs::remove_file(temp_file);
     }
     
+    #[test]
+    fn test_check_reports_corrupt_file() {
+        let temp_file = format!("/tmp/test_jobs_check_{}.json", std::process::id());
+        let _ = fs::remove_file(&temp_file);
+        
+        let storage = JobStorage::new(&temp_file).unwrap();
+        assert_eq!(storage.check(), Ok(0));
+        let job = JobMetadata::new("check-1".to_string(), JobType::IsPrime, "{}".to_string(), JobPriority::Normal);
+        storage.save(&job).unwrap();
+        assert_eq!(storage.check(), Ok(1));
+        
+        // Se carga vacío, pero el chequeo lo reporta
+        fs::write(&temp_file, "{not json").unwrap();
+        assert_eq!(JobStorage::new(&temp_file).unwrap().count(), 0);
+        assert!(storage.check().unwrap_err().contains("corrupt"));
+        
+        let _ = fs::remove_file(&temp_file);
+    }
+    
+    #[test]
+    fn test_save_publishes_state_changes() {
+        let temp_file = "/tmp/test_jobs_events.json";
+        let _ = fs::remove_file(temp_file);
+        
+        let events = EventBus::new();
+        let received = events.subscribe("test");
+        let storage = JobStorage::new(temp_file).unwrap().with_events(events);
+        
+        let mut job = JobMetadata::new(
+            "events-1".to_string(),
+            JobType::IsPrime,
+            r#"{"n":97}"#.to_string(),
+            JobPriority::Normal,
+        );
+        storage.save(&job).unwrap();
+        // Sin cambios de estado ni progreso no hay evento
+        storage.save(&job).unwrap();
+        job.mark_running();
+        storage.save(&job).unwrap();
+        
+        let statuses: Vec<JobStatus> = received.try_iter()
+            .map(|event| match event {
+                Event::JobStateChanged { status, .. } => status,
+                other => panic!("unexpected event {:?}", other),
+            })
+            .collect();
+        assert_eq!(statuses, vec![JobStatus::Queued, JobStatus::Running]);
+        
+        let _ = fs::remove_file(temp_file);
+    }
+    
     #[test]
     fn test_storage_persistence() {
         let temp_file = "/tmp/test_jobs_persist.json";
@@ -625,4 +734,27 @@ mod tests {
         
         let _ = fs::remove_file(temp_file);
     }
+    
+    // ==================== Freeze (handoff) ====================
+    
+    #[test]
+    fn test_storage_freeze_keeps_file_untouched() {
+        let temp_file = "/tmp/test_jobs_freeze.json";
+        let _ = fs::remove_file(temp_file);
+        
+        let storage = JobStorage::new(temp_file).unwrap();
+        let mut job = JobMetadata::new("job1".to_string(), JobType::IsPrime, "{}".to_string(), JobPriority::Normal);
+        storage.save(&job).unwrap();
+        storage.freeze().unwrap();
+        
+        job.mark_done("late".to_string());
+        storage.save(&job).unwrap();
+        assert_eq!(storage.get("job1").unwrap().status, JobStatus::Done);
+        assert_eq!(JobStorage::new(temp_file).unwrap().get("job1").unwrap().status, JobStatus::Queued);
+        
+        storage.unfreeze().unwrap();
+        assert_eq!(JobStorage::new(temp_file).unwrap().get("job1").unwrap().status, JobStatus::Done);
+        
+        let _ = fs::remove_file(temp_file);
+    }
 }
\ No newline at end of file
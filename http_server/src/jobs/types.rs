@@ -29,21 +29,17 @@ pub enum JobStatus {
 }
 
 /// Prioridad de un job
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum JobPriority {
     Low = 0,
+    #[default]
     Normal = 1,
     High = 2,
 }
 
-impl Default for JobPriority {
-    fn default() -> Self {
-        JobPriority::Normal
-    }
-}
-
 impl JobPriority {
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> Option<Self> {
         match s.to_lowercase().as_str() {
             "low" => Some(JobPriority::Low),
@@ -72,9 +68,15 @@ pub enum JobType {
     Compress,
     HashFile,
     
+    // Red saliente (corre en los pools IO: bloquea esperando la red)
+    Fetch,
+    
     // Básicos que pueden ser largos
     Fibonacci,
     Simulate,
+    
+    // Simulación de la fábrica de Tarea-2 (duerme en las estaciones)
+    Factory,
 }
 
 impl JobType {
@@ -90,8 +92,10 @@ impl JobType {
             "grep" => Some(JobType::Grep),
             "compress" => Some(JobType::Compress),
             "hashfile" => Some(JobType::HashFile),
+            "fetch" => Some(JobType::Fetch),
             "fibonacci" => Some(JobType::Fibonacci),
             "simulate" => Some(JobType::Simulate),
+            "factory" => Some(JobType::Factory),
             _ => None,
         }
     }
@@ -115,6 +119,7 @@ impl JobType {
                 | JobType::Grep
                 | JobType::Compress
                 | JobType::HashFile
+                | JobType::Fetch
         )
     }
 }
@@ -237,6 +242,14 @@ impl JobMetadata {
         self.finished_at = Some(now);
     }
     
+    /// Devuelve a la cola un job que no alcanzó a terminar (reinicio)
+    pub fn mark_requeued(&mut self) {
+        self.status = JobStatus::Queued;
+        self.started_at = None;
+        self.progress = 0;
+        self.eta_ms = None;
+    }
+    
     /// Actualiza el progreso
     pub fn update_progress(&mut self, progress: u8, eta_ms: Option<u64>) {
         self.progress = progress.min(100);
@@ -348,10 +361,12 @@ mod tests {
         assert_eq!(JobType::from_task_name("grep"), Some(JobType::Grep));
         assert_eq!(JobType::from_task_name("compress"), Some(JobType::Compress));
         assert_eq!(JobType::from_task_name("hashfile"), Some(JobType::HashFile));
+        assert_eq!(JobType::from_task_name("fetch"), Some(JobType::Fetch));
         
         // Basic
         assert_eq!(JobType::from_task_name("fibonacci"), Some(JobType::Fibonacci));
         assert_eq!(JobType::from_task_name("simulate"), Some(JobType::Simulate));
+        assert_eq!(JobType::from_task_name("factory"), Some(JobType::Factory));
         
         // Invalid
         assert_eq!(JobType::from_task_name("invalid"), None);
@@ -394,6 +409,8 @@ mod tests {
         assert!(!JobType::Fibonacci.is_io_bound());
         assert!(!JobType::Simulate.is_cpu_bound());
         assert!(!JobType::Simulate.is_io_bound());
+        assert!(!JobType::Factory.is_cpu_bound());
+        assert!(!JobType::Factory.is_io_bound());
     }
     
     // ==================== JobMetadata Tests ====================
@@ -15,6 +15,9 @@
 //! - `workers`: Sistema de pools de workers por tipo de tarea
 //! - `jobs`: Sistema asíncrono de trabajos largos
 //! - `metrics`: Recolección de métricas y observabilidad
+//! - `events`: Bus de eventos interno entre módulos
+//! - `client`: Cliente bloqueante para consumir la API desde Rust
+//! - `testing`: Servidor efímero para tests de integración
 //!
 //! ## Ejemplo de uso
 //!
@@ -35,13 +38,16 @@ pub mod server;
 pub mod router;
 pub mod commands;
 pub mod metrics;
+pub mod events;
 pub mod jobs;
+pub mod workers;
+pub mod client;
+pub mod testing;
 
 // Módulos que agregaremos después (comentados por ahora)
 // pub mod router;
 // pub mod config;
 // pub mod commands;
-// pub mod workers;
 // pub mod jobs;
 // pub mod metrics;
 // pub mod utils;
\ No newline at end of file
@@ -6,26 +6,73 @@
 //! Soporta configuración via CLI arguments y variables de entorno.
 
 use http_server::config::Config;
+use http_server::server::prefork::{self, Worker};
 use http_server::server::Server;
 
 fn main() {
-    println!("=================================");
-    println!("  RedUnix HTTP/1.0 Server");
-    println!("  Principios de Sistemas Operativos");
-    println!("=================================\n");
+    // Con --processes, este proceso puede ser uno de los workers del supervisor
+    let worker = Worker::current();
+    if worker.is_none() {
+        println!("=================================");
+        println!("  RedUnix HTTP/1.0 Server");
+        println!("  Principios de Sistemas Operativos");
+        println!("=================================\n");
+    }
+    
+    // Los mensajes de panic pueden incluir parámetros del request
+    http_server::http::redact::install_panic_hook();
     
     // Parsear configuración desde CLI/env
-    let config = Config::new();
+    let mut config = Config::new();
     
-    // Validar configuración
-    if let Err(e) = config.validate() {
-        eprintln!("❌ Error de configuración: {}", e);
+    // Validar configuración (el supervisor ya mostró las advertencias)
+    let report = config.check();
+    if worker.is_none() {
+        for warning in &report.warnings {
+            eprintln!("⚠️  {}: {}", warning.field, warning.message);
+        }
+    }
+    if !report.is_ok() {
+        for error in &report.errors {
+            eprintln!("❌ Error de configuración ({}): {}", error.field, error.message);
+        }
         eprintln!("\nUsa --help para ver las opciones disponibles");
         std::process::exit(1);
     }
     
+    // Crear y validar el sandbox del directorio de datos
+    if let Err(e) = http_server::commands::sandbox::init(&config.data_dir) {
+        eprintln!("❌ Error de configuración: {}", e);
+        std::process::exit(1);
+    }
+    
+    // --daemon y --pid-file, antes de que exista cualquier thread (los
+    // workers de --processes no se separan ni escriben el pidfile)
+    let _pid_file = match worker {
+        Some(_) => None,
+        None => match http_server::server::daemon::start(&config) {
+            Ok(pid_file) => pid_file,
+            Err(e) => {
+                eprintln!("❌ {}", e);
+                std::process::exit(1);
+            }
+        },
+    };
+    
     // Imprimir resumen de configuración
-    config.print_summary();
+    match worker {
+        Some(worker) => worker.isolate_files(&mut config),
+        None => config.print_summary(),
+    }
+    
+    // Modo multi-proceso: este proceso solo supervisa a los workers
+    if worker.is_none() && config.processes > 1 {
+        if let Err(e) = prefork::supervise(config.processes) {
+            eprintln!("💥 Error fatal: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
     
     // Crear el servidor
     let mut server = Server::new(config);
@@ -3,6 +3,8 @@
 //!
 //! Recolecta y agrega métricas del servidor en tiempo real.
 
+use crate::events::Event;
+use std::net::IpAddr;
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 use std::collections::HashMap;
@@ -33,8 +35,87 @@ struct MetricsData {
     
     /// Threads activos actualmente
     active_threads: u64,
+    
+    /// Requests cuyo handler hizo panic
+    panics: u64,
+    
+    /// Handlers que superaron el timeout de su ruta, por ruta registrada
+    route_timeouts: HashMap<String, u64>,
+    
+    /// Requests rechazados por rate limiting (429)
+    throttled: u64,
+    
+    /// Requests rechazados por rate limiting, por IP
+    throttled_per_ip: HashMap<IpAddr, u64>,
+    
+    /// Conexiones rechazadas por el filtro de IPs
+    denied_connections: u64,
+    
+    /// Conexiones rechazadas con 503 por `--max-connections`
+    rejected_connections: u64,
+    
+    /// Conexiones rechazadas con 503 por la cola de conexiones sobre el umbral
+    shed_connections: u64,
+    
+    /// Conexiones cortadas por lentas (slowloris)
+    slow_clients: u64,
+    
+    /// Conexiones cerradas por el reaper por inactivas
+    reaped_connections: u64,
+    
+    /// Veces que cada cola o pool se llenó (`QueueSaturated`)
+    saturations: HashMap<String, u64>,
+    
+    /// Conexiones abiertas ahora (aceptadas y todavía sin cerrar)
+    open_connections: u64,
+    
+    /// Conexiones ya cerradas, con sus bytes y duración acumulados
+    closed_connections: u64,
+    connection_bytes_read: u64,
+    connection_bytes_written: u64,
+    connection_time: Duration,
 }
 
+/// Conexión abierta cuyo tráfico se está midiendo
+///
+/// Se crea al aceptar la conexión (`MetricsCollector::open_connection`) y
+/// viaja con ella; al soltarla se registran sus bytes y su duración.
+pub struct ConnectionMeter {
+    metrics: MetricsCollector,
+    opened: Instant,
+    bytes_read: u64,
+    bytes_written: u64,
+}
+
+impl ConnectionMeter {
+    /// Suma bytes leídos del cliente
+    pub fn add_read(&mut self, bytes: u64) {
+        self.bytes_read += bytes;
+    }
+    
+    /// Suma bytes escritos al cliente
+    pub fn add_written(&mut self, bytes: u64) {
+        self.bytes_written += bytes;
+    }
+    
+    pub fn bytes_read(&self) -> u64 {
+        self.bytes_read
+    }
+    
+    pub fn bytes_written(&self) -> u64 {
+        self.bytes_written
+    }
+}
+
+impl Drop for ConnectionMeter {
+    fn drop(&mut self) {
+        self.metrics.record_connection(self.bytes_read, self.bytes_written, self.opened.elapsed());
+    }
+}
+
+/// Máximo de IPs distintas con contador de throttling
+const MAX_THROTTLED_IPS: usize = 10_000;
+
 impl MetricsCollector {
     /// Crea un nuevo collector de métricas
     pub fn new() -> Self {
@@ -46,6 +127,21 @@ impl MetricsCollector {
                 max_latencies: 10000, // Guardar últimas 10k latencias
                 requests_per_path: HashMap::new(),
                 active_threads: 0,
+                panics: 0,
+                route_timeouts: HashMap::new(),
+                throttled: 0,
+                throttled_per_ip: HashMap::new(),
+                denied_connections: 0,
+                rejected_connections: 0,
+                shed_connections: 0,
+                slow_clients: 0,
+                reaped_connections: 0,
+                saturations: HashMap::new(),
+                open_connections: 0,
+                closed_connections: 0,
+                connection_bytes_read: 0,
+                connection_bytes_written: 0,
+                connection_time: Duration::ZERO,
             })),
             start_time: Instant::now(),
         }
@@ -74,6 +170,18 @@ impl MetricsCollector {
         *data.requests_per_path.entry(path.to_string()).or_insert(0) += 1;
     }
     
+    /// Aplica un evento del bus (consumidor `metrics`)
+    pub fn record_event(&self, event: &Event) {
+        match event {
+            Event::RequestCompleted { path, status, latency } => self.record_request(path, *status, *latency),
+            // Los panics de jobs los cuenta el job manager
+            Event::WorkerPanicked { job_id: None, .. } => self.record_panic(),
+            Event::QueueSaturated { queue, .. } => self.record_saturation(queue),
+            Event::RouteTimedOut { path, .. } => self.record_route_timeout(path),
+            _ => {}
+        }
+    }
+    
     /// Incrementa el contador de threads activos
     pub fn increment_active_threads(&self) {
         let mut data = self.inner.lock().unwrap();
@@ -88,6 +196,165 @@ impl MetricsCollector {
         }
     }
     
+    /// Registra un handler que hizo panic
+    pub fn record_panic(&self) {
+        let mut data = self.inner.lock().unwrap();
+        data.panics += 1;
+    }
+    
+    /// Número de handlers que hicieron panic
+    pub fn panics(&self) -> u64 {
+        let data = self.inner.lock().unwrap();
+        data.panics
+    }
+    
+    /// Registra un handler de la ruta `path` que superó su timeout
+    pub fn record_route_timeout(&self, path: &str) {
+        let mut data = self.inner.lock().unwrap();
+        *data.route_timeouts.entry(path.to_string()).or_insert(0) += 1;
+    }
+    
+    /// Veces que el handler de la ruta `path` superó su timeout
+    pub fn route_timeouts(&self, path: &str) -> u64 {
+        let data = self.inner.lock().unwrap();
+        data.route_timeouts.get(path).copied().unwrap_or(0)
+    }
+    
+    /// Registra un request de `ip` rechazado por rate limiting
+    pub fn record_throttled(&self, ip: IpAddr) {
+        let mut data = self.inner.lock().unwrap();
+        data.throttled += 1;
+        
+        // Con demasiadas IPs solo se siguen contando las ya conocidas
+        if data.throttled_per_ip.len() < MAX_THROTTLED_IPS || data.throttled_per_ip.contains_key(&ip) {
+            *data.throttled_per_ip.entry(ip).or_insert(0) += 1;
+        }
+    }
+    
+    /// Número de requests rechazados por rate limiting
+    pub fn throttled(&self) -> u64 {
+        let data = self.inner.lock().unwrap();
+        data.throttled
+    }
+    
+    /// Registra una conexión rechazada por el filtro de IPs
+    pub fn record_denied_connection(&self) {
+        let mut data = self.inner.lock().unwrap();
+        data.denied_connections += 1;
+    }
+    
+    /// Número de conexiones rechazadas por el filtro de IPs
+    pub fn denied_connections(&self) -> u64 {
+        let data = self.inner.lock().unwrap();
+        data.denied_connections
+    }
+    
+    /// Registra una conexión rechazada por superar el máximo de conexiones
+    pub fn record_rejected_connection(&self) {
+        let mut data = self.inner.lock().unwrap();
+        data.rejected_connections += 1;
+    }
+    
+    /// Número de conexiones rechazadas por superar el máximo de conexiones
+    pub fn rejected_connections(&self) -> u64 {
+        let data = self.inner.lock().unwrap();
+        data.rejected_connections
+    }
+    
+    /// Registra una conexión rechazada por la cola de conexiones sobre el umbral
+    pub fn record_shed_connection(&self) {
+        let mut data = self.inner.lock().unwrap();
+        data.shed_connections += 1;
+    }
+    
+    /// Número de conexiones rechazadas por la cola sobre el umbral de backpressure
+    pub fn shed_connections(&self) -> u64 {
+        let data = self.inner.lock().unwrap();
+        data.shed_connections
+    }
+    
+    /// Registra una conexión cortada por enviar el request demasiado lento
+    pub fn record_slow_client(&self) {
+        let mut data = self.inner.lock().unwrap();
+        data.slow_clients += 1;
+    }
+    
+    /// Número de conexiones cortadas por lentas
+    pub fn slow_clients(&self) -> u64 {
+        let data = self.inner.lock().unwrap();
+        data.slow_clients
+    }
+    
+    /// Registra `count` conexiones cerradas por el reaper de inactivas
+    pub fn record_reaped(&self, count: u64) {
+        let mut data = self.inner.lock().unwrap();
+        data.reaped_connections += count;
+    }
+    
+    /// Número de conexiones cerradas por el reaper de inactivas
+    pub fn reaped_connections(&self) -> u64 {
+        let data = self.inner.lock().unwrap();
+        data.reaped_connections
+    }
+    
+    /// Registra que la cola o pool `queue` se llenó
+    pub fn record_saturation(&self, queue: &str) {
+        let mut data = self.inner.lock().unwrap();
+        *data.saturations.entry(queue.to_string()).or_insert(0) += 1;
+    }
+    
+    /// Veces que la cola o pool `queue` se llenó
+    pub fn saturations(&self, queue: &str) -> u64 {
+        let data = self.inner.lock().unwrap();
+        data.saturations.get(queue).copied().unwrap_or(0)
+    }
+    
+    /// Cuenta una conexión recién aceptada como abierta
+    /// 
+    /// La conexión se cierra (y se registra) al soltar el `ConnectionMeter`.
+    /// 
+    /// # Ejemplo
+    /// ```
+    /// use http_server::metrics::MetricsCollector;
+    ///
+    /// let metrics = MetricsCollector::new();
+    /// let mut meter = metrics.open_connection();
+    /// assert_eq!(metrics.open_connections(), 1);
+    ///
+    /// meter.add_read(120);
+    /// meter.add_written(2048);
+    /// drop(meter);
+    /// assert_eq!(metrics.open_connections(), 0);
+    /// assert_eq!(metrics.connection_bytes(), (120, 2048));
+    /// ```
+    pub fn open_connection(&self) -> ConnectionMeter {
+        let mut data = self.inner.lock().unwrap();
+        data.open_connections += 1;
+        ConnectionMeter { metrics: self.clone(), opened: Instant::now(), bytes_read: 0, bytes_written: 0 }
+    }
+    
+    /// Registra una conexión cerrada: bytes leídos, escritos y cuánto duró
+    pub fn record_connection(&self, bytes_read: u64, bytes_written: u64, lifetime: Duration) {
+        let mut data = self.inner.lock().unwrap();
+        data.open_connections = data.open_connections.saturating_sub(1);
+        data.closed_connections += 1;
+        data.connection_bytes_read += bytes_read;
+        data.connection_bytes_written += bytes_written;
+        data.connection_time += lifetime;
+    }
+    
+    /// Número de conexiones abiertas ahora
+    pub fn open_connections(&self) -> u64 {
+        let data = self.inner.lock().unwrap();
+        data.open_connections
+    }
+    
+    /// Bytes leídos y escritos por las conexiones ya cerradas
+    pub fn connection_bytes(&self) -> (u64, u64) {
+        let data = self.inner.lock().unwrap();
+        (data.connection_bytes_read, data.connection_bytes_written)
+    }
+    
     /// Obtiene el número de threads activos
     pub fn active_threads(&self) -> u64 {
         let data = self.inner.lock().unwrap();
@@ -114,12 +381,45 @@ impl MetricsCollector {
         // Top 10 rutas más accedidas
         let mut paths: Vec<_> = data.requests_per_path.iter().collect();
         paths.sort_by(|a, b| b.1.cmp(a.1));
+        // El path viene del cliente: se escapa como string JSON
         let top_paths_json = paths.iter()
             .take(10)
-            .map(|(path, count)| format!(r#"{{"path": "{}", "count": {}}}"#, path, count))
+            .map(|(path, count)| format!(r#"{{"path": {}, "count": {}}}"#, serde_json::Value::from(path.as_str()), count))
+            .collect::<Vec<_>>()
+            .join(", ");
+        
+        // Top 10 IPs más limitadas
+        let mut throttled_ips: Vec<_> = data.throttled_per_ip.iter().collect();
+        throttled_ips.sort_by(|a, b| b.1.cmp(a.1));
+        let throttled_ips_json = throttled_ips.iter()
+            .take(10)
+            .map(|(ip, count)| format!(r#"{{"ip": "{}", "count": {}}}"#, ip, count))
             .collect::<Vec<_>>()
             .join(", ");
         
+        // Saturaciones por cola, en orden estable
+        let mut saturations: Vec<_> = data.saturations.iter().collect();
+        saturations.sort();
+        let saturation_json = saturations.iter()
+            .map(|(queue, count)| format!(r#"{}: {}"#, serde_json::Value::from(queue.as_str()), count))
+            .collect::<Vec<_>>()
+            .join(", ");
+        
+        // Timeouts por ruta registrada (las rutas son del servidor, no del cliente)
+        let mut route_timeouts: Vec<_> = data.route_timeouts.iter().collect();
+        route_timeouts.sort();
+        let route_timeouts_json = route_timeouts.iter()
+            .map(|(path, count)| format!(r#"{}: {}"#, serde_json::Value::from(path.as_str()), count))
+            .collect::<Vec<_>>()
+            .join(", ");
+        
+        // Duración promedio de las conexiones ya cerradas
+        let mean_duration_ms = if data.closed_connections > 0 {
+            data.connection_time.as_secs_f64() * 1000.0 / data.closed_connections as f64
+        } else {
+            0.0
+        };
+        
         format!(
             r#"{{
   "server": {{
@@ -129,9 +429,29 @@ impl MetricsCollector {
   "requests": {{
     "total": {},
     "active_threads": {},
+    "panics": {},
+    "timeouts": {{{}}},
     "status_codes": {{{}}},
     "top_paths": [{}]
   }},
+  "rate_limit": {{
+    "throttled": {},
+    "top_ips": [{}]
+  }},
+  "connections": {{
+    "denied": {},
+    "rejected": {},
+    "shed": {},
+    "slow_dropped": {},
+    "reaped": {},
+    "pool_saturated": {},
+    "open": {},
+    "closed": {},
+    "bytes_read": {},
+    "bytes_written": {},
+    "mean_duration_ms": {:.2}
+  }},
+  "saturation": {{{}}},
   "latency_us": {{
     "p50": {},
     "p95": {},
@@ -145,14 +465,91 @@ impl MetricsCollector {
             self.start_time,
             data.total_requests,
             data.active_threads,
+            data.panics,
+            route_timeouts_json,
             status_codes_json,
             top_paths_json,
+            data.throttled,
+            throttled_ips_json,
+            data.denied_connections,
+            data.rejected_connections,
+            data.shed_connections,
+            data.slow_clients,
+            data.reaped_connections,
+            data.saturations.get("conn").copied().unwrap_or(0),
+            data.open_connections,
+            data.closed_connections,
+            data.connection_bytes_read,
+            data.connection_bytes_written,
+            mean_duration_ms,
+            saturation_json,
             p50, p95, p99, avg,
             stddev,
             data.latencies.len()
         )
     }
     
+    /// Obtiene un resumen compacto en texto plano
+    ///
+    /// Pensado para `watch curl -s localhost:8080/metrics/summary`.
+    /// `queues` contiene (nombre, jobs en cola, capacidad) por cola.
+    pub fn get_summary_text(
+        &self,
+        queues: &[(&str, usize, usize)],
+        workers_busy: usize,
+        workers_total: usize,
+    ) -> String {
+        let data = self.inner.lock().unwrap();
+
+        let uptime = self.start_time.elapsed();
+        let uptime_secs = uptime.as_secs();
+        let req_per_sec = if uptime.as_secs_f64() > 0.0 {
+            data.total_requests as f64 / uptime.as_secs_f64()
+        } else {
+            0.0
+        };
+
+        let (p50, p95, p99, _) = self.calculate_percentiles(&data.latencies);
+
+        // Top 5 rutas más accedidas
+        let mut paths: Vec<_> = data.requests_per_path.iter().collect();
+        paths.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+        let top_routes = if paths.is_empty() {
+            "-".to_string()
+        } else {
+            paths.iter()
+                .take(5)
+                .map(|(path, count)| format!("{} {}", path, count))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+
+        let queues_line = queues.iter()
+            .map(|(name, depth, capacity)| format!("{} {}/{}", name, depth, capacity))
+            .collect::<Vec<_>>()
+            .join("  ");
+
+        let mut out = String::new();
+        out.push_str("RedUnix HTTP/1.0 - metrics summary\n");
+        out.push_str(&format!(
+            "uptime      {}h {:02}m {:02}s\n",
+            uptime_secs / 3600, (uptime_secs % 3600) / 60, uptime_secs % 60
+        ));
+        out.push_str(&format!(
+            "requests    {} total, {:.2} req/s, {} active\n",
+            data.total_requests, req_per_sec, data.active_threads
+        ));
+        out.push_str(&format!(
+            "latency     p50 {:.2}ms  p95 {:.2}ms  p99 {:.2}ms\n",
+            p50 as f64 / 1000.0, p95 as f64 / 1000.0, p99 as f64 / 1000.0
+        ));
+        out.push_str(&format!("top routes  {}\n", top_routes));
+        out.push_str(&format!("queues      {}\n", queues_line));
+        out.push_str(&format!("workers     {}/{} busy\n", workers_busy, workers_total));
+
+        out
+    }
+
     /// Calcula percentiles de latencia
     fn calculate_percentiles(&self, latencies: &[u64]) -> (u64, u64, u64, u64) {
         if latencies.is_empty() {
@@ -327,6 +724,70 @@ mod tests {
         assert!(json.contains("latency") || json.contains("p50") || json.contains("requests"));
     }
     
+    #[test]
+    fn test_throttled_counts_per_ip() {
+        let collector = MetricsCollector::new();
+        let ip: IpAddr = "10.0.0.1".parse().unwrap();
+        collector.record_throttled(ip);
+        collector.record_throttled(ip);
+        collector.record_throttled("10.0.0.2".parse().unwrap());
+        
+        assert_eq!(collector.throttled(), 3);
+        let json: serde_json::Value = serde_json::from_str(&collector.get_metrics_json()).unwrap();
+        assert_eq!(json["rate_limit"]["throttled"], 3);
+        assert_eq!(json["rate_limit"]["top_ips"][0]["ip"], "10.0.0.1");
+        assert_eq!(json["rate_limit"]["top_ips"][0]["count"], 2);
+    }
+    
+    #[test]
+    fn test_saturation_events() {
+        let collector = MetricsCollector::new();
+        for queue in ["conn", "conn", "cpu"] {
+            collector.record_event(&Event::QueueSaturated { queue: queue.to_string(), len: 4, capacity: 4 });
+        }
+        
+        assert_eq!(collector.saturations("conn"), 2);
+        assert_eq!(collector.saturations("io"), 0);
+        let json: serde_json::Value = serde_json::from_str(&collector.get_metrics_json()).unwrap();
+        assert_eq!(json["connections"]["pool_saturated"], 2);
+        assert_eq!(json["saturation"]["cpu"], 1);
+    }
+    
+    #[test]
+    fn test_route_timeout_events() {
+        let collector = MetricsCollector::new();
+        for path in ["/fibonacci", "/fibonacci", "/jobs/:id"] {
+            collector.record_event(&Event::RouteTimedOut { path: path.to_string(), timeout: Duration::from_secs(2) });
+        }
+        
+        assert_eq!(collector.route_timeouts("/fibonacci"), 2);
+        assert_eq!(collector.route_timeouts("/status"), 0);
+        let json: serde_json::Value = serde_json::from_str(&collector.get_metrics_json()).unwrap();
+        assert_eq!(json["requests"]["timeouts"]["/jobs/:id"], 1);
+    }
+    
+    #[test]
+    fn test_connection_meters_aggregate_bytes_and_duration() {
+        let collector = MetricsCollector::new();
+        let mut first = collector.open_connection();
+        let mut second = collector.open_connection();
+        first.add_read(100);
+        first.add_written(1000);
+        second.add_read(50);
+        assert_eq!(collector.open_connections(), 2);
+        
+        std::thread::sleep(Duration::from_millis(20));
+        drop(first);
+        drop(second);
+        
+        let json: serde_json::Value = serde_json::from_str(&collector.get_metrics_json()).unwrap();
+        assert_eq!(json["connections"]["open"], 0);
+        assert_eq!(json["connections"]["closed"], 2);
+        assert_eq!(json["connections"]["bytes_read"], 150);
+        assert_eq!(json["connections"]["bytes_written"], 1000);
+        assert!(json["connections"]["mean_duration_ms"].as_f64().unwrap() >= 20.0);
+    }
+    
     #[test]
     fn test_uptime_increases() {
         let collector = MetricsCollector::new();
@@ -351,6 +812,31 @@ mod tests {
         assert!(json.contains("status"));
     }
     
+    #[test]
+    fn test_summary_text() {
+        let collector = MetricsCollector::new();
+
+        collector.record_request("/fibonacci", 200, Duration::from_millis(10));
+        collector.record_request("/fibonacci", 200, Duration::from_millis(20));
+        collector.record_request("/status", 200, Duration::from_millis(5));
+
+        let summary = collector.get_summary_text(&[("cpu", 3, 1000), ("io", 0, 1000)], 2, 10);
+        assert!(summary.contains("3 total"));
+        assert!(summary.contains("req/s"));
+        assert!(summary.contains("p95"));
+        assert!(summary.contains("/fibonacci 2, /status 1"));
+        assert!(summary.contains("cpu 3/1000  io 0/1000"));
+        assert!(summary.contains("2/10 busy"));
+    }
+
+    #[test]
+    fn test_summary_text_empty() {
+        let collector = MetricsCollector::new();
+        let summary = collector.get_summary_text(&[], 0, 0);
+        assert!(summary.contains("0 total"));
+        assert!(summary.contains("top routes  -"));
+    }
+
     #[test]
     fn test_latency_window_management() {
         let collector = MetricsCollector::new();
@@ -6,7 +6,8 @@
 //! - Latencias (p50, p95, p99)
 //! - Workers activos/ocupados
 //! - Tamaño de colas
+//! - Bytes y duración de cada conexión
 
 pub mod collector;
 
-pub use collector::MetricsCollector;
\ No newline at end of file
+pub use collector::{ConnectionMeter, MetricsCollector};
\ No newline at end of file
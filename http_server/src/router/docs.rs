@@ -0,0 +1,313 @@
+//! # Documentación de Rutas
+//! src/router/docs.rs
+//!
+//! Una ruta puede llevar su descripción y sus parámetros, que se declaran
+//! al registrarla:
+//!
+//! ```text
+//! router.register_method(Method::GET, "/fibonacci", fibonacci_handler)
+//!     .describe("Calculate Fibonacci number")
+//!     .required("num", "integer <= 90");
+//! ```
+//!
+//! `Router::register_help` arma con la tabla de rutas dos respuestas:
+//!
+//! - `/help`: cada ruta con su método, descripción y parámetros.
+//! - `/routes.json`: las mismas rutas como `paths` de OpenAPI 3, con los
+//!   segmentos `:id` y `*path` como `{id}` y `{path}`.
+//!
+//! Se generan una sola vez, con las rutas registradas hasta ese momento
+//! (el builder las registra al final). Los endpoints que el servidor
+//! atiende antes de rutear se declaran con `Router::register_external`
+//! para que también aparezcan.
+
+use super::table::Route;
+use super::Router;
+use crate::http::request::Method;
+use crate::http::{Request, Response};
+use serde_json::{json, Value};
+use std::sync::{Arc, OnceLock};
+
+/// Descripción y parámetros de una ruta
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RouteDoc {
+    description: String,
+    params: Vec<ParamDoc>,
+}
+
+/// Un parámetro de la query (o un segmento variable del path)
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ParamDoc {
+    name: String,
+    required: bool,
+    description: String,
+}
+
+impl ParamDoc {
+    /// `"num (required): integer <= 90"`
+    fn summary(&self) -> String {
+        let kind = if self.required { "required" } else { "optional" };
+        format!("{} ({}): {}", self.name, kind, self.description)
+    }
+}
+
+/// Ruta recién registrada, para documentarla (y agregarle middlewares o
+/// un timeout) encadenando llamadas
+pub struct RouteEntry<'a> {
+    pub(super) route: &'a mut Route,
+}
+
+impl<'a> RouteEntry<'a> {
+    pub(super) fn new(route: &'a mut Route) -> Self {
+        Self { route }
+    }
+
+    /// Descripción de una línea de la ruta
+    pub fn describe(self, description: &str) -> Self {
+        self.route.doc.get_or_insert_with(RouteDoc::default).description = description.to_string();
+        self
+    }
+
+    /// Agrega un parámetro obligatorio
+    pub fn required(self, name: &str, description: &str) -> Self {
+        self.param(name, true, description)
+    }
+
+    /// Agrega un parámetro opcional
+    pub fn optional(self, name: &str, description: &str) -> Self {
+        self.param(name, false, description)
+    }
+
+    fn param(self, name: &str, required: bool, description: &str) -> Self {
+        self.route.doc.get_or_insert_with(RouteDoc::default).params.push(ParamDoc {
+            name: name.to_string(),
+            required,
+            description: description.to_string(),
+        });
+        self
+    }
+}
+
+impl Router {
+    /// Documenta una ruta que el servidor atiende antes de rutear (`/metrics`,
+    /// `/ws`...)
+    ///
+    /// Aparece en `/help` y `/routes.json`, y `normalize` la encuentra como a
+    /// cualquier ruta, pero `route` la trata como un path sin ruta
+    /// (redirección, fallback o 404).
+    ///
+    /// # Ejemplo
+    /// ```
+    /// use http_server::router::Router;
+    /// use http_server::http::{Request, StatusCode};
+    /// use http_server::http::request::Method;
+    ///
+    /// let mut router = Router::new();
+    /// router.register_external(Method::GET, "/metrics").describe("Metrics as JSON");
+    /// router.register_help();
+    ///
+    /// let help = router.route(&Request::parse(b"GET /help HTTP/1.0\r\n\r\n").unwrap());
+    /// assert!(String::from_utf8_lossy(help.body()).contains("/metrics"));
+    /// let metrics = router.route(&Request::parse(b"GET /metrics HTTP/1.0\r\n\r\n").unwrap());
+    /// assert_eq!(metrics.status(), StatusCode::NotFound);
+    /// ```
+    pub fn register_external(&mut self, method: Method, path: &str) -> RouteEntry<'_> {
+        // El handler no se llama nunca: `route` salta las rutas externas
+        let entry = self.register_method(method, path, |_req: &Request| Response::text(""));
+        entry.route.external = true;
+        entry
+    }
+
+    /// Registra `/help` y `/routes.json` generados de las rutas registradas
+    ///
+    /// Las rutas que se registren después no aparecen en ninguna de las dos.
+    ///
+    /// # Ejemplo
+    /// ```
+    /// use http_server::router::Router;
+    /// use http_server::http::{Request, Response};
+    /// use http_server::http::request::Method;
+    ///
+    /// let mut router = Router::new();
+    /// router.register_method(Method::GET, "/hello", |_req: &Request| Response::text("hola"))
+    ///     .describe("Say hello")
+    ///     .optional("name", "who to greet");
+    /// router.register_help();
+    ///
+    /// let help = router.route(&Request::parse(b"GET /help HTTP/1.0\r\n\r\n").unwrap());
+    /// let help: serde_json::Value = serde_json::from_slice(help.body()).unwrap();
+    /// assert_eq!(help["commands"][0]["path"], "/hello");
+    /// assert_eq!(help["commands"][0]["parameters"][0], "name (optional): who to greet");
+    /// ```
+    pub fn register_help(&mut self) {
+        let pages: Arc<OnceLock<(String, String)>> = Arc::new(OnceLock::new());
+
+        let help = Arc::clone(&pages);
+        self.register_method(Method::GET, "/help", move |_req: &Request| {
+            Response::json(&help.get().unwrap().0)
+        })
+        .describe("List the routes with their methods and parameters");
+        let openapi = Arc::clone(&pages);
+        self.register_method(Method::GET, "/routes.json", move |_req: &Request| {
+            Response::json(&openapi.get().unwrap().1)
+        })
+        .describe("The same routes as an OpenAPI 3 document");
+
+        let routes = self.routes.routes();
+        let help = serde_json::to_string_pretty(&help_json(&routes)).unwrap();
+        let _ = pages.set((help, openapi_json(&routes).to_string()));
+    }
+}
+
+/// `{"commands": [...]}` con una entrada por ruta
+fn help_json(routes: &[&Route]) -> Value {
+    let commands: Vec<Value> = routes.iter().map(|route| {
+        let doc = route.doc.clone().unwrap_or_default();
+        let mut parameters: Vec<String> = route.params.iter()
+            .filter(|name| !doc.params.iter().any(|param| &param.name == *name))
+            .map(|name| format!("{} (path)", name))
+            .collect();
+        parameters.extend(doc.params.iter().map(ParamDoc::summary));
+        json!({
+            "path": route.path,
+            "method": route.method.map_or("ANY", |method| method.as_str()),
+            "description": doc.description,
+            "parameters": parameters,
+        })
+    }).collect();
+    json!({"commands": commands})
+}
+
+/// Documento OpenAPI 3 con un `paths` por ruta registrada
+fn openapi_json(routes: &[&Route]) -> Value {
+    let mut paths = serde_json::Map::new();
+    for route in routes {
+        let doc = route.doc.clone().unwrap_or_default();
+        let mut parameters: Vec<Value> = route.params.iter().map(|name| {
+            let description = doc.params.iter().find(|param| &param.name == name)
+                .map_or("", |param| param.description.as_str());
+            json!({"name": name, "in": "path", "required": true, "description": description, "schema": {"type": "string"}})
+        }).collect();
+        parameters.extend(doc.params.iter().filter(|param| !route.params.contains(&param.name)).map(|param| {
+            json!({
+                "name": param.name,
+                "in": "query",
+                "required": param.required,
+                "description": param.description,
+                "schema": {"type": "string"},
+            })
+        }));
+        let operation = json!({
+            "summary": doc.description,
+            "parameters": parameters,
+            "responses": {"default": {"description": "JSON response (errors: {\"error\", \"code\", \"status\"})"}},
+        });
+
+        let methods = match route.method {
+            Some(method) => vec![method.as_str().to_lowercase()],
+            None => vec!["get".to_string(), "post".to_string()],
+        };
+        let item = paths.entry(openapi_path(&route.path)).or_insert_with(|| json!({}));
+        for method in methods {
+            item[method] = operation.clone();
+        }
+    }
+
+    json!({
+        "openapi": "3.0.3",
+        "info": {"title": "RedUnix HTTP Server", "version": env!("CARGO_PKG_VERSION")},
+        "paths": paths,
+    })
+}
+
+/// Path de OpenAPI: `/jobs/:id` → `/jobs/{id}`, `/files/*path` → `/files/{path}`
+fn openapi_path(path: &str) -> String {
+    path.split('/')
+        .map(|segment| match segment.strip_prefix(':').or_else(|| segment.strip_prefix('*')) {
+            Some(name) => format!("{{{}}}", name),
+            None => segment.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ok(_req: &Request) -> Response {
+        Response::text("ok")
+    }
+
+    fn get(router: &Router, path: &str) -> Value {
+        let raw = format!("GET {} HTTP/1.0\r\n\r\n", path);
+        serde_json::from_slice(router.route(&Request::parse(raw.as_bytes()).unwrap()).body()).unwrap()
+    }
+
+    #[test]
+    fn test_help_and_openapi_follow_the_route_table() {
+        let mut router = Router::new();
+        router.register_method(Method::GET, "/fibonacci", ok)
+            .describe("Calculate Fibonacci number")
+            .required("num", "integer <= 90");
+        router.group("/jobs", |jobs| {
+            jobs.register_method(Method::GET, "/:id", ok)
+                .describe("Job status")
+                .required("id", "job id");
+            jobs.register_method(Method::POST, "/submit", ok);
+        });
+        router.register("/echo", ok);
+        router.register_help();
+
+        let help = get(&router, "/help");
+        let commands = help["commands"].as_array().unwrap();
+        let paths: Vec<&str> = commands.iter().map(|command| command["path"].as_str().unwrap()).collect();
+        assert_eq!(paths, ["/echo", "/fibonacci", "/help", "/jobs/:id", "/jobs/submit", "/routes.json"]);
+        assert_eq!(commands[1]["parameters"], json!(["num (required): integer <= 90"]));
+        assert_eq!(commands[0]["method"], "ANY");
+        assert_eq!(commands[3]["parameters"], json!(["id (required): job id"]));
+        assert_eq!(commands[4]["description"], "");
+
+        let openapi = get(&router, "/routes.json");
+        assert_eq!(openapi["openapi"], "3.0.3");
+        let job = &openapi["paths"]["/jobs/{id}"]["get"];
+        assert_eq!(job["summary"], "Job status");
+        assert_eq!(job["parameters"][0]["in"], "path");
+        assert_eq!(job["parameters"].as_array().unwrap().len(), 1);
+        assert_eq!(openapi["paths"]["/fibonacci"]["get"]["parameters"][0]["in"], "query");
+        assert!(openapi["paths"]["/echo"]["post"].is_object());
+        assert!(openapi["paths"]["/jobs/submit"]["get"].is_null());
+    }
+
+    #[test]
+    fn test_external_routes_are_listed_but_not_routed() {
+        let mut router = Router::new();
+        router.register_external(Method::GET, "/metrics").describe("Metrics");
+        router.set_trailing_slash(crate::router::TrailingSlash::Ignore);
+        router.set_case_insensitive(true);
+        router.register_help();
+
+        let help = get(&router, "/help");
+        assert!(help["commands"].as_array().unwrap().iter().any(|command| command["path"] == "/metrics"));
+        assert_eq!(router.normalize("/METRICS/"), "/metrics");
+
+        // Sin handler: 404 con el formato del router, también para POST
+        for raw in ["GET /metrics HTTP/1.0\r\n\r\n", "POST /METRICS/ HTTP/1.0\r\n\r\n"] {
+            let response = router.route(&Request::parse(raw.as_bytes()).unwrap());
+            assert_eq!(response.status(), crate::http::StatusCode::NotFound, "{}", raw);
+            let body: Value = serde_json::from_slice(response.body()).unwrap();
+            assert_eq!(body["code"], "not_found");
+        }
+
+        router.set_fallback(|req: &Request| Response::text(&format!("fallback {}", req.path())));
+        let response = router.route(&Request::parse(b"GET /Metrics HTTP/1.0\r\n\r\n").unwrap());
+        assert_eq!(response.body(), b"fallback /Metrics");
+    }
+
+    #[test]
+    fn test_openapi_path() {
+        assert_eq!(openapi_path("/jobs/:id/result"), "/jobs/{id}/result");
+        assert_eq!(openapi_path("/files/*path"), "/files/{path}");
+        assert_eq!(openapi_path("/files/"), "/files/");
+    }
+}
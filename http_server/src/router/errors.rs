@@ -0,0 +1,121 @@
+//! # Respuestas de Error del Servidor
+//! src/router/errors.rs
+//!
+//! Los errores que no genera un handler sino el propio servidor (un request
+//! mal formado, una ruta inexistente o que no admite el método, un panic)
+//! pasan por un único `ErrorHandler`, así todos tienen la misma forma. El de
+//! fábrica (`json_envelope`) responde:
+//!
+//! ```json
+//! {"error": "Route not found: /nada", "code": "not_found", "status": 404, "request_id": "5f0c..."}
+//! ```
+//!
+//! `error` es el mismo mensaje de `Response::error`, así los clientes que
+//! solo leen ese campo siguen funcionando. Con `Router::set_error_handler`
+//! (o `ServerBuilder::error_handler`) se reemplaza, por ejemplo por una
+//! página HTML.
+
+use crate::http::{Response, StatusCode};
+
+/// Qué originó el error
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCause {
+    /// Request que no se pudo parsear o cuyo body se rechazó
+    BadRequest,
+
+    /// Ninguna ruta ni redirección para el path
+    NotFound,
+
+    /// El path tiene rutas, pero no para el método del request
+    MethodNotAllowed,
+
+    /// Panic mientras se generaba la respuesta
+    Panic,
+
+    /// El handler de la ruta superó su timeout
+    Timeout,
+}
+
+impl ErrorCause {
+    /// Código estable para el campo `code`
+    pub fn code(self) -> &'static str {
+        match self {
+            ErrorCause::BadRequest => "bad_request",
+            ErrorCause::NotFound => "not_found",
+            ErrorCause::MethodNotAllowed => "method_not_allowed",
+            ErrorCause::Panic => "internal_error",
+            ErrorCause::Timeout => "timeout",
+        }
+    }
+}
+
+/// Error generado por el servidor, antes de convertirlo en respuesta
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServerError {
+    pub cause: ErrorCause,
+
+    /// Status que corresponde (ej: 413 para un body demasiado grande)
+    pub status: StatusCode,
+
+    /// Mensaje para el cliente (ya redactado si venía de un panic)
+    pub message: String,
+
+    /// Id del request (`X-Request-Id`), si ya se asignó
+    pub request_id: Option<String>,
+}
+
+impl ServerError {
+    pub fn new(cause: ErrorCause, status: StatusCode, message: &str, request_id: Option<&str>) -> Self {
+        Self {
+            cause,
+            status,
+            message: message.to_string(),
+            request_id: request_id.map(str::to_string),
+        }
+    }
+}
+
+/// Convierte un `ServerError` en la respuesta que recibe el cliente
+pub type ErrorHandler = fn(&ServerError) -> Response;
+
+/// Handler de fábrica: sobre JSON con `error`, `code`, `status` y `request_id`
+///
+/// # Ejemplo
+/// ```
+/// use http_server::http::StatusCode;
+/// use http_server::router::errors::{json_envelope, ErrorCause, ServerError};
+///
+/// let error = ServerError::new(ErrorCause::NotFound, StatusCode::NotFound, "Route not found: /x", Some("abc"));
+/// let response = json_envelope(&error);
+/// assert_eq!(response.status(), StatusCode::NotFound);
+///
+/// let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+/// assert_eq!(body["code"], "not_found");
+/// assert_eq!(body["request_id"], "abc");
+/// ```
+pub fn json_envelope(error: &ServerError) -> Response {
+    Response::builder(error.status).json(&serde_json::json!({
+        "error": error.message,
+        "code": error.cause.code(),
+        "status": error.status.as_u16(),
+        "request_id": error.request_id,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_envelope_keeps_the_error_message_field() {
+        let error = ServerError::new(ErrorCause::BadRequest, StatusCode::PayloadTooLarge, "Body too large", None);
+        let response = json_envelope(&error);
+        assert_eq!(response.status(), StatusCode::PayloadTooLarge);
+
+        let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(body["error"], "Body too large");
+        assert_eq!(body["code"], "bad_request");
+        assert_eq!(body["status"], 413);
+        assert!(body["request_id"].is_null());
+    }
+}
@@ -0,0 +1,216 @@
+//! # Grupos de Rutas
+//! src/router/group.rs
+//!
+//! `Router::group` registra varias rutas bajo un mismo prefijo y con los
+//! mismos middlewares, sin repetir el prefijo en cada una:
+//!
+//! ```text
+//! router.group("/jobs", |g| {
+//!     g.middleware(requiere_token);      // aplica a todo /jobs/*
+//!     g.register("/submit", submit);     // → /jobs/submit
+//!     g.register("/list", list);         // → /jobs/list
+//! });
+//! ```
+//!
+//! Un middleware ve el request antes que el handler: si retorna una
+//! respuesta (ej: 401), el handler no se ejecuta. Se aplica a todas las
+//! rutas del grupo, aunque se agregue después de registrarlas, en el orden
+//! en que se agregaron. Los grupos se pueden anidar; el interno suma su
+//! prefijo y sus middlewares a los del externo.
+//!
+//! Una sola ruta también puede llevar middlewares propios
+//! (`.middleware(...)` al registrarla), que corren después de los de sus
+//! grupos.
+
+use super::docs::RouteEntry;
+use super::table::Route;
+use super::{Handler, Router};
+use crate::http::request::Method;
+use crate::http::{Request, Response};
+use std::sync::Arc;
+
+/// Middleware de grupo o de ruta: `Some(respuesta)` corta el request antes
+/// del handler
+pub type Middleware = Arc<dyn Fn(&Request) -> Option<Response> + Send + Sync>;
+
+/// Rutas que comparten prefijo y middlewares
+pub struct RouteGroup {
+    prefix: String,
+    routes: Vec<Route>,
+    middleware: Vec<Middleware>,
+}
+
+impl RouteGroup {
+    fn new(prefix: &str) -> Self {
+        Self {
+            prefix: prefix.trim_end_matches('/').to_string(),
+            routes: Vec::new(),
+            middleware: Vec::new(),
+        }
+    }
+
+    /// Registra `prefijo + path` para cualquier método
+    pub fn register<F>(&mut self, path: &str, handler: F) -> RouteEntry<'_>
+    where
+        F: Fn(&Request) -> Response + Send + Sync + 'static,
+    {
+        self.push(path, None, Arc::new(handler))
+    }
+
+    /// Registra `prefijo + path` solo para `method` (ver `Router::register_method`)
+    pub fn register_method<F>(&mut self, method: Method, path: &str, handler: F) -> RouteEntry<'_>
+    where
+        F: Fn(&Request) -> Response + Send + Sync + 'static,
+    {
+        self.push(path, Some(method), Arc::new(handler))
+    }
+
+    /// Agrega un middleware a todas las rutas del grupo
+    pub fn middleware<F>(&mut self, middleware: F)
+    where
+        F: Fn(&Request) -> Option<Response> + Send + Sync + 'static,
+    {
+        self.middleware.push(Arc::new(middleware));
+    }
+
+    /// Grupo anidado bajo `prefijo + prefix`
+    pub fn group(&mut self, prefix: &str, build: impl FnOnce(&mut RouteGroup)) {
+        let mut inner = RouteGroup::new(&self.full_path(prefix));
+        build(&mut inner);
+        self.routes.extend(inner.into_routes());
+    }
+
+    fn full_path(&self, path: &str) -> String {
+        format!("{}{}", self.prefix, path)
+    }
+
+    fn push(&mut self, path: &str, method: Option<Method>, handler: Handler) -> RouteEntry<'_> {
+        self.routes.push(Route::new(&self.full_path(path), method, Vec::new(), handler));
+        RouteEntry::new(self.routes.last_mut().unwrap())
+    }
+
+    /// Rutas con los middlewares del grupo delante de los de cada una
+    fn into_routes(mut self) -> Vec<Route> {
+        for route in &mut self.routes {
+            route.middleware.splice(0..0, self.middleware.iter().cloned());
+        }
+        self.routes
+    }
+}
+
+impl RouteEntry<'_> {
+    /// Agrega un middleware solo a esta ruta
+    ///
+    /// Corre después de los middlewares de los grupos de la ruta, en el
+    /// orden en que se agregaron.
+    ///
+    /// # Ejemplo
+    /// ```
+    /// use http_server::router::Router;
+    /// use http_server::http::{Request, Response, StatusCode};
+    ///
+    /// let mut router = Router::new();
+    /// router.register("/report", |_req: &Request| Response::text("ok"))
+    ///     .middleware(|req: &Request| {
+    ///         req.query_param("day").is_none().then(|| Response::error(StatusCode::BadRequest, "Missing day"))
+    ///     });
+    ///
+    /// let request = Request::parse(b"GET /report HTTP/1.0\r\n\r\n").unwrap();
+    /// assert_eq!(router.route(&request).status(), StatusCode::BadRequest);
+    /// ```
+    pub fn middleware<F>(self, middleware: F) -> Self
+    where
+        F: Fn(&Request) -> Option<Response> + Send + Sync + 'static,
+    {
+        self.route.middleware.push(Arc::new(middleware));
+        self
+    }
+}
+
+impl Router {
+    /// Registra un grupo de rutas con prefijo y middlewares comunes
+    ///
+    /// # Ejemplo
+    /// ```
+    /// use http_server::router::Router;
+    /// use http_server::http::{Request, Response, StatusCode};
+    ///
+    /// fn list(_req: &Request) -> Response {
+    ///     Response::json(r#"{"items": []}"#)
+    /// }
+    ///
+    /// let mut router = Router::new();
+    /// router.group("/admin", |g| {
+    ///     g.middleware(|req: &Request| match req.headers().get("X-Token") {
+    ///         Some("secreto") => None,
+    ///         _ => Some(Response::error(StatusCode::Unauthorized, "Missing token")),
+    ///     });
+    ///     g.register("/items", list);
+    /// });
+    ///
+    /// let denied = router.route(&Request::parse(b"GET /admin/items HTTP/1.0\r\n\r\n").unwrap());
+    /// assert_eq!(denied.status(), StatusCode::Unauthorized);
+    ///
+    /// let raw = b"GET /admin/items HTTP/1.0\r\nX-Token: secreto\r\n\r\n";
+    /// assert_eq!(router.route(&Request::parse(raw).unwrap()).status(), StatusCode::Ok);
+    /// ```
+    pub fn group(&mut self, prefix: &str, build: impl FnOnce(&mut RouteGroup)) {
+        let mut group = RouteGroup::new(prefix);
+        build(&mut group);
+        for route in group.into_routes() {
+            let registered = self.routes.insert(&route.path, route.method, route.handler);
+            registered.doc = route.doc;
+            registered.middleware = route.middleware;
+            registered.timeout = route.timeout;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::StatusCode;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn ok(_req: &Request) -> Response {
+        Response::text("ok")
+    }
+
+    #[test]
+    fn test_nested_groups_share_prefix_and_middleware() {
+        let checks = Arc::new(AtomicUsize::new(0));
+        let mut router = Router::new();
+        router.group("/api/", |api| {
+            api.register("/open", ok);
+            api.group("/private", |private| {
+                private.register_method(Method::POST, "/save", ok);
+                private.middleware(|req: &Request| {
+                    req.query_param("key").is_none()
+                        .then(|| Response::error(StatusCode::Forbidden, "Missing key"))
+                });
+            });
+            let checks = Arc::clone(&checks);
+            api.middleware(move |_req: &Request| {
+                checks.fetch_add(1, Ordering::Relaxed);
+                None
+            });
+        });
+
+        let route = |raw: &[u8]| router.route(&Request::parse(raw).unwrap());
+        assert_eq!(route(b"GET /api/open HTTP/1.0\r\n\r\n").status(), StatusCode::Ok);
+        assert_eq!(
+            route(b"POST /api/private/save HTTP/1.0\r\nContent-Length: 0\r\n\r\n").status(),
+            StatusCode::Forbidden
+        );
+        assert_eq!(
+            route(b"POST /api/private/save?key=1 HTTP/1.0\r\nContent-Length: 0\r\n\r\n").status(),
+            StatusCode::Ok
+        );
+        // El middleware externo corre en todas, también las anidadas
+        assert_eq!(checks.load(Ordering::Relaxed), 3);
+
+        // El método sigue aplicando dentro del grupo
+        assert_eq!(route(b"GET /api/private/save HTTP/1.0\r\n\r\n").status(), StatusCode::MethodNotAllowed);
+        assert_eq!(route(b"GET /open HTTP/1.0\r\n\r\n").status(), StatusCode::NotFound);
+    }
+}
@@ -9,31 +9,126 @@
 //! Request → Router → Handler → Response
 //! ```
 //!
-//! El router examina el path del request y lo dirige al handler apropiado.
-//! Si no hay handler para ese path, retorna 404 Not Found.
+//! El router examina el path y el método del request y lo dirige al handler
+//! apropiado. Una ruta se registra para un método (`register_method`) o
+//! para cualquiera (`register`); la de GET también atiende HEAD. Si el path
+//! existe pero no para ese método, responde 405 Method Not Allowed con el
+//! header `Allow`. Un `OPTIONS` a un path existente responde 204 con el
+//! `Allow` de sus rutas, salvo que una ruta registre OPTIONS. Si no hay
+//! handler para ese path pero sí una redirección registrada
+//! (`register_redirect`), responde la redirección; si no, el handler de
+//! fallback (`set_fallback`) o 404 Not Found. El 404 y el 405, igual que
+//! los requests inválidos y los panics que atrapa el servidor, pasan por el
+//! `ErrorHandler` del router (ver `errors`).
+//!
+//! Un segmento `:nombre` en el path de la ruta es variable
+//! (`/jobs/:id/result`); el handler lo lee con `Request::path_param`. La
+//! búsqueda no depende de cuántas rutas haya (ver `table`). Lo que los
+//! segmentos no expresan (ej: un id solo numérico) se registra con una
+//! regex (`register_regex`), cuyos grupos de captura son los parámetros.
+//!
+//! Por defecto el path se busca tal como llegó. `set_trailing_slash` y
+//! `set_case_insensitive` hacen que `/status/` o `/Status` encuentren la
+//! ruta `/status` (ver `normalize`).
+//!
+//! Al registrar una ruta se le puede agregar su descripción y sus
+//! parámetros (`.describe(...)`, `.required(...)`); `register_help` arma
+//! con ellos `/help` y `/routes.json` (ver `docs`).
+//!
+//! Las rutas relacionadas se pueden registrar en un grupo (`Router::group`)
+//! que les antepone un prefijo y les aplica los mismos middlewares (ver
+//! `group`). Una ruta también puede llevar middlewares propios y un
+//! timeout para su handler (`.middleware(...)`, `.timeout(...)`; ver
+//! `timeout`).
+//!
+//! Antes de despachar, el servidor pasa cada request por el middleware de
+//! rate limiting (`Router::rate_limit`), que aplica a todas las rutas,
+//! incluidas las especiales (`/metrics`, `/admin/*`).
 
+use crate::events::{Event, EventBus};
+use crate::http::request::Method;
 use crate::http::{Request, Response, StatusCode};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+pub mod docs;
+pub mod errors;
+pub mod group;
+pub mod normalize;
+pub mod rate_limit;
+mod table;
+pub mod timeout;
+
+pub use docs::{RouteDoc, RouteEntry};
+pub use errors::{ErrorHandler, ErrorCause, ServerError};
+pub use group::{Middleware, RouteGroup};
+pub use normalize::TrailingSlash;
+pub use rate_limit::{RateLimiter, RouteRateLimit};
+use normalize::{restore_case, toggle_slash};
+use table::{Route, RouteTable};
+use crate::workers::pool::ThreadPool;
 
 /// Tipo de función handler
 /// 
-/// Un handler recibe un Request y retorna una Response
-pub type Handler = fn(&Request) -> Response;
+/// Un handler recibe un Request y retorna una Response. Puede ser una
+/// función o un closure que capture estado compartido (métricas, el job
+/// manager); se llama desde varios threads a la vez.
+pub type Handler = Arc<dyn Fn(&Request) -> Response + Send + Sync>;
 
 /// Router que mapea paths a handlers
 pub struct Router {
-    /// Mapa de path → handler
-    routes: Vec<(String, Handler)>,
+    /// Rutas por path (fijos en un `HashMap`, variables en un árbol)
+    routes: RouteTable,
+    
+    /// Redirecciones: path → (código, destino)
+    redirects: HashMap<String, (StatusCode, String)>,
+    
+    /// Ventanas de rate limiting por (patrón, IP)
+    rate_limiter: RateLimiter,
+    
+    /// Arma las respuestas de error del servidor (404, 400, panics)
+    error_handler: ErrorHandler,
+    
+    /// Qué hacer con la `/` final de un path sin ruta
+    trailing_slash: TrailingSlash,
+    
+    /// Buscar las rutas sin distinguir mayúsculas
+    case_insensitive: bool,
+    
+    /// Bus donde se publican los timeouts de las rutas
+    events: Option<EventBus>,
+    
+    /// Workers del pool de los handlers con timeout
+    timeout_workers: usize,
+    
+    /// Pool de los handlers con timeout (se crea con el primero que corre)
+    timeout_pool: OnceLock<ThreadPool>,
+    
+    /// Atiende los paths sin ruta ni redirección (`None`: 404)
+    fallback: Option<Handler>,
 }
 
 impl Router {
     /// Crea un nuevo router vacío
     pub fn new() -> Self {
         Self {
-            routes: Vec::new(),
+            routes: RouteTable::default(),
+            redirects: HashMap::new(),
+            rate_limiter: RateLimiter::new(),
+            error_handler: errors::json_envelope,
+            trailing_slash: TrailingSlash::Strict,
+            case_insensitive: false,
+            events: None,
+            timeout_workers: timeout::DEFAULT_WORKERS,
+            timeout_pool: OnceLock::new(),
+            fallback: None,
         }
     }
     
-    /// Registra una ruta con su handler
+    /// Registra una ruta con su handler, para cualquier método
     /// 
     /// # Ejemplo
     /// ```
@@ -46,14 +141,224 @@ impl Router {
     /// 
     /// let mut router = Router::new();
     /// router.register("/hello", hello_handler);
+    /// 
+    /// // Un closure puede capturar estado
+    /// let greeting = String::from("hola");
+    /// router.register("/greet", move |_req: &Request| Response::text(&greeting));
+    /// ```
+    pub fn register<F>(&mut self, path: &str, handler: F) -> RouteEntry<'_>
+    where
+        F: Fn(&Request) -> Response + Send + Sync + 'static,
+    {
+        RouteEntry::new(self.routes.insert(path, None, Arc::new(handler)))
+    }
+    
+    /// Registra una ruta con su handler solo para `method`
+    /// 
+    /// Una ruta GET también atiende HEAD. Se puede registrar el mismo path
+    /// con otro método y otro handler.
+    /// 
+    /// # Ejemplo
+    /// ```
+    /// use http_server::router::Router;
+    /// use http_server::http::{Request, Response, StatusCode};
+    /// use http_server::http::request::Method;
+    /// 
+    /// fn hello_handler(_req: &Request) -> Response {
+    ///     Response::json(r#"{"message": "Hello"}"#)
+    /// }
+    /// 
+    /// let mut router = Router::new();
+    /// router.register_method(Method::GET, "/hello", hello_handler);
+    /// 
+    /// let request = Request::parse(b"POST /hello HTTP/1.0\r\nContent-Length: 0\r\n\r\n").unwrap();
+    /// let response = router.route(&request);
+    /// assert_eq!(response.status(), StatusCode::MethodNotAllowed);
+    /// assert_eq!(response.headers().get("Allow").unwrap(), "GET, HEAD");
+    /// ```
+    pub fn register_method<F>(&mut self, method: Method, path: &str, handler: F) -> RouteEntry<'_>
+    where
+        F: Fn(&Request) -> Response + Send + Sync + 'static,
+    {
+        RouteEntry::new(self.routes.insert(path, Some(method), Arc::new(handler)))
+    }
+    
+    /// Registra una ruta para `method` cuyo path completo coincide con la
+    /// regex `pattern`
+    /// 
+    /// Los grupos de captura se leen con `Request::path_param`: los
+    /// nombrados (`(?P<id>...)`) por su nombre y los demás por su número
+    /// (`"1"`, `"2"`). Un grupo opcional que no participó no queda como
+    /// parámetro. Los patrones se prueban en orden de registro, después de
+    /// las rutas fijas y de las de segmentos `:nombre`. Entra en pánico si
+    /// la regex no es válida.
+    /// 
+    /// # Ejemplo
     /// ```
-    pub fn register(&mut self, path: &str, handler: Handler) {
-        self.routes.push((path.to_string(), handler));
+    /// use http_server::router::Router;
+    /// use http_server::http::{Request, Response, StatusCode};
+    /// use http_server::http::request::Method;
+    /// 
+    /// let mut router = Router::new();
+    /// router.register_regex(Method::GET, r"/users/(?P<id>[0-9]+)", |req: &Request| {
+    ///     Response::text(req.path_param("id").unwrap())
+    /// });
+    /// 
+    /// let request = Request::parse(b"GET /users/42 HTTP/1.0\r\n\r\n").unwrap();
+    /// assert_eq!(router.route(&request).body(), b"42");
+    /// 
+    /// let request = Request::parse(b"GET /users/abc HTTP/1.0\r\n\r\n").unwrap();
+    /// assert_eq!(router.route(&request).status(), StatusCode::NotFound);
+    /// ```
+    pub fn register_regex<F>(&mut self, method: Method, pattern: &str, handler: F) -> RouteEntry<'_>
+    where
+        F: Fn(&Request) -> Response + Send + Sync + 'static,
+    {
+        RouteEntry::new(self.routes.insert_regex(pattern, Some(method), Arc::new(handler)))
+    }
+    
+    /// Registra una redirección de `from` a `to` (301, 302, 303, 307 o 308)
+    /// 
+    /// La query del request se conserva si `to` no trae una propia. Un
+    /// handler registrado para `from` tiene prioridad.
+    /// 
+    /// # Ejemplo
+    /// ```
+    /// use http_server::router::Router;
+    /// use http_server::http::{Request, StatusCode};
+    /// 
+    /// let mut router = Router::new();
+    /// router.register_redirect("/docs", StatusCode::MovedPermanently, "/help");
+    /// 
+    /// let request = Request::parse(b"GET /docs?v=1 HTTP/1.0\r\n\r\n").unwrap();
+    /// let response = router.route(&request);
+    /// assert_eq!(response.status(), StatusCode::MovedPermanently);
+    /// assert_eq!(response.headers().get("Location").unwrap(), "/help?v=1");
+    /// ```
+    pub fn register_redirect(&mut self, from: &str, status: StatusCode, to: &str) {
+        assert!(status.is_redirect(), "{} is not a redirect status", status);
+        self.redirects.entry(from.to_string()).or_insert((status, to.to_string()));
+    }
+    
+    /// Reemplaza el handler de errores (por defecto `errors::json_envelope`)
+    /// 
+    /// # Ejemplo
+    /// ```
+    /// use http_server::http::{Request, Response};
+    /// use http_server::router::{Router, ServerError};
+    /// 
+    /// fn html_error(error: &ServerError) -> Response {
+    ///     Response::builder(error.status)
+    ///         .content_type("text/html; charset=utf-8")
+    ///         .body(&format!("<h1>{}</h1>", error.message))
+    /// }
+    /// 
+    /// let mut router = Router::new();
+    /// router.set_error_handler(html_error);
+    /// 
+    /// let request = Request::parse(b"GET /nada HTTP/1.0\r\n\r\n").unwrap();
+    /// let response = router.route(&request);
+    /// assert_eq!(response.body(), b"<h1>Route not found: /nada</h1>");
+    /// ```
+    pub fn set_error_handler(&mut self, handler: ErrorHandler) {
+        self.error_handler = handler;
+    }
+    
+    /// Atiende con `handler` los requests cuyo path no tiene ruta ni
+    /// redirección, en lugar del 404 del `ErrorHandler`
+    /// 
+    /// A diferencia de `set_error_handler`, recibe el request y puede
+    /// responder cualquier status (ej: el `index.html` de una SPA). Los 405
+    /// de un path con rutas no pasan por él.
+    /// 
+    /// # Ejemplo
+    /// ```
+    /// use http_server::router::Router;
+    /// use http_server::http::{Request, Response, StatusCode};
+    /// 
+    /// let mut router = Router::new();
+    /// router.register("/status", |_req: &Request| Response::text("ok"));
+    /// router.set_fallback(|req: &Request| {
+    ///     Response::builder(StatusCode::NotFound)
+    ///         .content_type("text/html; charset=utf-8")
+    ///         .body(&format!("<p>{} no existe. ¿Quisiste decir <a href=\"/status\">/status</a>?</p>", req.path()))
+    /// });
+    /// 
+    /// let response = router.route(&Request::parse(b"GET /stats HTTP/1.0\r\n\r\n").unwrap());
+    /// assert_eq!(response.status(), StatusCode::NotFound);
+    /// assert!(response.body().starts_with(b"<p>/stats no existe."));
+    /// ```
+    pub fn set_fallback<F>(&mut self, handler: F)
+    where
+        F: Fn(&Request) -> Response + Send + Sync + 'static,
+    {
+        self.fallback = Some(Arc::new(handler));
+    }
+    
+    /// Cómo tratar un path sin ruta que la tiene con o sin `/` final
+    /// 
+    /// # Ejemplo
+    /// ```
+    /// use http_server::router::{Router, TrailingSlash};
+    /// use http_server::http::{Request, Response, StatusCode};
+    /// 
+    /// let mut router = Router::new();
+    /// router.register("/status", |_req: &Request| Response::text("ok"));
+    /// router.set_trailing_slash(TrailingSlash::Redirect);
+    /// 
+    /// let response = router.route(&Request::parse(b"GET /status/?v=1 HTTP/1.0\r\n\r\n").unwrap());
+    /// assert_eq!(response.status(), StatusCode::MovedPermanently);
+    /// assert_eq!(response.headers().get("Location"), Some("/status?v=1"));
+    /// ```
+    pub fn set_trailing_slash(&mut self, mode: TrailingSlash) {
+        self.trailing_slash = mode;
+    }
+    
+    /// Busca en minúsculas los paths que no tienen ruta tal como llegaron
+    /// 
+    /// Las rutas se registran en minúsculas; los parámetros conservan las
+    /// mayúsculas del request.
+    pub fn set_case_insensitive(&mut self, enabled: bool) {
+        self.case_insensitive = enabled;
+    }
+    
+    /// Fija el timeout de las rutas registradas con el path o patrón `path`
+    /// (ver `timeout`)
+    /// 
+    /// Retorna `false` si no hay ninguna.
+    pub fn set_timeout(&mut self, path: &str, timeout: Duration) -> bool {
+        let routes = self.routes.registered_mut(path);
+        let found = !routes.is_empty();
+        for route in routes {
+            route.timeout = Some(timeout);
+        }
+        found
+    }
+    
+    /// Workers (y lugares en cola) para los handlers con timeout; más
+    /// requests a la vez reciben 503
+    pub fn set_timeout_workers(&mut self, workers: usize) {
+        self.timeout_workers = workers;
+    }
+    
+    /// Publica en `events` los handlers que superan su timeout y el pool
+    /// de timeouts lleno
+    pub fn set_events(&mut self, events: EventBus) {
+        self.events = Some(events);
+    }
+    
+    /// Respuesta para un error del servidor, con los headers comunes
+    pub fn error_response(&self, error: &ServerError) -> Response {
+        let mut response = (self.error_handler)(error);
+        self.add_common_headers(&mut response);
+        response
     }
     
     /// Encuentra y ejecuta el handler apropiado para un request
     /// 
-    /// Si no encuentra un handler para el path, retorna 404 Not Found.
+    /// Si el path tiene rutas pero ninguna para el método, retorna 405
+    /// Method Not Allowed; si no encuentra un handler para el path, 404 Not
+    /// Found.
     /// 
     /// # Ejemplo
     /// ```
@@ -70,26 +375,259 @@ impl Router {
     pub fn route(&self, request: &Request) -> Response {
         let path = request.path();
         
-        // Buscar handler para este path
-        for (route_path, handler) in &self.routes {
-            if route_path == path {
-                // Encontramos el handler, ejecutarlo
-                let mut response = handler(request);
-                // Agregar headers comunes a todas las respuestas
-                self.add_common_headers(&mut response);
-                return response;
+        // Sin ruta para el path, la variante con o sin `/` final
+        let alternate = toggle_slash(path).filter(|_| self.trailing_slash != TrailingSlash::Strict);
+        let found = match self.lookup(path) {
+            Some(found) => Some(found),
+            None => match alternate.as_deref().and_then(|alternate| Some((alternate, self.lookup(alternate)?))) {
+                Some((alternate, _)) if self.trailing_slash == TrailingSlash::Redirect => {
+                    return self.slash_redirect(request, alternate);
+                }
+                found => found.map(|(_, found)| found),
+            },
+        };
+        
+        // Buscar handler para este path y método (las rutas externas no
+        // tienen handler aquí: el path cuenta como sin ruta)
+        let found = found.filter(|(routes, _)| routes.iter().any(|route| !route.external));
+        if let Some((routes, values)) = found {
+            let mut allowed = Vec::new();
+            let options = request.method() == Method::OPTIONS;
+            for route in routes.iter().filter(|route| !route.external) {
+                match route.method {
+                    Some(method) if !accepts(method, request.method()) => allowed.push(method),
+                    // OPTIONS lo responde el router salvo que la ruta lo registre
+                    None if options => allowed.extend([Method::GET, Method::POST, Method::OPTIONS]),
+                    _ => {
+                        // Encontramos el handler, ejecutarlo
+                        let request = if route.params.is_empty() {
+                            Cow::Borrowed(request)
+                        } else {
+                            let mut request = request.clone();
+                            request.set_path_params(route.params.iter().cloned()
+                                .zip(values.iter())
+                                .filter(|(_, value)| !value.is_empty())
+                                .map(|(name, value)| (name, value.to_string()))
+                                .collect());
+                            Cow::Owned(request)
+                        };
+                        return self.dispatch(route, &request);
+                    }
+                }
+            }
+            
+            if options {
+                return self.options_response(allowed);
             }
+            
+            // El path existe, pero no para este método
+            return self.method_not_allowed(request, &allowed);
+        }
+        
+        self.not_found(request)
+    }
+    
+    /// Respuesta para un path sin ruta: su redirección declarada, el
+    /// fallback o 404
+    fn not_found(&self, request: &Request) -> Response {
+        let path = request.path();
+        
+        // Redirecciones declaradas
+        let redirect = self.redirects.get(path).or_else(|| {
+            self.case_insensitive.then(|| self.redirects.get(&path.to_ascii_lowercase())).flatten()
+        });
+        if let Some((status, to)) = redirect {
+            let location = match request.target().split_once('?') {
+                Some((_, query)) if !to.contains('?') => format!("{}?{}", to, query),
+                _ => to.clone(),
+            };
+            let mut response = Response::redirect(*status, &location);
+            self.add_common_headers(&mut response);
+            return response;
         }
         
         // No se encontró handler para este path
-        let mut response = Response::error(
+        if let Some(fallback) = &self.fallback {
+            let mut response = fallback(request);
+            self.add_common_headers(&mut response);
+            return response;
+        }
+        self.error_response(&ServerError::new(
+            ErrorCause::NotFound,
             StatusCode::NotFound,
-            &format!("Route not found: {}", path)
-        );
+            &format!("Route not found: {}", path),
+            request.id(),
+        ))
+    }
+    
+    /// Corre los middlewares de `route` y su handler (con su timeout)
+    fn dispatch(&self, route: &Route, request: &Request) -> Response {
+        let mut response = match route.middleware.iter().find_map(|check| check(request)) {
+            Some(response) => response,
+            None => match route.timeout {
+                None => (route.handler)(request),
+                Some(limit) => {
+                    let pool = self.timeout_pool.get_or_init(|| timeout::pool(self.timeout_workers));
+                    match timeout::call(pool, &route.handler, request, limit) {
+                        timeout::Outcome::Done(response) => response,
+                        timeout::Outcome::TimedOut => return self.timed_out(route, request, limit),
+                        timeout::Outcome::Busy => return self.timeout_pool_full(pool, request),
+                    }
+                }
+            },
+        };
+        // Agregar headers comunes a todas las respuestas
+        self.add_common_headers(&mut response);
+        response
+    }
+    
+    /// 503 para un handler de `route` que superó `limit` (se publica como
+    /// `RouteTimedOut`)
+    fn timed_out(&self, route: &Route, request: &Request, limit: Duration) -> Response {
+        if let Some(events) = &self.events {
+            events.publish(Event::RouteTimedOut { path: route.path.clone(), timeout: limit });
+        }
+        self.error_response(&ServerError::new(
+            ErrorCause::Timeout,
+            StatusCode::ServiceUnavailable,
+            &format!("Route {} timed out after {} ms", request.path(), limit.as_millis()),
+            request.id(),
+        ))
+    }
+    
+    /// 503 para un handler con timeout que no entró al pool (se publica
+    /// como `QueueSaturated`)
+    fn timeout_pool_full(&self, pool: &ThreadPool, request: &Request) -> Response {
+        let (len, capacity) = pool.queue_usage();
+        if let Some(events) = &self.events {
+            events.publish(Event::QueueSaturated { queue: timeout::POOL_NAME.to_string(), len, capacity });
+        }
+        self.error_response(&ServerError::new(
+            ErrorCause::Timeout,
+            StatusCode::ServiceUnavailable,
+            &format!("Server busy: no worker free to run {} within its timeout", request.path()),
+            request.id(),
+        ))
+    }
+    
+    /// Rutas de `path`; sin distinguir mayúsculas, también las de `path` en
+    /// minúsculas (con los valores de los parámetros tomados de `path`)
+    fn lookup<'a>(&self, path: &'a str) -> Option<(&[Route], Vec<&'a str>)> {
+        if let Some(found) = self.routes.lookup(path) {
+            return Some(found);
+        }
+        if !self.case_insensitive || !path.bytes().any(|b| b.is_ascii_uppercase()) {
+            return None;
+        }
+        let lowered = path.to_ascii_lowercase();
+        let (routes, values) = self.routes.lookup(&lowered)?;
+        Some((routes, restore_case(path, &lowered, values)))
+    }
+    
+    /// Redirección de `--trailing-slash redirect` a `to`, con la query del
+    /// request (308 si el método lleva body)
+    fn slash_redirect(&self, request: &Request, to: &str) -> Response {
+        let status = match request.method() {
+            Method::GET | Method::HEAD => StatusCode::MovedPermanently,
+            _ => StatusCode::PermanentRedirect,
+        };
+        let location = match request.target().split_once('?') {
+            Some((_, query)) => format!("{}?{}", to, query),
+            None => to.to_string(),
+        };
+        let mut response = Response::redirect(status, &location);
         self.add_common_headers(&mut response);
         response
     }
     
+    /// OPTIONS y 405 como los de una ruta registrada, para un endpoint que
+    /// el servidor atiende fuera de la tabla y acepta solo `methods`
+    /// 
+    /// `None` si el método del request es uno de `methods` (GET también
+    /// acepta HEAD).
+    /// 
+    /// # Ejemplo
+    /// ```
+    /// use http_server::router::Router;
+    /// use http_server::http::{Request, StatusCode};
+    /// use http_server::http::request::Method;
+    ///
+    /// let router = Router::new();
+    /// let check = |raw: &[u8]| router.check_method(&Request::parse(raw).unwrap(), &[Method::GET]);
+    /// assert!(check(b"HEAD /metrics HTTP/1.0\r\n\r\n").is_none());
+    ///
+    /// let post = check(b"POST /metrics HTTP/1.0\r\n\r\n").unwrap();
+    /// assert_eq!(post.status(), StatusCode::MethodNotAllowed);
+    /// assert_eq!(post.headers().get("Allow"), Some("GET, HEAD"));
+    ///
+    /// let options = check(b"OPTIONS /metrics HTTP/1.0\r\n\r\n").unwrap();
+    /// assert_eq!(options.status(), StatusCode::NoContent);
+    /// assert_eq!(options.headers().get("Allow"), Some("GET, HEAD, OPTIONS"));
+    /// ```
+    pub fn check_method(&self, request: &Request, methods: &[Method]) -> Option<Response> {
+        if methods.iter().any(|method| accepts(*method, request.method())) {
+            return None;
+        }
+        if request.method() == Method::OPTIONS {
+            return Some(self.options_response(methods.to_vec()));
+        }
+        Some(self.method_not_allowed(request, methods))
+    }
+    
+    /// 204 a OPTIONS con `Allow`: los métodos de `allowed` y OPTIONS
+    fn options_response(&self, mut allowed: Vec<Method>) -> Response {
+        allowed.push(Method::OPTIONS);
+        let mut response = Response::builder(StatusCode::NoContent)
+            .header("Allow", &allow_header(&allowed))
+            .build();
+        self.add_common_headers(&mut response);
+        response
+    }
+    
+    /// 405 con `Allow`: los métodos de `allowed` (GET agrega HEAD)
+    fn method_not_allowed(&self, request: &Request, allowed: &[Method]) -> Response {
+        let allow = allow_header(allowed);
+        let mut response = self.error_response(&ServerError::new(
+            ErrorCause::MethodNotAllowed,
+            StatusCode::MethodNotAllowed,
+            &format!("Method {} not allowed for {}. Use {}", request.method().as_str(), request.path(), allow),
+            request.id(),
+        ));
+        response.add_header("Allow", &allow);
+        response
+    }
+    
+    /// Cantidad de rutas registradas
+    pub fn len(&self) -> usize {
+        self.routes.len()
+    }
+    
+    /// Indica si no hay rutas registradas
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    
+    /// Middleware de rate limiting por ruta e IP
+    /// 
+    /// Retorna `Some(429)` con `Retry-After` si `ip` excedió el límite que
+    /// aplica a `path` (`limits` por ruta, o `default_per_sec` como `*`).
+    /// Los límites se pasan en cada llamada para respetar las recargas.
+    pub fn rate_limit(
+        &self,
+        path: &str,
+        ip: IpAddr,
+        limits: &[RouteRateLimit],
+        default_per_sec: u32,
+    ) -> Option<Response> {
+        let retry_ms = self.rate_limiter.check(limits, default_per_sec, path, ip).err()?;
+        
+        let mut response = Response::builder(StatusCode::TooManyRequests)
+            .retry_after(Duration::from_millis(retry_ms))
+            .json(&serde_json::json!({"error": format!("Rate limit exceeded for {}", path)}));
+        self.add_common_headers(&mut response);
+        Some(response)
+    }
+    
     /// Agrega headers comunes a todas las respuestas
     fn add_common_headers(&self, response: &mut Response) {
         response.add_header("Server", "RedUnix-HTTP/1.0");
@@ -97,6 +635,21 @@ impl Router {
     }
 }
 
+/// Valor de `Allow` para las rutas de `allowed` (GET agrega HEAD)
+fn allow_header(allowed: &[Method]) -> String {
+    [Method::GET, Method::HEAD, Method::POST, Method::OPTIONS]
+        .into_iter()
+        .filter(|candidate| allowed.iter().any(|method| accepts(*method, *candidate)))
+        .map(|method| method.as_str())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Indica si una ruta registrada para `route` atiende un request `method`
+fn accepts(route: Method, method: Method) -> bool {
+    route == method || (route == Method::GET && method == Method::HEAD)
+}
+
 impl Default for Router {
     fn default() -> Self {
         Self::new()
@@ -118,7 +671,7 @@ mod tests {
     #[test]
     fn test_router_creation() {
         let router = Router::new();
-        assert_eq!(router.routes.len(), 0);
+        assert_eq!(router.len(), 0);
     }
     
     #[test]
@@ -126,7 +679,7 @@ mod tests {
         let mut router = Router::new();
         router.register("/test", test_handler);
         
-        assert_eq!(router.routes.len(), 1);
+        assert_eq!(router.len(), 1);
     }
     
     #[test]
@@ -168,4 +721,259 @@ mod tests {
         let response2 = router.route(&request2);
         assert_eq!(response2.status(), StatusCode::Ok);
     }
-}
\ No newline at end of file
+    
+    #[test]
+    fn test_redirect_routes() {
+        let mut router = Router::new();
+        router.register("/help", hello_handler);
+        router.register("/test", test_handler);
+        router.register_redirect("/docs", StatusCode::MovedPermanently, "/help");
+        router.register_redirect("/old", StatusCode::TemporaryRedirect, "/test?from=old");
+        router.register_redirect("/test", StatusCode::Found, "/help");
+        
+        let route = |raw: &[u8]| router.route(&Request::parse(raw).unwrap());
+        
+        let docs = route(b"GET /docs HTTP/1.0\r\n\r\n");
+        assert_eq!(docs.status(), StatusCode::MovedPermanently);
+        assert_eq!(docs.headers().get("Location"), Some("/help"));
+        assert_eq!(docs.headers().get("Server"), Some("RedUnix-HTTP/1.0"));
+        
+        // El destino con query propia no recibe la del request
+        let old = route(b"POST /old?x=1 HTTP/1.0\r\n\r\n");
+        assert_eq!(old.status(), StatusCode::TemporaryRedirect);
+        assert_eq!(old.headers().get("Location"), Some("/test?from=old"));
+        
+        // Un handler registrado gana sobre la redirección
+        assert_eq!(route(b"GET /test HTTP/1.0\r\n\r\n").status(), StatusCode::Ok);
+    }
+    
+    #[test]
+    fn test_fallback_replaces_only_the_404() {
+        let mut router = Router::new();
+        router.register_method(Method::GET, "/file", test_handler);
+        router.register_redirect("/old", StatusCode::MovedPermanently, "/file");
+        router.set_fallback(|req: &Request| Response::text(&format!("fallback {}", req.path())));
+        
+        let route = |raw: &[u8]| router.route(&Request::parse(raw).unwrap());
+        let missing = route(b"GET /nada HTTP/1.0\r\n\r\n");
+        assert_eq!(missing.status(), StatusCode::Ok);
+        assert_eq!(missing.body(), b"fallback /nada");
+        assert_eq!(missing.headers().get("Server"), Some("RedUnix-HTTP/1.0"));
+        // Las rutas, sus 405 y las redirecciones no cambian
+        assert_eq!(route(b"GET /file HTTP/1.0\r\n\r\n").body(), br#"{"test": "ok"}"#);
+        assert_eq!(
+            route(b"POST /file HTTP/1.0\r\nContent-Length: 0\r\n\r\n").status(),
+            StatusCode::MethodNotAllowed
+        );
+        assert_eq!(route(b"GET /old HTTP/1.0\r\n\r\n").status(), StatusCode::MovedPermanently);
+    }
+    
+    #[test]
+    fn test_closure_handler_shares_state() {
+        use std::sync::atomic::{AtomicU64, Ordering};
+        
+        let hits = Arc::new(AtomicU64::new(0));
+        let mut router = Router::new();
+        router.register("/count", {
+            let hits = Arc::clone(&hits);
+            move |_req: &Request| {
+                let count = hits.fetch_add(1, Ordering::Relaxed) + 1;
+                Response::json_value(&serde_json::json!({"count": count}))
+            }
+        });
+        
+        let request = Request::parse(b"GET /count HTTP/1.0\r\n\r\n").unwrap();
+        router.route(&request);
+        let response = router.route(&request);
+        assert_eq!(response.body(), br#"{"count":2}"#);
+        assert_eq!(hits.load(Ordering::Relaxed), 2);
+    }
+    
+    #[test]
+    fn test_routes_by_method() {
+        fn upload_handler(_req: &Request) -> Response {
+            Response::json(r#"{"uploaded": true}"#)
+        }
+        
+        let mut router = Router::new();
+        router.register_method(Method::GET, "/file", test_handler);
+        router.register_method(Method::POST, "/file", upload_handler);
+        router.register_method(Method::GET, "/only-get", test_handler);
+        router.register_redirect("/only-get", StatusCode::Found, "/file");
+        
+        let route = |raw: &[u8]| router.route(&Request::parse(raw).unwrap());
+        
+        assert_eq!(route(b"GET /file HTTP/1.0\r\n\r\n").body(), br#"{"test": "ok"}"#);
+        assert_eq!(route(b"HEAD /file HTTP/1.0\r\n\r\n").status(), StatusCode::Ok);
+        assert_eq!(
+            route(b"POST /file HTTP/1.0\r\nContent-Length: 0\r\n\r\n").body(),
+            br#"{"uploaded": true}"#
+        );
+        
+        // El path existe pero no para POST: 405 antes que la redirección
+        let post = route(b"POST /only-get HTTP/1.0\r\nContent-Length: 0\r\n\r\n");
+        assert_eq!(post.status(), StatusCode::MethodNotAllowed);
+        assert_eq!(post.headers().get("Allow"), Some("GET, HEAD"));
+        assert_eq!(post.headers().get("Server"), Some("RedUnix-HTTP/1.0"));
+        let body: serde_json::Value = serde_json::from_slice(post.body()).unwrap();
+        assert_eq!(body["code"], "method_not_allowed");
+        
+        // Un path sin rutas sigue siendo 404
+        assert_eq!(route(b"POST /nada HTTP/1.0\r\nContent-Length: 0\r\n\r\n").status(), StatusCode::NotFound);
+    }
+    
+    #[test]
+    fn test_trailing_slash_and_case_insensitive_paths() {
+        fn show(req: &Request) -> Response {
+            Response::text(req.path_param("id").unwrap_or("-"))
+        }
+        
+        let mut router = Router::new();
+        router.register("/status", test_handler);
+        router.register("/files/", test_handler);
+        router.register_method(Method::GET, "/jobs/:id", show);
+        let status = |router: &Router, raw: &[u8]| router.route(&Request::parse(raw).unwrap()).status();
+        
+        // strict (default): cada variante es otra ruta
+        assert_eq!(status(&router, b"GET /status/ HTTP/1.0\r\n\r\n"), StatusCode::NotFound);
+        assert_eq!(status(&router, b"GET /Status HTTP/1.0\r\n\r\n"), StatusCode::NotFound);
+        
+        router.set_trailing_slash(TrailingSlash::Ignore);
+        assert_eq!(status(&router, b"GET /status/ HTTP/1.0\r\n\r\n"), StatusCode::Ok);
+        assert_eq!(status(&router, b"GET /files HTTP/1.0\r\n\r\n"), StatusCode::Ok);
+        assert_eq!(status(&router, b"GET / HTTP/1.0\r\n\r\n"), StatusCode::NotFound);
+        
+        router.set_trailing_slash(TrailingSlash::Redirect);
+        let redirect = router.route(&Request::parse(b"GET /files?x=1 HTTP/1.0\r\n\r\n").unwrap());
+        assert_eq!(redirect.status(), StatusCode::MovedPermanently);
+        assert_eq!(redirect.headers().get("Location"), Some("/files/?x=1"));
+        // Un POST se redirige con 308 para que repita el body
+        let post = router.route(&Request::parse(b"POST /status/ HTTP/1.0\r\nContent-Length: 0\r\n\r\n").unwrap());
+        assert_eq!(post.status(), StatusCode::PermanentRedirect);
+        
+        router.set_case_insensitive(true);
+        assert_eq!(status(&router, b"GET /STATUS HTTP/1.0\r\n\r\n"), StatusCode::Ok);
+        let job = router.route(&Request::parse(b"GET /Jobs/AbC HTTP/1.0\r\n\r\n").unwrap());
+        assert_eq!(job.body(), b"AbC");
+        let both = router.route(&Request::parse(b"GET /Status/ HTTP/1.0\r\n\r\n").unwrap());
+        assert_eq!(both.headers().get("Location"), Some("/Status"));
+    }
+    
+    #[test]
+    fn test_options_lists_the_methods_of_the_path() {
+        let mut router = Router::new();
+        router.register_method(Method::GET, "/file", test_handler);
+        router.register_method(Method::POST, "/file", test_handler);
+        router.register("/any", test_handler);
+        router.register_method(Method::OPTIONS, "/custom", |_req: &Request| Response::text("custom"));
+        
+        let route = |raw: &[u8]| router.route(&Request::parse(raw).unwrap());
+        let file = route(b"OPTIONS /file HTTP/1.0\r\n\r\n");
+        assert_eq!(file.status(), StatusCode::NoContent);
+        assert_eq!(file.headers().get("Allow"), Some("GET, HEAD, POST, OPTIONS"));
+        assert!(file.body().is_empty());
+        // Una ruta de cualquier método no recibe el OPTIONS
+        assert_eq!(route(b"OPTIONS /any HTTP/1.0\r\n\r\n").headers().get("Allow"), Some("GET, HEAD, POST, OPTIONS"));
+        assert_eq!(route(b"OPTIONS /custom HTTP/1.0\r\n\r\n").body(), b"custom");
+        assert_eq!(route(b"OPTIONS /nada HTTP/1.0\r\n\r\n").status(), StatusCode::NotFound);
+    }
+    
+    #[test]
+    fn test_path_params_and_exact_priority() {
+        fn show(req: &Request) -> Response {
+            Response::text(&format!("{}:{}", req.path_param("id").unwrap_or("-"), req.path_param("part").unwrap_or("-")))
+        }
+        
+        let mut router = Router::new();
+        router.register_method(Method::GET, "/items/:id", show);
+        router.register("/items/:id/:part", show);
+        router.register("/items/new", test_handler);
+        
+        let route = |raw: &[u8]| router.route(&Request::parse(raw).unwrap());
+        assert_eq!(route(b"GET /items/7 HTTP/1.0\r\n\r\n").body(), b"7:-");
+        assert_eq!(route(b"GET /items/7/name HTTP/1.0\r\n\r\n").body(), b"7:name");
+        assert_eq!(route(b"GET /items/new HTTP/1.0\r\n\r\n").body(), br#"{"test": "ok"}"#);
+        
+        let post = route(b"POST /items/7 HTTP/1.0\r\nContent-Length: 0\r\n\r\n");
+        assert_eq!(post.status(), StatusCode::MethodNotAllowed);
+        assert_eq!(route(b"GET /items HTTP/1.0\r\n\r\n").status(), StatusCode::NotFound);
+        assert_eq!(router.len(), 3);
+    }
+    
+    #[test]
+    fn test_regex_routes_expose_captures_as_params() {
+        fn show(req: &Request) -> Response {
+            Response::text(&format!("{}:{}", req.path_param("id").unwrap_or("-"), req.path_param("2").unwrap_or("-")))
+        }
+        
+        let mut router = Router::new();
+        router.register_regex(Method::GET, r"/orders/(?P<id>\d+)(?:/(\w+))?", show);
+        router.register("/orders/:id/summary", test_handler);
+        
+        let route = |raw: &[u8]| router.route(&Request::parse(raw).unwrap());
+        assert_eq!(route(b"GET /orders/12/csv HTTP/1.0\r\n\r\n").body(), b"12:csv");
+        // El grupo opcional que no participó no es un parámetro
+        assert_eq!(route(b"GET /orders/12 HTTP/1.0\r\n\r\n").body(), b"12:-");
+        assert_eq!(route(b"GET /orders/ab HTTP/1.0\r\n\r\n").status(), StatusCode::NotFound);
+        // La ruta de segmentos se prueba antes que la regex
+        assert_eq!(route(b"GET /orders/12/summary HTTP/1.0\r\n\r\n").body(), br#"{"test": "ok"}"#);
+        assert_eq!(
+            route(b"POST /orders/12/json HTTP/1.0\r\nContent-Length: 0\r\n\r\n").status(),
+            StatusCode::MethodNotAllowed
+        );
+    }
+    
+    #[test]
+    fn test_route_middleware_and_timeout() {
+        fn slow(_req: &Request) -> Response {
+            std::thread::sleep(Duration::from_millis(300));
+            Response::text("tarde")
+        }
+        
+        let events = EventBus::new();
+        let timeouts = events.subscribe("test");
+        let mut router = Router::new();
+        router.set_events(events);
+        router.register_method(Method::GET, "/items/:id", test_handler)
+            .middleware(|req: &Request| {
+                (req.path_param("id") == Some("0")).then(|| Response::error(StatusCode::BadRequest, "Invalid id"))
+            });
+        router.register("/slow", slow).timeout(Duration::from_millis(20));
+        router.register("/later", slow);
+        assert!(router.set_timeout("/later", Duration::from_millis(20)));
+        assert!(!router.set_timeout("/nada", Duration::from_millis(20)));
+        
+        let route = |raw: &[u8]| router.route(&Request::parse(raw).unwrap());
+        // El middleware ve los parámetros del path
+        assert_eq!(route(b"GET /items/0 HTTP/1.0\r\n\r\n").status(), StatusCode::BadRequest);
+        assert_eq!(route(b"GET /items/7 HTTP/1.0\r\n\r\n").status(), StatusCode::Ok);
+        
+        let slow = route(b"GET /slow HTTP/1.0\r\n\r\n");
+        assert_eq!(slow.status(), StatusCode::ServiceUnavailable);
+        assert_eq!(slow.headers().get("Server"), Some("RedUnix-HTTP/1.0"));
+        let body: serde_json::Value = serde_json::from_slice(slow.body()).unwrap();
+        assert_eq!(body["code"], "timeout");
+        assert_eq!(route(b"GET /later HTTP/1.0\r\n\r\n").status(), StatusCode::ServiceUnavailable);
+        
+        let published: Vec<Event> = timeouts.try_iter().collect();
+        assert_eq!(published, [
+            Event::RouteTimedOut { path: "/slow".to_string(), timeout: Duration::from_millis(20) },
+            Event::RouteTimedOut { path: "/later".to_string(), timeout: Duration::from_millis(20) },
+        ]);
+    }
+    
+    #[test]
+    fn test_rate_limit_middleware() {
+        let router = Router::new();
+        let ip: IpAddr = "10.0.0.1".parse().unwrap();
+        let limits = vec![RouteRateLimit::parse("/test=1").unwrap()];
+        
+        assert!(router.rate_limit("/test", ip, &limits, 0).is_none());
+        
+        let response = router.rate_limit("/test", ip, &limits, 0).unwrap();
+        assert_eq!(response.status(), StatusCode::TooManyRequests);
+        
+        // Sin límites configurados no se limita nada
+        assert!(router.rate_limit("/other", ip, &[], 0).is_none());
+    }
+}
@@ -0,0 +1,143 @@
+//! # Normalización de Paths
+//! src/router/normalize.rs
+//!
+//! Opciones del router para que variantes del mismo path se comporten de
+//! forma predecible (`--trailing-slash`, `--case-insensitive-paths`):
+//!
+//! ```text
+//! --trailing-slash    GET /status/ (ruta registrada: /status)
+//! strict              404
+//! redirect            301 → /status
+//! ignore              200 (la atiende /status)
+//! ```
+//!
+//! - La `/` final se prueba agregada o quitada solo si el path tal como
+//!   llegó no tiene ruta; `/` nunca cambia. Con `redirect` la respuesta es
+//!   301 (308 si el método no es GET ni HEAD, para que se repita el body)
+//!   conservando la query.
+//! - Sin distinguir mayúsculas, un path sin ruta se vuelve a buscar en
+//!   minúsculas (las rutas se registran en minúsculas). Los parámetros
+//!   (`:id`, capturas) conservan las mayúsculas del request.
+//! - `Router::normalize` da la variante con la que se rutea un path; el
+//!   servidor la usa para todo lo que decide por path fuera del router.
+
+use super::Router;
+use std::borrow::Cow;
+
+/// Qué hacer con un path que solo difiere de una ruta en la `/` final
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TrailingSlash {
+    /// `/status/` y `/status` son rutas distintas
+    #[default]
+    Strict,
+
+    /// Redirige a la variante que tiene ruta
+    Redirect,
+
+    /// Atiende con la ruta de la otra variante
+    Ignore,
+}
+
+impl TrailingSlash {
+    /// Nombres válidos en la configuración
+    pub const NAMES: &'static [&'static str] = &["strict", "redirect", "ignore"];
+
+    /// Parsea la opción desde su nombre en la configuración
+    ///
+    /// # Ejemplo
+    /// ```
+    /// use http_server::router::TrailingSlash;
+    ///
+    /// assert_eq!(TrailingSlash::parse("Redirect").unwrap(), TrailingSlash::Redirect);
+    /// assert!(TrailingSlash::parse("loose").is_err());
+    /// ```
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name.to_lowercase().as_str() {
+            "strict" => Ok(TrailingSlash::Strict),
+            "redirect" => Ok(TrailingSlash::Redirect),
+            "ignore" => Ok(TrailingSlash::Ignore),
+            _ => Err(format!(
+                "Invalid trailing slash mode: {} (expected one of: {})",
+                name,
+                Self::NAMES.join(", ")
+            )),
+        }
+    }
+}
+
+impl Router {
+    /// Path con el que se rutea `path`: la variante (en minúsculas, con o
+    /// sin la `/` final) que tiene ruta, según las opciones del router
+    ///
+    /// Es la clave para lo que se decide por path fuera del router (rate
+    /// limiting, rutas de administración, caché, compresión, métricas): así
+    /// `/STATUS` o `/status/` cuentan como `/status`. Sin ruta, el path en
+    /// minúsculas si no se distinguen mayúsculas.
+    ///
+    /// # Ejemplo
+    /// ```
+    /// use http_server::router::{Router, TrailingSlash};
+    /// use http_server::http::{Request, Response};
+    ///
+    /// let mut router = Router::new();
+    /// router.register("/status", |_req: &Request| Response::text("ok"));
+    /// router.set_trailing_slash(TrailingSlash::Ignore);
+    /// router.set_case_insensitive(true);
+    ///
+    /// assert_eq!(router.normalize("/Status/"), "/status");
+    /// assert_eq!(router.normalize("/Nope"), "/nope");
+    /// ```
+    pub fn normalize<'a>(&self, path: &'a str) -> Cow<'a, str> {
+        if self.routes.lookup(path).is_some() {
+            return Cow::Borrowed(path);
+        }
+        let lowered = (self.case_insensitive && path.bytes().any(|b| b.is_ascii_uppercase()))
+            .then(|| path.to_ascii_lowercase());
+        // El mismo orden en que prueba `route`
+        let mut candidates: Vec<String> = lowered.iter().cloned().collect();
+        if self.trailing_slash != TrailingSlash::Strict {
+            candidates.extend(std::iter::once(path).chain(lowered.as_deref()).filter_map(toggle_slash));
+        }
+        match candidates.into_iter().find(|candidate| self.routes.lookup(candidate).is_some()) {
+            Some(found) => Cow::Owned(found),
+            None => lowered.map_or(Cow::Borrowed(path), Cow::Owned),
+        }
+    }
+}
+
+/// `path` con la `/` final quitada o agregada (`None` para `/`)
+pub(super) fn toggle_slash(path: &str) -> Option<String> {
+    match path.strip_suffix('/') {
+        _ if path == "/" => None,
+        Some(trimmed) => Some(trimmed.to_string()),
+        None => Some(format!("{}/", path)),
+    }
+}
+
+/// Lleva los `values` tomados de `lowered` (el mismo path en minúsculas
+/// ASCII, de igual largo) a los mismos rangos de `original`
+pub(super) fn restore_case<'a>(original: &'a str, lowered: &str, values: Vec<&str>) -> Vec<&'a str> {
+    values.into_iter()
+        .map(|value| {
+            let start = value.as_ptr() as usize - lowered.as_ptr() as usize;
+            &original[start..start + value.len()]
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_toggle_slash_and_restore_case() {
+        assert_eq!(toggle_slash("/status/").as_deref(), Some("/status"));
+        assert_eq!(toggle_slash("/files").as_deref(), Some("/files/"));
+        assert_eq!(toggle_slash("/"), None);
+
+        let original = "/Jobs/AbC/Result";
+        let lowered = original.to_ascii_lowercase();
+        let values = vec![&lowered[6..9]];
+        assert_eq!(restore_case(original, &lowered, values), ["AbC"]);
+    }
+}
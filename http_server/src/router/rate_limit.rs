@@ -0,0 +1,254 @@
+//! # Rate Limiting por Ruta
+//! src/router/rate_limit.rs
+//!
+//! Limita cuántas requests por segundo puede hacer cada IP a una ruta.
+//! Los límites se configuran con patrones:
+//!
+//! - `/jobs/submit`: ruta exacta
+//! - `/jobs/*`: cualquier ruta bajo `/jobs/`
+//! - `*`: todas las rutas
+//!
+//! Si varios patrones coinciden gana el más específico (exacto, luego el
+//! prefijo más largo). Si ninguno coincide se usa el límite global
+//! (`--rate-limit`) como un `*` implícito. Un límite de 0 desactiva el
+//! límite para ese patrón, útil para excluir rutas de un comodín.
+//!
+//! Cada (patrón, IP) tiene un token bucket: admite ráfagas de hasta N
+//! requests y se recarga a N tokens por segundo.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Tiempo en que un bucket vacío se recarga por completo
+const REFILL_PERIOD: Duration = Duration::from_secs(1);
+
+/// Máximo de buckets guardados antes de purgar los que ya están llenos
+const MAX_TRACKED_BUCKETS: usize = 10_000;
+
+/// Límite de requests por segundo para un patrón de ruta
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RouteRateLimit {
+    pub pattern: String,
+    pub per_sec: u32,
+}
+
+impl RouteRateLimit {
+    /// Parsea `PATRON=N` (formato de `--rate-limit-route`)
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let (pattern, per_sec) = spec.split_once('=')
+            .ok_or_else(|| format!("Invalid route rate limit '{}': expected PATTERN=N", spec))?;
+        Self::from_parts(pattern.trim(), per_sec.trim())
+    }
+
+    /// Construye un límite a partir del patrón y el valor en texto
+    pub fn from_parts(pattern: &str, per_sec: &str) -> Result<Self, String> {
+        if pattern != "*" && !pattern.starts_with('/') {
+            return Err(format!("Invalid route pattern '{}': must start with / or be *", pattern));
+        }
+        if pattern.len() > 1 && pattern[..pattern.len() - 1].contains('*') {
+            return Err(format!("Invalid route pattern '{}': * is only allowed at the end", pattern));
+        }
+
+        let per_sec = per_sec.parse()
+            .map_err(|_| format!("Invalid rate limit for {}: {}", pattern, per_sec))?;
+
+        Ok(Self { pattern: pattern.to_string(), per_sec })
+    }
+
+    /// Especificidad del patrón si coincide con `path` (mayor = más específico)
+    fn specificity(&self, path: &str) -> Option<usize> {
+        match self.pattern.strip_suffix('*') {
+            Some(prefix) if path.starts_with(prefix) => Some(prefix.len()),
+            Some(_) => None,
+            None if self.pattern == path => Some(usize::MAX),
+            None => None,
+        }
+    }
+}
+
+/// Busca el límite más específico que aplica a `path`
+pub fn find_limit<'a>(limits: &'a [RouteRateLimit], path: &str) -> Option<&'a RouteRateLimit> {
+    limits.iter()
+        .filter_map(|limit| limit.specificity(path).map(|score| (score, limit)))
+        .max_by_key(|(score, _)| *score)
+        .map(|(_, limit)| limit)
+}
+
+/// Estado de un token bucket
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    /// Tokens disponibles (fraccionarios mientras se recargan)
+    tokens: f64,
+    /// Última vez que se recargó
+    refilled_at: Instant,
+}
+
+/// Middleware de rate limiting con un token bucket por (patrón, IP)
+///
+/// Cada bucket tiene capacidad `per_sec` (la ráfaga máxima) y se recarga a
+/// `per_sec` tokens por segundo; cada request consume uno. A diferencia de
+/// una ventana fija, no permite el doble de requests en el borde entre dos
+/// ventanas.
+pub struct RateLimiter {
+    /// (patrón, IP) -> bucket
+    buckets: Mutex<HashMap<(String, IpAddr), Bucket>>,
+}
+
+impl RateLimiter {
+    /// Crea un limitador vacío
+    pub fn new() -> Self {
+        Self {
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Registra una request de `ip` a `path`
+    ///
+    /// `default_per_sec` es el límite global, aplicado como `*` cuando
+    /// ningún patrón coincide. Retorna `Err(ms)` con el tiempo hasta que
+    /// haya un token disponible si el bucket está vacío.
+    pub fn check(
+        &self,
+        limits: &[RouteRateLimit],
+        default_per_sec: u32,
+        path: &str,
+        ip: IpAddr,
+    ) -> Result<(), u64> {
+        let fallback = RouteRateLimit { pattern: "*".to_string(), per_sec: default_per_sec };
+        let limit = find_limit(limits, path).unwrap_or(&fallback);
+        if limit.per_sec == 0 {
+            return Ok(());
+        }
+
+        let capacity = limit.per_sec as f64;
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().unwrap();
+
+        // Un bucket que lleva un período completo sin uso ya está lleno,
+        // así que descartarlo no cambia el resultado
+        if buckets.len() >= MAX_TRACKED_BUCKETS {
+            buckets.retain(|_, bucket| now.duration_since(bucket.refilled_at) < REFILL_PERIOD);
+        }
+
+        let bucket = buckets
+            .entry((limit.pattern.clone(), ip))
+            .or_insert(Bucket { tokens: capacity, refilled_at: now });
+
+        // Recargar según el tiempo transcurrido (el límite puede haber
+        // cambiado con una recarga en caliente)
+        let elapsed = now.duration_since(bucket.refilled_at).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * capacity).min(capacity);
+        bucket.refilled_at = now;
+
+        if bucket.tokens < 1.0 {
+            let missing_secs = (1.0 - bucket.tokens) / capacity;
+            return Err(((missing_secs * 1000.0).ceil() as u64).max(1));
+        }
+
+        bucket.tokens -= 1.0;
+        Ok(())
+    }
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limits(specs: &[&str]) -> Vec<RouteRateLimit> {
+        specs.iter().map(|s| RouteRateLimit::parse(s).unwrap()).collect()
+    }
+
+    #[test]
+    fn test_parse_route_rate_limit() {
+        let limit = RouteRateLimit::parse("/jobs/submit=10").unwrap();
+        assert_eq!(limit.pattern, "/jobs/submit");
+        assert_eq!(limit.per_sec, 10);
+
+        assert!(RouteRateLimit::parse("/jobs/submit").is_err());
+        assert!(RouteRateLimit::parse("jobs=10").is_err());
+        assert!(RouteRateLimit::parse("/jobs/*/x=10").is_err());
+        assert!(RouteRateLimit::parse("/jobs=lots").is_err());
+    }
+
+    #[test]
+    fn test_find_limit_most_specific() {
+        let limits = limits(&["*=100", "/jobs/*=20", "/jobs/submit=5"]);
+
+        assert_eq!(find_limit(&limits, "/jobs/submit").unwrap().per_sec, 5);
+        assert_eq!(find_limit(&limits, "/jobs/status").unwrap().per_sec, 20);
+        assert_eq!(find_limit(&limits, "/fibonacci").unwrap().per_sec, 100);
+        assert!(find_limit(&limits[1..], "/fibonacci").is_none());
+    }
+
+    #[test]
+    fn test_rate_limiter_blocks_after_limit() {
+        let limiter = RateLimiter::new();
+        let limits = limits(&["/jobs/submit=2"]);
+        let ip: IpAddr = "10.0.0.1".parse().unwrap();
+        let other: IpAddr = "10.0.0.2".parse().unwrap();
+
+        assert!(limiter.check(&limits, 0, "/jobs/submit", ip).is_ok());
+        assert!(limiter.check(&limits, 0, "/jobs/submit", ip).is_ok());
+
+        let retry_ms = limiter.check(&limits, 0, "/jobs/submit", ip).unwrap_err();
+        assert!(retry_ms > 0 && retry_ms <= 1000);
+
+        // Otras IPs y rutas sin límite no se ven afectadas
+        assert!(limiter.check(&limits, 0, "/jobs/submit", other).is_ok());
+        assert!(limiter.check(&limits, 0, "/status", ip).is_ok());
+    }
+
+    #[test]
+    fn test_token_bucket_refills_over_time() {
+        let limiter = RateLimiter::new();
+        let limits = limits(&["/status=20"]);
+        let ip: IpAddr = "10.0.0.1".parse().unwrap();
+
+        // Ráfaga completa y luego vacío: un token cada 50 ms
+        for _ in 0..20 {
+            assert!(limiter.check(&limits, 0, "/status", ip).is_ok());
+        }
+        let retry_ms = limiter.check(&limits, 0, "/status", ip).unwrap_err();
+        assert!(retry_ms > 0 && retry_ms <= 50);
+
+        std::thread::sleep(Duration::from_millis(120));
+        assert!(limiter.check(&limits, 0, "/status", ip).is_ok());
+        assert!(limiter.check(&limits, 0, "/status", ip).is_ok());
+    }
+
+    #[test]
+    fn test_rate_limiter_zero_disables() {
+        let limiter = RateLimiter::new();
+        let limits = limits(&["*=1", "/status=0"]);
+        let ip: IpAddr = "10.0.0.1".parse().unwrap();
+
+        for _ in 0..5 {
+            assert!(limiter.check(&limits, 0, "/status", ip).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_rate_limiter_global_fallback() {
+        let limiter = RateLimiter::new();
+        let limits = limits(&["/status=0"]);
+        let ip: IpAddr = "10.0.0.1".parse().unwrap();
+
+        // Sin patrón que coincida se aplica el límite global
+        assert!(limiter.check(&limits, 1, "/fibonacci", ip).is_ok());
+        assert!(limiter.check(&limits, 1, "/fibonacci", ip).is_err());
+
+        // Un patrón explícito gana sobre el global
+        for _ in 0..3 {
+            assert!(limiter.check(&limits, 1, "/status", ip).is_ok());
+        }
+    }
+}
@@ -0,0 +1,299 @@
+//! # Tabla de Rutas
+//! src/router/table.rs
+//!
+//! Buscar la ruta no recorre la lista de rutas registradas:
+//!
+//! - Los paths fijos (`/fibonacci`) van a un `HashMap`: una búsqueda por
+//!   request, sin importar cuántas rutas haya.
+//! - Los paths con segmentos variables (`/jobs/:id/result`) van a un árbol
+//!   por segmentos: la búsqueda cuesta lo que el path tiene de segmentos.
+//!   Un último segmento `*nombre` toma todo el resto del path, con sus `/`
+//!   (`/files/*path` coincide con `/files/a/b.txt`, `path` = `a/b.txt`).
+//! - Los patrones regex (`Router::register_regex`) se prueban en orden de
+//!   registro, después del `HashMap` y del árbol; son para lo que los
+//!   segmentos no expresan (`/users/(?P<id>[0-9]+)`).
+//!
+//! Un path fijo gana sobre uno variable que también coincida
+//! (`/jobs/list` antes que `/jobs/:id`), y en cada nivel del árbol se
+//! prueba primero el segmento fijo, después el variable y por último el
+//! resto del path. Las rutas de un mismo path conservan el orden de
+//! registro (la primera que acepta el método atiende).
+
+use super::docs::RouteDoc;
+use super::group::Middleware;
+use super::Handler;
+use crate::http::request::Method;
+use regex::Regex;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Una ruta registrada para un path
+pub(super) struct Route {
+    /// Path o patrón tal como se registró (`/jobs/:id`)
+    pub path: String,
+
+    /// Método que atiende (`None`: cualquiera)
+    pub method: Option<Method>,
+
+    /// Nombres de los segmentos variables, en orden
+    pub params: Vec<String>,
+
+    pub handler: Handler,
+
+    /// Descripción y parámetros para `/help` y `/routes.json`
+    pub doc: Option<RouteDoc>,
+
+    /// Middlewares que corren antes del handler (los del grupo primero)
+    pub middleware: Vec<Middleware>,
+
+    /// Tiempo máximo del handler (`None`: sin límite)
+    pub timeout: Option<Duration>,
+
+    /// La atiende el servidor antes de rutear (`Router::register_external`)
+    pub external: bool,
+}
+
+impl Route {
+    pub(super) fn new(path: &str, method: Option<Method>, params: Vec<String>, handler: Handler) -> Self {
+        Self { path: path.to_string(), method, params, handler, doc: None, middleware: Vec::new(), timeout: None, external: false }
+    }
+}
+
+/// Nodo del árbol de rutas variables (un segmento del path)
+#[derive(Default)]
+struct Node {
+    statics: HashMap<String, Node>,
+    param: Option<Box<Node>>,
+    rest: Option<Box<Node>>,
+    routes: Vec<Route>,
+}
+
+impl Node {
+    /// Nodo con rutas para `path` (sin la `/` inicial; `None` si ya no
+    /// quedan segmentos); anota los valores variables en `values`
+    fn find<'a, 'p>(&'a self, path: Option<&'p str>, values: &mut Vec<&'p str>) -> Option<&'a Node> {
+        let Some(path) = path else {
+            return (!self.routes.is_empty()).then_some(self);
+        };
+        let (segment, rest) = match path.split_once('/') {
+            Some((segment, rest)) => (segment, Some(rest)),
+            None => (path, None),
+        };
+        if let Some(found) = self.statics.get(segment).and_then(|child| child.find(rest, values)) {
+            return Some(found);
+        }
+        if let Some(child) = self.param.as_ref().filter(|_| !segment.is_empty()) {
+            values.push(segment);
+            if let Some(found) = child.find(rest, values) {
+                return Some(found);
+            }
+            values.pop();
+        }
+        let child = self.rest.as_ref().filter(|_| !path.is_empty())?;
+        values.push(path);
+        Some(child)
+    }
+
+    /// Agrega a `routes` las rutas de este nodo y de sus hijos
+    fn collect<'a>(&'a self, routes: &mut Vec<&'a Route>) {
+        routes.extend(&self.routes);
+        for child in self.statics.values().chain(self.param.as_deref()).chain(self.rest.as_deref()) {
+            child.collect(routes);
+        }
+    }
+
+    /// Como `collect`, para modificar las rutas
+    fn collect_mut<'a>(&'a mut self, routes: &mut Vec<&'a mut Route>) {
+        routes.extend(&mut self.routes);
+        for child in self.statics.values_mut().chain(self.param.as_deref_mut()).chain(self.rest.as_deref_mut()) {
+            child.collect_mut(routes);
+        }
+    }
+}
+
+/// Rutas indexadas por path
+#[derive(Default)]
+pub(super) struct RouteTable {
+    exact: HashMap<String, Vec<Route>>,
+    tree: Node,
+    patterns: Vec<(Regex, Vec<Route>)>,
+    len: usize,
+}
+
+impl RouteTable {
+    /// Agrega una ruta; los segmentos `:nombre` son variables y un último
+    /// `*nombre` toma el resto del path
+    pub fn insert(&mut self, path: &str, method: Option<Method>, handler: Handler) -> &mut Route {
+        self.len += 1;
+        let segments: Vec<&str> = path.split('/').skip(1).collect();
+        if !segments.iter().any(|segment| segment.starts_with(':') || segment.starts_with('*')) {
+            let routes = self.exact.entry(path.to_string()).or_default();
+            routes.push(Route::new(path, method, Vec::new(), handler));
+            return routes.last_mut().unwrap();
+        }
+
+        let mut node = &mut self.tree;
+        let mut params = Vec::new();
+        let last = segments.len() - 1;
+        for (i, segment) in segments.into_iter().enumerate() {
+            node = if let Some(name) = segment.strip_prefix(':') {
+                params.push(name.to_string());
+                node.param.get_or_insert_with(Box::default)
+            } else if let Some(name) = segment.strip_prefix('*') {
+                assert!(i == last, "{}: *{} must be the last segment", path, name);
+                params.push(name.to_string());
+                node.rest.get_or_insert_with(Box::default)
+            } else {
+                node.statics.entry(segment.to_string()).or_default()
+            };
+        }
+        node.routes.push(Route::new(path, method, params, handler));
+        node.routes.last_mut().unwrap()
+    }
+
+    /// Agrega una ruta que coincide con el path completo por `pattern`
+    ///
+    /// Los grupos de captura son los parámetros: los nombrados
+    /// (`(?P<id>...)`) con su nombre, los demás con su número (`"1"`).
+    /// Entra en pánico si `pattern` no es una regex válida.
+    pub fn insert_regex(&mut self, pattern: &str, method: Option<Method>, handler: Handler) -> &mut Route {
+        self.len += 1;
+        let anchored = format!("^(?:{})$", pattern);
+        let i = match self.patterns.iter().position(|(regex, _)| regex.as_str() == anchored) {
+            Some(i) => i,
+            None => {
+                let regex = Regex::new(&anchored)
+                    .unwrap_or_else(|e| panic!("invalid route pattern {}: {}", pattern, e));
+                self.patterns.push((regex, Vec::new()));
+                self.patterns.len() - 1
+            }
+        };
+        let (regex, routes) = &mut self.patterns[i];
+        let params = regex.capture_names()
+            .enumerate()
+            .skip(1)
+            .map(|(i, name)| name.map_or_else(|| i.to_string(), str::to_string))
+            .collect();
+        routes.push(Route::new(pattern, method, params, handler));
+        routes.last_mut().unwrap()
+    }
+
+    /// Rutas registradas para `path`: fijas, variables o por regex (un
+    /// grupo que no participó del match queda como `""`)
+    pub fn lookup<'a>(&self, path: &'a str) -> Option<(&[Route], Vec<&'a str>)> {
+        if let Some(routes) = self.exact.get(path) {
+            return Some((routes, Vec::new()));
+        }
+        let mut values = Vec::new();
+        if let Some(node) = self.tree.find(path.strip_prefix('/'), &mut values) {
+            return Some((&node.routes, values));
+        }
+        self.patterns.iter().find_map(|(regex, routes)| {
+            let captures = regex.captures(path)?;
+            let values = captures.iter().skip(1)
+                .map(|value| value.map_or("", |value| value.as_str()))
+                .collect();
+            Some((routes.as_slice(), values))
+        })
+    }
+
+    /// Todas las rutas, ordenadas por path (las de un mismo path, en orden
+    /// de registro)
+    pub fn routes(&self) -> Vec<&Route> {
+        let mut routes: Vec<&Route> = self.exact.values().flatten().collect();
+        self.tree.collect(&mut routes);
+        routes.extend(self.patterns.iter().flat_map(|(_, routes)| routes));
+        routes.sort_by(|a, b| a.path.cmp(&b.path));
+        routes
+    }
+
+    /// Rutas registradas con el path o patrón `path`, para modificarlas
+    pub fn registered_mut(&mut self, path: &str) -> Vec<&mut Route> {
+        let mut routes: Vec<&mut Route> = self.exact.values_mut().flatten().collect();
+        self.tree.collect_mut(&mut routes);
+        routes.extend(self.patterns.iter_mut().flat_map(|(_, routes)| routes));
+        routes.retain(|route| route.path == path);
+        routes
+    }
+
+    /// Cantidad de rutas registradas
+    pub fn len(&self) -> usize {
+        self.len
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::Response;
+    use std::sync::Arc;
+
+    fn handler() -> Handler {
+        Arc::new(|_req| Response::text("ok"))
+    }
+
+    #[test]
+    fn test_lookup_prefers_exact_and_static_segments() {
+        let mut table = RouteTable::default();
+        table.insert("/jobs/:id", None, handler());
+        table.insert("/jobs/:id/result", Some(Method::GET), handler());
+        table.insert("/jobs/list", None, handler());
+        table.insert("/files/:dir/latest", None, handler());
+        table.insert("/files/:dir/:name", None, handler());
+        assert_eq!(table.len(), 5);
+
+        let (routes, values) = table.lookup("/jobs/list").unwrap();
+        assert!(routes[0].params.is_empty() && values.is_empty());
+
+        let (routes, values) = table.lookup("/jobs/abc/result").unwrap();
+        assert_eq!(routes[0].params, ["id"]);
+        assert_eq!(values, ["abc"]);
+
+        assert_eq!(table.lookup("/files/docs/latest").unwrap().1, ["docs"]);
+        // Si el fijo no lleva a una ruta, se vuelve a probar el variable
+        assert_eq!(table.lookup("/files/docs/a.txt").unwrap().1, ["docs", "a.txt"]);
+
+        table.insert("/static/*path", None, handler());
+        assert_eq!(table.lookup("/static/css/site.css").unwrap().1, ["css/site.css"]);
+        assert_eq!(table.lookup("/static/docs/").unwrap().1, ["docs/"]);
+        assert!(table.lookup("/static/").is_none());
+
+        assert!(table.lookup("/jobs").is_none());
+        assert!(table.lookup("/jobs/").is_none());
+        assert!(table.lookup("/jobs/abc/other").is_none());
+
+        let paths: Vec<&str> = table.routes().iter().map(|route| route.path.as_str()).collect();
+        assert_eq!(paths, ["/files/:dir/:name", "/files/:dir/latest", "/jobs/:id", "/jobs/:id/result", "/jobs/list", "/static/*path"]);
+        assert_eq!(table.registered_mut("/jobs/:id").len(), 1);
+        assert!(table.registered_mut("/jobs/abc").is_empty());
+    }
+
+    #[test]
+    fn test_regex_patterns_after_segments() {
+        let mut table = RouteTable::default();
+        table.insert("/users/me", None, handler());
+        table.insert_regex(r"/users/(?P<id>[0-9]+)", Some(Method::GET), handler());
+        table.insert_regex(r"/users/(?P<id>[0-9]+)", Some(Method::POST), handler());
+        table.insert_regex(r"/v([12])/(\w+)(\.json)?", None, handler());
+        assert_eq!(table.len(), 4);
+
+        let (routes, values) = table.lookup("/users/42").unwrap();
+        assert_eq!(routes.len(), 2);
+        assert_eq!(routes[0].params, ["id"]);
+        assert_eq!(values, ["42"]);
+        assert!(table.lookup("/users/me").unwrap().1.is_empty());
+        // Coincide con el path completo, no con una parte
+        assert!(table.lookup("/users/abc").is_none());
+        assert!(table.lookup("/users/42/x").is_none());
+
+        let (routes, values) = table.lookup("/v2/items").unwrap();
+        assert_eq!(routes[0].params, ["1", "2", "3"]);
+        assert_eq!(values, ["2", "items", ""]);
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid route pattern")]
+    fn test_invalid_regex_panics() {
+        RouteTable::default().insert_regex("/users/(", None, handler());
+    }
+}
@@ -0,0 +1,192 @@
+//! # Timeouts por Ruta
+//! src/router/timeout.rs
+//!
+//! Una ruta puede tener un tiempo máximo para su handler, al registrarla
+//! (`.timeout(...)`) o por configuración (`--route-timeouts`):
+//!
+//! ```text
+//! --route-timeouts "/fibonacci=2000,/sortfile=60000"
+//!
+//! GET /fibonacci?num=90   (el handler tarda más de 2 s)
+//! 503 {"error": "Route /fibonacci timed out after 2000 ms", "code": "timeout", ...}
+//! ```
+//!
+//! - El handler corre en el pool `route-timeout` (`--route-timeout-workers`
+//!   workers, con una cola del mismo tamaño) y el thread de la conexión
+//!   espera su respuesta hasta el timeout. Los middlewares corren antes y no
+//!   cuentan.
+//! - Al vencer, el cliente recibe 503 (por el `ErrorHandler` del router) y
+//!   se publica `Event::RouteTimedOut`, que las métricas cuentan por ruta
+//!   (`requests.timeouts` en `/metrics`). Un handler que todavía no empezó
+//!   se descarta.
+//! - Un thread no se puede cancelar: un handler que ya empezó sigue hasta
+//!   terminar y su respuesta se descarta. El pool acota cuántos pueden
+//!   quedar corriendo así; con el pool y su cola llenos el request recibe
+//!   503 sin ejecutarse (`QueueSaturated` para `route-timeout`). Para
+//!   cortar el trabajo están los jobs asíncronos (`/jobs/*`).
+//! - Un panic del handler se propaga igual que sin timeout (500).
+
+use super::docs::RouteEntry;
+use super::Handler;
+use crate::http::{Request, Response};
+use crate::workers::pool::{panic_message, ThreadPool};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Nombre del pool que ejecuta los handlers con timeout
+pub const POOL_NAME: &str = "route-timeout";
+
+/// Workers del pool si no se configuran (`--route-timeout-workers`)
+pub const DEFAULT_WORKERS: usize = 4;
+
+/// Resultado de un handler con timeout
+pub(super) enum Outcome {
+    Done(Response),
+
+    /// No respondió dentro del timeout
+    TimedOut,
+
+    /// El pool y su cola están llenos: el handler no se ejecutó
+    Busy,
+}
+
+impl RouteEntry<'_> {
+    /// Tiempo máximo del handler de esta ruta
+    ///
+    /// # Ejemplo
+    /// ```
+    /// use http_server::router::Router;
+    /// use http_server::http::{Request, Response, StatusCode};
+    /// use std::time::Duration;
+    ///
+    /// let mut router = Router::new();
+    /// router.register("/slow", |_req: &Request| {
+    ///     std::thread::sleep(Duration::from_millis(200));
+    ///     Response::text("tarde")
+    /// })
+    /// .timeout(Duration::from_millis(20));
+    ///
+    /// let request = Request::parse(b"GET /slow HTTP/1.0\r\n\r\n").unwrap();
+    /// assert_eq!(router.route(&request).status(), StatusCode::ServiceUnavailable);
+    /// ```
+    pub fn timeout(self, timeout: Duration) -> Self {
+        self.route.timeout = Some(timeout);
+        self
+    }
+}
+
+/// Parsea `PATH=MS` separados por comas (formato de `--route-timeouts`)
+///
+/// # Ejemplo
+/// ```
+/// use http_server::router::timeout::parse_route_timeouts;
+/// use std::time::Duration;
+///
+/// let timeouts = parse_route_timeouts("/fibonacci=2000, /sortfile=60000").unwrap();
+/// assert_eq!(timeouts[0], ("/fibonacci".to_string(), Duration::from_secs(2)));
+/// assert!(parse_route_timeouts("").unwrap().is_empty());
+/// assert!(parse_route_timeouts("/fibonacci=0").is_err());
+/// ```
+pub fn parse_route_timeouts(spec: &str) -> Result<Vec<(String, Duration)>, String> {
+    spec.split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(|item| {
+            let (path, ms) = item.split_once('=')
+                .ok_or_else(|| format!("Invalid route timeout '{}': expected PATH=MS", item))?;
+            let path = path.trim();
+            if !path.starts_with('/') {
+                return Err(format!("Invalid route timeout path '{}': must start with /", path));
+            }
+            match ms.trim().parse::<u64>() {
+                Ok(ms) if ms > 0 => Ok((path.to_string(), Duration::from_millis(ms))),
+                _ => Err(format!("Invalid route timeout for {}: {} (expected milliseconds > 0)", path, ms.trim())),
+            }
+        })
+        .collect()
+}
+
+/// Pool acotado para los handlers con timeout
+pub(super) fn pool(workers: usize) -> ThreadPool {
+    ThreadPool::new(POOL_NAME, workers.max(1), workers.max(1))
+}
+
+/// Ejecuta `handler` en `pool` y espera su respuesta hasta `timeout`
+pub(super) fn call(pool: &ThreadPool, handler: &Handler, request: &Request, timeout: Duration) -> Outcome {
+    let (sender, receiver) = mpsc::sync_channel(1);
+    let abandoned = Arc::new(AtomicBool::new(false));
+    let submitted = pool.try_execute({
+        let handler = Handler::clone(handler);
+        let request = request.clone();
+        let abandoned = Arc::clone(&abandoned);
+        move || {
+            // Venció mientras esperaba en la cola: ya nadie espera la respuesta
+            if abandoned.load(Ordering::SeqCst) {
+                return;
+            }
+            let outcome = panic::catch_unwind(AssertUnwindSafe(|| handler(&request)))
+                .map_err(|payload| panic_message(payload.as_ref()));
+            // El receptor ya no existe si venció el timeout
+            let _ = sender.send(outcome);
+        }
+    });
+    if submitted.is_err() {
+        return Outcome::Busy;
+    }
+
+    match receiver.recv_timeout(timeout) {
+        Ok(Ok(response)) => Outcome::Done(response),
+        // El panic se relanza en el thread del request, como sin timeout
+        Ok(Err(message)) => panic::resume_unwind(Box::new(message)),
+        Err(RecvTimeoutError::Timeout) => {
+            abandoned.store(true, Ordering::SeqCst);
+            Outcome::TimedOut
+        }
+        // El pool se apagó sin ejecutar la tarea
+        Err(RecvTimeoutError::Disconnected) => Outcome::Busy,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn test_call_waits_up_to_the_timeout_on_a_bounded_pool() {
+        let pool = pool(1);
+        let request = Request::parse(b"GET /x HTTP/1.0\r\n\r\n").unwrap();
+        let fast: Handler = Arc::new(|_req| Response::text("ok"));
+        assert!(matches!(call(&pool, &fast, &request, Duration::from_secs(5)), Outcome::Done(r) if r.body() == b"ok"));
+
+        let slow: Handler = Arc::new(|_req| {
+            thread::sleep(Duration::from_millis(300));
+            Response::text("tarde")
+        });
+        assert!(matches!(call(&pool, &slow, &request, Duration::from_millis(20)), Outcome::TimedOut));
+        // El primero sigue corriendo en el único worker y el segundo ocupa la
+        // cola: el tercero ya no entra
+        assert!(matches!(call(&pool, &slow, &request, Duration::from_millis(20)), Outcome::TimedOut));
+        assert!(matches!(call(&pool, &fast, &request, Duration::from_millis(20)), Outcome::Busy));
+
+        let pool = super::pool(1);
+        let panics: Handler = Arc::new(|_req| panic!("boom"));
+        let caught = panic::catch_unwind(AssertUnwindSafe(|| call(&pool, &panics, &request, Duration::from_secs(5))));
+        let Err(payload) = caught else { panic!("the panic must reach the caller") };
+        assert_eq!(panic_message(payload.as_ref()), "boom");
+    }
+
+    #[test]
+    fn test_parse_route_timeouts() {
+        assert_eq!(
+            parse_route_timeouts("/a=1,/b = 2").unwrap(),
+            [("/a".to_string(), Duration::from_millis(1)), ("/b".to_string(), Duration::from_millis(2))]
+        );
+        assert!(parse_route_timeouts("/a").unwrap_err().contains("PATH=MS"));
+        assert!(parse_route_timeouts("a=1").unwrap_err().contains("must start with /"));
+        assert!(parse_route_timeouts("/a=soon").unwrap_err().contains("milliseconds"));
+    }
+}
@@ -0,0 +1,279 @@
+//! # Access Log
+//! src/server/access_log.rs
+//!
+//! Una línea por request en Common o Combined Log Format, con la latencia
+//! y el request id agregados al final:
+//!
+//! ```text
+//! common:   127.0.0.1 - - [06/Nov/1994:08:49:37 +0000] "GET /status HTTP/1.0" 200 83 1.204 3f2a9c1e0b7d4a55
+//! combined: 127.0.0.1 - - [06/Nov/1994:08:49:37 +0000] "GET /status HTTP/1.0" 200 83 "-" "curl/8.5.0" 1.204 3f2a9c1e0b7d4a55
+//! ```
+//!
+//! La latencia va en milisegundos. Los requests que se rechazan antes de
+//! parsearse (400, 413, 431...) se registran con `"-"` como request line.
+//!
+//! Cuando el archivo supera `--access-log-max-bytes` se rota:
+//! `access.log` → `access.log.1` → ... → `access.log.N` (se conservan
+//! `--access-log-keep` archivos y el más viejo se borra).
+
+use crate::config::Config;
+use crate::http::{date, Request};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
+/// Formatos de línea disponibles
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LogFormat {
+    /// `host ident authuser [fecha] "request" status bytes`
+    Common,
+
+    /// Common más `"referer" "user-agent"`
+    #[default]
+    Combined,
+}
+
+impl LogFormat {
+    /// Parsea el valor de `--access-log-format`
+    ///
+    /// # Ejemplo
+    /// ```
+    /// use http_server::server::access_log::LogFormat;
+    ///
+    /// assert_eq!(LogFormat::parse("Common"), Ok(LogFormat::Common));
+    /// assert!(LogFormat::parse("json").is_err());
+    /// ```
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.to_lowercase().as_str() {
+            "common" => Ok(LogFormat::Common),
+            "combined" => Ok(LogFormat::Combined),
+            other => Err(format!("Invalid access log format: {} (use common or combined)", other)),
+        }
+    }
+}
+
+/// Datos de un request para su línea en el access log
+#[derive(Debug, Clone)]
+pub struct AccessEntry {
+    pub remote: Option<IpAddr>,
+    pub time: SystemTime,
+
+    /// `MÉTODO target versión`, o `None` si el request no llegó a parsearse
+    pub request_line: Option<String>,
+    pub referer: Option<String>,
+    pub user_agent: Option<String>,
+    pub status: u16,
+
+    /// Bytes del body enviados
+    pub bytes: u64,
+    pub latency: Duration,
+    pub request_id: String,
+}
+
+impl AccessEntry {
+    /// Entrada para un request que todavía no se leyó
+    pub fn new(remote: Option<IpAddr>, request_id: &str) -> Self {
+        Self {
+            remote,
+            time: SystemTime::now(),
+            request_line: None,
+            referer: None,
+            user_agent: None,
+            status: 0,
+            bytes: 0,
+            latency: Duration::ZERO,
+            request_id: request_id.to_string(),
+        }
+    }
+
+    /// Toma la request line y los headers que se registran
+    pub fn set_request(&mut self, request: &Request) {
+        self.request_line = Some(format!("{} {} {}", request.method().as_str(), request.target(), request.version()));
+        self.referer = request.header("Referer").map(str::to_string);
+        self.user_agent = request.header("User-Agent").map(str::to_string);
+    }
+
+    /// Línea del access log (sin el salto de línea)
+    ///
+    /// # Ejemplo
+    /// ```
+    /// use http_server::server::access_log::{AccessEntry, LogFormat};
+    /// use std::time::{Duration, UNIX_EPOCH};
+    ///
+    /// let mut entry = AccessEntry::new(Some("10.0.0.7".parse().unwrap()), "abc123");
+    /// entry.time = UNIX_EPOCH + Duration::from_secs(784111777);
+    /// entry.request_line = Some("GET /help HTTP/1.0".to_string());
+    /// entry.status = 200;
+    /// entry.bytes = 512;
+    /// entry.latency = Duration::from_micros(1500);
+    /// assert_eq!(
+    ///     entry.format(LogFormat::Common),
+    ///     "10.0.0.7 - - [06/Nov/1994:08:49:37 +0000] \"GET /help HTTP/1.0\" 200 512 1.500 abc123"
+    /// );
+    /// ```
+    pub fn format(&self, format: LogFormat) -> String {
+        let remote = self.remote.map(|ip| ip.to_string()).unwrap_or_else(|| "-".to_string());
+        let mut line = format!(
+            "{} - - [{}] \"{}\" {} {}",
+            remote,
+            date::format_clf(self.time),
+            escape(self.request_line.as_deref()),
+            self.status,
+            self.bytes,
+        );
+        if format == LogFormat::Combined {
+            line.push_str(&format!(
+                " \"{}\" \"{}\"",
+                escape(self.referer.as_deref()),
+                escape(self.user_agent.as_deref())
+            ));
+        }
+        line.push_str(&format!(" {:.3} {}", self.latency.as_secs_f64() * 1000.0, self.request_id));
+        line
+    }
+}
+
+/// Escapa comillas, barras y caracteres de control de un campo entre comillas
+fn escape(value: Option<&str>) -> String {
+    let Some(value) = value.filter(|v| !v.is_empty()) else {
+        return "-".to_string();
+    };
+    value.chars().fold(String::with_capacity(value.len()), |mut out, c| {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if c.is_control() => out.push_str(&format!("\\x{:02x}", c as u32)),
+            c => out.push(c),
+        }
+        out
+    })
+}
+
+/// Archivo abierto y bytes que ya tiene
+struct LogFile {
+    file: File,
+    size: u64,
+}
+
+/// Access log con rotación por tamaño
+pub struct AccessLog {
+    path: PathBuf,
+    format: LogFormat,
+
+    /// Tamaño a partir del cual se rota (0 = nunca)
+    max_bytes: u64,
+
+    /// Archivos rotados que se conservan
+    keep: usize,
+    file: Mutex<LogFile>,
+}
+
+impl AccessLog {
+    /// Abre (o crea) el archivo en modo append
+    pub fn open(path: impl AsRef<Path>, format: LogFormat, max_bytes: u64, keep: usize) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let file = Self::open_file(&path)?;
+        Ok(Self { path, format, max_bytes, keep, file: Mutex::new(file) })
+    }
+
+    /// Access log configurado con `--access-log` (`None` si está deshabilitado)
+    pub fn from_config(config: &Config) -> io::Result<Option<Self>> {
+        let Some(path) = &config.access_log else {
+            return Ok(None);
+        };
+        let format = LogFormat::parse(&config.access_log_format).map_err(io::Error::other)?;
+        Self::open(path, format, config.access_log_max_bytes, config.access_log_keep).map(Some)
+    }
+
+    /// Ruta del archivo activo
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Escribe la línea de `entry`, rotando antes si el archivo se pasaría del máximo
+    pub fn log(&self, entry: &AccessEntry) -> io::Result<()> {
+        let mut line = entry.format(self.format);
+        line.push('\n');
+
+        let mut current = self.file.lock().unwrap();
+        if self.max_bytes > 0 && current.size > 0 && current.size + line.len() as u64 > self.max_bytes {
+            self.rotate()?;
+            *current = Self::open_file(&self.path)?;
+        }
+        current.file.write_all(line.as_bytes())?;
+        current.size += line.len() as u64;
+        Ok(())
+    }
+
+    /// Corre `path.N-1` → `path.N` ... `path` → `path.1`, descartando el más viejo
+    fn rotate(&self) -> io::Result<()> {
+        let rotated = |n: usize| PathBuf::from(format!("{}.{}", self.path.display(), n));
+        if self.keep == 0 {
+            return fs::remove_file(&self.path);
+        }
+        for n in (1..self.keep).rev() {
+            if rotated(n).exists() {
+                fs::rename(rotated(n), rotated(n + 1))?;
+            }
+        }
+        fs::rename(&self.path, rotated(1))
+    }
+
+    fn open_file(path: &Path) -> io::Result<LogFile> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let size = file.metadata()?.len();
+        Ok(LogFile { file, size })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("access-log-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        dir.join("access.log")
+    }
+
+    #[test]
+    fn test_combined_format_escapes_fields() {
+        let request = Request::parse(b"GET /reverse?text=a HTTP/1.0\r\nUser-Agent: evil \"agent\"\r\n\r\n").unwrap();
+        let mut entry = AccessEntry::new(None, "id");
+        entry.set_request(&request);
+        entry.status = 404;
+
+        let line = entry.format(LogFormat::Combined);
+        assert!(line.starts_with("- - - ["), "{}", line);
+        assert!(line.contains("\"GET /reverse?text=a HTTP/1.0\" 404 0 \"-\" \"evil \\\"agent\\\"\" 0.000 id"), "{}", line);
+
+        // Sin request parseado
+        assert!(AccessEntry::new(None, "id").format(LogFormat::Common).contains("] \"-\" 0 0 "));
+    }
+
+    #[test]
+    fn test_rotates_by_size_and_keeps_n_files() {
+        let path = temp_path("rotate");
+        let entry = AccessEntry::new(None, "0123456789abcdef");
+        let line_len = entry.format(LogFormat::Common).len() as u64 + 1;
+        let log = AccessLog::open(&path, LogFormat::Common, 2 * line_len, 2).unwrap();
+
+        // Cada archivo admite dos líneas; 7 líneas → activo + .1 + .2 (la más vieja se descarta)
+        for _ in 0..7 {
+            log.log(&entry).unwrap();
+        }
+        let size = |p: PathBuf| fs::metadata(p).map(|m| m.len()).unwrap_or(0);
+        assert_eq!(size(path.clone()), line_len);
+        assert_eq!(size(PathBuf::from(format!("{}.1", path.display()))), 2 * line_len);
+        assert_eq!(size(PathBuf::from(format!("{}.2", path.display()))), 2 * line_len);
+        assert!(!PathBuf::from(format!("{}.3", path.display())).exists());
+
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+}
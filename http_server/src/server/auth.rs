@@ -0,0 +1,324 @@
+//! # Autenticación de Endpoints de Administración
+//! src/server/auth.rs
+//!
+//! Protege las rutas administrativas (`/config`, `/config/reload`,
+//! `/workers`, `/admin/*`) con HTTP Basic usando las credenciales de
+//! `--admin-user`/`--admin-password`, o con `Authorization: Bearer` y el
+//! token de `--admin-token` (pensado para scripts). Si no hay credenciales
+//! configuradas las rutas quedan abiertas, como antes, salvo
+//! `/admin/shutdown`, que sin credenciales se rechaza.
+//!
+//! - La comparación de usuario y contraseña es de tiempo constante, para no
+//!   filtrar por timing cuántos bytes coinciden.
+//! - Cada IP tiene un contador de fallos seguidos; al llegar a
+//!   `--admin-max-failures` queda bloqueada `--admin-lockout-secs` segundos
+//!   (429 con `Retry-After`), aunque después envíe credenciales correctas.
+
+use crate::config::Config;
+use crate::http::{base64, Request, Response, StatusCode};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Rutas que requieren credenciales de administración
+pub const ADMIN_PATHS: &[&str] = &["/config", "/config/reload", "/workers", "/admin/state", "/admin/shutdown"];
+
+/// Realm anunciado en `WWW-Authenticate`
+const REALM: &str = "RedUnix admin";
+
+/// Máximo de IPs con fallos guardadas antes de purgar las que no están bloqueadas
+const MAX_TRACKED_IPS: usize = 10_000;
+
+/// Fallos seguidos de una IP y, si corresponde, hasta cuándo está bloqueada
+#[derive(Debug, Default)]
+struct FailureRecord {
+    failures: u32,
+    locked_until: Option<Instant>,
+}
+
+/// Contadores de autenticación expuestos en `/metrics`
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AuthStats {
+    pub enabled: bool,
+    pub failures: u64,
+    pub lockouts: u64,
+    pub locked_ips: usize,
+}
+
+/// Verificador de credenciales con bloqueo por IP
+pub struct AdminAuth {
+    records: Mutex<HashMap<IpAddr, FailureRecord>>,
+    totals: Mutex<(u64, u64)>,
+}
+
+impl AdminAuth {
+    /// Crea un verificador sin fallos registrados
+    pub fn new() -> Self {
+        Self {
+            records: Mutex::new(HashMap::new()),
+            totals: Mutex::new((0, 0)),
+        }
+    }
+
+    /// Indica si `path` es una ruta de administración
+    pub fn is_admin_path(path: &str) -> bool {
+        ADMIN_PATHS.contains(&path)
+    }
+
+    /// Indica si hay credenciales configuradas (usuario y contraseña, o token)
+    pub fn is_enabled(config: &Config) -> bool {
+        (config.admin_user.is_some() && config.admin_password.is_some()) || config.admin_token.is_some()
+    }
+
+    /// Verifica las credenciales de `request` enviado desde `ip`
+    ///
+    /// Retorna `Err` con la respuesta a enviar (401 o 429) si el acceso se
+    /// rechaza. Sin credenciales configuradas siempre permite el acceso.
+    pub fn check(&self, request: &Request, ip: IpAddr, config: &Config) -> Result<(), Response> {
+        if !Self::is_enabled(config) {
+            return Ok(());
+        }
+        let basic = match (&config.admin_user, &config.admin_password) {
+            (Some(user), Some(password)) => Some((user, password)),
+            _ => None,
+        };
+
+        let now = Instant::now();
+        let mut records = self.records.lock().unwrap();
+
+        if let Some(until) = records.get(&ip).and_then(|r| r.locked_until) {
+            if until > now {
+                return Err(Self::locked_response(until - now));
+            }
+            records.remove(&ip);
+        }
+
+        let header = request.header("Authorization");
+        let basic_ok = header.and_then(parse_basic)
+            .zip(basic)
+            .map(|((given_user, given_password), (user, password))| {
+                // Ambas comparaciones se evalúan siempre (`&`, no `&&`)
+                constant_time_eq(given_user.as_bytes(), user.as_bytes())
+                    & constant_time_eq(given_password.as_bytes(), password.as_bytes())
+            })
+            .unwrap_or(false);
+        let token_ok = header.and_then(parse_bearer)
+            .zip(config.admin_token.as_deref())
+            .map(|(given, token)| constant_time_eq(given.as_bytes(), token.as_bytes()))
+            .unwrap_or(false);
+        let authorized = basic_ok || token_ok;
+
+        if authorized {
+            records.remove(&ip);
+            return Ok(());
+        }
+
+        if records.len() >= MAX_TRACKED_IPS {
+            records.retain(|_, r| r.locked_until.is_some_and(|until| until > now));
+        }
+
+        let record = records.entry(ip).or_default();
+        record.failures += 1;
+        let locked = record.failures >= config.admin_max_failures;
+        if locked {
+            record.failures = 0;
+            record.locked_until = Some(now + Duration::from_secs(config.admin_lockout_secs));
+        }
+        drop(records);
+
+        {
+            let mut totals = self.totals.lock().unwrap();
+            totals.0 += 1;
+            if locked {
+                totals.1 += 1;
+            }
+        }
+
+        if locked {
+            if config.log_enabled("warn") {
+                println!("   🔒 Admin auth: {} locked out for {}s", ip, config.admin_lockout_secs);
+            }
+            return Err(Self::locked_response(Duration::from_secs(config.admin_lockout_secs)));
+        }
+
+        let scheme = if basic.is_some() { "Basic" } else { "Bearer" };
+        let mut response = Response::error(StatusCode::Unauthorized, "Admin credentials required");
+        response.add_header("WWW-Authenticate", &format!("{} realm=\"{}\"", scheme, REALM));
+        Err(response)
+    }
+
+    /// Contadores actuales
+    pub fn stats(&self, config: &Config) -> AuthStats {
+        let now = Instant::now();
+        let locked_ips = self.records.lock().unwrap()
+            .values()
+            .filter(|r| r.locked_until.is_some_and(|until| until > now))
+            .count();
+        let (failures, lockouts) = *self.totals.lock().unwrap();
+
+        AuthStats {
+            enabled: Self::is_enabled(config),
+            failures,
+            lockouts,
+            locked_ips,
+        }
+    }
+
+    /// Respuesta para una IP bloqueada
+    fn locked_response(remaining: Duration) -> Response {
+        Response::builder(StatusCode::TooManyRequests)
+            .retry_after(remaining.max(Duration::from_secs(1)))
+            .json(&serde_json::json!({"error": "Too many failed admin login attempts"}))
+    }
+}
+
+impl Default for AdminAuth {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Extrae `(usuario, contraseña)` de un header `Basic <base64>`
+fn parse_basic(header: &str) -> Option<(String, String)> {
+    let (scheme, encoded) = header.trim().split_once(' ')?;
+    if !scheme.eq_ignore_ascii_case("basic") {
+        return None;
+    }
+
+    let decoded = String::from_utf8(base64::decode(encoded.trim())?).ok()?;
+    let (user, password) = decoded.split_once(':')?;
+    Some((user.to_string(), password.to_string()))
+}
+
+/// Extrae el token de un header `Bearer <token>`
+fn parse_bearer(header: &str) -> Option<&str> {
+    let (scheme, token) = header.trim().split_once(' ')?;
+    scheme.eq_ignore_ascii_case("bearer").then(|| token.trim()).filter(|t| !t.is_empty())
+}
+
+/// Compara dos secuencias sin cortar en la primera diferencia
+///
+/// El tiempo depende solo del largo de la más larga, no de cuántos bytes
+/// coinciden.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    let len = a.len().max(b.len());
+    let mut diff = (a.len() != b.len()) as u8;
+    for i in 0..len {
+        diff |= a.get(i).copied().unwrap_or(0) ^ b.get(i).copied().unwrap_or(0);
+    }
+    diff == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config_with_credentials() -> Config {
+        Config {
+            admin_user: Some("admin".to_string()),
+            admin_password: Some("hunter2".to_string()),
+            admin_max_failures: 3,
+            admin_lockout_secs: 60,
+            log_level: "error".to_string(),
+            ..Config::default()
+        }
+    }
+
+    fn request_with_auth(header: Option<&str>) -> Request {
+        let raw = match header {
+            Some(value) => format!("GET /config HTTP/1.0\r\nAuthorization: {}\r\n\r\n", value),
+            None => "GET /config HTTP/1.0\r\n\r\n".to_string(),
+        };
+        Request::parse(raw.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn test_parse_basic() {
+        assert_eq!(
+            parse_basic("Basic YWRtaW46aHVudGVyMg=="),
+            Some(("admin".to_string(), "hunter2".to_string()))
+        );
+        assert!(parse_basic("Bearer YWRtaW46aHVudGVyMg==").is_none());
+    }
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq(b"hunter2", b"hunter2"));
+        assert!(!constant_time_eq(b"hunter2", b"hunter3"));
+        assert!(!constant_time_eq(b"hunter2", b"hunter"));
+        assert!(!constant_time_eq(b"", b"x"));
+        assert!(constant_time_eq(b"", b""));
+    }
+
+    #[test]
+    fn test_open_without_credentials() {
+        let auth = AdminAuth::new();
+        let ip: IpAddr = "127.0.0.1".parse().unwrap();
+        assert!(auth.check(&request_with_auth(None), ip, &Config::default()).is_ok());
+    }
+
+    #[test]
+    fn test_valid_and_invalid_credentials() {
+        let auth = AdminAuth::new();
+        let config = config_with_credentials();
+        let ip: IpAddr = "10.0.0.1".parse().unwrap();
+
+        let missing = auth.check(&request_with_auth(None), ip, &config).unwrap_err();
+        assert_eq!(missing.status(), StatusCode::Unauthorized);
+        assert!(missing.headers().contains("WWW-Authenticate"));
+
+        // admin:wrong
+        let wrong = auth.check(&request_with_auth(Some("Basic YWRtaW46d3Jvbmc=")), ip, &config);
+        assert_eq!(wrong.unwrap_err().status(), StatusCode::Unauthorized);
+
+        assert!(auth.check(&request_with_auth(Some("Basic YWRtaW46aHVudGVyMg==")), ip, &config).is_ok());
+        assert_eq!(auth.stats(&config).failures, 2);
+    }
+
+    #[test]
+    fn test_bearer_token() {
+        let auth = AdminAuth::new();
+        let config = Config { admin_token: Some("t0ken".to_string()), ..Config::default() };
+        let ip: IpAddr = "10.0.0.4".parse().unwrap();
+        assert!(AdminAuth::is_enabled(&config));
+
+        assert!(auth.check(&request_with_auth(Some("Bearer t0ken")), ip, &config).is_ok());
+        let wrong = auth.check(&request_with_auth(Some("Bearer t0ke")), ip, &config).unwrap_err();
+        assert_eq!(wrong.status(), StatusCode::Unauthorized);
+        assert_eq!(wrong.headers().get("WWW-Authenticate"), Some("Bearer realm=\"RedUnix admin\""));
+
+        // Con token y Basic configurados sirve cualquiera de los dos
+        let both = Config { admin_token: Some("t0ken".to_string()), ..config_with_credentials() };
+        assert!(auth.check(&request_with_auth(Some("Basic YWRtaW46aHVudGVyMg==")), ip, &both).is_ok());
+        assert!(auth.check(&request_with_auth(Some("bearer t0ken")), ip, &both).is_ok());
+        assert_eq!(parse_bearer("Bearer "), None);
+    }
+
+    #[test]
+    fn test_lockout_after_max_failures() {
+        let auth = AdminAuth::new();
+        let config = config_with_credentials();
+        let ip: IpAddr = "10.0.0.2".parse().unwrap();
+        let other: IpAddr = "10.0.0.3".parse().unwrap();
+
+        for _ in 0..2 {
+            let err = auth.check(&request_with_auth(None), ip, &config).unwrap_err();
+            assert_eq!(err.status(), StatusCode::Unauthorized);
+        }
+        let locked = auth.check(&request_with_auth(None), ip, &config).unwrap_err();
+        assert_eq!(locked.status(), StatusCode::TooManyRequests);
+        assert_eq!(locked.headers().get("Retry-After"), Some("60"));
+
+        // Bloqueada aun con credenciales correctas; otras IPs no se afectan
+        let good = request_with_auth(Some("Basic YWRtaW46aHVudGVyMg=="));
+        assert_eq!(auth.check(&good, ip, &config).unwrap_err().status(), StatusCode::TooManyRequests);
+        assert!(auth.check(&good, other, &config).is_ok());
+
+        let stats = auth.stats(&config);
+        assert_eq!(stats.lockouts, 1);
+        assert_eq!(stats.locked_ips, 1);
+        assert!(stats.enabled);
+    }
+}
@@ -0,0 +1,394 @@
+//! # Construcción Programática del Servidor
+//! src/server/builder.rs
+//!
+//! `Server::new` arma el servidor completo con los comandos de siempre. Para
+//! usar el crate como librería (o en tests) `Server::builder()` permite
+//! agregar rutas propias, quitar las integradas y pasar subsistemas ya
+//! creados (métricas, job manager):
+//!
+//! ```no_run
+//! use http_server::http::{Request, Response};
+//! use http_server::server::Server;
+//!
+//! fn hello(_request: &Request) -> Response {
+//!     Response::text("hola")
+//! }
+//!
+//! let mut server = Server::builder()
+//!     .configure(|config| config.port = 9090)
+//!     .route("/hello", hello)
+//!     .build();
+//! server.run().unwrap();
+//! ```
+//!
+//! Las rutas propias se registran antes que las integradas, así que una
+//! ruta con el mismo path reemplaza al comando integrado. Un handler puede
+//! ser un closure que capture estado compartido (un `Arc`), como `/status`
+//! con las métricas o `/jobs/*` con el job manager.
+
+use super::dashboard;
+use super::static_files::StaticFiles;
+use super::tcp::{self, Server};
+use crate::commands;
+use crate::config::Config;
+use crate::http::request::Method;
+use crate::http::{Request, Response, StatusCode};
+use crate::jobs::{handlers as job_handlers, JobManager};
+use crate::metrics::MetricsCollector;
+use crate::router::timeout::parse_route_timeouts;
+use crate::router::{ErrorHandler, RouteEntry, RouteGroup, Router, TrailingSlash};
+use std::sync::Arc;
+
+/// Registra los comandos integrados en `router`
+///
+/// Todos responden a GET (y HEAD) salvo `/uploadfile`, que es POST; otro
+/// método recibe 405. `/status` informa las métricas de `metrics`.
+/// La descripción y los parámetros de cada uno son los que lista `/help`,
+/// junto con los endpoints del servidor (`/metrics`, `/config`, `/admin/*`,
+/// `/ws`...), que se registran aparte.
+pub fn register_builtin_routes(router: &mut Router, metrics: &Arc<MetricsCollector>) {
+    // Comandos básicos
+    let status_metrics = Arc::clone(metrics);
+    router.register_method(Method::GET, "/status", move |req: &Request| {
+        commands::status_handler(req, &status_metrics)
+    })
+    .describe("Server status and metrics");
+    router.register_method(Method::GET, "/fibonacci", commands::fibonacci_handler)
+        .describe("Calculate Fibonacci number")
+        .required("num", "integer <= 90");
+    router.register_method(Method::GET, "/reverse", commands::reverse_handler)
+        .describe("Reverse a text string")
+        .required("text", "string to reverse");
+    router.register_method(Method::GET, "/toupper", commands::toupper_handler)
+        .describe("Convert text to uppercase")
+        .required("text", "string to convert");
+    router.register_method(Method::GET, "/timestamp", commands::timestamp_handler)
+        .describe("Get current Unix timestamp");
+    router.register_method(Method::GET, "/random", commands::random_handler)
+        .describe("Generate random numbers")
+        .optional("count", "number of values")
+        .optional("min", "minimum value")
+        .optional("max", "maximum value");
+    router.register_method(Method::GET, "/hash", commands::hash_handler)
+        .describe("Calculate SHA256 hash of text")
+        .required("text", "text to hash");
+    router.register_method(Method::GET, "/createfile", commands::createfile_handler)
+        .describe("Create a file with content")
+        .required("name", "filename")
+        .required("content", "text content")
+        .optional("repeat", "repetitions");
+    router.register_method(Method::GET, "/deletefile", commands::deletefile_handler)
+        .describe("Delete a file")
+        .required("name", "filename");
+    router.register_method(Method::GET, "/simulate", commands::simulate_handler)
+        .describe("Simulate a task with real work")
+        .required("seconds", "duration")
+        .optional("task", "task name");
+    router.register_method(Method::GET, "/sleep", commands::sleep_handler)
+        .describe("Sleep for N seconds")
+        .required("seconds", "duration");
+    router.register_method(Method::GET, "/loadtest", commands::loadtest_handler)
+        .describe("Generate test load")
+        .optional("tasks", "number of tasks")
+        .optional("sleep", "sleep per task in ms");
+    router.register_redirect("/docs", StatusCode::MovedPermanently, "/help");
+
+    // Comandos CPU-bound
+    router.register_method(Method::GET, "/isprime", commands::isprime_handler)
+        .describe("Check whether a number is prime")
+        .required("n", "integer <= 2^63-1");
+    router.register_method(Method::GET, "/factor", commands::factor_handler)
+        .describe("Factor a number into primes")
+        .required("n", "integer between 2 and 10^15");
+    router.register_method(Method::GET, "/pi", commands::pi_handler)
+        .describe("Compute digits of pi")
+        .required("digits", "number of decimal digits");
+    router.register_method(Method::GET, "/mandelbrot", commands::mandelbrot_handler)
+        .describe("Render the Mandelbrot set")
+        .optional("width", "columns (default 80)")
+        .optional("height", "rows (default 40)")
+        .optional("max_iter", "iterations per point (default 100)");
+    router.register_method(Method::GET, "/matrixmul", commands::matrixmul_handler)
+        .describe("Multiply two random square matrices")
+        .required("size", "matrix size")
+        .optional("seed", "random seed (default 42)");
+
+    // Comandos IO-bound
+    router.register_method(Method::GET, "/sortfile", commands::sortfile_handler)
+        .describe("Sort the numbers of a data file")
+        .required("name", "file in the data directory")
+        .optional("algo", "merge or quick (default merge)");
+    router.register_method(Method::GET, "/wordcount", commands::wordcount_handler)
+        .describe("Count lines, words and bytes of a data file")
+        .required("name", "file in the data directory");
+    router.register_method(Method::GET, "/grep", commands::grep_handler)
+        .describe("Find the lines of a data file that match a regex")
+        .required("name", "file in the data directory")
+        .required("pattern", "regular expression");
+    router.register_method(Method::GET, "/compress", commands::compress_handler)
+        .describe("Compress a data file")
+        .required("name", "file in the data directory")
+        .optional("codec", "gzip");
+    router.register_method(Method::GET, "/hashfile", commands::hashfile_handler)
+        .describe("SHA-256 of a data file")
+        .required("name", "file in the data directory")
+        .optional("algo", "sha256");
+    router.register_method(Method::GET, "/download", commands::download_handler)
+        .describe("Download a data file (supports Range and ETag)")
+        .required("name", "file in the data directory");
+    router.register_method(Method::POST, "/uploadfile", commands::uploadfile_handler)
+        .describe("Upload files to the data directory (multipart/form-data body)");
+
+    // Comandos de red saliente
+    router.register_method(Method::GET, "/fetch", commands::fetch_handler)
+        .describe("Download a URL into the data directory")
+        .required("url", "http:// URL on a host allowed by --fetch-allow")
+        .optional("name", "target file (default: last path segment)");
+
+    // Simulación de la fábrica (Tarea-2)
+    router.register_method(Method::GET, "/factory", commands::factory_handler)
+        .describe("Simulate the three-station factory")
+        .required("products", "number of products")
+        .optional("algorithm", "fcfs or rr (default fcfs)")
+        .optional("quantum_ms", "Round Robin quantum (default 750)");
+
+    // Dashboard HTML (consulta /metrics y /jobs/list desde el navegador)
+    router.register_method(Method::GET, dashboard::DASHBOARD_PATH, dashboard::dashboard_handler)
+        .describe("Live HTML dashboard");
+}
+
+/// Registra los endpoints de jobs (`/jobs/*`) sobre `job_manager`
+///
+/// Son del servidor, no comandos: se registran también sin los integrados.
+/// `/jobs/submit` acepta GET (query) y POST (JSON); el resto, GET.
+pub fn register_job_routes(router: &mut Router, job_manager: &Arc<JobManager>) {
+    router.group("/jobs", |jobs| {
+        job_route(jobs, Method::GET, "/submit", job_handlers::submit_handler, job_manager)
+            .describe("Queue a job and return its id")
+            .required("task", "command to run (isprime, factor, ...)")
+            .optional("prio", "low, normal or high (default normal)");
+        job_route(jobs, Method::POST, "/submit", job_handlers::submit_handler, job_manager)
+            .describe("Queue a job from a JSON body {\"task\", \"params\", \"prio\"}");
+        job_route(jobs, Method::GET, "/status", job_handlers::status_handler, job_manager)
+            .describe("Job status, progress and ETA")
+            .required("id", "job id");
+        job_route(jobs, Method::GET, "/result", job_handlers::result_handler, job_manager)
+            .describe("Result of a finished job")
+            .required("id", "job id");
+        job_route(jobs, Method::GET, "/cancel", job_handlers::cancel_handler, job_manager)
+            .describe("Cancel a queued or running job")
+            .required("id", "job id");
+        job_route(jobs, Method::GET, "/list", job_handlers::list_handler, job_manager)
+            .describe("Most recent jobs first")
+            .optional("status", "only jobs in this state")
+            .optional("limit", "maximum jobs (default 50, max 500)");
+    });
+}
+
+/// Registra en `jobs` un handler de jobs con su `job_manager`
+fn job_route<'a>(
+    jobs: &'a mut RouteGroup,
+    method: Method,
+    path: &str,
+    handler: fn(&Request, &JobManager) -> Response,
+    job_manager: &Arc<JobManager>,
+) -> RouteEntry<'a> {
+    let job_manager = Arc::clone(job_manager);
+    jobs.register_method(method, path, move |req: &Request| handler(req, &job_manager))
+}
+
+/// Constructor fluido de `Server`
+pub struct ServerBuilder {
+    config: Config,
+    router: Router,
+    builtin_routes: bool,
+    metrics: Option<Arc<MetricsCollector>>,
+    job_manager: Option<Arc<JobManager>>,
+}
+
+impl ServerBuilder {
+    /// Builder con la configuración por defecto y los comandos integrados
+    pub fn new() -> Self {
+        Self {
+            config: Config::default(),
+            router: Router::new(),
+            builtin_routes: true,
+            metrics: None,
+            job_manager: None,
+        }
+    }
+
+    /// Reemplaza la configuración completa
+    pub fn with_config(mut self, config: Config) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Modifica la configuración actual
+    pub fn configure(mut self, configure: impl FnOnce(&mut Config)) -> Self {
+        configure(&mut self.config);
+        self
+    }
+
+    /// Configuración con la que se va a construir el servidor
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Agrega una ruta (tiene prioridad sobre un comando integrado del mismo path)
+    pub fn route<F>(mut self, path: &str, handler: F) -> Self
+    where
+        F: Fn(&Request) -> Response + Send + Sync + 'static,
+    {
+        self.router.register(path, handler);
+        self
+    }
+
+    /// Agrega una ruta solo para `method` (ver `Router::register_method`)
+    pub fn route_method<F>(mut self, method: Method, path: &str, handler: F) -> Self
+    where
+        F: Fn(&Request) -> Response + Send + Sync + 'static,
+    {
+        self.router.register_method(method, path, handler);
+        self
+    }
+
+    /// Agrega una ruta por regex (ver `Router::register_regex`)
+    pub fn route_regex<F>(mut self, method: Method, pattern: &str, handler: F) -> Self
+    where
+        F: Fn(&Request) -> Response + Send + Sync + 'static,
+    {
+        self.router.register_regex(method, pattern, handler);
+        self
+    }
+
+    /// Registra rutas directamente en el router, por ejemplo con su
+    /// descripción para `/help` (ver `router::docs`)
+    pub fn routes(mut self, register: impl FnOnce(&mut Router)) -> Self {
+        register(&mut self.router);
+        self
+    }
+
+    /// Agrega un grupo de rutas con prefijo y middlewares (ver `Router::group`)
+    pub fn group(mut self, prefix: &str, build: impl FnOnce(&mut RouteGroup)) -> Self {
+        self.router.group(prefix, build);
+        self
+    }
+
+    /// Sirve un directorio bajo `prefix` (ver `static_files`)
+    pub fn static_files(mut self, prefix: &str, files: StaticFiles) -> Self {
+        files.mount(&mut self.router, prefix);
+        self
+    }
+
+    /// Agrega una redirección (ver `Router::register_redirect`)
+    pub fn redirect(mut self, from: &str, status: StatusCode, to: &str) -> Self {
+        self.router.register_redirect(from, status, to);
+        self
+    }
+
+    /// Reemplaza el handler de los errores del servidor (404, 400, panics)
+    /// 
+    /// Ver `router::errors`; por defecto es `errors::json_envelope`.
+    pub fn error_handler(mut self, handler: ErrorHandler) -> Self {
+        self.router.set_error_handler(handler);
+        self
+    }
+    
+    /// Atiende los paths sin ruta con `handler` en lugar del 404 (ver
+    /// `Router::set_fallback`)
+    pub fn fallback<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(&Request) -> Response + Send + Sync + 'static,
+    {
+        self.router.set_fallback(handler);
+        self
+    }
+    
+    /// No registra los comandos integrados (`/status`, `/fibonacci`, ...)
+    pub fn without_builtin_routes(mut self) -> Self {
+        self.builtin_routes = false;
+        self
+    }
+
+    /// Usa un colector de métricas ya creado (ej: para leerlo desde el test)
+    pub fn with_metrics(mut self, metrics: Arc<MetricsCollector>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Usa un job manager ya creado en vez de armarlo desde la configuración
+    pub fn with_job_manager(mut self, job_manager: Arc<JobManager>) -> Self {
+        self.job_manager = Some(job_manager);
+        self
+    }
+
+    /// Construye el servidor (todavía sin escuchar; ver `run` y `serve`)
+    ///
+    /// Crea el `data_dir` configurado y lo fija como sandbox de los comandos
+    /// de archivos (es global del proceso). Si no sirve (no se puede crear o
+    /// no es escribible), `run`, `serve` y `run_with_shutdown` retornan ese
+    /// error sin abrir el puerto.
+    ///
+    /// # Ejemplo
+    /// ```
+    /// use http_server::http::{Request, Response, StatusCode};
+    /// use http_server::metrics::MetricsCollector;
+    /// use http_server::server::Server;
+    /// use std::sync::Arc;
+    ///
+    /// fn ping(_request: &Request) -> Response {
+    ///     Response::text("pong")
+    /// }
+    ///
+    /// let metrics = Arc::new(MetricsCollector::new());
+    /// let builder = Server::builder()
+    ///     .configure(|config| config.cpu_workers = 1)
+    ///     .without_builtin_routes()
+    ///     .route("/ping", ping)
+    ///     .redirect("/", StatusCode::Found, "/ping")
+    ///     .with_metrics(Arc::clone(&metrics));
+    /// assert_eq!(builder.config().cpu_workers, 1);
+    /// let _server = builder.build();
+    /// ```
+    pub fn build(self) -> Server {
+        let job_manager = self.job_manager.unwrap_or_else(|| {
+            let job_manager_config = crate::jobs::manager::JobManagerConfig::from_config(&self.config);
+            Arc::new(JobManager::new(job_manager_config))
+        });
+        let metrics = self.metrics.unwrap_or_else(|| Arc::new(MetricsCollector::new()));
+
+        let mut router = self.router;
+        // La configuración ya se validó (`Config::check`)
+        router.set_trailing_slash(TrailingSlash::parse(&self.config.trailing_slash).unwrap_or_default());
+        router.set_case_insensitive(self.config.case_insensitive_paths);
+        router.set_timeout_workers(self.config.route_timeout_workers);
+        if self.builtin_routes {
+            register_builtin_routes(&mut router, &metrics);
+        }
+        // Los endpoints del servidor se atienden siempre: registrarlos
+        // también sin los comandos, para que `normalize` los encuentre
+        tcp::register_server_routes(&mut router);
+        register_job_routes(&mut router, &job_manager);
+        if let Ok(Some((prefix, dir))) = self.config.static_mount() {
+            StaticFiles::new(dir).with_listing(self.config.static_listing).mount(&mut router, &prefix);
+        }
+        for (path, timeout) in parse_route_timeouts(&self.config.route_timeouts).unwrap_or_default() {
+            if !router.set_timeout(&path, timeout) {
+                eprintln!("   ⚠️  --route-timeouts: no hay ruta registrada para {}", path);
+            }
+        }
+        // Al final, para que listen todas las rutas
+        if self.builtin_routes {
+            router.register_help();
+        }
+
+        Server::from_parts(self.config, router, metrics, job_manager)
+    }
+}
+
+impl Default for ServerBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
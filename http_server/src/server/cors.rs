@@ -0,0 +1,213 @@
+//! # CORS
+//! src/server/cors.rs
+//!
+//! Un navegador solo deja que una página de otro origen (un dashboard en
+//! `http://localhost:3000`) lea las respuestas de la API si estas lo
+//! autorizan con headers `Access-Control-Allow-*`. Con `--cors-origins` el
+//! servidor los agrega:
+//!
+//! ```text
+//! OPTIONS /jobs/submit                    ← preflight del navegador
+//! Origin: http://localhost:3000
+//! Access-Control-Request-Method: POST
+//!
+//! 204 No Content
+//! Access-Control-Allow-Origin: http://localhost:3000
+//! Access-Control-Allow-Methods: GET, HEAD, POST
+//! Access-Control-Allow-Headers: Content-Type, Authorization
+//! Access-Control-Max-Age: 600
+//! ```
+//!
+//! - El preflight se responde antes de rutear, aunque la ruta no exista o
+//!   sea de administración (el navegador no manda credenciales en él).
+//! - Las demás respuestas a un origen permitido, errores incluidos, llevan
+//!   `Access-Control-Allow-Origin` y exponen `X-Request-Id` y `Retry-After`.
+//! - Con `*` se permite cualquier origen; con una lista, el origen del
+//!   request se repite en la respuesta y se agrega `Vary: Origin`.
+//! - A un origen no permitido no se le agrega nada (el navegador bloquea la
+//!   lectura) y su preflight recibe 403.
+
+use crate::http::request::Method;
+use crate::http::{Request, Response, StatusCode};
+
+/// Headers de la respuesta que el JavaScript del otro origen puede leer
+const EXPOSED_HEADERS: &str = "X-Request-Id, Retry-After";
+
+/// Política CORS del servidor
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cors {
+    /// Orígenes permitidos (`None`: cualquiera)
+    origins: Option<Vec<String>>,
+    methods: String,
+    headers: String,
+    max_age_secs: u64,
+}
+
+impl Cors {
+    /// Construye la política; `origins` vacío la deshabilita (`None`)
+    ///
+    /// `origins`, `methods` y `headers` son listas separadas por comas. Un
+    /// origen es `*` o `esquema://host[:puerto]`, sin path.
+    ///
+    /// # Ejemplo
+    /// ```
+    /// use http_server::server::cors::Cors;
+    ///
+    /// assert!(Cors::parse("", "GET", "", 600).unwrap().is_none());
+    /// assert!(Cors::parse("http://localhost:3000", "GET, POST", "Content-Type", 600).unwrap().is_some());
+    /// assert!(Cors::parse("localhost:3000", "GET", "", 600).is_err());
+    /// ```
+    pub fn parse(origins: &str, methods: &str, headers: &str, max_age_secs: u64) -> Result<Option<Self>, String> {
+        let origins = list(origins);
+        if origins.is_empty() {
+            return Ok(None);
+        }
+        for origin in &origins {
+            let valid = origin == "*"
+                || ["http://", "https://"].iter().any(|scheme| {
+                    origin.strip_prefix(scheme)
+                        .is_some_and(|host| !host.is_empty() && !host.contains('/'))
+                });
+            if !valid {
+                return Err(format!("Invalid CORS origin '{}': use * or scheme://host[:port]", origin));
+            }
+        }
+
+        let methods = list(methods);
+        if methods.is_empty() {
+            return Err("CORS methods cannot be empty".to_string());
+        }
+        if let Some(method) = methods.iter().find(|method| !is_token(method)) {
+            return Err(format!("Invalid CORS method '{}'", method));
+        }
+        let headers = list(headers);
+        if let Some(header) = headers.iter().find(|header| !is_token(header)) {
+            return Err(format!("Invalid CORS header '{}'", header));
+        }
+
+        Ok(Some(Self {
+            origins: (!origins.iter().any(|origin| origin == "*")).then_some(origins),
+            methods: methods.join(", ").to_uppercase(),
+            headers: headers.join(", "),
+            max_age_secs,
+        }))
+    }
+
+    /// Valor de `Access-Control-Allow-Origin` para `origin`, si está permitido
+    pub fn allow_origin<'a>(&self, origin: &'a str) -> Option<&'a str> {
+        match &self.origins {
+            None => Some("*"),
+            Some(origins) => origins.iter().any(|allowed| allowed.eq_ignore_ascii_case(origin)).then_some(origin),
+        }
+    }
+
+    /// Respuesta a un preflight (`OPTIONS` con `Origin` y
+    /// `Access-Control-Request-Method`); `None` si `request` no lo es
+    ///
+    /// # Ejemplo
+    /// ```
+    /// use http_server::http::{Request, StatusCode};
+    /// use http_server::server::cors::Cors;
+    ///
+    /// let cors = Cors::parse("https://app.example", "GET, POST", "Content-Type", 600).unwrap().unwrap();
+    /// let raw = b"OPTIONS /jobs/submit HTTP/1.0\r\nOrigin: https://app.example\r\nAccess-Control-Request-Method: POST\r\n\r\n";
+    /// let response = cors.preflight(&Request::parse(raw).unwrap()).unwrap();
+    /// assert_eq!(response.status(), StatusCode::NoContent);
+    /// assert_eq!(response.headers().get("Access-Control-Allow-Methods"), Some("GET, POST"));
+    ///
+    /// let raw = b"OPTIONS /jobs/submit HTTP/1.0\r\n\r\n";
+    /// assert!(cors.preflight(&Request::parse(raw).unwrap()).is_none());
+    /// ```
+    pub fn preflight(&self, request: &Request) -> Option<Response> {
+        if request.method() != Method::OPTIONS {
+            return None;
+        }
+        let origin = request.headers().get("Origin")?;
+        request.headers().get("Access-Control-Request-Method")?;
+
+        if self.allow_origin(origin).is_none() {
+            return Some(Response::error(StatusCode::Forbidden, &format!("CORS origin not allowed: {}", origin)));
+        }
+        let mut response = Response::builder(StatusCode::NoContent)
+            .header("Access-Control-Allow-Methods", &self.methods)
+            .header("Access-Control-Max-Age", &self.max_age_secs.to_string());
+        if !self.headers.is_empty() {
+            response = response.header("Access-Control-Allow-Headers", &self.headers);
+        }
+        Some(response.build())
+    }
+
+    /// Agrega los headers CORS a la respuesta de un request con `origin`
+    pub fn apply(&self, origin: Option<&str>, response: Response) -> Response {
+        let Some(allowed) = origin.and_then(|origin| self.allow_origin(origin)) else {
+            return response;
+        };
+        let response = response
+            .with_header("Access-Control-Allow-Origin", allowed)
+            .with_header("Access-Control-Expose-Headers", EXPOSED_HEADERS);
+        if self.origins.is_some() {
+            response.with_appended_header("Vary", "Origin")
+        } else {
+            response
+        }
+    }
+}
+
+/// Elementos de una lista separada por comas, sin espacios ni vacíos
+fn list(value: &str) -> Vec<String> {
+    value.split(',').map(str::trim).filter(|item| !item.is_empty()).map(str::to_string).collect()
+}
+
+/// Indica si `value` es un token HTTP (nombre de método o de header)
+fn is_token(value: &str) -> bool {
+    value.bytes().all(|b| b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(raw: &str) -> Request {
+        Request::parse(raw.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn test_origin_list_echoes_the_origin_and_varies() {
+        let cors = Cors::parse("http://localhost:3000, https://app.example", "get, post", "", 60).unwrap().unwrap();
+        assert_eq!(cors.allow_origin("http://localhost:3000"), Some("http://localhost:3000"));
+        assert_eq!(cors.allow_origin("http://evil.example"), None);
+
+        let response = cors.apply(Some("https://app.example"), Response::text("x").with_header("Vary", "Accept-Encoding"));
+        assert_eq!(response.headers().get("Access-Control-Allow-Origin"), Some("https://app.example"));
+        assert_eq!(response.headers().get_all("Vary").collect::<Vec<_>>(), ["Accept-Encoding", "Origin"]);
+        assert_eq!(response.headers().get("Access-Control-Expose-Headers"), Some(EXPOSED_HEADERS));
+
+        // Sin Origin (curl) o con uno ajeno no se agrega nada
+        assert!(cors.apply(None, Response::text("x")).headers().get("Access-Control-Allow-Origin").is_none());
+        assert!(cors.apply(Some("http://evil.example"), Response::text("x")).headers().get("Vary").is_none());
+
+        let denied = cors.preflight(&request(
+            "OPTIONS /x HTTP/1.0\r\nOrigin: http://evil.example\r\nAccess-Control-Request-Method: GET\r\n\r\n",
+        )).unwrap();
+        assert_eq!(denied.status(), StatusCode::Forbidden);
+
+        let allowed = cors.preflight(&request(
+            "OPTIONS /x HTTP/1.0\r\nOrigin: http://localhost:3000\r\nAccess-Control-Request-Method: GET\r\n\r\n",
+        )).unwrap();
+        assert_eq!(allowed.headers().get("Access-Control-Allow-Methods"), Some("GET, POST"));
+        assert_eq!(allowed.headers().get("Access-Control-Max-Age"), Some("60"));
+        assert!(allowed.headers().get("Access-Control-Allow-Headers").is_none());
+    }
+
+    #[test]
+    fn test_wildcard_and_invalid_values() {
+        let cors = Cors::parse("*", "GET", "Content-Type, X-Token", 600).unwrap().unwrap();
+        let response = cors.apply(Some("http://any.example"), Response::text("x"));
+        assert_eq!(response.headers().get("Access-Control-Allow-Origin"), Some("*"));
+        assert!(response.headers().get("Vary").is_none());
+
+        assert!(Cors::parse("https://app.example/", "GET", "", 0).unwrap_err().contains("Invalid CORS origin"));
+        assert!(Cors::parse("*", "", "", 0).unwrap_err().contains("methods cannot be empty"));
+        assert!(Cors::parse("*", "GET", "Bad Header", 0).unwrap_err().contains("Invalid CORS header"));
+    }
+}
@@ -0,0 +1,329 @@
+//! # Modo Daemon y Pidfile
+//! src/server/daemon.rs
+//!
+//! Con `--daemon` el servidor se separa de la terminal (doble `fork` y
+//! `setsid`), redirige stdout y stderr al log (`--log-file`, por defecto
+//! `<data-dir>/redunix.log`) y escribe su PID en el pidfile (`--pid-file`,
+//! por defecto `<data-dir>/redunix.pid`). `--pid-file` también sirve sin
+//! `--daemon`.
+//!
+//! - Una segunda instancia con el mismo pidfile encuentra el PID de un
+//!   proceso vivo y termina con error antes de separarse, así el mensaje se
+//!   ve en la terminal. Un pidfile de un proceso que ya no existe (un
+//!   `kill -9`) se reemplaza.
+//! - El proceso que escribe el pidfile lo mantiene abierto con un `flock`
+//!   exclusivo mientras vive: dos instancias que arrancan a la vez no pueden
+//!   pasar las dos el chequeo, y el kernel suelta el lock si el proceso muere.
+//! - El directorio de trabajo no cambia: las rutas relativas (`./data`)
+//!   siguen apuntando al mismo lugar.
+//! - En un reinicio sin cortes (SIGUSR2) el proceso nuevo ya está separado y
+//!   hereda el log; solo reescribe el pidfile con su PID y toma el lock
+//!   cuando el anterior lo suelta. Al terminar, cada proceso borra el
+//!   pidfile solo si todavía tiene su PID.
+//!
+//! Hay que separarse antes de lanzar cualquier thread: `fork` solo copia el
+//! thread que lo llama. Todo usa FFI directo a libc, sin crates externos.
+
+use crate::config::Config;
+use crate::server::handoff;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Seek, Write};
+use std::path::{Path, PathBuf};
+
+#[cfg(unix)]
+mod ffi {
+    /// `kill` devuelve EPERM si el proceso existe pero es de otro usuario
+    pub const EPERM: i32 = 1;
+    /// Lock exclusivo de `flock`
+    pub const LOCK_EX: i32 = 2;
+    /// `flock` sin esperar: falla si otro tiene el lock
+    pub const LOCK_NB: i32 = 4;
+
+    extern "C" {
+        /// `pid_t fork(void)`
+        pub fn fork() -> i32;
+        /// `pid_t setsid(void)`
+        pub fn setsid() -> i32;
+        /// `int dup2(int oldfd, int newfd)`
+        pub fn dup2(oldfd: i32, newfd: i32) -> i32;
+        /// `int kill(pid_t pid, int sig)`
+        pub fn kill(pid: i32, sig: i32) -> i32;
+        /// `int flock(int fd, int operation)`
+        pub fn flock(fd: i32, operation: i32) -> i32;
+    }
+}
+
+/// Pidfile escrito por este proceso; se borra al soltarlo
+#[derive(Debug)]
+pub struct PidFile {
+    path: PathBuf,
+    pid: u32,
+
+    /// Abierto mientras viva el proceso: sostiene el `flock`
+    _file: File,
+}
+
+impl PidFile {
+    /// Escribe el PID de este proceso en `path` y toma su lock
+    ///
+    /// Falla si otro proceso tiene el lock o si el archivo tiene el PID de
+    /// otro proceso vivo.
+    pub fn create(path: &Path) -> Result<Self, String> {
+        let mut file = open(path)?;
+        let already_running = |pid: Option<u32>| {
+            let pid = pid.map_or_else(|| "?".to_string(), |pid| pid.to_string());
+            format!("Another instance is already running (PID {}, {})", pid, path.display())
+        };
+        if !lock(&file, false) {
+            return Err(already_running(read_pid(path)));
+        }
+        // Con el lock tomado nadie más puede estar reemplazando el archivo
+        if let Some(pid) = running_instance(path) {
+            return Err(already_running(Some(pid)));
+        }
+        Self::write(path, &mut file)?;
+        Ok(Self { path: path.to_path_buf(), pid: std::process::id(), _file: file })
+    }
+
+    /// Escribe el PID de este proceso en `path` sin mirar lo que tenía
+    ///
+    /// Para el proceso nuevo de un reinicio sin cortes: el anterior sigue
+    /// vivo (y con el lock) hasta que el nuevo avisa que está listo, así
+    /// que el lock se toma en segundo plano cuando el anterior lo suelta.
+    pub fn take_over(path: &Path) -> Result<Self, String> {
+        let mut file = open(path)?;
+        Self::write(path, &mut file)?;
+        // El lock es del archivo abierto, no del descriptor: el clon lo toma
+        // para `file` y se puede cerrar
+        if let Ok(waiter) = file.try_clone() {
+            std::thread::Builder::new()
+                .name("pidfile-lock".to_string())
+                .spawn(move || lock(&waiter, true))
+                .map_err(|e| format!("Cannot lock pid file {}: {}", path.display(), e))?;
+        }
+        Ok(Self { path: path.to_path_buf(), pid: std::process::id(), _file: file })
+    }
+
+    /// Reemplaza el contenido de `file` por el PID de este proceso
+    fn write(path: &Path, file: &mut File) -> Result<(), String> {
+        file.set_len(0)
+            .and_then(|_| file.rewind())
+            .and_then(|_| writeln!(file, "{}", std::process::id()))
+            .map_err(|e| format!("Cannot write pid file {}: {}", path.display(), e))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for PidFile {
+    fn drop(&mut self) {
+        // Tras un reinicio sin cortes el archivo ya es del proceso nuevo
+        if read_pid(&self.path) == Some(self.pid) {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+}
+
+/// Abre (o crea) el pidfile sin truncarlo: lo que tiene es del dueño del lock
+fn open(path: &Path) -> Result<File, String> {
+    OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(path)
+        .map_err(|e| format!("Cannot open pid file {}: {}", path.display(), e))
+}
+
+/// Toma el `flock` exclusivo de `file`; sin `wait`, falla si otro lo tiene
+#[cfg(unix)]
+fn lock(file: &File, wait: bool) -> bool {
+    use std::os::unix::io::AsRawFd;
+
+    let operation = if wait { ffi::LOCK_EX } else { ffi::LOCK_EX | ffi::LOCK_NB };
+    unsafe { ffi::flock(file.as_raw_fd(), operation) == 0 }
+}
+
+#[cfg(not(unix))]
+fn lock(_file: &File, _wait: bool) -> bool {
+    true
+}
+
+/// PID guardado en `path`, si el archivo existe y tiene uno
+fn read_pid(path: &Path) -> Option<u32> {
+    fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+/// PID de otra instancia viva según el pidfile `path`
+///
+/// # Ejemplo
+/// ```
+/// use http_server::server::daemon::running_instance;
+///
+/// let path = std::env::temp_dir().join(format!("doc-{}.pid", std::process::id()));
+/// assert_eq!(running_instance(&path), None);
+/// // El PID propio no cuenta como otra instancia
+/// std::fs::write(&path, std::process::id().to_string()).unwrap();
+/// assert_eq!(running_instance(&path), None);
+/// std::fs::remove_file(&path).unwrap();
+/// ```
+pub fn running_instance(path: &Path) -> Option<u32> {
+    read_pid(path).filter(|&pid| pid != std::process::id() && is_alive(pid))
+}
+
+/// Indica si existe un proceso con PID `pid`
+#[cfg(unix)]
+fn is_alive(pid: u32) -> bool {
+    let Ok(pid) = i32::try_from(pid) else {
+        return false;
+    };
+    if pid <= 0 {
+        return false;
+    }
+    // La señal 0 no se envía: solo verifica que el proceso exista
+    let result = unsafe { ffi::kill(pid, 0) };
+    result == 0 || io::Error::last_os_error().raw_os_error() == Some(ffi::EPERM)
+}
+
+#[cfg(not(unix))]
+fn is_alive(_pid: u32) -> bool {
+    false
+}
+
+/// Se separa de la terminal y manda stdout y stderr a `log`
+///
+/// El proceso que llama termina; sigue un nieto sin terminal de control,
+/// líder de una sesión nueva.
+#[cfg(unix)]
+pub fn daemonize(log: &Path) -> io::Result<()> {
+    use std::io::Write;
+    use std::os::unix::io::AsRawFd;
+
+    // Se abren antes: si fallan, el error se ve en la terminal
+    let log = fs::OpenOptions::new().create(true).append(true).open(log)?;
+    let null = fs::File::open("/dev/null")?;
+    // Lo que quedó en el buffer no debe repetirse en el log
+    io::stdout().flush()?;
+
+    fork_and_exit_parent()?;
+    if unsafe { ffi::setsid() } < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    // El segundo fork deja un proceso que no es líder de sesión y no puede
+    // volver a tomar una terminal
+    fork_and_exit_parent()?;
+
+    for (from, to) in [(null.as_raw_fd(), 0), (log.as_raw_fd(), 1), (log.as_raw_fd(), 2)] {
+        if unsafe { ffi::dup2(from, to) } < 0 {
+            return Err(io::Error::last_os_error());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn fork_and_exit_parent() -> io::Result<()> {
+    match unsafe { ffi::fork() } {
+        pid if pid < 0 => Err(io::Error::last_os_error()),
+        0 => Ok(()),
+        _ => std::process::exit(0),
+    }
+}
+
+#[cfg(not(unix))]
+pub fn daemonize(_log: &Path) -> io::Result<()> {
+    Err(io::Error::new(io::ErrorKind::Unsupported, "--daemon needs a Unix system"))
+}
+
+/// Aplica `--daemon` y `--pid-file` al arrancar
+///
+/// Retorna el pidfile (hay que conservarlo mientras el servidor corre) o
+/// el motivo por el que no se puede arrancar.
+pub fn start(config: &Config) -> Result<Option<PidFile>, String> {
+    let restart = handoff::is_restart();
+    let pid_path = config.pid_file_path();
+
+    // Una segunda instancia falla acá, con la terminal todavía conectada
+    if let Some(path) = pid_path.as_deref().filter(|_| !restart) {
+        if let Some(pid) = running_instance(path) {
+            return Err(format!("Another instance is already running (PID {}, {})", pid, path.display()));
+        }
+    }
+
+    if config.daemon && !restart {
+        let log = config.daemon_log_path();
+        println!("[+] Pasando a segundo plano (log: {})", log.display());
+        daemonize(&log).map_err(|e| format!("Cannot daemonize: {}", e))?;
+    }
+
+    pid_path
+        .map(|path| if restart { PidFile::take_over(&path) } else { PidFile::create(&path) })
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_pid_file(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("redunix-{}-{}.pid", name, std::process::id()));
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn test_create_writes_pid_and_drop_removes_it() {
+        let path = temp_pid_file("create");
+        let pid_file = PidFile::create(&path).unwrap();
+        assert_eq!(read_pid(&path), Some(std::process::id()));
+        drop(pid_file);
+        assert!(!path.exists());
+
+        // Si otro proceso lo reescribió, no se borra
+        let pid_file = PidFile::create(&path).unwrap();
+        fs::write(&path, "1\n").unwrap();
+        drop(pid_file);
+        assert!(path.exists());
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_live_instance_blocks_and_stale_pid_is_replaced() {
+        let path = temp_pid_file("live");
+
+        // El proceso que lanzó los tests sigue vivo
+        fs::write(&path, format!("{}\n", std::os::unix::process::parent_id())).unwrap();
+        assert_eq!(running_instance(&path), Some(std::os::unix::process::parent_id()));
+        assert!(PidFile::create(&path).unwrap_err().contains("already running"));
+
+        let mut child = std::process::Command::new("true").spawn().unwrap();
+        let dead = child.id();
+        child.wait().unwrap();
+        fs::write(&path, format!("{}\n", dead)).unwrap();
+        assert_eq!(running_instance(&path), None);
+        let pid_file = PidFile::create(&path).unwrap();
+        assert_eq!(read_pid(pid_file.path()), Some(std::process::id()));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_lock_blocks_a_concurrent_start() {
+        let path = temp_pid_file("lock");
+        let first = PidFile::create(&path).unwrap();
+
+        // Otro proceso que vea el PID propio (no cuenta como otra
+        // instancia) igual choca con el lock
+        let second = open(&path).unwrap();
+        assert!(!lock(&second, false));
+        assert!(PidFile::create(&path).unwrap_err().contains("already running"));
+        assert_eq!(read_pid(&path), Some(std::process::id()));
+
+        drop(first);
+        assert!(!path.exists());
+        let third = PidFile::create(&path).unwrap();
+        drop(third);
+    }
+}
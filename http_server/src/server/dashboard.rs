@@ -0,0 +1,44 @@
+//! # Dashboard en Vivo
+//! src/server/dashboard.rs
+//!
+//! Página HTML autocontenida (sin assets externos) servida en `/dashboard`.
+//! El navegador consulta `/metrics` y `/jobs/list` cada segundo (o cada
+//! `?interval=MS`) y muestra colas, utilización de workers, latencias y
+//! los últimos jobs. La página se embebe en el binario al compilar.
+
+use crate::http::{Request, Response, StatusCode};
+use crate::http::cache::CacheControl;
+
+/// Ruta del dashboard
+pub const DASHBOARD_PATH: &str = "/dashboard";
+
+/// HTML del dashboard (ver `dashboard.html`)
+const DASHBOARD_HTML: &str = include_str!("dashboard.html");
+
+/// Handler para /dashboard
+pub fn dashboard_handler(_req: &Request) -> Response {
+    Response::builder(StatusCode::Ok)
+        .content_type("text/html; charset=utf-8")
+        .body(DASHBOARD_HTML)
+        .with_cache(CacheControl::NoCache)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dashboard_serves_html_that_polls_the_api() {
+        let request = Request::parse(b"GET /dashboard HTTP/1.0\r\n\r\n").unwrap();
+        let response = dashboard_handler(&request);
+
+        assert_eq!(response.status(), StatusCode::Ok);
+        assert_eq!(response.headers().get("Content-Type").unwrap(), "text/html; charset=utf-8");
+        let body = String::from_utf8(response.body().to_vec()).unwrap();
+        assert!(body.starts_with("<!DOCTYPE html>"));
+        assert!(body.contains("getJson(\"/metrics\")"));
+        assert!(body.contains("/jobs/list?limit="));
+        // Autocontenida: nada se carga desde otros hosts
+        assert!(!body.contains("http://") && !body.contains("https://"));
+    }
+}
@@ -0,0 +1,332 @@
+//! # Event Loop (`--io-model eventloop`)
+//! src/server/event_loop.rs
+//!
+//! Alternativa al modelo de un worker por conexión: unos pocos threads
+//! multiplexan todos los sockets con `poll(2)` (FFI directo a libc, sin
+//! crates externos) y sockets no bloqueantes.
+//!
+//! ```text
+//! listener ──▶ loop thread: poll([listener, c1, c2, ...])
+//!                 ├─ listener listo  → accept (no bloqueante)
+//!                 └─ cliente listo   → read + RequestParser::feed
+//!                                        └─ request completo → pool de conexiones
+//! ```
+//!
+//! Mientras un cliente no termina de enviar su request no ocupa ningún
+//! worker: solo una entrada en el `poll`. El pool de conexiones recibe la
+//! conexión con el request ya parseado y la atiende en modo bloqueante
+//! (`Server::respond`), igual que en el modelo de threads.
+//!
+//! Las conexiones HTTPS se entregan al pool apenas se aceptan: el handshake
+//! TLS se sigue haciendo en el worker.
+
+use super::slow_client::{TransferGuard, TransferLimits};
+use super::proxy_protocol::HeaderReader;
+use super::tcp::{apply_timeouts, request_limits, ReadOutcome, Server, SharedState};
+use super::tls::Connection;
+use super::websocket::ClientSlot;
+use crate::http::parser::{ParseStatus, RequestParser};
+use crate::metrics::ConnectionMeter;
+use crate::workers::ThreadPool;
+use std::io::{self, ErrorKind, Read};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::os::unix::io::AsRawFd;
+use std::sync::atomic::Ordering;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Cada cuánto se revisa la bandera de shutdown aunque no haya actividad
+const POLL_TIMEOUT: Duration = Duration::from_millis(100);
+
+mod ffi {
+    /// Hay datos para leer (o una conexión para aceptar)
+    pub const POLLIN: i16 = 0x001;
+
+    /// `struct pollfd`
+    #[repr(C)]
+    pub struct PollFd {
+        pub fd: i32,
+        pub events: i16,
+        pub revents: i16,
+    }
+
+    /// `nfds_t` (unsigned long en Linux, unsigned int en los BSD/macOS)
+    #[cfg(target_os = "linux")]
+    pub type Nfds = std::os::raw::c_ulong;
+    #[cfg(not(target_os = "linux"))]
+    pub type Nfds = std::os::raw::c_uint;
+
+    extern "C" {
+        /// `int poll(struct pollfd *fds, nfds_t nfds, int timeout)`
+        pub fn poll(fds: *mut PollFd, nfds: Nfds, timeout: i32) -> i32;
+    }
+}
+
+/// Espera hasta que algún descriptor de `fds` tenga algo para leer
+///
+/// Retorna cuántos están listos (0 si venció `timeout`); los listos
+/// quedan con `revents` distinto de 0.
+fn poll(fds: &mut [ffi::PollFd], timeout: Duration) -> io::Result<usize> {
+    let timeout_ms = timeout.as_millis().min(i32::MAX as u128) as i32;
+    let ready = unsafe { ffi::poll(fds.as_mut_ptr(), fds.len() as ffi::Nfds, timeout_ms) };
+    if ready < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(ready as usize)
+}
+
+/// Conexión aceptada cuyo request todavía se está leyendo
+struct Pending {
+    stream: TcpStream,
+    slot: ClientSlot,
+    meter: ConnectionMeter,
+    parser: RequestParser,
+    received: usize,
+    start: Instant,
+    guard: TransferGuard,
+
+    /// Última vez que llegaron bytes (para el timeout de lectura)
+    last_read: Instant,
+
+    /// Línea del PROXY protocol a medio leer (`--proxy-protocol`)
+    proxy: Option<HeaderReader>,
+
+    /// Dirección original del cliente, según la línea PROXY
+    client: Option<SocketAddr>,
+}
+
+impl Pending {
+    /// Lee todo lo disponible sin bloquear
+    ///
+    /// Retorna `None` mientras el request esté incompleto; si no, cómo
+    /// terminó la lectura (completo, rechazado o cerrado por el cliente).
+    fn read_available(&mut self, state: &SharedState) -> Option<ReadOutcome> {
+        if let Some(proxy) = self.proxy.as_mut() {
+            match proxy.read_from(&mut self.stream) {
+                Ok(header) => {
+                    self.client = header.source();
+                    self.proxy = None;
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => return None,
+                Err(e) => {
+                    Server::reject_proxy_header(&self.stream, state, &e);
+                    return Some(ReadOutcome::Closed);
+                }
+            }
+        }
+
+        let mut chunk = [0u8; 8192];
+        loop {
+            let bytes_read = match self.stream.read(&mut chunk) {
+                Ok(n) => n,
+                Err(e) if e.kind() == ErrorKind::WouldBlock => return None,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(_) => return Some(ReadOutcome::Closed),
+            };
+
+            let parsed = if bytes_read == 0 {
+                self.parser.finish()
+            } else {
+                self.received += bytes_read;
+                self.meter.add_read(bytes_read as u64);
+                self.last_read = Instant::now();
+                match self.parser.feed(&chunk[..bytes_read]) {
+                    Ok(ParseStatus::NeedMoreData) => match self.check_progress() {
+                        Some(slow) => return Some(slow),
+                        None => continue,
+                    },
+                    Ok(ParseStatus::Complete(request)) => Ok(Some(*request)),
+                    Err(e) => Err(e),
+                }
+            };
+            return Some(ReadOutcome::from_parsed(parsed, self.received - self.parser.buffered()));
+        }
+    }
+
+    /// `TooSlow` si pasó el plazo de los headers o el body va por debajo de la tasa mínima
+    fn check_progress(&mut self) -> Option<ReadOutcome> {
+        self.guard.check(Instant::now(), self.parser.head_complete(), self.received)
+            .err()
+            .map(ReadOutcome::TooSlow)
+    }
+}
+
+/// Atiende el listener con `threads` event loops hasta el shutdown
+///
+/// Deja el listener de nuevo en modo bloqueante al terminar (lo necesitan
+/// el modelo de threads y el handoff de SIGUSR2).
+pub fn run(listener: &TcpListener, state: SharedState, pool: &ThreadPool, threads: usize) -> io::Result<()> {
+    println!("[*] Event loop: {} threads multiplexan las conexiones (poll)\n", threads);
+    listener.set_nonblocking(true)?;
+
+    thread::scope(|scope| {
+        for i in 0..threads {
+            let state = state.clone();
+            thread::Builder::new()
+                .name(format!("event-loop-{}", i))
+                .spawn_scoped(scope, move || event_loop(listener, state, pool))?;
+        }
+        Ok::<_, io::Error>(())
+    })?;
+
+    listener.set_nonblocking(false)
+}
+
+/// Un event loop: acepta del listener compartido y lee los requests de sus clientes
+///
+/// Varios loops comparten el listener; los que despiertan sin conexión
+/// para aceptar reciben `WouldBlock` y siguen.
+fn event_loop(listener: &TcpListener, state: SharedState, pool: &ThreadPool) {
+    let mut pending: Vec<Pending> = Vec::new();
+    let mut fds: Vec<ffi::PollFd> = Vec::new();
+
+    while !state.shutdown.load(Ordering::SeqCst) {
+        fds.clear();
+        fds.extend(std::iter::once(listener.as_raw_fd())
+            .chain(pending.iter().map(|conn| conn.stream.as_raw_fd()))
+            .map(|fd| ffi::PollFd { fd, events: ffi::POLLIN, revents: 0 }));
+
+        // Sin actividad (timeout o señal) ningún revents queda levantado,
+        // pero igual se revisan los clientes que pasaron el timeout
+        match poll(&mut fds, POLL_TIMEOUT) {
+            Ok(_) => {}
+            Err(e) if e.kind() == ErrorKind::Interrupted => {}
+            Err(e) => {
+                eprintln!("   ❌ Error en poll: {}", e);
+                break;
+            }
+        }
+
+        // De atrás hacia adelante: swap_remove trae un cliente ya revisado
+        for i in (1..fds.len()).rev() {
+            if fds[i].revents == 0 {
+                continue;
+            }
+            if let Some(outcome) = pending[i - 1].read_available(&state) {
+                hand_off(pending.swap_remove(i - 1), outcome, &state, pool);
+            }
+        }
+
+        if fds[0].revents != 0 {
+            accept_ready(listener, &state, pool, &mut pending);
+        }
+
+        expire(&mut pending, &state, pool);
+    }
+
+    if !pending.is_empty() && state.config.read().unwrap().log_enabled("info") {
+        println!("   ✅ Event loop: {} conexiones sin request completo cerradas", pending.len());
+    }
+}
+
+/// Acepta todas las conexiones que esperan en el listener
+fn accept_ready(listener: &TcpListener, state: &SharedState, pool: &ThreadPool, pending: &mut Vec<Pending>) {
+    loop {
+        let stream = match listener.accept() {
+            Ok((stream, _)) => stream,
+            Err(e) if e.kind() == ErrorKind::WouldBlock => return,
+            Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => {
+                eprintln!("   ❌ Error al aceptar conexión: {}", e);
+                return;
+            }
+        };
+
+        if !Server::admit(&stream, state) {
+            continue;
+        }
+        if state.tls.is_some() {
+            Server::dispatch(stream, state.clone(), pool);
+            continue;
+        }
+        if Server::shed_overload(&stream, state, pool) {
+            continue;
+        }
+        let Some(slot) = Server::reserve_slot(&stream, state) else {
+            continue;
+        };
+        if let Err(e) = stream.set_nonblocking(true) {
+            eprintln!("   ❌ Conexión descartada: {}", e);
+            continue;
+        }
+
+        let (limits, transfer, proxy_protocol) = {
+            let config = state.config.read().unwrap();
+            (request_limits(&config), TransferLimits::from_config(&config), config.proxy_protocol)
+        };
+        pending.push(Pending {
+            stream,
+            slot,
+            meter: state.metrics.open_connection(),
+            parser: RequestParser::pooled(limits, &state.buffers),
+            received: 0,
+            start: Instant::now(),
+            guard: TransferGuard::new(transfer, Instant::now()),
+            last_read: Instant::now(),
+            proxy: proxy_protocol.then(HeaderReader::new),
+            client: None,
+        });
+    }
+}
+
+/// Corta a los clientes lentos y responde 408 a los que dejaron de enviar
+///
+/// El timeout de lectura es el equivalente de `SO_RCVTIMEO` en el modelo de
+/// threads: cuenta desde la última lectura, no desde que se aceptó la
+/// conexión. El plazo de headers y la tasa mínima se revisan también acá
+/// porque un cliente que no envía nada no genera lecturas.
+fn expire(pending: &mut Vec<Pending>, state: &SharedState, pool: &ThreadPool) {
+    let read_timeout = state.config.read().unwrap().read_timeout();
+    for i in (0..pending.len()).rev() {
+        let outcome = pending[i].check_progress().or_else(|| {
+            let idle = pending[i].last_read.elapsed();
+            read_timeout.filter(|timeout| idle >= *timeout).map(|_| ReadOutcome::timed_out())
+        });
+        if let Some(outcome) = outcome {
+            hand_off(pending.swap_remove(i), outcome, state, pool);
+        }
+    }
+}
+
+/// Entrega una conexión con su request leído al pool de conexiones
+fn hand_off(conn: Pending, outcome: ReadOutcome, state: &SharedState, pool: &ThreadPool) {
+    match outcome {
+        ReadOutcome::Closed => return,
+        ReadOutcome::TooSlow(slow) => {
+            Server::drop_slow_client(&conn.stream, state, &slow);
+            return;
+        }
+        _ => {}
+    }
+    // El worker escribe la respuesta (y descarta lo no leído) bloqueando
+    let blocking = conn.stream.set_nonblocking(false)
+        .and_then(|_| apply_timeouts(&conn.stream, &state.config.read().unwrap()));
+    if let Err(e) = blocking {
+        eprintln!("   ❌ Conexión descartada: {}", e);
+        return;
+    }
+
+    let Pending { stream, slot, meter, start, client, .. } = conn;
+    Server::submit(pool, state.clone(), move |state| {
+        let _slot = slot;
+        let watch = state.reaper.watch(&stream);
+        let conn = Connection::from(stream).with_meter(meter).with_idle_watch(watch).with_client_addr(client);
+        Server::respond(conn, state, outcome, start)
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_poll_reports_readable_sockets() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut fds = [ffi::PollFd { fd: listener.as_raw_fd(), events: ffi::POLLIN, revents: 0 }];
+        assert_eq!(poll(&mut fds, Duration::from_millis(10)).unwrap(), 0);
+
+        let _client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        assert_eq!(poll(&mut fds, Duration::from_secs(5)).unwrap(), 1);
+        assert_ne!(fds[0].revents & ffi::POLLIN, 0);
+    }
+}
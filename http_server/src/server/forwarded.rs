@@ -0,0 +1,124 @@
+//! # X-Forwarded-For de Proxies Confiables
+//! src/server/forwarded.rs
+//!
+//! Detrás de un proxy HTTP (nginx, un balanceador) todos los requests llegan
+//! desde la IP del proxy, que informa la del cliente en `X-Forwarded-For`.
+//! Ese header lo puede escribir cualquiera, así que solo se cree cuando la
+//! conexión viene de un proxy listado en `--trusted-proxies`:
+//!
+//! ```text
+//! X-Forwarded-For: 203.0.113.7, 10.0.0.5
+//!                  ^ cliente    ^ proxy intermedio (confiable)
+//! ```
+//!
+//! Cada proxy agrega al final la IP de quien le habló, por eso la lista se
+//! recorre de derecha a izquierda saltando los proxies confiables: la
+//! primera IP que no es de uno es el cliente. Lo que esté más a la izquierda
+//! lo escribió el propio cliente y no se mira.
+//!
+//! El rate limiting, los bloqueos de administración y el access log usan la
+//! IP obtenida. El filtro de IPs se evalúa al aceptar, con la del socket.
+
+use crate::http::request::Request;
+use crate::server::ip_filter::{parse_list, Cidr};
+use std::net::IpAddr;
+
+/// Rangos de los proxies cuyo `X-Forwarded-For` se cree
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TrustedProxies {
+    proxies: Vec<Cidr>,
+}
+
+impl TrustedProxies {
+    /// Construye la lista a partir de rangos CIDR separados por comas
+    pub fn parse(list: &str) -> Result<Self, String> {
+        Ok(Self { proxies: parse_list(list)? })
+    }
+
+    /// Indica si no hay proxies confiables (se ignora `X-Forwarded-For`)
+    pub fn is_empty(&self) -> bool {
+        self.proxies.is_empty()
+    }
+
+    /// Indica si `ip` es de un proxy confiable
+    pub fn trusts(&self, ip: IpAddr) -> bool {
+        self.proxies.iter().any(|cidr| cidr.contains(ip))
+    }
+
+    /// IP del cliente de un request que llegó desde `peer`
+    ///
+    /// Si `peer` no es confiable, o el header falta o trae algo que no es
+    /// una IP, retorna `peer`. Si todas las IPs son de proxies confiables,
+    /// la de más a la izquierda.
+    ///
+    /// # Ejemplo
+    /// ```
+    /// use http_server::server::forwarded::TrustedProxies;
+    /// use std::net::IpAddr;
+    ///
+    /// let trusted = TrustedProxies::parse("10.0.0.0/8").unwrap();
+    /// let ip = |s: &str| s.parse::<IpAddr>().unwrap();
+    /// let header = Some("198.51.100.9, 203.0.113.7, 10.0.0.5");
+    ///
+    /// assert_eq!(trusted.resolve(ip("10.0.0.2"), header), ip("203.0.113.7"));
+    /// // Desde fuera de los proxies el header no se cree
+    /// assert_eq!(trusted.resolve(ip("192.0.2.1"), header), ip("192.0.2.1"));
+    /// ```
+    pub fn resolve<'a>(&self, peer: IpAddr, forwarded_for: impl IntoIterator<Item = &'a str>) -> IpAddr {
+        if !self.trusts(peer) {
+            return peer;
+        }
+        let mut client = peer;
+        // Varios headers equivalen a uno con las listas unidas, en orden
+        let hops: Vec<&str> = forwarded_for.into_iter().flat_map(|value| value.split(',')).collect();
+        for hop in hops.iter().rev() {
+            let Ok(ip) = hop.trim().parse::<IpAddr>() else {
+                return client;
+            };
+            client = ip;
+            if !self.trusts(ip) {
+                break;
+            }
+        }
+        client
+    }
+
+    /// IP del cliente de `request`, según sus headers `X-Forwarded-For`
+    pub fn client_ip(&self, peer: IpAddr, request: &Request) -> IpAddr {
+        if self.is_empty() {
+            return peer;
+        }
+        self.resolve(peer, request.headers().get_all("X-Forwarded-For"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_resolve_skips_trusted_hops() {
+        let trusted = TrustedProxies::parse("10.0.0.0/8, 127.0.0.1").unwrap();
+        let peer = ip("127.0.0.1");
+
+        assert_eq!(trusted.resolve(peer, None), peer);
+        assert_eq!(trusted.resolve(peer, Some("10.1.1.1, 10.2.2.2")), ip("10.1.1.1"));
+        assert_eq!(trusted.resolve(peer, ["203.0.113.7", "10.0.0.5"]), ip("203.0.113.7"));
+        assert_eq!(trusted.resolve(peer, Some("2001:db8::7")), ip("2001:db8::7"));
+        // Una entrada inválida corta en el último salto válido
+        assert_eq!(trusted.resolve(peer, Some("203.0.113.7, unknown, 10.0.0.5")), ip("10.0.0.5"));
+        assert_eq!(trusted.resolve(peer, Some("garbage")), peer);
+    }
+
+    #[test]
+    fn test_empty_list_trusts_nobody() {
+        let trusted = TrustedProxies::parse(" ").unwrap();
+        assert!(trusted.is_empty());
+        assert_eq!(trusted.resolve(ip("127.0.0.1"), Some("203.0.113.7")), ip("127.0.0.1"));
+        assert!(TrustedProxies::parse("10.0.0.0/99").is_err());
+    }
+}
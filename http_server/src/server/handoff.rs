@@ -0,0 +1,276 @@
+//! # Reinicio sin Cortes (Handoff del Listener)
+//! src/server/handoff.rs
+//!
+//! Con `SIGUSR2` el servidor se reemplaza por una nueva instancia del
+//! ejecutable (por ejemplo, recién compilado) sin cerrar el puerto:
+//!
+//! 1. El proceso viejo deja de aceptar conexiones; las nuevas esperan en el
+//!    backlog del socket, que sigue abierto.
+//! 2. Termina las conexiones en curso y detiene los workers de jobs; los
+//!    jobs en cola quedan en el storage y los que siguen corriendo al
+//!    vencer `--restart-drain-secs` vuelven a la cola.
+//! 3. Lanza el ejecutable que está en la ruta original (el recompilado, no
+//!    el borrado) con los mismos argumentos. El hijo hereda los
+//!    listeners (se limpia `FD_CLOEXEC`) y recibe sus números de descriptor
+//!    en `REDUNIX_LISTEN_FD` (separados por coma, uno por dirección de
+//!    `--host`), junto con un pipe (`REDUNIX_READY_FD`) por el que avisa que
+//!    ya está listo.
+//! 4. Cuando el hijo avisa, el proceso viejo termina. Si el hijo falla o no
+//!    avisa a tiempo, el viejo sigue atendiendo como si nada.
+//!
+//! Todo usa FFI directo a libc (`fcntl`), sin crates externos.
+
+use std::io;
+use std::net::TcpListener;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Variable con los descriptores de los listeners heredados (ej: `5,6`)
+pub const LISTEN_FD_ENV: &str = "REDUNIX_LISTEN_FD";
+
+/// Variable con el descriptor del pipe de "listo"
+pub const READY_FD_ENV: &str = "REDUNIX_READY_FD";
+
+/// Mensaje que el hijo escribe en el pipe de "listo"
+const READY_MESSAGE: &str = "READY";
+
+#[cfg(unix)]
+mod ffi {
+    pub const F_GETFD: i32 = 1;
+    pub const F_SETFD: i32 = 2;
+    pub const FD_CLOEXEC: i32 = 1;
+
+    extern "C" {
+        /// `int fcntl(int fd, int cmd, ...)`
+        pub fn fcntl(fd: i32, cmd: i32, ...) -> i32;
+    }
+}
+
+/// Marca si `fd` se hereda o no al hacer exec (`FD_CLOEXEC`)
+#[cfg(unix)]
+pub(super) fn set_inheritable(fd: i32, inheritable: bool) -> io::Result<()> {
+    unsafe {
+        let flags = ffi::fcntl(fd, ffi::F_GETFD);
+        if flags < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let flags = if inheritable { flags & !ffi::FD_CLOEXEC } else { flags | ffi::FD_CLOEXEC };
+        if ffi::fcntl(fd, ffi::F_SETFD, flags) < 0 {
+            return Err(io::Error::last_os_error());
+        }
+    }
+    Ok(())
+}
+
+/// Lee y consume una variable con números de descriptor separados por coma
+///
+/// Se borra para que no la hereden los procesos que lance este.
+#[cfg(unix)]
+fn take_fds_env(name: &str) -> io::Result<Vec<i32>> {
+    let Some(value) = std::env::var_os(name) else {
+        return Ok(Vec::new());
+    };
+    std::env::remove_var(name);
+
+    value.to_str()
+        .and_then(|v| v.split(',').map(|fd| fd.trim().parse::<i32>().ok().filter(|fd| *fd > 2)).collect())
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, format!("Invalid {}: {:?}", name, value)))
+}
+
+/// Indica si este proceso es el nuevo de un reinicio sin cortes
+/// 
+/// Hay que consultarlo antes de `inherited_listeners`, que consume la
+/// variable.
+pub fn is_restart() -> bool {
+    std::env::var_os(LISTEN_FD_ENV).is_some()
+}
+
+/// Listeners heredados del proceso anterior (vacío si no viene de un handoff)
+#[cfg(unix)]
+pub fn inherited_listeners() -> io::Result<Vec<TcpListener>> {
+    use std::os::unix::io::FromRawFd;
+
+    take_fds_env(LISTEN_FD_ENV)?
+        .into_iter()
+        .map(|fd| {
+            let listener = unsafe { TcpListener::from_raw_fd(fd) };
+            // Que no lo hereden otros procesos lanzados por este
+            set_inheritable(fd, false)?;
+            listener.local_addr()?;
+            Ok(listener)
+        })
+        .collect()
+}
+
+#[cfg(not(unix))]
+pub fn inherited_listeners() -> io::Result<Vec<TcpListener>> {
+    Ok(Vec::new())
+}
+
+/// Avisa al proceso anterior que este ya está atendiendo
+///
+/// No hace nada si el proceso no viene de un handoff.
+#[cfg(unix)]
+pub fn notify_ready() -> io::Result<()> {
+    use std::io::Write;
+    use std::os::unix::io::FromRawFd;
+
+    let Some(&fd) = take_fds_env(READY_FD_ENV)?.first() else {
+        return Ok(());
+    };
+    let mut pipe = unsafe { std::fs::File::from_raw_fd(fd) };
+    writeln!(pipe, "{}", READY_MESSAGE)
+}
+
+#[cfg(not(unix))]
+pub fn notify_ready() -> io::Result<()> {
+    Ok(())
+}
+
+/// Ejecutable a lanzar como proceso nuevo
+///
+/// En Linux `current_exe` lee `/proc/self/exe`, que después de recompilar
+/// apunta al archivo viejo ya borrado (`.../http_server (deleted)`). El
+/// proceso nuevo tiene que ser el ejecutable que ahora está en esa ruta.
+#[cfg(unix)]
+pub(super) fn successor_executable() -> io::Result<PathBuf> {
+    std::env::current_exe().map(without_deleted_suffix)
+}
+
+#[cfg(unix)]
+fn without_deleted_suffix(exe: PathBuf) -> PathBuf {
+    match exe.to_str().and_then(|path| path.strip_suffix(" (deleted)")) {
+        Some(path) => PathBuf::from(path),
+        None => exe,
+    }
+}
+
+/// Lanza el nuevo proceso con `listeners` heredados y espera su aviso
+///
+/// Retorna el PID del hijo cuando avisa que está listo. Si no avisa dentro
+/// de `timeout` (o termina antes) lo mata y retorna el error.
+#[cfg(unix)]
+pub fn spawn_successor(listeners: &[TcpListener], timeout: Duration) -> Result<u32, String> {
+    use std::io::{BufRead, BufReader};
+    use std::os::unix::io::AsRawFd;
+    use std::process::Command;
+    use std::sync::mpsc;
+
+    let exe = successor_executable().map_err(|e| format!("Cannot locate executable: {}", e))?;
+    let (reader, writer) = io::pipe().map_err(|e| format!("Cannot create ready pipe: {}", e))?;
+
+    let listen_fds: Vec<i32> = listeners.iter().map(AsRawFd::as_raw_fd).collect();
+    let ready_fd = writer.as_raw_fd();
+    for &fd in &listen_fds {
+        set_inheritable(fd, true).map_err(|e| format!("Cannot share listener: {}", e))?;
+    }
+    set_inheritable(ready_fd, true).map_err(|e| format!("Cannot share ready pipe: {}", e))?;
+
+    let fd_list: Vec<String> = listen_fds.iter().map(i32::to_string).collect();
+    let spawned = Command::new(&exe)
+        .args(std::env::args_os().skip(1))
+        .env(LISTEN_FD_ENV, fd_list.join(","))
+        .env(READY_FD_ENV, ready_fd.to_string())
+        // El watchdog de systemd pasa al proceso nuevo (ver `systemd`)
+        .env_remove("WATCHDOG_PID")
+        .spawn();
+
+    // Solo el hijo debe conservar el extremo de escritura: si muere, el
+    // lector ve EOF
+    for &fd in &listen_fds {
+        let _ = set_inheritable(fd, false);
+    }
+    drop(writer);
+    let mut child = spawned.map_err(|e| format!("Cannot start {}: {}", exe.display(), e))?;
+
+    let (tx, rx) = mpsc::channel();
+    let waiter = std::thread::Builder::new().name("handoff-ready".to_string()).spawn(move || {
+        let mut line = String::new();
+        let _ = BufReader::new(reader).read_line(&mut line);
+        let _ = tx.send(line);
+    });
+    if let Err(e) = waiter {
+        let _ = child.kill();
+        let _ = child.wait();
+        return Err(format!("Cannot wait for the successor: {}", e));
+    }
+
+    match rx.recv_timeout(timeout) {
+        Ok(line) if line.trim() == READY_MESSAGE => Ok(child.id()),
+        outcome => {
+            let _ = child.kill();
+            let _ = child.wait();
+            Err(match outcome {
+                Ok(_) => format!("Process {} exited before becoming ready", child.id()),
+                Err(_) => format!("Process {} not ready after {:?}", child.id(), timeout),
+            })
+        }
+    }
+}
+
+#[cfg(not(unix))]
+pub fn spawn_successor(_listeners: &[TcpListener], _timeout: Duration) -> Result<u32, String> {
+    Err("Listener handoff is only supported on Unix".to_string())
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::io::{AsRawFd, IntoRawFd};
+
+    #[test]
+    fn test_successor_is_the_rebuilt_executable() {
+        assert_eq!(
+            without_deleted_suffix(PathBuf::from("/srv/bin/http_server (deleted)")),
+            PathBuf::from("/srv/bin/http_server")
+        );
+        assert_eq!(
+            without_deleted_suffix(PathBuf::from("/srv/bin/http_server")),
+            PathBuf::from("/srv/bin/http_server")
+        );
+        assert!(successor_executable().unwrap().exists());
+    }
+
+    #[test]
+    fn test_inheritable_flag_roundtrip() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let fd = listener.as_raw_fd();
+        let cloexec = || unsafe { ffi::fcntl(fd, ffi::F_GETFD) } & ffi::FD_CLOEXEC;
+
+        assert_eq!(cloexec(), ffi::FD_CLOEXEC);
+        set_inheritable(fd, true).unwrap();
+        assert_eq!(cloexec(), 0);
+        set_inheritable(fd, false).unwrap();
+        assert_eq!(cloexec(), ffi::FD_CLOEXEC);
+    }
+
+    #[test]
+    fn test_inherited_listener_and_ready_pipe_from_env() {
+        // Simula lo que recibe el hijo: fds duplicados de dos listeners y el pipe
+        let originals = [TcpListener::bind("127.0.0.1:0").unwrap(), TcpListener::bind("127.0.0.1:0").unwrap()];
+        let addrs: Vec<_> = originals.iter().map(|l| l.local_addr().unwrap()).collect();
+        let dups: Vec<String> = originals.iter().map(|l| l.try_clone().unwrap().into_raw_fd().to_string()).collect();
+        let (reader, writer) = io::pipe().unwrap();
+
+        std::env::set_var(LISTEN_FD_ENV, dups.join(","));
+        std::env::set_var(READY_FD_ENV, writer.into_raw_fd().to_string());
+
+        let inherited = inherited_listeners().unwrap();
+        let inherited_addrs: Vec<_> = inherited.iter().map(|l| l.local_addr().unwrap()).collect();
+        assert_eq!(inherited_addrs, addrs);
+        assert!(std::env::var_os(LISTEN_FD_ENV).is_none());
+
+        notify_ready().unwrap();
+        let mut line = String::new();
+        io::BufRead::read_line(&mut io::BufReader::new(reader), &mut line).unwrap();
+        assert_eq!(line.trim(), READY_MESSAGE);
+
+        // Sin variables no hay nada que heredar
+        assert!(inherited_listeners().unwrap().is_empty());
+        notify_ready().unwrap();
+
+        // Un fd inválido es un error, no un listener
+        std::env::set_var(LISTEN_FD_ENV, "stdin");
+        assert!(inherited_listeners().is_err());
+    }
+}
@@ -0,0 +1,158 @@
+//! # Filtro de IPs
+//! src/server/ip_filter.rs
+//!
+//! Listas de rangos CIDR permitidos y denegados, evaluadas al aceptar cada
+//! conexión (antes de leer el request). Se configuran como listas separadas
+//! por comas:
+//!
+//! - `--ip-allow 10.0.0.0/8,192.168.1.0/24`: solo se aceptan esas redes
+//! - `--ip-deny 10.0.0.13`: se rechazan aunque estén permitidas
+//!
+//! La lista de denegados gana sobre la de permitidos. Una lista de
+//! permitidos vacía acepta cualquier IP. Una IP sin `/` equivale a `/32`
+//! (o `/128` en IPv6), y las IPv4 mapeadas en IPv6 (`::ffff:a.b.c.d`) se
+//! evalúan como IPv4.
+
+use std::net::IpAddr;
+
+/// Rango de direcciones en notación CIDR
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cidr {
+    network: IpAddr,
+    prefix: u8,
+}
+
+impl Cidr {
+    /// Parsea `a.b.c.d/n`, `x::y/n` o una IP sola
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let spec = spec.trim();
+        let (addr, prefix) = match spec.split_once('/') {
+            Some((addr, prefix)) => (addr, Some(prefix)),
+            None => (spec, None),
+        };
+
+        let network: IpAddr = addr.parse()
+            .map_err(|_| format!("Invalid CIDR '{}': bad address", spec))?;
+        let network = network.to_canonical();
+        let max_prefix = if network.is_ipv4() { 32 } else { 128 };
+
+        let prefix = match prefix {
+            Some(p) => p.parse::<u8>()
+                .ok()
+                .filter(|p| *p <= max_prefix)
+                .ok_or_else(|| format!("Invalid CIDR '{}': prefix must be 0-{}", spec, max_prefix))?,
+            None => max_prefix,
+        };
+
+        Ok(Self { network, prefix })
+    }
+
+    /// Indica si `ip` pertenece al rango
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        match (self.network, ip.to_canonical()) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                let mask = u32::MAX.checked_shl(32 - self.prefix as u32).unwrap_or(0);
+                u32::from(net) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                let mask = u128::MAX.checked_shl(128 - self.prefix as u32).unwrap_or(0);
+                u128::from(net) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Listas de rangos permitidos y denegados
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IpFilter {
+    allow: Vec<Cidr>,
+    deny: Vec<Cidr>,
+}
+
+impl IpFilter {
+    /// Construye el filtro a partir de listas separadas por comas
+    pub fn parse(allow: &str, deny: &str) -> Result<Self, String> {
+        Ok(Self {
+            allow: parse_list(allow)?,
+            deny: parse_list(deny)?,
+        })
+    }
+
+    /// Indica si no hay ninguna regla configurada
+    pub fn is_empty(&self) -> bool {
+        self.allow.is_empty() && self.deny.is_empty()
+    }
+
+    /// Indica si se acepta una conexión desde `ip`
+    pub fn allows(&self, ip: IpAddr) -> bool {
+        if self.deny.iter().any(|cidr| cidr.contains(ip)) {
+            return false;
+        }
+        self.allow.is_empty() || self.allow.iter().any(|cidr| cidr.contains(ip))
+    }
+}
+
+/// Parsea una lista `cidr,cidr,...` (vacía = sin reglas)
+pub fn parse_list(list: &str) -> Result<Vec<Cidr>, String> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|spec| !spec.is_empty())
+        .map(Cidr::parse)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_cidr() {
+        assert!(Cidr::parse("10.0.0.0/8").is_ok());
+        assert!(Cidr::parse("192.168.1.7").is_ok());
+        assert!(Cidr::parse("fd00::/8").is_ok());
+        assert!(Cidr::parse("10.0.0.0/33").is_err());
+        assert!(Cidr::parse("10.0.0/8").is_err());
+        assert!(Cidr::parse("campus").is_err());
+    }
+
+    #[test]
+    fn test_cidr_contains() {
+        let net = Cidr::parse("10.20.0.0/16").unwrap();
+        assert!(net.contains(ip("10.20.3.4")));
+        assert!(!net.contains(ip("10.21.0.1")));
+        assert!(net.contains(ip("::ffff:10.20.0.1")));
+        assert!(!net.contains(ip("fd00::1")));
+
+        assert!(Cidr::parse("0.0.0.0/0").unwrap().contains(ip("8.8.8.8")));
+        assert!(Cidr::parse("fd00::/8").unwrap().contains(ip("fd12::1")));
+        assert!(Cidr::parse("127.0.0.1").unwrap().contains(ip("127.0.0.1")));
+        assert!(!Cidr::parse("127.0.0.1").unwrap().contains(ip("127.0.0.2")));
+    }
+
+    #[test]
+    fn test_filter_deny_wins_over_allow() {
+        let filter = IpFilter::parse("10.0.0.0/8, 127.0.0.1", "10.0.0.13").unwrap();
+        assert!(filter.allows(ip("10.1.2.3")));
+        assert!(filter.allows(ip("127.0.0.1")));
+        assert!(!filter.allows(ip("10.0.0.13")));
+        assert!(!filter.allows(ip("192.168.0.1")));
+    }
+
+    #[test]
+    fn test_empty_filter_allows_all() {
+        let filter = IpFilter::parse("", " ").unwrap();
+        assert!(filter.is_empty());
+        assert!(filter.allows(ip("8.8.8.8")));
+
+        let deny_only = IpFilter::parse("", "8.8.8.0/24").unwrap();
+        assert!(!deny_only.allows(ip("8.8.8.8")));
+        assert!(deny_only.allows(ip("1.1.1.1")));
+
+        assert!(IpFilter::parse("10.0.0.0/8,nope", "").is_err());
+    }
+}
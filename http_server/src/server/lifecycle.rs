@@ -0,0 +1,112 @@
+//! # Ciclo de Vida del Servidor
+//! src/server/lifecycle.rs
+//!
+//! Fase en la que está el servidor, compartida entre el accept loop y los
+//! handlers de administración (`GET /admin/state` la reporta):
+//!
+//! ```text
+//! starting ──▶ running ── POST /admin/shutdown ──▶ draining ──▶ (el proceso termina)
+//! ```
+//!
+//! `starting` dura hasta que el accept loop tiene el listener, TLS y el
+//! access log listos.
+//!
+//! Al pasar a `draining` el accept loop deja de aceptar conexiones,
+//! termina las que están en curso, espera a los jobs que están corriendo
+//! hasta que vence el plazo de gracia y deja el storage de jobs escrito
+//! (los que no terminaron vuelven a la cola para el próximo arranque).
+
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Fase del servidor
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Phase {
+    /// Preparando el listener y los recursos de arranque
+    Starting,
+
+    /// Aceptando y atendiendo conexiones
+    Running,
+
+    /// Terminando lo que está en curso antes de salir
+    Draining,
+}
+
+/// Fase actual y, al drenar, hasta cuándo se espera a los jobs
+#[derive(Debug, Default)]
+pub struct Lifecycle {
+    running: AtomicBool,
+    drain_deadline: Mutex<Option<Instant>>,
+}
+
+impl Lifecycle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fase actual
+    pub fn phase(&self) -> Phase {
+        match *self.drain_deadline.lock().unwrap() {
+            Some(_) => Phase::Draining,
+            None if self.running.load(Ordering::SeqCst) => Phase::Running,
+            None => Phase::Starting,
+        }
+    }
+
+    /// Marca que el servidor ya acepta conexiones
+    pub fn mark_running(&self) {
+        self.running.store(true, Ordering::SeqCst);
+    }
+
+    /// Pasa a `draining` con `grace` de plazo
+    ///
+    /// Retorna `false` si ya estaba drenando (se conserva el primer plazo).
+    ///
+    /// # Ejemplo
+    /// ```
+    /// use http_server::server::lifecycle::{Lifecycle, Phase};
+    /// use std::time::Duration;
+    ///
+    /// let lifecycle = Lifecycle::new();
+    /// lifecycle.mark_running();
+    /// assert!(lifecycle.begin_drain(Duration::from_secs(5)));
+    /// assert!(!lifecycle.begin_drain(Duration::from_secs(60)));
+    /// assert_eq!(lifecycle.phase(), Phase::Draining);
+    /// ```
+    pub fn begin_drain(&self, grace: Duration) -> bool {
+        let mut deadline = self.drain_deadline.lock().unwrap();
+        if deadline.is_some() {
+            return false;
+        }
+        *deadline = Some(Instant::now() + grace);
+        true
+    }
+
+    /// Hasta cuándo se espera a los jobs, si se está drenando
+    pub fn drain_deadline(&self) -> Option<Instant> {
+        *self.drain_deadline.lock().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_drain_keeps_first_deadline() {
+        let lifecycle = Lifecycle::new();
+        assert_eq!(lifecycle.phase(), Phase::Starting);
+        lifecycle.mark_running();
+        assert_eq!(lifecycle.phase(), Phase::Running);
+        assert_eq!(lifecycle.drain_deadline(), None);
+
+        lifecycle.begin_drain(Duration::from_millis(100));
+        let deadline = lifecycle.drain_deadline().unwrap();
+        lifecycle.begin_drain(Duration::from_secs(3600));
+        assert_eq!(lifecycle.drain_deadline(), Some(deadline));
+        assert_eq!(serde_json::json!(lifecycle.phase()), "draining");
+    }
+}
@@ -0,0 +1,61 @@
+//! # Cola de Conexiones del Listener
+//! src/server/listen.rs
+//!
+//! `TcpListener::bind` de std deja el backlog en 128: las conexiones que
+//! completan el handshake mientras los accept loops están ocupados esperan
+//! ahí, y las que no entran se descartan (el cliente reintenta el SYN). Con
+//! `--listen-backlog` se vuelve a llamar a `listen` sobre el socket ya
+//! abierto, que en Linux y los BSD solo actualiza el tamaño de la cola.
+//!
+//! El kernel recorta el valor a `net.core.somaxconn`.
+
+use std::io;
+use std::net::TcpListener;
+
+#[cfg(unix)]
+mod ffi {
+    extern "C" {
+        /// `int listen(int sockfd, int backlog)`
+        pub fn listen(fd: i32, backlog: i32) -> i32;
+    }
+}
+
+/// Fija el tamaño de la cola de conexiones pendientes de `listener`
+#[cfg(unix)]
+pub fn set_backlog(listener: &TcpListener, backlog: u32) -> io::Result<()> {
+    use std::os::unix::io::AsRawFd;
+
+    let backlog = backlog.min(i32::MAX as u32) as i32;
+    if unsafe { ffi::listen(listener.as_raw_fd(), backlog) } < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+pub fn set_backlog(_listener: &TcpListener, _backlog: u32) -> io::Result<()> {
+    Ok(())
+}
+
+/// Máximo que acepta el kernel (`net.core.somaxconn`), si se puede leer
+pub fn somaxconn() -> Option<u32> {
+    std::fs::read_to_string("/proc/sys/net/core/somaxconn").ok()?.trim().parse().ok()
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::net::TcpStream;
+
+    #[test]
+    fn test_backlog_can_change_on_a_listening_socket() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        set_backlog(&listener, 16).unwrap();
+        set_backlog(&listener, u32::MAX).unwrap();
+
+        // Sigue escuchando en el mismo puerto
+        let addr = listener.local_addr().unwrap();
+        let _client = TcpStream::connect(addr).unwrap();
+        assert!(listener.accept().is_ok());
+    }
+}
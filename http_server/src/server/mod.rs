@@ -11,6 +11,30 @@
 //! una conexión a la vez. Luego la haremos concurrente.
 
 pub mod tcp;
+pub mod builder;
+pub mod access_log;
+pub mod signals;
+pub mod systemd;
+pub mod auth;
+pub mod handoff;
+pub mod lifecycle;
+pub mod listen;
+pub mod prefork;
+pub mod ip_filter;
+pub mod slow_client;
+pub mod reaper;
+pub mod proxy_protocol;
+pub mod forwarded;
+pub mod cors;
+pub mod readiness;
+pub mod daemon;
+pub mod websocket;
+pub mod tls;
+pub mod dashboard;
+pub mod static_files;
+#[cfg(unix)]
+pub mod event_loop;
 
 // Re-exportar para facilitar el uso
-pub use tcp::Server;
\ No newline at end of file
+pub use tcp::Server;
+pub use builder::ServerBuilder;
\ No newline at end of file
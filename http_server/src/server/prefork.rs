@@ -0,0 +1,329 @@
+//! # Modo Multi-proceso (`--processes N`)
+//! src/server/prefork.rs
+//!
+//! Alternativa al modelo de threads para comparar concurrencia basada en
+//! procesos: un supervisor lanza N procesos worker (el mismo ejecutable con
+//! los mismos argumentos) y cada uno abre su propio listener en el mismo
+//! puerto con `SO_REUSEPORT`. El kernel reparte las conexiones entrantes
+//! entre los listeners.
+//!
+//! ```text
+//! supervisor (no atiende)
+//!   ├─ worker 0: bind :8080 (SO_REUSEPORT) ─┐
+//!   ├─ worker 1: bind :8080 (SO_REUSEPORT) ─┼─ el kernel reparte las conexiones
+//!   └─ worker 2: bind :8080 (SO_REUSEPORT) ─┘
+//! ```
+//!
+//! Cada worker es un servidor completo con sus propios pools, métricas y
+//! jobs: `GET /metrics` reporta las del proceso que atendió la conexión
+//! (sección `process`) y `X-Worker-Pid` identifica a cuál llegó cada
+//! request. El storage de jobs y el access log llevan el índice del worker
+//! como sufijo para que los procesos no escriban el mismo archivo.
+//!
+//! El supervisor reenvía `SIGHUP` a los workers y relanza a los que mueren
+//! con error. Si un worker muere apenas arranca (ej: el puerto está
+//! ocupado) el supervisor detiene a todos y termina con error.
+//!
+//! `SO_REUSEPORT` con reparto de carga es propio de Linux; todo usa FFI
+//! directo a libc (`socket`, `setsockopt`, `bind`, `listen`, `kill`).
+
+use crate::config::Config;
+use std::io;
+use std::net::{SocketAddr, TcpListener};
+use std::time::Duration;
+
+/// Variable con `índice/total` que identifica a un proceso worker
+pub const WORKER_ENV: &str = "REDUNIX_WORKER";
+
+/// Conexiones pendientes por listener (el mismo valor que usa std)
+const LISTEN_BACKLOG: i32 = 128;
+
+/// Un worker que muere antes de esto se considera un fallo de arranque
+const MIN_UPTIME: Duration = Duration::from_secs(2);
+
+/// Cada cuánto el supervisor revisa a sus workers
+const SUPERVISE_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Identidad de un proceso worker dentro del grupo
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Worker {
+    pub index: usize,
+    pub count: usize,
+}
+
+impl Worker {
+    /// Parsea el valor de `REDUNIX_WORKER` (`índice/total`)
+    ///
+    /// # Ejemplo
+    /// ```
+    /// use http_server::server::prefork::Worker;
+    ///
+    /// assert_eq!(Worker::parse("1/4"), Some(Worker { index: 1, count: 4 }));
+    /// assert_eq!(Worker::parse("4/4"), None);
+    /// ```
+    pub fn parse(value: &str) -> Option<Self> {
+        let (index, count) = value.split_once('/')?;
+        let worker = Worker { index: index.parse().ok()?, count: count.parse().ok()? };
+        (worker.index < worker.count).then_some(worker)
+    }
+
+    /// Worker que es este proceso, o `None` si no lo lanzó un supervisor
+    pub fn current() -> Option<Self> {
+        std::env::var(WORKER_ENV).ok().and_then(|value| Self::parse(&value))
+    }
+
+    /// Separa los archivos que cada worker escribe (storage de jobs y access log)
+    ///
+    /// `./data/jobs.json` pasa a `./data/jobs.json.1` para el worker 1.
+    pub fn isolate_files(&self, config: &mut Config) {
+        config.jobs_storage_path = format!("{}.{}", config.jobs_storage_path, self.index);
+        if let Some(path) = &mut config.access_log {
+            path.push_str(&format!(".{}", self.index));
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod ffi {
+    use std::os::raw::c_void;
+
+    pub const SOCK_STREAM: i32 = 1;
+    pub const SOL_SOCKET: i32 = 1;
+    pub const SO_REUSEADDR: i32 = 2;
+    pub const SO_REUSEPORT: i32 = 15;
+    pub const AF_INET: u16 = 2;
+    pub const AF_INET6: u16 = 10;
+
+    /// `SOCK_CLOEXEC`: el listener no se hereda en un exec
+    pub const SOCK_CLOEXEC: i32 = 0o2000000;
+
+    pub const SIGHUP: i32 = 1;
+    pub const SIGTERM: i32 = 15;
+
+    /// `PR_SET_PDEATHSIG` de prctl(2)
+    pub const PR_SET_PDEATHSIG: i32 = 1;
+
+    /// `struct sockaddr_in` (puerto y dirección en orden de red)
+    #[repr(C)]
+    pub struct SockAddrIn {
+        pub family: u16,
+        pub port: u16,
+        pub addr: [u8; 4],
+        pub zero: [u8; 8],
+    }
+
+    /// `struct sockaddr_in6`
+    #[repr(C)]
+    pub struct SockAddrIn6 {
+        pub family: u16,
+        pub port: u16,
+        pub flowinfo: u32,
+        pub addr: [u8; 16],
+        pub scope_id: u32,
+    }
+
+    extern "C" {
+        pub fn socket(domain: i32, kind: i32, protocol: i32) -> i32;
+        pub fn setsockopt(fd: i32, level: i32, name: i32, value: *const c_void, len: u32) -> i32;
+        pub fn bind(fd: i32, addr: *const c_void, len: u32) -> i32;
+        pub fn listen(fd: i32, backlog: i32) -> i32;
+        pub fn kill(pid: i32, sig: i32) -> i32;
+        pub fn prctl(option: i32, arg: u64, ...) -> i32;
+    }
+}
+
+/// Falla con el error del sistema si `ret` es negativo
+#[cfg(target_os = "linux")]
+fn check(ret: i32) -> io::Result<i32> {
+    if ret < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(ret)
+}
+
+/// Abre un listener en `addr` con `SO_REUSEPORT`
+///
+/// Varios procesos (o sockets) pueden escuchar en el mismo puerto; el
+/// kernel reparte las conexiones entre ellos.
+#[cfg(target_os = "linux")]
+pub fn bind_reuseport(addr: SocketAddr) -> io::Result<TcpListener> {
+    use std::os::raw::c_void;
+    use std::os::unix::io::FromRawFd;
+
+    let family = if addr.is_ipv4() { ffi::AF_INET } else { ffi::AF_INET6 };
+    let fd = check(unsafe { ffi::socket(family as i32, ffi::SOCK_STREAM | ffi::SOCK_CLOEXEC, 0) })?;
+    // Desde acá el listener es dueño del fd y lo cierra si algo falla
+    let listener = unsafe { TcpListener::from_raw_fd(fd) };
+
+    let enable: i32 = 1;
+    for option in [ffi::SO_REUSEADDR, ffi::SO_REUSEPORT] {
+        check(unsafe {
+            ffi::setsockopt(fd, ffi::SOL_SOCKET, option, &enable as *const i32 as *const c_void, 4)
+        })?;
+    }
+
+    let bound = match addr {
+        SocketAddr::V4(v4) => {
+            let raw = ffi::SockAddrIn {
+                family: ffi::AF_INET,
+                port: v4.port().to_be(),
+                addr: v4.ip().octets(),
+                zero: [0; 8],
+            };
+            unsafe { ffi::bind(fd, &raw as *const _ as *const c_void, std::mem::size_of_val(&raw) as u32) }
+        }
+        SocketAddr::V6(v6) => {
+            let raw = ffi::SockAddrIn6 {
+                family: ffi::AF_INET6,
+                port: v6.port().to_be(),
+                flowinfo: v6.flowinfo(),
+                addr: v6.ip().octets(),
+                scope_id: v6.scope_id(),
+            };
+            unsafe { ffi::bind(fd, &raw as *const _ as *const c_void, std::mem::size_of_val(&raw) as u32) }
+        }
+    };
+    check(bound)?;
+    check(unsafe { ffi::listen(fd, LISTEN_BACKLOG) })?;
+    Ok(listener)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn bind_reuseport(_addr: SocketAddr) -> io::Result<TcpListener> {
+    Err(io::Error::new(io::ErrorKind::Unsupported, "SO_REUSEPORT load balancing requires Linux"))
+}
+
+/// Lanza `processes` workers y los supervisa hasta que todos terminan
+///
+/// Retorna error si un worker no llega a arrancar; en ese caso detiene
+/// a los demás.
+#[cfg(target_os = "linux")]
+pub fn supervise(processes: usize) -> io::Result<()> {
+    use super::signals;
+    use std::os::unix::process::CommandExt;
+    use std::process::{Child, Command};
+    use std::time::Instant;
+
+    // Los workers relanzados usan el ejecutable actual aunque se haya recompilado
+    let exe = super::handoff::successor_executable()?;
+    let spawn = |index: usize| -> io::Result<(Child, Instant)> {
+        let mut command = Command::new(&exe);
+        command
+            .args(std::env::args_os().skip(1))
+            .env(WORKER_ENV, format!("{}/{}", index, processes));
+        // Si el supervisor muere, los workers reciben SIGTERM
+        unsafe {
+            command.pre_exec(|| {
+                check(ffi::prctl(ffi::PR_SET_PDEATHSIG, ffi::SIGTERM as u64)).map(|_| ())
+            });
+        }
+        Ok((command.spawn()?, Instant::now()))
+    };
+    let stop_all = |workers: &mut Vec<Option<(Child, Instant)>>| {
+        for (child, _) in workers.iter_mut().flatten() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    };
+
+    signals::install_reload_handler();
+    let mut workers = Vec::with_capacity(processes);
+    for index in 0..processes {
+        match spawn(index) {
+            Ok(worker) => workers.push(Some(worker)),
+            Err(e) => {
+                stop_all(&mut workers);
+                return Err(e);
+            }
+        }
+    }
+    let pids: Vec<String> = workers.iter().flatten().map(|(child, _)| child.id().to_string()).collect();
+    println!("[+] Supervisor {}: {} procesos worker (PIDs {})", std::process::id(), processes, pids.join(", "));
+
+    loop {
+        if signals::take_reload_request() {
+            println!("[*] SIGHUP recibido: reenviando a los workers");
+            for (child, _) in workers.iter().flatten() {
+                unsafe { ffi::kill(child.id() as i32, ffi::SIGHUP) };
+            }
+        }
+
+        for index in 0..workers.len() {
+            let Some((child, started)) = &mut workers[index] else {
+                continue;
+            };
+            let Some(status) = child.try_wait()? else {
+                continue;
+            };
+            if status.success() {
+                println!("[*] Worker {} (PID {}) terminó", index, child.id());
+                workers[index] = None;
+            } else if started.elapsed() < MIN_UPTIME {
+                let pid = child.id();
+                workers[index] = None;
+                stop_all(&mut workers);
+                return Err(io::Error::other(format!("Worker {} (PID {}) failed to start: {}", index, pid, status)));
+            } else {
+                eprintln!("   ❌ Worker {} (PID {}) terminó con {}; relanzando", index, child.id(), status);
+                workers[index] = Some(spawn(index)?);
+            }
+        }
+
+        if workers.iter().all(Option::is_none) {
+            return Ok(());
+        }
+        std::thread::sleep(SUPERVISE_INTERVAL);
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn supervise(_processes: usize) -> io::Result<()> {
+    Err(io::Error::new(io::ErrorKind::Unsupported, "--processes requires Linux (SO_REUSEPORT)"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_worker_parse_and_isolated_files() {
+        for value in ["", "1", "a/2", "2/2", "1/"] {
+            assert_eq!(Worker::parse(value), None, "{}", value);
+        }
+
+        let mut config = Config { access_log: Some("./logs/access.log".to_string()), ..Config::default() };
+        Worker { index: 2, count: 3 }.isolate_files(&mut config);
+        assert_eq!(config.jobs_storage_path, "./data/jobs.json.2");
+        assert_eq!(config.access_log.as_deref(), Some("./logs/access.log.2"));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_reuseport_listeners_share_a_port() {
+        use std::io::{Read, Write};
+        use std::net::TcpStream;
+
+        let first = bind_reuseport("127.0.0.1:0".parse().unwrap()).unwrap();
+        let addr = first.local_addr().unwrap();
+        let second = bind_reuseport(addr).unwrap();
+        assert_eq!(second.local_addr().unwrap(), addr);
+
+        // Sin SO_REUSEPORT el puerto está ocupado
+        assert!(TcpListener::bind(addr).is_err());
+
+        // Cada conexión la acepta alguno de los dos
+        let mut client = TcpStream::connect(addr).unwrap();
+        client.write_all(b"ping").unwrap();
+        first.set_nonblocking(true).unwrap();
+        let mut accepted = first.accept().map(|(s, _)| s).or_else(|_| second.accept().map(|(s, _)| s)).unwrap();
+        let mut buf = [0u8; 4];
+        accepted.set_nonblocking(false).unwrap();
+        accepted.read_exact(&mut buf).unwrap();
+        assert_eq!(&buf, b"ping");
+
+        let v6 = bind_reuseport("[::1]:0".parse().unwrap());
+        if let Ok(v6) = v6 {
+            assert!(v6.local_addr().unwrap().is_ipv6());
+        }
+    }
+}
@@ -0,0 +1,210 @@
+//! # PROXY Protocol v1
+//! src/server/proxy_protocol.rs
+//!
+//! Detrás de HAProxy o nginx en modo TCP el servidor solo ve la dirección
+//! del proxy. Con `--proxy-protocol` cada conexión tiene que empezar con la
+//! línea del PROXY protocol v1, que trae la dirección original:
+//!
+//! ```text
+//! PROXY TCP4 203.0.113.7 10.0.0.2 51234 8080\r\n
+//! GET /status HTTP/1.0\r\n
+//! ...
+//! ```
+//!
+//! El rate limiting, los bloqueos de administración, el access log y los
+//! logs usan esa dirección (`Connection::peer_addr`). `PROXY UNKNOWN` (los
+//! health checks del proxy) deja la dirección del socket. Una conexión sin
+//! la línea, o con una inválida, se cierra sin responder.
+//!
+//! - La línea se lee de a un byte, así no se consume nada del request (ni
+//!   del handshake TLS, que viene después).
+//! - El filtro de IPs se aplica al aceptar, antes de leerla: filtra por la
+//!   dirección del proxy.
+//! - Solo la versión 1 (texto); la 2 (binaria) no está soportada.
+
+use std::io::{self, Read};
+use std::net::{IpAddr, SocketAddr};
+
+/// Largo máximo de la línea, con el CRLF (según la especificación)
+pub const MAX_HEADER_BYTES: usize = 107;
+
+const PREFIX: &[u8] = b"PROXY ";
+
+/// Línea del PROXY protocol ya parseada
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProxyHeader {
+    /// Conexión TCP reenviada por el proxy
+    Tcp { source: SocketAddr, destination: SocketAddr },
+
+    /// El proxy no conoce el origen (por ejemplo, sus health checks)
+    Unknown,
+}
+
+impl ProxyHeader {
+    /// Dirección original del cliente, si el proxy la informó
+    pub fn source(&self) -> Option<SocketAddr> {
+        match self {
+            ProxyHeader::Tcp { source, .. } => Some(*source),
+            ProxyHeader::Unknown => None,
+        }
+    }
+}
+
+/// Parsea una línea completa (con el CRLF final)
+///
+/// # Ejemplo
+/// ```
+/// use http_server::server::proxy_protocol::{parse, ProxyHeader};
+///
+/// let header = parse(b"PROXY TCP4 203.0.113.7 10.0.0.2 51234 8080\r\n").unwrap();
+/// assert_eq!(header.source(), Some("203.0.113.7:51234".parse().unwrap()));
+/// assert_eq!(parse(b"PROXY UNKNOWN\r\n").unwrap(), ProxyHeader::Unknown);
+/// assert!(parse(b"GET / HTTP/1.0\r\n").is_err());
+/// ```
+pub fn parse(line: &[u8]) -> Result<ProxyHeader, String> {
+    if line.len() > MAX_HEADER_BYTES {
+        return Err(format!("PROXY header longer than {} bytes", MAX_HEADER_BYTES));
+    }
+    let line = line.strip_suffix(b"\r\n").ok_or("PROXY header must end with CRLF")?;
+    let line = std::str::from_utf8(line).map_err(|_| "PROXY header is not ASCII")?;
+    let mut fields = line.split(' ');
+    if fields.next() != Some("PROXY") {
+        return Err("missing PROXY signature".to_string());
+    }
+
+    let v6 = match fields.next() {
+        Some("TCP4") => false,
+        Some("TCP6") => true,
+        // El resto de la línea se ignora
+        Some("UNKNOWN") => return Ok(ProxyHeader::Unknown),
+        other => return Err(format!("unsupported PROXY protocol '{}'", other.unwrap_or(""))),
+    };
+    let (Some(source), Some(destination), Some(source_port), Some(destination_port), None) =
+        (fields.next(), fields.next(), fields.next(), fields.next(), fields.next())
+    else {
+        return Err("PROXY header needs source, destination and both ports".to_string());
+    };
+
+    Ok(ProxyHeader::Tcp {
+        source: SocketAddr::new(parse_ip(source, v6)?, parse_port(source_port)?),
+        destination: SocketAddr::new(parse_ip(destination, v6)?, parse_port(destination_port)?),
+    })
+}
+
+fn parse_ip(text: &str, v6: bool) -> Result<IpAddr, String> {
+    let ip: IpAddr = text.parse().map_err(|_| format!("invalid PROXY address '{}'", text))?;
+    if ip.is_ipv6() != v6 {
+        return Err(format!("PROXY address '{}' does not match the protocol", text));
+    }
+    Ok(ip)
+}
+
+fn parse_port(text: &str) -> Result<u16, String> {
+    text.parse().map_err(|_| format!("invalid PROXY port '{}'", text))
+}
+
+/// Lee la línea del PROXY protocol sin consumir nada más del socket
+///
+/// Sirve para sockets bloqueantes y no bloqueantes: con uno no bloqueante
+/// `read_from` falla con `WouldBlock` y se vuelve a llamar cuando haya
+/// más datos, siguiendo donde quedó.
+#[derive(Debug, Default)]
+pub struct HeaderReader {
+    line: Vec<u8>,
+}
+
+impl HeaderReader {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Lee hasta completar la línea y la parsea
+    ///
+    /// Falla con `InvalidData` apenas lo recibido no puede ser una línea
+    /// válida (un cliente que habla HTTP directo falla en el primer byte) y
+    /// con `UnexpectedEof` si el cliente cierra antes.
+    pub fn read_from(&mut self, stream: &mut impl Read) -> io::Result<ProxyHeader> {
+        let mut byte = [0u8; 1];
+        while !self.line.ends_with(b"\r\n") {
+            if self.line.len() >= MAX_HEADER_BYTES {
+                return Err(invalid(format!("PROXY header longer than {} bytes", MAX_HEADER_BYTES)));
+            }
+            match stream.read(&mut byte) {
+                Ok(0) => return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "closed before the PROXY header")),
+                Ok(_) => self.line.push(byte[0]),
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            }
+            let checked = self.line.len().min(PREFIX.len());
+            if self.line[..checked] != PREFIX[..checked] {
+                return Err(invalid("missing PROXY signature".to_string()));
+            }
+        }
+        parse(&self.line).map_err(invalid)
+    }
+}
+
+fn invalid(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tcp6_and_rejects_mismatches() {
+        let header = parse(b"PROXY TCP6 2001:db8::1 2001:db8::2 4000 443\r\n").unwrap();
+        assert_eq!(header.source(), Some("[2001:db8::1]:4000".parse().unwrap()));
+
+        assert!(parse(b"PROXY TCP4 2001:db8::1 10.0.0.2 1 2\r\n").is_err());
+        assert!(parse(b"PROXY TCP4 1.2.3.4 10.0.0.2 1\r\n").is_err());
+        assert!(parse(b"PROXY TCP4 1.2.3.4 10.0.0.2 1 70000\r\n").is_err());
+        assert!(parse(b"PROXY TCP4 1.2.3.4 10.0.0.2 1 2").is_err());
+        assert!(parse(b"PROXY UDP4 1.2.3.4 10.0.0.2 1 2\r\n").is_err());
+    }
+
+    #[test]
+    fn test_reader_stops_after_the_line() {
+        let mut input: &[u8] = b"PROXY TCP4 1.2.3.4 5.6.7.8 10 20\r\nGET / HTTP/1.0\r\n\r\n";
+        let header = HeaderReader::new().read_from(&mut input).unwrap();
+        assert_eq!(header.source(), Some("1.2.3.4:10".parse().unwrap()));
+        assert_eq!(input, b"GET / HTTP/1.0\r\n\r\n");
+
+        // Un request HTTP sin la línea falla en el primer byte
+        let mut input: &[u8] = b"GET / HTTP/1.0\r\n\r\n";
+        let err = HeaderReader::new().read_from(&mut input).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(input.len(), 17);
+
+        let mut input: &[u8] = b"PROXY TCP4";
+        let err = HeaderReader::new().read_from(&mut input).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn test_reader_resumes_after_would_block() {
+        struct Chunks(Vec<&'static [u8]>);
+        impl Read for Chunks {
+            fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+                match self.0.first_mut() {
+                    None => Err(io::ErrorKind::WouldBlock.into()),
+                    Some([]) => {
+                        self.0.remove(0);
+                        Err(io::ErrorKind::WouldBlock.into())
+                    }
+                    Some(chunk) => {
+                        buf[0] = chunk[0];
+                        *chunk = &chunk[1..];
+                        Ok(1)
+                    }
+                }
+            }
+        }
+
+        let mut stream = Chunks(vec![b"PROXY TCP4 1.2.", b"3.4 5.6.7.8 10 20\r\n"]);
+        let mut reader = HeaderReader::new();
+        assert_eq!(reader.read_from(&mut stream).unwrap_err().kind(), io::ErrorKind::WouldBlock);
+        assert_eq!(reader.read_from(&mut stream).unwrap().source(), Some("1.2.3.4:10".parse().unwrap()));
+    }
+}
@@ -0,0 +1,117 @@
+//! # Chequeo de Arranque y /readyz
+//! src/server/readiness.rs
+//!
+//! Antes de abrir el puerto, `Server::run` verifica que el servidor pueda
+//! trabajar; si algo falla no llega a aceptar conexiones y termina con el
+//! detalle. Los mismos chequeos responden `GET /readyz`, para que un script
+//! de despliegue mande tráfico solo cuando el servidor está listo:
+//!
+//! - `data_dir`: el directorio de datos (sandbox) existe y es escribible
+//! - `job_storage`: el archivo de jobs se puede leer y no está corrupto
+//! - `worker_pools`: cada pool tiene vivos al menos sus workers permanentes
+//!
+//! `/readyz` responde 200 si todo pasa y el servidor está en `running`, y
+//! 503 si falla un chequeo o el servidor está arrancando o drenando.
+
+use crate::commands::sandbox;
+use crate::workers::pool::PoolStats;
+use serde::Serialize;
+
+/// Resultado de un chequeo
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Check {
+    pub name: &'static str,
+    pub ok: bool,
+
+    /// Qué se verificó, o por qué falló
+    pub detail: String,
+}
+
+impl Check {
+    fn new(name: &'static str, result: Result<String, String>) -> Self {
+        match result {
+            Ok(detail) => Self { name, ok: true, detail },
+            Err(detail) => Self { name, ok: false, detail },
+        }
+    }
+}
+
+/// Todos los chequeos
+#[derive(Debug, Clone, Serialize)]
+pub struct Readiness {
+    pub ready: bool,
+    pub checks: Vec<Check>,
+}
+
+impl Readiness {
+    pub fn new(checks: Vec<Check>) -> Self {
+        Self { ready: checks.iter().all(|check| check.ok), checks }
+    }
+
+    /// Chequeos que fallaron
+    pub fn failures(&self) -> impl Iterator<Item = &Check> {
+        self.checks.iter().filter(|check| !check.ok)
+    }
+}
+
+/// El directorio de datos es escribible
+pub fn data_dir() -> Check {
+    Check::new("data_dir", sandbox::root().and_then(|root| {
+        sandbox::check_writable(&root)?;
+        Ok(format!("{} is writable", root.display()))
+    }))
+}
+
+/// El storage de jobs carga (`JobManager::check_storage`)
+pub fn job_storage(loaded: Result<usize, String>) -> Check {
+    Check::new("job_storage", loaded.map(|jobs| format!("{} jobs on disk", jobs)))
+}
+
+/// Cada pool tiene vivos al menos sus workers permanentes
+///
+/// # Ejemplo
+/// ```
+/// use http_server::server::readiness;
+/// use http_server::workers::pool::ThreadPool;
+///
+/// let pool = ThreadPool::new("ready", 2, 4);
+/// let check = readiness::worker_pools(&[pool.stats()]);
+/// assert!(check.ok);
+/// assert_eq!(check.detail, "ready 2/2");
+/// ```
+pub fn worker_pools(pools: &[PoolStats]) -> Check {
+    let summary = pools.iter()
+        .map(|pool| format!("{} {}/{}", pool.name, pool.workers, pool.min_workers))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let short: Vec<&str> = pools.iter()
+        .filter(|pool| pool.workers < pool.min_workers)
+        .map(|pool| pool.name.as_str())
+        .collect();
+    let result = if short.is_empty() {
+        Ok(summary)
+    } else {
+        Err(format!("missing workers in {} ({})", short.join(", "), summary))
+    };
+    Check::new("worker_pools", result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workers::pool::ThreadPool;
+
+    #[test]
+    fn test_worker_pools_reports_short_pools() {
+        let mut stats = ThreadPool::new("conn", 1, 4).stats();
+        stats.workers = 0;
+        let check = worker_pools(&[ThreadPool::new("cpu", 1, 4).stats(), stats]);
+        assert!(!check.ok);
+        assert!(check.detail.starts_with("missing workers in conn"), "{}", check.detail);
+
+        let readiness = Readiness::new(vec![job_storage(Ok(3)), check]);
+        assert!(!readiness.ready);
+        assert_eq!(readiness.failures().map(|c| c.name).collect::<Vec<_>>(), vec!["worker_pools"]);
+        assert_eq!(readiness.checks[0].detail, "3 jobs on disk");
+    }
+}
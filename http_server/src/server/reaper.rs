@@ -0,0 +1,200 @@
+//! # Reaper de Conexiones Ociosas
+//! src/server/reaper.rs
+//!
+//! Un thread en segundo plano revisa las conexiones que están atendiendo
+//! los workers y cierra las que llevan más de `--idle-timeout-ms` sin leer
+//! ni escribir un byte.
+//!
+//! El timeout de lectura (`--read-timeout-ms`) ya corta a un cliente que no
+//! envía nada, pero cuenta por cada lectura y se puede desactivar; el reaper
+//! es la red de seguridad: un worker nunca queda retenido más que el
+//! timeout de inactividad, sin importar en qué está bloqueado.
+//!
+//! - Se vigila desde que un worker toma la conexión: la espera en la cola
+//!   del pool no es culpa del cliente.
+//! - Cerrar es `shutdown(2)` sobre un duplicado del socket: la lectura o
+//!   escritura bloqueada del worker retorna y la conexión termina sola.
+//! - Las conexiones que todavía lee el event loop las corta su propio
+//!   timeout de lectura.
+
+use std::collections::HashMap;
+use std::net::{Shutdown, TcpStream};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Marca de una conexión que está enviando una respuesta entera
+const HOLD: u64 = u64::MAX;
+
+/// Registro de las conexiones vigiladas
+pub struct IdleReaper {
+    /// Origen de los instantes guardados como milisegundos
+    epoch: Instant,
+    next_id: AtomicU64,
+    watched: Mutex<HashMap<u64, Watched>>,
+}
+
+/// Conexión vigilada
+struct Watched {
+    /// Duplicado del socket, para cerrarlo desde el reaper
+    socket: TcpStream,
+    last_activity: Arc<AtomicU64>,
+}
+
+/// Una conexión bajo vigilancia; al soltarla deja de vigilarse
+pub struct IdleWatch {
+    id: u64,
+    last_activity: Arc<AtomicU64>,
+    reaper: Arc<IdleReaper>,
+}
+
+impl IdleReaper {
+    pub fn new() -> Self {
+        Self {
+            epoch: Instant::now(),
+            next_id: AtomicU64::new(0),
+            watched: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Empieza a vigilar `socket`; la actividad cuenta desde ahora
+    ///
+    /// Retorna `None` si no se pudo duplicar el socket (la conexión se
+    /// atiende igual, sin vigilancia).
+    pub fn watch(self: &Arc<Self>, socket: &TcpStream) -> Option<IdleWatch> {
+        let socket = socket.try_clone().ok()?;
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let last_activity = Arc::new(AtomicU64::new(self.now_ms()));
+        self.watched.lock().unwrap().insert(id, Watched { socket, last_activity: Arc::clone(&last_activity) });
+        Some(IdleWatch { id, last_activity, reaper: Arc::clone(self) })
+    }
+
+    /// Conexiones vigiladas ahora
+    pub fn watched(&self) -> usize {
+        self.watched.lock().unwrap().len()
+    }
+
+    /// Cierra las conexiones sin actividad hace `idle_timeout` o más
+    ///
+    /// Retorna cuántas cerró. Dejan de vigilarse en el acto, aunque el
+    /// worker tarde un poco en soltar la suya.
+    ///
+    /// # Ejemplo
+    /// ```
+    /// use http_server::server::reaper::IdleReaper;
+    /// use std::net::{TcpListener, TcpStream};
+    /// use std::sync::Arc;
+    /// use std::time::Duration;
+    ///
+    /// let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    /// let _client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+    /// let (socket, _) = listener.accept().unwrap();
+    ///
+    /// let reaper = Arc::new(IdleReaper::new());
+    /// let _watch = reaper.watch(&socket).unwrap();
+    /// assert_eq!(reaper.reap(Duration::from_secs(60)), 0);
+    /// assert_eq!(reaper.reap(Duration::ZERO), 1);
+    /// assert_eq!(reaper.watched(), 0);
+    /// ```
+    pub fn reap(&self, idle_timeout: Duration) -> usize {
+        let now = self.now_ms();
+        let limit = idle_timeout.as_millis() as u64;
+        let mut watched = self.watched.lock().unwrap();
+        let idle: Vec<u64> = watched.iter()
+            .filter(|(_, conn)| {
+                let last = conn.last_activity.load(Ordering::Relaxed);
+                last != HOLD && now.saturating_sub(last) >= limit
+            })
+            .map(|(&id, _)| id)
+            .collect();
+
+        for id in &idle {
+            if let Some(conn) = watched.remove(id) {
+                let _ = conn.socket.shutdown(Shutdown::Both);
+            }
+        }
+        idle.len()
+    }
+
+    fn now_ms(&self) -> u64 {
+        self.epoch.elapsed().as_millis() as u64
+    }
+}
+
+impl Default for IdleReaper {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IdleWatch {
+    /// Registra actividad (se leyó o escribió algo)
+    pub fn touch(&self) {
+        self.last_activity.store(self.reaper.now_ms(), Ordering::Relaxed);
+    }
+
+    /// No cuenta como ociosa hasta el próximo `touch`
+    ///
+    /// Para envíos que no pasan por el `Write` de la conexión (un
+    /// `sendfile` largo a un cliente lento); los acota el timeout de
+    /// escritura.
+    pub fn hold(&self) {
+        self.last_activity.store(HOLD, Ordering::Relaxed);
+    }
+}
+
+impl Drop for IdleWatch {
+    fn drop(&mut self) {
+        self.reaper.watched.lock().unwrap().remove(&self.id);
+    }
+}
+
+/// Cada cuánto revisa el reaper para un timeout dado
+///
+/// La mitad del timeout, entre 10 ms y 1 s: una conexión se cierra como
+/// mucho medio timeout (o un segundo) después de vencer.
+pub fn tick(idle_timeout: Duration) -> Duration {
+    (idle_timeout / 2).clamp(Duration::from_millis(10), Duration::from_secs(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+    use std::net::TcpListener;
+
+    #[test]
+    fn test_activity_postpones_and_drop_unwatches() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (socket, _) = listener.accept().unwrap();
+
+        let reaper = Arc::new(IdleReaper::new());
+        let watch = reaper.watch(&socket).unwrap();
+        std::thread::sleep(Duration::from_millis(60));
+        watch.touch();
+        assert_eq!(reaper.reap(Duration::from_millis(50)), 0);
+        watch.hold();
+        assert_eq!(reaper.reap(Duration::ZERO), 0);
+        watch.touch();
+
+        std::thread::sleep(Duration::from_millis(60));
+        assert_eq!(reaper.reap(Duration::from_millis(50)), 1);
+        // El cliente ve el cierre
+        let mut buf = [0u8; 1];
+        assert_eq!(client.read(&mut buf).unwrap(), 0);
+
+        drop(watch);
+        let other = reaper.watch(&socket).unwrap();
+        assert_eq!(reaper.watched(), 1);
+        drop(other);
+        assert_eq!(reaper.watched(), 0);
+    }
+
+    #[test]
+    fn test_tick_is_half_the_timeout_within_bounds() {
+        assert_eq!(tick(Duration::from_millis(100)), Duration::from_millis(50));
+        assert_eq!(tick(Duration::from_millis(4)), Duration::from_millis(10));
+        assert_eq!(tick(Duration::from_secs(60)), Duration::from_secs(1));
+    }
+}
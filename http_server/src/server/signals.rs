@@ -0,0 +1,98 @@
+//! # Manejo de Señales
+//! src/server/signals.rs
+//!
+//! Instala handlers mínimos para señales POSIX usando FFI directo a libc
+//! (sin crates externos). El handler solo levanta una bandera atómica;
+//! el trabajo real lo hace un thread normal que consulta la bandera,
+//! porque dentro de un signal handler solo se pueden usar operaciones
+//! async-signal-safe.
+//!
+//! - `SIGHUP`: solicita recargar el archivo de configuración
+//! - `SIGUSR2`: solicita un reinicio sin cortes (ver `server::handoff`)
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Bandera levantada por el handler de SIGHUP
+static RELOAD_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+/// Bandera levantada por el handler de SIGUSR2
+static RESTART_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+#[cfg(unix)]
+mod ffi {
+    /// Número de SIGHUP (igual en Linux y macOS)
+    pub const SIGHUP: i32 = 1;
+
+    /// Número de SIGUSR2 (difiere entre Linux y los BSD/macOS)
+    #[cfg(target_os = "linux")]
+    pub const SIGUSR2: i32 = 12;
+    #[cfg(not(target_os = "linux"))]
+    pub const SIGUSR2: i32 = 31;
+
+    extern "C" {
+        /// `sighandler_t signal(int signum, sighandler_t handler)`
+        pub fn signal(signum: i32, handler: usize) -> usize;
+    }
+}
+
+#[cfg(unix)]
+extern "C" fn on_sighup(_signum: i32) {
+    RELOAD_REQUESTED.store(true, Ordering::SeqCst);
+}
+
+#[cfg(unix)]
+extern "C" fn on_sigusr2(_signum: i32) {
+    RESTART_REQUESTED.store(true, Ordering::SeqCst);
+}
+
+/// Instala el handler de SIGHUP
+///
+/// En plataformas no-Unix no hace nada (la recarga sigue disponible
+/// vía `/config/reload`).
+pub fn install_reload_handler() {
+    #[cfg(unix)]
+    unsafe {
+        let handler: extern "C" fn(i32) = on_sighup;
+        ffi::signal(ffi::SIGHUP, handler as usize);
+    }
+}
+
+/// Consume una solicitud de recarga pendiente
+///
+/// Retorna `true` si llegó un SIGHUP desde la última llamada.
+pub fn take_reload_request() -> bool {
+    RELOAD_REQUESTED.swap(false, Ordering::SeqCst)
+}
+
+/// Simula la llegada de un SIGHUP (útil para tests)
+pub fn request_reload() {
+    RELOAD_REQUESTED.store(true, Ordering::SeqCst);
+}
+
+/// Instala el handler de SIGUSR2
+///
+/// En plataformas no-Unix no hace nada (no hay handoff de sockets).
+pub fn install_restart_handler() {
+    #[cfg(unix)]
+    unsafe {
+        let handler: extern "C" fn(i32) = on_sigusr2;
+        ffi::signal(ffi::SIGUSR2, handler as usize);
+    }
+}
+
+/// Consume una solicitud de reinicio pendiente
+pub fn take_restart_request() -> bool {
+    RESTART_REQUESTED.swap(false, Ordering::SeqCst)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_take_reload_request() {
+        request_reload();
+        assert!(take_reload_request());
+        assert!(!take_reload_request());
+    }
+}
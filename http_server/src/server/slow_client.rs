@@ -0,0 +1,177 @@
+//! # Clientes Lentos (slowloris)
+//! src/server/slow_client.rs
+//!
+//! Un cliente que envía un byte por segundo nunca dispara el timeout de
+//! lectura (cada lectura recibe algo) y puede retener un worker para
+//! siempre. `TransferGuard` acota la lectura de un request con dos reglas:
+//!
+//! ```text
+//! aceptada ──── headers (≤ --header-timeout-ms) ────┬──── body ────▶
+//!                                                   └─ tras RATE_GRACE, ≥ --min-transfer-rate bytes/s
+//! ```
+//!
+//! Las conexiones que no cumplen se cierran sin respuesta y se cuentan en
+//! `/metrics` (`connections.slow_dropped`).
+
+use crate::config::Config;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// Tiempo del body antes de exigir la tasa mínima (los bodies chicos nunca se miden)
+pub const RATE_GRACE: Duration = Duration::from_secs(2);
+
+/// Timeout mínimo de una lectura cuando el plazo está por vencer
+const MIN_READ_TIMEOUT: Duration = Duration::from_millis(1);
+
+/// Límites de tiempo al leer un request, tomados de la configuración
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TransferLimits {
+    /// Espera máxima entre dos lecturas (`None` = sin límite)
+    pub read_timeout: Option<Duration>,
+
+    /// Plazo para recibir la request line y los headers, desde que se acepta
+    pub header_timeout: Option<Duration>,
+
+    /// Bytes por segundo mínimos del body (0 = sin mínimo)
+    pub min_rate: u64,
+}
+
+impl TransferLimits {
+    /// Límites vigentes según `config`
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            read_timeout: config.read_timeout(),
+            header_timeout: Some(Duration::from_millis(config.header_timeout_ms)).filter(|t| !t.is_zero()),
+            min_rate: config.min_transfer_rate,
+        }
+    }
+}
+
+/// Motivo por el que se corta a un cliente lento
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SlowClient {
+    /// Los headers no terminaron de llegar dentro del plazo
+    HeaderTimeout(Duration),
+
+    /// El body llega más lento que la tasa mínima
+    BelowMinRate { rate: u64, min: u64 },
+}
+
+impl fmt::Display for SlowClient {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SlowClient::HeaderTimeout(limit) => {
+                write!(f, "headers not received within {} ms", limit.as_millis())
+            }
+            SlowClient::BelowMinRate { rate, min } => {
+                write!(f, "body arriving at {} bytes/s (minimum {})", rate, min)
+            }
+        }
+    }
+}
+
+/// Controla el progreso de la lectura de un request
+#[derive(Debug, Clone)]
+pub struct TransferGuard {
+    limits: TransferLimits,
+    start: Instant,
+
+    /// Cuándo terminaron los headers y cuántos bytes se habían recibido
+    body_start: Option<(Instant, usize)>,
+}
+
+impl TransferGuard {
+    /// Empieza a controlar una conexión aceptada en `start`
+    pub fn new(limits: TransferLimits, start: Instant) -> Self {
+        Self { limits, start, body_start: None }
+    }
+
+    /// Timeout para la próxima lectura bloqueante
+    ///
+    /// El de lectura, acotado por lo que le queda al plazo de los headers
+    /// para que un cliente que no envía nada no lo extienda.
+    pub fn next_read_timeout(&self, now: Instant) -> Option<Duration> {
+        let header_left = match (self.limits.header_timeout, self.body_start) {
+            (Some(limit), None) => Some((self.start + limit).saturating_duration_since(now).max(MIN_READ_TIMEOUT)),
+            _ => None,
+        };
+        match (self.limits.read_timeout, header_left) {
+            (Some(read), Some(header)) => Some(read.min(header)),
+            (read, header) => read.or(header),
+        }
+    }
+
+    /// Revisa el progreso con `received` bytes leídos en total
+    ///
+    /// `head_complete` indica si ya llegaron la request line y los headers.
+    ///
+    /// # Ejemplo
+    /// ```
+    /// use http_server::server::slow_client::{SlowClient, TransferGuard, TransferLimits};
+    /// use std::time::{Duration, Instant};
+    ///
+    /// let limits = TransferLimits { header_timeout: Some(Duration::from_secs(10)), ..Default::default() };
+    /// let start = Instant::now();
+    /// let mut guard = TransferGuard::new(limits, start);
+    /// assert!(guard.check(start + Duration::from_secs(9), false, 20).is_ok());
+    /// assert!(matches!(guard.check(start + Duration::from_secs(11), false, 21), Err(SlowClient::HeaderTimeout(_))));
+    /// ```
+    pub fn check(&mut self, now: Instant, head_complete: bool, received: usize) -> Result<(), SlowClient> {
+        if !head_complete {
+            return match self.limits.header_timeout {
+                Some(limit) if now.duration_since(self.start) >= limit => Err(SlowClient::HeaderTimeout(limit)),
+                _ => Ok(()),
+            };
+        }
+
+        let (body_start, body_offset) = *self.body_start.get_or_insert((now, received));
+        let elapsed = now.duration_since(body_start);
+        if self.limits.min_rate == 0 || elapsed < RATE_GRACE {
+            return Ok(());
+        }
+        let rate = ((received - body_offset) as f64 / elapsed.as_secs_f64()) as u64;
+        if rate < self.limits.min_rate {
+            return Err(SlowClient::BelowMinRate { rate, min: self.limits.min_rate });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_read_timeout_shrinks_with_header_deadline() {
+        let limits = TransferLimits {
+            read_timeout: Some(Duration::from_secs(30)),
+            header_timeout: Some(Duration::from_secs(10)),
+            min_rate: 0,
+        };
+        let start = Instant::now();
+        let mut guard = TransferGuard::new(limits, start);
+        assert_eq!(guard.next_read_timeout(start + Duration::from_secs(4)), Some(Duration::from_secs(6)));
+        assert_eq!(guard.next_read_timeout(start + Duration::from_secs(20)), Some(MIN_READ_TIMEOUT));
+
+        // Con los headers completos vuelve el timeout de lectura
+        guard.check(start + Duration::from_secs(1), true, 100).unwrap();
+        assert_eq!(guard.next_read_timeout(start + Duration::from_secs(20)), Some(Duration::from_secs(30)));
+        assert_eq!(TransferGuard::new(TransferLimits::default(), start).next_read_timeout(start), None);
+    }
+
+    #[test]
+    fn test_min_rate_after_grace() {
+        let limits = TransferLimits { min_rate: 100, ..Default::default() };
+        let start = Instant::now();
+        let mut guard = TransferGuard::new(limits, start);
+
+        // Los headers terminan con 50 bytes; el body se mide desde ahí
+        guard.check(start, true, 50).unwrap();
+        assert!(guard.check(start + Duration::from_secs(1), true, 51).is_ok());
+        assert!(guard.check(start + Duration::from_secs(3), true, 350).is_ok());
+        assert_eq!(
+            guard.check(start + Duration::from_secs(4), true, 350),
+            Err(SlowClient::BelowMinRate { rate: 75, min: 100 })
+        );
+    }
+}
@@ -0,0 +1,212 @@
+//! # Archivos Estáticos
+//! src/server/static_files.rs
+//!
+//! Sirve un directorio bajo un prefijo de URL, por ejemplo los archivos
+//! que crean los comandos (`--static-files /files=./data`):
+//!
+//! ```text
+//! GET /files/out.txt      → ./data/out.txt (Content-Type según extensión)
+//! GET /files/reports/     → listado de ./data/reports (con --static-listing)
+//! GET /files/reports      → 301 a /files/reports/
+//! ```
+//!
+//! - El path se resuelve con el sandbox (`sandbox::resolve_in`): `..`, paths
+//!   absolutos y symlinks que salen del directorio responden 404.
+//! - Los archivos y directorios que empiezan con `.` no se sirven ni se
+//!   listan (pruebas de escritura, uploads a medio escribir).
+//! - La respuesta es la de `/download` (ETag, 304, `Range`, sendfile y el
+//!   límite `max_download_bytes`), pero con el `Content-Type` del archivo y
+//!   para mostrar en el navegador.
+//! - Sin `--static-listing` un directorio responde 403.
+
+use crate::commands::io_bound::file_response;
+use crate::commands::{limits, sandbox};
+use crate::http::request::Method;
+use crate::http::{mime, Request, Response, StatusCode};
+use crate::router::Router;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Directorio servido como archivos estáticos
+#[derive(Debug, Clone)]
+pub struct StaticFiles {
+    root: PathBuf,
+    listing: bool,
+}
+
+impl StaticFiles {
+    /// Sirve `root` (sin listados de directorios)
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into(), listing: false }
+    }
+
+    /// Habilita los listados HTML de directorios
+    pub fn with_listing(mut self, listing: bool) -> Self {
+        self.listing = listing;
+        self
+    }
+
+    /// Registra el directorio bajo `prefix` (GET y HEAD)
+    ///
+    /// # Ejemplo
+    /// ```
+    /// use http_server::http::{Request, StatusCode};
+    /// use http_server::router::Router;
+    /// use http_server::server::static_files::StaticFiles;
+    ///
+    /// let dir = std::env::temp_dir().join(format!("static-doc-{}", std::process::id()));
+    /// std::fs::create_dir_all(&dir).unwrap();
+    /// std::fs::write(dir.join("hola.txt"), "hola").unwrap();
+    ///
+    /// let mut router = Router::new();
+    /// StaticFiles::new(&dir).mount(&mut router, "/files");
+    ///
+    /// let request = Request::parse(b"GET /files/hola.txt HTTP/1.0\r\n\r\n").unwrap();
+    /// let response = router.route(&request);
+    /// assert_eq!(response.headers().get("Content-Type"), Some("text/plain; charset=utf-8"));
+    ///
+    /// let request = Request::parse(b"GET /files/../etc/passwd HTTP/1.0\r\n\r\n").unwrap();
+    /// assert_eq!(router.route(&request).status(), StatusCode::NotFound);
+    /// # std::fs::remove_dir_all(&dir).unwrap();
+    /// ```
+    pub fn mount(self, router: &mut Router, prefix: &str) {
+        let prefix = prefix.trim_end_matches('/');
+        let files = Arc::new(self);
+        router.register_redirect(prefix, StatusCode::MovedPermanently, &format!("{}/", prefix));
+        for path in [format!("{}/", prefix), format!("{}/*path", prefix)] {
+            let files = Arc::clone(&files);
+            router.register_method(Method::GET, &path, move |req: &Request| files.serve(req))
+                .describe("Static file or directory listing");
+        }
+    }
+
+    /// Responde el archivo o directorio del parámetro `path` del request
+    fn serve(&self, req: &Request) -> Response {
+        let relative = req.path_param("path").unwrap_or("");
+        let not_found = || Response::error(StatusCode::NotFound, &format!("File not found: {}", relative));
+        if relative.split('/').any(|segment| segment.starts_with('.')) {
+            return not_found();
+        }
+        let Ok(root) = fs::canonicalize(&self.root) else {
+            return not_found();
+        };
+        let name = relative.trim_end_matches('/');
+        let path = if name.is_empty() {
+            root
+        } else {
+            match sandbox::resolve_in(&root, name) {
+                Ok(path) => path,
+                Err(_) => return not_found(),
+            }
+        };
+
+        match fs::metadata(&path) {
+            Ok(metadata) if metadata.is_dir() => {
+                if !req.path().ends_with('/') {
+                    return Response::redirect(StatusCode::MovedPermanently, &format!("{}/", req.path()));
+                }
+                if !self.listing {
+                    return Response::error(StatusCode::Forbidden, "Directory listing is disabled");
+                }
+                listing(&path, req.path())
+            }
+            Ok(_) => file_response(&path, name, limits::current().max_download_bytes, req, Some(mime::for_path(&path))),
+            Err(_) => not_found(),
+        }
+    }
+}
+
+/// Página HTML con el contenido de `dir` (directorios primero)
+fn listing(dir: &Path, url: &str) -> Response {
+    let mut entries: Vec<(bool, String, u64)> = match fs::read_dir(dir) {
+        Ok(entries) => entries
+            .filter_map(Result::ok)
+            .filter_map(|entry| {
+                let name = entry.file_name().into_string().ok()?;
+                let metadata = entry.metadata().ok()?;
+                (!name.starts_with('.')).then_some((metadata.is_dir(), name, metadata.len()))
+            })
+            .collect(),
+        Err(e) => return Response::error(StatusCode::InternalServerError, &format!("Cannot list directory: {}", e)),
+    };
+    entries.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+
+    let title = escape_html(url);
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>{0}</title></head>\n<body><h1>{0}</h1>\n<ul>\n",
+        title
+    );
+    for (is_dir, name, len) in entries {
+        let name = escape_html(&name);
+        if is_dir {
+            html.push_str(&format!("<li><a href=\"{0}/\">{0}/</a></li>\n", name));
+        } else {
+            html.push_str(&format!("<li><a href=\"{0}\">{0}</a> ({1} bytes)</li>\n", name, len));
+        }
+    }
+    html.push_str("</ul></body></html>\n");
+
+    Response::builder(StatusCode::Ok)
+        .content_type("text/html; charset=utf-8")
+        .body(&html)
+}
+
+/// Escapa `&`, `<`, `>` y comillas para insertar texto en HTML
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("static-files-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("sub")).unwrap();
+        fs::write(dir.join("page.html"), "<p>hola</p>").unwrap();
+        fs::write(dir.join("sub").join("a<b>.txt"), "abc").unwrap();
+        fs::write(dir.join(".hidden"), "secreto").unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_serves_files_listings_and_rejects_escapes() {
+        let dir = temp_dir("serve");
+        let mut router = Router::new();
+        StaticFiles::new(&dir).with_listing(true).mount(&mut router, "/files/");
+        let route = |target: &str| {
+            let raw = format!("GET {} HTTP/1.0\r\n\r\n", target);
+            router.route(&Request::parse(raw.as_bytes()).unwrap())
+        };
+
+        let page = route("/files/page.html");
+        assert_eq!(page.status(), StatusCode::Ok);
+        assert_eq!(page.headers().get("Content-Type"), Some("text/html; charset=utf-8"));
+        assert!(page.headers().get("Content-Disposition").is_none());
+        assert!(page.headers().get("ETag").is_some());
+
+        let root = String::from_utf8(route("/files/").body().to_vec()).unwrap();
+        assert!(root.find("sub/").unwrap() < root.find("page.html").unwrap(), "{}", root);
+        assert!(!root.contains(".hidden"));
+        let sub = String::from_utf8(route("/files/sub/").body().to_vec()).unwrap();
+        assert!(sub.contains("<a href=\"a&lt;b&gt;.txt\">"), "{}", sub);
+
+        assert_eq!(route("/files").headers().get("Location"), Some("/files/"));
+        assert_eq!(route("/files/sub").headers().get("Location"), Some("/files/sub/"));
+        for escape in ["/files/../Cargo.toml", "/files/.hidden", "/files/sub/../../x", "/files/missing.txt"] {
+            assert_eq!(route(escape).status(), StatusCode::NotFound, "{}", escape);
+        }
+
+        // Sin listados, un directorio es 403
+        let mut router = Router::new();
+        StaticFiles::new(&dir).mount(&mut router, "/files");
+        let request = Request::parse(b"GET /files/sub/ HTTP/1.0\r\n\r\n").unwrap();
+        assert_eq!(router.route(&request).status(), StatusCode::Forbidden);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
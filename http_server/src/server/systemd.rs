@@ -0,0 +1,303 @@
+//! # Integración con systemd
+//! src/server/systemd.rs
+//!
+//! Permite correr el servidor como unidad `Type=notify` sin depender de
+//! libsystemd (el protocolo son variables de entorno y un socket Unix):
+//!
+//! - **Socket activation**: si systemd abrió el puerto (`redunix.socket`),
+//!   los listeners llegan desde el fd 3 con `LISTEN_PID`/`LISTEN_FDS`, uno
+//!   por cada `ListenStream=`. Como systemd los mantiene abiertos, un
+//!   reinicio del servicio no pierde las conexiones en la cola del kernel.
+//! - **sd_notify**: `READY=1` y `STATUS=...` al empezar a atender, con
+//!   `MAINPID=` para que tras un reinicio con SIGUSR2 systemd siga al
+//!   proceso nuevo (requiere `NotifyAccess=all`). Se envían al datagram
+//!   socket de `NOTIFY_SOCKET`.
+//! - **Watchdog**: con `WatchdogSec=` un thread hace un `GET /status` al
+//!   propio servidor cada medio intervalo y solo envía `WATCHDOG=1` si
+//!   recibe respuesta. Si el servidor se cuelga, systemd lo reinicia.
+//!
+//! Fuera de systemd (sin esas variables) todo esto no hace nada.
+
+use std::io::{self, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::thread;
+use std::time::Duration;
+
+/// Primer descriptor pasado por socket activation (`SD_LISTEN_FDS_START`)
+#[cfg(unix)]
+const LISTEN_FDS_START: i32 = 3;
+
+/// Descriptores recibidos por socket activation para el proceso `pid`
+///
+/// `LISTEN_PID` debe coincidir con `pid`: si no, las variables eran para
+/// otro proceso (por ejemplo, se heredaron).
+fn parse_listen_fds(listen_pid: Option<&str>, listen_fds: Option<&str>, pid: u32) -> Option<usize> {
+    if listen_pid?.trim().parse::<u32>().ok()? != pid {
+        return None;
+    }
+    listen_fds?.trim().parse::<usize>().ok().filter(|n| *n > 0)
+}
+
+/// Listeners recibidos por socket activation, en el orden de la unidad
+///
+/// Vacío si el proceso no fue activado por systemd.
+#[cfg(unix)]
+pub fn listen_fds() -> io::Result<Vec<TcpListener>> {
+    let count = parse_listen_fds(
+        std::env::var("LISTEN_PID").ok().as_deref(),
+        std::env::var("LISTEN_FDS").ok().as_deref(),
+        std::process::id(),
+    );
+    // No deben llegar a los procesos que lance este (ej: un reinicio)
+    for name in ["LISTEN_PID", "LISTEN_FDS", "LISTEN_FDNAMES"] {
+        std::env::remove_var(name);
+    }
+
+    let Some(count) = count else {
+        return Ok(Vec::new());
+    };
+    (LISTEN_FDS_START..LISTEN_FDS_START + count as i32).map(take_listener).collect()
+}
+
+/// Toma el descriptor `fd` que pasó systemd como listener
+#[cfg(unix)]
+fn take_listener(fd: i32) -> io::Result<TcpListener> {
+    use std::os::unix::io::FromRawFd;
+
+    let not_listener = |e: io::Error| {
+        io::Error::new(e.kind(), format!("fd {} from systemd is not a TCP listener: {}", fd, e))
+    };
+    check_stream_listener(fd).map_err(not_listener)?;
+    let listener = unsafe { TcpListener::from_raw_fd(fd) };
+    listener.local_addr().map_err(not_listener)?;
+
+    // systemd los pasa heredables; como los que abre std, solo se heredan
+    // en un reinicio sin cortes
+    super::handoff::set_inheritable(fd, false)?;
+    Ok(listener)
+}
+
+#[cfg(target_os = "linux")]
+mod ffi {
+    pub const SOL_SOCKET: i32 = 1;
+    pub const SO_TYPE: i32 = 3;
+    pub const SO_ACCEPTCONN: i32 = 30;
+    pub const SOCK_STREAM: i32 = 1;
+
+    extern "C" {
+        /// `int getsockopt(int fd, int level, int name, void *value, socklen_t *len)`
+        pub fn getsockopt(fd: i32, level: i32, name: i32, value: *mut i32, len: *mut u32) -> i32;
+    }
+}
+
+/// Verifica que `fd` sea un socket de stream en escucha
+///
+/// Un `ListenDatagram=` o un socket sin `listen` pasarían `local_addr` y
+/// recién fallarían en el primer `accept`.
+#[cfg(target_os = "linux")]
+fn check_stream_listener(fd: i32) -> io::Result<()> {
+    let option = |name: i32| -> io::Result<i32> {
+        let mut value = 0i32;
+        let mut len = std::mem::size_of::<i32>() as u32;
+        if unsafe { ffi::getsockopt(fd, ffi::SOL_SOCKET, name, &mut value, &mut len) } < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(value)
+    };
+    if option(ffi::SO_TYPE)? != ffi::SOCK_STREAM {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "not a stream socket"));
+    }
+    if option(ffi::SO_ACCEPTCONN)? == 0 {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "socket is not listening"));
+    }
+    Ok(())
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+fn check_stream_listener(_fd: i32) -> io::Result<()> {
+    Ok(())
+}
+
+#[cfg(not(unix))]
+pub fn listen_fds() -> io::Result<Vec<TcpListener>> {
+    Ok(Vec::new())
+}
+
+/// Envía `state` (ej: `READY=1`) al socket de `NOTIFY_SOCKET`
+///
+/// Retorna `Ok(false)` si el proceso no corre bajo systemd.
+pub fn notify(state: &str) -> io::Result<bool> {
+    match std::env::var("NOTIFY_SOCKET") {
+        Ok(socket) if !socket.is_empty() => notify_to(&socket, state).map(|_| true),
+        _ => Ok(false),
+    }
+}
+
+/// Envía `state` al socket `socket` (ruta, o `@nombre` abstracto en Linux)
+#[cfg(unix)]
+fn notify_to(socket: &str, state: &str) -> io::Result<()> {
+    use std::os::unix::net::UnixDatagram;
+
+    let sender = UnixDatagram::unbound()?;
+    if let Some(name) = socket.strip_prefix('@') {
+        #[cfg(target_os = "linux")]
+        {
+            use std::os::linux::net::SocketAddrExt;
+            let addr = std::os::unix::net::SocketAddr::from_abstract_name(name.as_bytes())?;
+            sender.send_to_addr(state.as_bytes(), &addr)?;
+            return Ok(());
+        }
+        #[cfg(not(target_os = "linux"))]
+        return Err(io::Error::new(io::ErrorKind::Unsupported, format!("Abstract socket @{} not supported", name)));
+    }
+    sender.send_to(state.as_bytes(), socket)?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn notify_to(_socket: &str, _state: &str) -> io::Result<()> {
+    Err(io::Error::new(io::ErrorKind::Unsupported, "sd_notify needs Unix sockets"))
+}
+
+/// Envía `state` y registra el error sin interrumpir al servidor
+pub fn notify_or_log(state: &str) {
+    if let Err(e) = notify(state) {
+        eprintln!("   ❌ sd_notify({}): {}", state.lines().next().unwrap_or(""), e);
+    }
+}
+
+/// Intervalo de watchdog pedido por systemd (`WatchdogSec=`)
+fn parse_watchdog(usec: Option<&str>, watchdog_pid: Option<&str>, pid: u32) -> Option<Duration> {
+    if let Some(watchdog_pid) = watchdog_pid {
+        if watchdog_pid.trim().parse::<u32>().ok()? != pid {
+            return None;
+        }
+    }
+    usec?.trim().parse::<u64>().ok().filter(|us| *us > 0).map(Duration::from_micros)
+}
+
+/// Intervalo de watchdog de este proceso, si systemd lo pidió
+pub fn watchdog_interval() -> Option<Duration> {
+    parse_watchdog(
+        std::env::var("WATCHDOG_USEC").ok().as_deref(),
+        std::env::var("WATCHDOG_PID").ok().as_deref(),
+        std::process::id(),
+    )
+}
+
+/// Hace un `GET /status` a `addr` y verifica que llegue una respuesta HTTP
+fn probe(addr: SocketAddr, timeout: Duration) -> bool {
+    let attempt = || -> io::Result<bool> {
+        let mut stream = TcpStream::connect_timeout(&addr, timeout)?;
+        stream.set_read_timeout(Some(timeout))?;
+        stream.set_write_timeout(Some(timeout))?;
+        stream.write_all(b"GET /status HTTP/1.0\r\nUser-Agent: systemd-watchdog\r\n\r\n")?;
+
+        let mut head = [0u8; 12];
+        stream.read_exact(&mut head)?;
+        Ok(head.starts_with(b"HTTP/1."))
+    };
+    attempt().unwrap_or(false)
+}
+
+/// Lanza el thread que envía `WATCHDOG=1` mientras el servidor responda
+///
+/// `addr` es la dirección del propio listener. Se prueba cada
+/// `interval / 2`, como recomienda `sd_watchdog_enabled(3)`.
+pub fn spawn_watchdog(addr: SocketAddr, interval: Duration) {
+    let period = interval / 2;
+    let spawned = thread::Builder::new()
+        .name("systemd-watchdog".to_string())
+        .spawn(move || loop {
+            if probe(addr, period / 2) {
+                notify_or_log("WATCHDOG=1");
+            } else {
+                eprintln!("   ⚠️  Watchdog: el servidor no respondió a /status, no se envía WATCHDOG=1");
+            }
+            thread::sleep(period);
+        });
+
+    if let Err(e) = spawned {
+        eprintln!("   ❌ No se pudo iniciar el thread de watchdog: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_listen_fds() {
+        assert_eq!(parse_listen_fds(Some("42"), Some("1"), 42), Some(1));
+        assert_eq!(parse_listen_fds(Some("42"), Some("2"), 42), Some(2));
+        // Variables de otro proceso, sin sockets o inválidas
+        assert_eq!(parse_listen_fds(Some("41"), Some("1"), 42), None);
+        assert_eq!(parse_listen_fds(Some("42"), Some("0"), 42), None);
+        assert_eq!(parse_listen_fds(None, Some("1"), 42), None);
+        assert_eq!(parse_listen_fds(Some("42"), Some("x"), 42), None);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_check_stream_listener() {
+        use std::os::unix::io::AsRawFd;
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        assert!(check_stream_listener(listener.as_raw_fd()).is_ok());
+
+        let client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let err = check_stream_listener(client.as_raw_fd()).unwrap_err();
+        assert!(err.to_string().contains("not listening"));
+
+        let udp = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+        assert!(check_stream_listener(udp.as_raw_fd()).unwrap_err().to_string().contains("stream"));
+    }
+
+    #[test]
+    fn test_parse_watchdog() {
+        assert_eq!(parse_watchdog(Some("30000000"), None, 7), Some(Duration::from_secs(30)));
+        assert_eq!(parse_watchdog(Some("30000000"), Some("7"), 7), Some(Duration::from_secs(30)));
+        assert_eq!(parse_watchdog(Some("30000000"), Some("8"), 7), None);
+        assert_eq!(parse_watchdog(Some("0"), None, 7), None);
+        assert_eq!(parse_watchdog(None, None, 7), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_notify_sends_datagram() {
+        use std::os::unix::net::UnixDatagram;
+
+        let path = std::env::temp_dir().join(format!("redunix-notify-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let receiver = UnixDatagram::bind(&path).unwrap();
+
+        notify_to(path.to_str().unwrap(), "READY=1\nSTATUS=Atendiendo").unwrap();
+        let mut buf = [0u8; 64];
+        let n = receiver.recv(&mut buf).unwrap();
+        assert_eq!(&buf[..n], b"READY=1\nSTATUS=Atendiendo");
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_probe_requires_http_response() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let server = thread::spawn(move || {
+            // Primera conexión: responde; segunda: no contesta nada
+            let (mut ok, _) = listener.accept().unwrap();
+            let mut buf = [0u8; 256];
+            let _ = ok.read(&mut buf);
+            ok.write_all(b"HTTP/1.0 200 OK\r\n\r\n").unwrap();
+
+            let (hung, _) = listener.accept().unwrap();
+            thread::sleep(Duration::from_millis(300));
+            drop(hung);
+        });
+
+        assert!(probe(addr, Duration::from_secs(2)));
+        assert!(!probe(addr, Duration::from_millis(100)));
+        server.join().unwrap();
+    }
+}
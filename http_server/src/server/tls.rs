@@ -0,0 +1,288 @@
+//! # HTTPS (TLS)
+//! src/server/tls.rs
+//!
+//! Con `--tls-cert` y `--tls-key` el mismo listener atiende HTTPS: cada
+//! conexión hace el handshake TLS en su worker y luego pasa por el mismo
+//! pipeline (router, handlers, métricas) que una conexión plana.
+//!
+//! El soporte es opcional y se compila con `--features tls` (usa rustls con
+//! el proveedor criptográfico `ring`). Sin la feature, configurar TLS hace
+//! que el servidor no arranque.
+//!
+//! Un cliente que habla HTTP plano al puerto TLS se detecta mirando el
+//! primer byte (un handshake TLS empieza con `0x16`) y recibe un 301 a
+//! `https://` (`--tls-redirect-http`) o un 400.
+
+use crate::config::Config;
+use crate::http::Response;
+use crate::metrics::ConnectionMeter;
+use crate::server::reaper::IdleWatch;
+use std::io::{self, Read, Write};
+use std::net::{Shutdown, SocketAddr, TcpStream};
+use std::time::Duration;
+
+#[cfg(feature = "tls")]
+use std::sync::Arc;
+
+/// Primer byte de un registro TLS de handshake
+#[cfg(feature = "tls")]
+const TLS_HANDSHAKE_RECORD: u8 = 0x16;
+
+/// Tiempo máximo para completar el handshake TLS
+#[cfg(feature = "tls")]
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Conexión de un cliente, plana o cifrada
+///
+/// Implementa `Read` y `Write`, así que el resto del servidor no distingue
+/// entre HTTP y HTTPS. Con un `ConnectionMeter` cuenta los bytes del lado
+/// HTTP (con TLS, ya descifrados) y con un `IdleWatch` avisa al reaper de
+/// cada lectura y escritura.
+pub struct Connection {
+    stream: Stream,
+    meter: Option<ConnectionMeter>,
+    watch: Option<IdleWatch>,
+
+    /// Dirección original del cliente (PROXY protocol)
+    client: Option<SocketAddr>,
+}
+
+enum Stream {
+    /// HTTP plano
+    Plain(TcpStream),
+
+    /// HTTPS, con el handshake ya completo
+    #[cfg(feature = "tls")]
+    Tls(Box<rustls::StreamOwned<rustls::ServerConnection, TcpStream>>),
+}
+
+impl Connection {
+    /// Mide el tráfico de la conexión con `meter`, que se registra al cerrarla
+    pub fn with_meter(mut self, meter: ConnectionMeter) -> Self {
+        self.meter = Some(meter);
+        self
+    }
+
+    /// Deja la conexión bajo la vigilancia del reaper de conexiones ociosas
+    pub fn with_idle_watch(mut self, watch: Option<IdleWatch>) -> Self {
+        self.watch = watch;
+        self
+    }
+
+    /// Usa `client` como dirección del cliente en vez de la del socket
+    pub fn with_client_addr(mut self, client: Option<SocketAddr>) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Registra tráfico: `read` y `written` bytes
+    fn record(&mut self, read: usize, written: usize) {
+        if let Some(meter) = self.meter.as_mut() {
+            meter.add_read(read as u64);
+            meter.add_written(written as u64);
+        }
+        if let Some(watch) = &self.watch {
+            if read + written > 0 {
+                watch.touch();
+            }
+        }
+    }
+
+    /// Socket TCP subyacente
+    pub fn tcp(&self) -> &TcpStream {
+        match &self.stream {
+            Stream::Plain(stream) => stream,
+            #[cfg(feature = "tls")]
+            Stream::Tls(stream) => &stream.sock,
+        }
+    }
+
+    /// Indica si la conexión está cifrada
+    pub fn is_tls(&self) -> bool {
+        !matches!(self.stream, Stream::Plain(_))
+    }
+
+    /// Dirección del cliente: la del PROXY protocol si llegó una, si no la
+    /// del socket
+    pub fn peer_addr(&self) -> io::Result<SocketAddr> {
+        match self.client {
+            Some(client) => Ok(client),
+            None => self.tcp().peer_addr(),
+        }
+    }
+
+    /// Timeout de lectura del socket subyacente
+    pub fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        self.tcp().set_read_timeout(timeout)
+    }
+
+    /// Escribe `response` completa
+    ///
+    /// En una conexión plana un body de archivo se envía con `sendfile(2)`;
+    /// con TLS los bytes tienen que cifrarse en el proceso. Solo una
+    /// respuesta enviada completa cuenta como bytes escritos.
+    pub fn send_response(&mut self, response: &mut Response) -> io::Result<()> {
+        let len = response.wire_len();
+        if let Some(watch) = &self.watch {
+            watch.hold();
+        }
+        let sent = match &mut self.stream {
+            Stream::Plain(stream) => response.send_to(stream),
+            #[cfg(feature = "tls")]
+            Stream::Tls(stream) => response.write_to(stream),
+        };
+        if let Some(watch) = &self.watch {
+            watch.touch();
+        }
+        sent?;
+        if let Some(meter) = self.meter.as_mut() {
+            meter.add_written(len);
+        }
+        Ok(())
+    }
+
+    /// Cierra el lado de escritura (con `close_notify` si es TLS)
+    pub fn shutdown_write(&mut self) -> io::Result<()> {
+        #[cfg(feature = "tls")]
+        if let Stream::Tls(stream) = &mut self.stream {
+            stream.conn.send_close_notify();
+            stream.flush()?;
+        }
+        self.tcp().shutdown(Shutdown::Write)
+    }
+}
+
+impl From<TcpStream> for Connection {
+    fn from(stream: TcpStream) -> Self {
+        Self { stream: Stream::Plain(stream), meter: None, watch: None, client: None }
+    }
+}
+
+impl Read for Connection {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = match &mut self.stream {
+            Stream::Plain(stream) => stream.read(buf),
+            #[cfg(feature = "tls")]
+            Stream::Tls(stream) => stream.read(buf),
+        }?;
+        self.record(n, 0);
+        Ok(n)
+    }
+}
+
+impl Write for Connection {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = match &mut self.stream {
+            Stream::Plain(stream) => stream.write(buf),
+            #[cfg(feature = "tls")]
+            Stream::Tls(stream) => stream.write(buf),
+        }?;
+        self.record(0, n);
+        Ok(n)
+    }
+
+    fn write_vectored(&mut self, bufs: &[io::IoSlice<'_>]) -> io::Result<usize> {
+        let n = match &mut self.stream {
+            Stream::Plain(stream) => stream.write_vectored(bufs),
+            #[cfg(feature = "tls")]
+            Stream::Tls(stream) => stream.write_vectored(bufs),
+        }?;
+        self.record(0, n);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match &mut self.stream {
+            Stream::Plain(stream) => stream.flush(),
+            #[cfg(feature = "tls")]
+            Stream::Tls(stream) => stream.flush(),
+        }
+    }
+}
+
+/// Resultado de recibir una conexión en el listener TLS
+pub enum Accepted {
+    /// Handshake completo
+    Tls(Connection),
+
+    /// El cliente habló HTTP plano (o cerró sin enviar nada)
+    Plaintext(TcpStream),
+}
+
+/// Configuración TLS cargada (certificado y llave)
+pub struct TlsAcceptor {
+    #[cfg(feature = "tls")]
+    config: Arc<rustls::ServerConfig>,
+
+    /// Sin la feature `tls` no se puede construir
+    #[cfg(not(feature = "tls"))]
+    never: std::convert::Infallible,
+}
+
+impl TlsAcceptor {
+    /// Carga el certificado y la llave configurados
+    ///
+    /// Retorna `Ok(None)` si TLS no está configurado.
+    pub fn from_config(config: &Config) -> Result<Option<Self>, String> {
+        let (Some(cert), Some(key)) = (&config.tls_cert, &config.tls_key) else {
+            return Ok(None);
+        };
+        Self::load(cert, key).map(Some)
+    }
+
+    #[cfg(feature = "tls")]
+    fn load(cert: &str, key: &str) -> Result<Self, String> {
+        use rustls::pki_types::pem::PemObject;
+        use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+
+        let certs = CertificateDer::pem_file_iter(cert)
+            .and_then(|certs| certs.collect::<Result<Vec<_>, _>>())
+            .map_err(|e| format!("Cannot load TLS cert {}: {}", cert, e))?;
+        let private_key = PrivateKeyDer::from_pem_file(key)
+            .map_err(|e| format!("Cannot load TLS key {}: {}", key, e))?;
+
+        let provider = Arc::new(rustls::crypto::ring::default_provider());
+        let config = rustls::ServerConfig::builder_with_provider(provider)
+            .with_safe_default_protocol_versions()
+            .map_err(|e| format!("Invalid TLS configuration: {}", e))?
+            .with_no_client_auth()
+            .with_single_cert(certs, private_key)
+            .map_err(|e| format!("Invalid TLS cert/key pair: {}", e))?;
+
+        Ok(Self { config: Arc::new(config) })
+    }
+
+    #[cfg(not(feature = "tls"))]
+    fn load(_cert: &str, _key: &str) -> Result<Self, String> {
+        Err("TLS support not compiled in (rebuild with --features tls)".to_string())
+    }
+
+    /// Hace el handshake TLS sobre una conexión recién aceptada
+    ///
+    /// Si el primer byte no es un handshake TLS la conexión se retorna tal
+    /// cual para responderle en HTTP plano.
+    #[cfg(feature = "tls")]
+    pub fn accept(&self, mut stream: TcpStream) -> io::Result<Accepted> {
+        let mut first = [0u8; 1];
+        stream.set_read_timeout(Some(HANDSHAKE_TIMEOUT))?;
+        if stream.peek(&mut first)? == 0 || first[0] != TLS_HANDSHAKE_RECORD {
+            stream.set_read_timeout(None)?;
+            return Ok(Accepted::Plaintext(stream));
+        }
+
+        let mut conn = rustls::ServerConnection::new(Arc::clone(&self.config))
+            .map_err(io::Error::other)?;
+        while conn.is_handshaking() {
+            conn.complete_io(&mut stream)?;
+        }
+        stream.set_read_timeout(None)?;
+
+        let stream = Stream::Tls(Box::new(rustls::StreamOwned::new(conn, stream)));
+        Ok(Accepted::Tls(Connection { stream, meter: None, watch: None, client: None }))
+    }
+
+    #[cfg(not(feature = "tls"))]
+    pub fn accept(&self, _stream: TcpStream) -> io::Result<Accepted> {
+        match self.never {}
+    }
+}
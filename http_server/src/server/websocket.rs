@@ -0,0 +1,484 @@
+//! # WebSocket (RFC 6455)
+//! src/server/websocket.rs
+//!
+//! Implementa `/ws`: un canal de solo envío para dashboards que recibe, cada
+//! `--ws-interval-ms`, un snapshot de métricas. Al conectarse recibe además
+//! el estado de los jobs recientes y, desde ahí, un evento por cada cambio de
+//! estado o de progreso apenas se publica en el bus de eventos.
+//!
+//! Mensajes (frames de texto con JSON):
+//!
+//! ```text
+//! {"type":"metrics","total_requests":120,"active_threads":2,...,"queues":[...]}
+//! {"type":"job","id":"job-...","task":"isprime","status":"running","progress":40,"eta_ms":900}
+//! ```
+//!
+//! - El handshake valida `Upgrade`, `Connection`, `Sec-WebSocket-Version: 13`
+//!   y `Sec-WebSocket-Key`, y responde 101 con `Sec-WebSocket-Accept`.
+//! - Cada cliente ocupa un worker de conexión mientras está conectado; como
+//!   máximo hay `--ws-max-clients` a la vez (503 al resto, 0 = deshabilitado).
+//! - Los frames del cliente deben venir enmascarados. Se responden ping y
+//!   close; los mensajes de datos se ignoran.
+
+use super::tcp::SharedState;
+use super::tls::Connection;
+use crate::config::Config;
+use crate::http::{base64, Request, Response, StatusCode};
+use crate::http::request::Method;
+use crate::events::Event;
+use std::io::{self, ErrorKind, Read, Write};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Ruta del endpoint
+pub const WS_PATH: &str = "/ws";
+
+/// GUID fijo del RFC 6455 para calcular `Sec-WebSocket-Accept`
+const HANDSHAKE_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+/// Máximo payload aceptado en un frame del cliente
+const MAX_FRAME_PAYLOAD: usize = 64 * 1024;
+
+/// Jobs recientes cuyo estado se envía al conectarse
+const WATCHED_JOBS: usize = 100;
+
+/// Cada cuánto se revisan, como mínimo, el shutdown y los eventos nuevos
+const EVENT_POLL: Duration = Duration::from_millis(50);
+
+// Opcodes
+pub const OP_CONTINUATION: u8 = 0x0;
+pub const OP_TEXT: u8 = 0x1;
+pub const OP_BINARY: u8 = 0x2;
+pub const OP_CLOSE: u8 = 0x8;
+pub const OP_PING: u8 = 0x9;
+pub const OP_PONG: u8 = 0xA;
+
+// Códigos de cierre
+const CLOSE_NORMAL: u16 = 1000;
+const CLOSE_GOING_AWAY: u16 = 1001;
+const CLOSE_PROTOCOL_ERROR: u16 = 1002;
+const CLOSE_TOO_BIG: u16 = 1009;
+
+/// Frame recibido del cliente (ya desenmascarado)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    pub fin: bool,
+    pub opcode: u8,
+    pub payload: Vec<u8>,
+}
+
+/// Cupo de un cliente conectado; lo libera al hacer drop
+pub struct ClientSlot(Arc<AtomicUsize>);
+
+impl ClientSlot {
+    /// Reserva un cupo si hay menos de `max` clientes
+    pub fn acquire(clients: &Arc<AtomicUsize>, max: usize) -> Option<Self> {
+        clients
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| (n < max).then_some(n + 1))
+            .ok()
+            .map(|_| Self(Arc::clone(clients)))
+    }
+}
+
+impl Drop for ClientSlot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Valor de `Sec-WebSocket-Accept` para `key`
+pub fn accept_key(key: &str) -> String {
+    base64::encode(&sha1(format!("{}{}", key, HANDSHAKE_GUID).as_bytes()))
+}
+
+/// Valida el handshake y arma la respuesta 101
+pub fn handshake(request: &Request) -> Result<Response, Response> {
+    let bad_request = |message: &str| Err(Response::error(StatusCode::BadRequest, message));
+
+    if request.method() != Method::GET {
+        return bad_request("WebSocket handshake requires GET");
+    }
+    if !request.header("Upgrade").is_some_and(|v| v.eq_ignore_ascii_case("websocket")) {
+        return bad_request("Expected 'Upgrade: websocket'");
+    }
+    let connection_upgrade = request.header("Connection")
+        .is_some_and(|v| v.split(',').any(|token| token.trim().eq_ignore_ascii_case("upgrade")));
+    if !connection_upgrade {
+        return bad_request("Expected 'Connection: Upgrade'");
+    }
+    if request.header("Sec-WebSocket-Version") != Some("13") {
+        return Err(Response::error(StatusCode::BadRequest, "Unsupported WebSocket version (use 13)")
+            .with_header("Sec-WebSocket-Version", "13"));
+    }
+    let key = match request.header("Sec-WebSocket-Key") {
+        Some(key) if base64::decode(key).is_some_and(|nonce| nonce.len() == 16) => key,
+        _ => return bad_request("Invalid Sec-WebSocket-Key"),
+    };
+
+    Ok(Response::new(StatusCode::SwitchingProtocols)
+        .with_header("Upgrade", "websocket")
+        .with_header("Connection", "Upgrade")
+        .with_header("Sec-WebSocket-Accept", &accept_key(key)))
+}
+
+/// Handshake + reserva de cupo para un request a `/ws`
+pub fn upgrade(request: &Request, clients: &Arc<AtomicUsize>, config: &Config) -> Result<(Response, ClientSlot), Response> {
+    if config.ws_max_clients == 0 {
+        return Err(Response::error(StatusCode::NotFound, "WebSocket endpoint disabled"));
+    }
+    let response = handshake(request)?;
+    let slot = ClientSlot::acquire(clients, config.ws_max_clients).ok_or_else(|| {
+        Response::builder(StatusCode::ServiceUnavailable)
+            .retry_after(Duration::from_millis(config.retry_after_ms))
+            .json(&serde_json::json!({
+                "error": format!("Too many WebSocket clients (max {})", config.ws_max_clients),
+            }))
+    })?;
+    Ok((response, slot))
+}
+
+/// Codifica un frame del servidor (FIN, sin máscara)
+pub fn encode_frame(opcode: u8, payload: &[u8]) -> Vec<u8> {
+    let mut frame = Vec::with_capacity(payload.len() + 10);
+    frame.push(0x80 | opcode);
+    match payload.len() {
+        len @ 0..=125 => frame.push(len as u8),
+        len @ 126..=0xFFFF => {
+            frame.push(126);
+            frame.extend_from_slice(&(len as u16).to_be_bytes());
+        }
+        len => {
+            frame.push(127);
+            frame.extend_from_slice(&(len as u64).to_be_bytes());
+        }
+    }
+    frame.extend_from_slice(payload);
+    frame
+}
+
+/// Payload de un frame close
+fn close_payload(code: u16, reason: &str) -> Vec<u8> {
+    let mut payload = code.to_be_bytes().to_vec();
+    payload.extend_from_slice(reason.as_bytes());
+    payload
+}
+
+/// Intenta parsear un frame del cliente al inicio de `buf`
+///
+/// Retorna `Ok(None)` si faltan bytes, `Ok(Some((frame, consumidos)))` si
+/// hay un frame completo, o `Err(código de cierre)` si el frame es inválido.
+pub fn parse_frame(buf: &[u8]) -> Result<Option<(Frame, usize)>, u16> {
+    if buf.len() < 2 {
+        return Ok(None);
+    }
+    let fin = buf[0] & 0x80 != 0;
+    let opcode = buf[0] & 0x0F;
+    let masked = buf[1] & 0x80 != 0;
+
+    if buf[0] & 0x70 != 0 || !masked {
+        return Err(CLOSE_PROTOCOL_ERROR);
+    }
+    if opcode >= OP_CLOSE && (!fin || buf[1] & 0x7F > 125) {
+        // Los frames de control no se fragmentan y llevan ≤ 125 bytes
+        return Err(CLOSE_PROTOCOL_ERROR);
+    }
+
+    let (len, mut pos) = match buf[1] & 0x7F {
+        126 if buf.len() >= 4 => (u16::from_be_bytes([buf[2], buf[3]]) as u64, 4),
+        127 if buf.len() >= 10 => (u64::from_be_bytes(buf[2..10].try_into().unwrap()), 10),
+        126 | 127 => return Ok(None),
+        len => (len as u64, 2),
+    };
+    if len > MAX_FRAME_PAYLOAD as u64 {
+        return Err(CLOSE_TOO_BIG);
+    }
+    let len = len as usize;
+    if buf.len() < pos + 4 + len {
+        return Ok(None);
+    }
+
+    let mask = [buf[pos], buf[pos + 1], buf[pos + 2], buf[pos + 3]];
+    pos += 4;
+    let payload = buf[pos..pos + len]
+        .iter()
+        .enumerate()
+        .map(|(i, byte)| byte ^ mask[i % 4])
+        .collect();
+    Ok(Some((Frame { fin, opcode, payload }, pos + len)))
+}
+
+/// Snapshot de métricas para los clientes
+fn metrics_message(state: &SharedState) -> String {
+    let snapshot = state.metrics.get_snapshot();
+    let queues: Vec<serde_json::Value> = state.job_manager.queue_depths()
+        .into_iter()
+        .map(|(name, depth, capacity)| serde_json::json!({ "name": name, "depth": depth, "capacity": capacity }))
+        .collect();
+    serde_json::json!({
+        "type": "metrics",
+        "total_requests": snapshot.total_requests,
+        "active_threads": snapshot.active_threads,
+        "uptime_secs": snapshot.uptime_secs,
+        "latency_p50_us": snapshot.latency_p50_us,
+        "latency_p95_us": snapshot.latency_p95_us,
+        "latency_p99_us": snapshot.latency_p99_us,
+        "running_jobs": state.job_manager.running_count(),
+        "queues": queues,
+    })
+    .to_string()
+}
+
+/// Mensaje para un cambio de job (`None` para los demás eventos)
+fn job_message(event: &Event) -> Option<String> {
+    let Event::JobStateChanged { id, job_type, status, progress, eta_ms } = event else {
+        return None;
+    };
+    Some(serde_json::json!({
+        "type": "job",
+        "id": id,
+        "task": job_type,
+        "status": status,
+        "progress": progress,
+        "eta_ms": eta_ms,
+    })
+    .to_string())
+}
+
+/// Atiende una conexión ya actualizada hasta que el cliente la cierre
+///
+/// Corre en el worker de conexión que recibió el handshake; el cupo se
+/// libera al terminar.
+pub fn run_session(mut stream: Connection, state: &SharedState, _slot: ClientSlot) -> io::Result<()> {
+    let peer = stream.peer_addr().map(|addr| addr.to_string()).unwrap_or_else(|_| "unknown".to_string());
+    let verbose = state.config.read().unwrap().log_enabled("info");
+    if verbose {
+        println!("   🔌 WebSocket conectado: {}", peer);
+    }
+
+    let result = session_loop(&mut stream, state);
+
+    if verbose {
+        println!("   🔌 WebSocket desconectado: {}", peer);
+    }
+    result
+}
+
+fn session_loop(stream: &mut Connection, state: &SharedState) -> io::Result<()> {
+    // Suscribirse antes de leer el estado inicial para no perder cambios
+    let events = state.events.subscribe("ws");
+    for job in state.job_manager.list_jobs(None, WATCHED_JOBS).iter().rev() {
+        if let Some(message) = job_message(&Event::job_state(job)) {
+            stream.write_all(&encode_frame(OP_TEXT, message.as_bytes()))?;
+        }
+    }
+
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 4096];
+    let mut next_snapshot = Instant::now();
+
+    loop {
+        if state.shutdown.load(Ordering::SeqCst) {
+            stream.write_all(&encode_frame(OP_CLOSE, &close_payload(CLOSE_GOING_AWAY, "server shutting down")))?;
+            return Ok(());
+        }
+
+        if Instant::now() >= next_snapshot {
+            // El intervalo se puede recargar en caliente
+            let interval = Duration::from_millis(state.config.read().unwrap().ws_interval_ms);
+            next_snapshot = Instant::now() + interval;
+
+            stream.write_all(&encode_frame(OP_TEXT, metrics_message(state).as_bytes()))?;
+        }
+
+        for message in events.try_iter().filter_map(|event| job_message(&event)) {
+            stream.write_all(&encode_frame(OP_TEXT, message.as_bytes()))?;
+        }
+        stream.flush()?;
+
+        // Esperar frames del cliente hasta el próximo snapshot
+        let wait = next_snapshot.saturating_duration_since(Instant::now())
+            .clamp(Duration::from_millis(1), EVENT_POLL);
+        stream.set_read_timeout(Some(wait))?;
+        match stream.read(&mut chunk) {
+            Ok(0) => return Ok(()),
+            Ok(n) => buffer.extend_from_slice(&chunk[..n]),
+            Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => continue,
+            Err(e) => return Err(e),
+        }
+
+        loop {
+            let (frame, consumed) = match parse_frame(&buffer) {
+                Ok(Some(parsed)) => parsed,
+                Ok(None) => break,
+                Err(code) => {
+                    stream.write_all(&encode_frame(OP_CLOSE, &close_payload(code, "invalid frame")))?;
+                    return Ok(());
+                }
+            };
+            buffer.drain(..consumed);
+
+            match frame.opcode {
+                OP_PING => stream.write_all(&encode_frame(OP_PONG, &frame.payload))?,
+                OP_CLOSE => {
+                    // Responder con el mismo código (o 1000 si no vino ninguno)
+                    let code = match frame.payload.get(..2) {
+                        Some(&[hi, lo]) => u16::from_be_bytes([hi, lo]),
+                        _ => CLOSE_NORMAL,
+                    };
+                    stream.write_all(&encode_frame(OP_CLOSE, &code.to_be_bytes()))?;
+                    return Ok(());
+                }
+                // El canal es de solo envío: datos y pongs se ignoran
+                OP_TEXT | OP_BINARY | OP_CONTINUATION | OP_PONG => {}
+                _ => {
+                    stream.write_all(&encode_frame(OP_CLOSE, &close_payload(CLOSE_PROTOCOL_ERROR, "unknown opcode")))?;
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
+
+/// SHA-1 (RFC 3174), solo para `Sec-WebSocket-Accept`
+fn sha1(data: &[u8]) -> [u8; 20] {
+    let mut h: [u32; 5] = [0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476, 0xC3D2E1F0];
+
+    let mut message = data.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&((data.len() as u64) * 8).to_be_bytes());
+
+    for block in message.chunks(64) {
+        let mut w = [0u32; 80];
+        for (i, word) in block.chunks(4).enumerate() {
+            w[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        }
+        for i in 16..80 {
+            w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e] = h;
+        for (i, word) in w.iter().enumerate() {
+            let (f, k) = match i {
+                0..=19 => ((b & c) | (!b & d), 0x5A827999),
+                20..=39 => (b ^ c ^ d, 0x6ED9EBA1),
+                40..=59 => ((b & c) | (b & d) | (c & d), 0x8F1BBCDC),
+                _ => (b ^ c ^ d, 0xCA62C1D6),
+            };
+            let temp = a.rotate_left(5)
+                .wrapping_add(f)
+                .wrapping_add(e)
+                .wrapping_add(k)
+                .wrapping_add(*word);
+            e = d;
+            d = c;
+            c = b.rotate_left(30);
+            b = a;
+            a = temp;
+        }
+
+        for (state, value) in h.iter_mut().zip([a, b, c, d, e]) {
+            *state = state.wrapping_add(value);
+        }
+    }
+
+    let mut digest = [0u8; 20];
+    for (i, word) in h.iter().enumerate() {
+        digest[i * 4..i * 4 + 4].copy_from_slice(&word.to_be_bytes());
+    }
+    digest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Frame enmascarado como los que envía un navegador
+    fn client_frame(opcode: u8, payload: &[u8]) -> Vec<u8> {
+        let mask = [0x37, 0xfa, 0x21, 0x3d];
+        let mut frame = encode_frame(opcode, payload);
+        let header_len = frame.len() - payload.len();
+        frame[1] |= 0x80;
+        let masked: Vec<u8> = payload.iter().enumerate().map(|(i, b)| b ^ mask[i % 4]).collect();
+        frame.truncate(header_len);
+        frame.extend_from_slice(&mask);
+        frame.extend_from_slice(&masked);
+        frame
+    }
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    #[test]
+    fn test_sha1_and_accept_key() {
+        assert_eq!(hex(&sha1(b"")), "da39a3ee5e6b4b0d3255bfef95601890afd80709");
+        assert_eq!(hex(&sha1(b"abc")), "a9993e364706816aba3e25717850c26c9cd0d89d");
+        // Ejemplo del RFC 6455, sección 1.3
+        assert_eq!(accept_key("dGhlIHNhbXBsZSBub25jZQ=="), "s3pPLMBiTxaQ9kYGzzhZRbK+xOo=");
+    }
+
+    #[test]
+    fn test_handshake_validation() {
+        let raw = "GET /ws HTTP/1.1\r\nHost: x\r\nUpgrade: websocket\r\nConnection: keep-alive, Upgrade\r\n\
+                   Sec-WebSocket-Version: 13\r\nSec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\n\r\n";
+        let response = handshake(&Request::parse(raw.as_bytes()).unwrap()).unwrap();
+        assert_eq!(response.status(), StatusCode::SwitchingProtocols);
+        assert_eq!(&response.headers()["Sec-WebSocket-Accept"], "s3pPLMBiTxaQ9kYGzzhZRbK+xOo=");
+
+        for broken in [
+            raw.replace("Upgrade: websocket", "Upgrade: h2c"),
+            raw.replace("Version: 13", "Version: 8"),
+            raw.replace("dGhlIHNhbXBsZSBub25jZQ==", "c2hvcnQ="),
+        ] {
+            let error = handshake(&Request::parse(broken.as_bytes()).unwrap()).unwrap_err();
+            assert_eq!(error.status(), StatusCode::BadRequest);
+        }
+    }
+
+    #[test]
+    fn test_client_slots_are_limited_and_released() {
+        let clients = Arc::new(AtomicUsize::new(0));
+        let first = ClientSlot::acquire(&clients, 1).unwrap();
+        assert!(ClientSlot::acquire(&clients, 1).is_none());
+        drop(first);
+        assert!(ClientSlot::acquire(&clients, 1).is_some());
+        assert_eq!(clients.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn test_frame_roundtrip() {
+        for len in [0, 5, 125, 126, 300, 70_000] {
+            let encoded = encode_frame(OP_TEXT, &vec![b'x'; len]);
+            assert_eq!(encoded[0], 0x81);
+            let header_len = encoded.len() - len;
+            assert_eq!(header_len, if len < 126 { 2 } else if len <= 0xFFFF { 4 } else { 10 });
+        }
+
+        let frame = client_frame(OP_TEXT, b"hola");
+        assert_eq!(parse_frame(&frame[..3]), Ok(None));
+        let (parsed, consumed) = parse_frame(&frame).unwrap().unwrap();
+        assert_eq!(parsed, Frame { fin: true, opcode: OP_TEXT, payload: b"hola".to_vec() });
+        assert_eq!(consumed, frame.len());
+
+        let long = client_frame(OP_BINARY, &[7; 1000]);
+        assert_eq!(parse_frame(&long).unwrap().unwrap().0.payload, vec![7; 1000]);
+    }
+
+    #[test]
+    fn test_invalid_client_frames() {
+        // Sin máscara
+        assert_eq!(parse_frame(&encode_frame(OP_TEXT, b"hi")), Err(CLOSE_PROTOCOL_ERROR));
+        // Demasiado grande
+        let mut huge = vec![0x82, 0xFF];
+        huge.extend_from_slice(&((MAX_FRAME_PAYLOAD as u64) + 1).to_be_bytes());
+        assert_eq!(parse_frame(&huge), Err(CLOSE_TOO_BIG));
+        // Ping fragmentado
+        let mut ping = client_frame(OP_PING, b"");
+        ping[0] &= 0x7F;
+        assert_eq!(parse_frame(&ping), Err(CLOSE_PROTOCOL_ERROR));
+    }
+}
@@ -0,0 +1,167 @@
+//! # Utilidades para Tests de Integración
+//! src/testing.rs
+//!
+//! Levanta un `Server` completo en un puerto efímero de `127.0.0.1`, con su
+//! propio directorio de datos y de jobs en un directorio temporal, y lo
+//! detiene al salir del scope:
+//!
+//! ```no_run
+//! use http_server::testing::TestServer;
+//!
+//! let server = TestServer::start();
+//! let response = server.client().get("/fibonacci?num=10").unwrap();
+//! assert_eq!(response.status, 200);
+//! // Al hacer drop se detiene el servidor y se borra el directorio temporal
+//! ```
+//!
+//! El sandbox de archivos (`commands::sandbox`) y los límites por comando
+//! son globales del proceso: cada `TestServer` los apunta a su
+//! configuración, así que los tests que dependen de ellos deberían usar un
+//! solo servidor por binario de test (ej: un `OnceLock` compartido).
+
+use crate::client::Client;
+use crate::config::Config;
+use crate::server::{Server, ServerBuilder};
+use std::fs;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::thread::{self, JoinHandle};
+
+/// Contador para nombres únicos de directorios temporales
+static INSTANCE_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Servidor corriendo en un thread de fondo; se detiene con `drop`
+pub struct TestServer {
+    addr: SocketAddr,
+    /// Directorio de datos efectivo (puede venir de `configure`)
+    data_dir: PathBuf,
+    /// Directorio temporal creado para este servidor (se borra al detenerlo)
+    temp_dir: PathBuf,
+    shutdown: Sender<()>,
+    handle: Option<JoinHandle<std::io::Result<()>>>,
+}
+
+impl TestServer {
+    /// Levanta un servidor con la configuración de `test_config`
+    pub fn start() -> Self {
+        Self::start_with(|_| {})
+    }
+
+    /// Levanta un servidor aplicando `configure` sobre `test_config`
+    ///
+    /// `configure` corre después de fijar los directorios temporales, así
+    /// que puede cambiarlos. `host` y `port` se reemplazan por los del
+    /// puerto efímero.
+    pub fn start_with(configure: impl FnOnce(&mut Config)) -> Self {
+        Self::start_builder(|builder| builder.configure(configure))
+    }
+
+    /// Levanta un servidor armado por `build` (rutas propias, subsistemas
+    /// simulados...) a partir de un builder con `test_config`
+    ///
+    /// ```no_run
+    /// use http_server::http::{Request, Response};
+    /// use http_server::testing::TestServer;
+    ///
+    /// fn hello(_request: &Request) -> Response {
+    ///     Response::text("hola")
+    /// }
+    ///
+    /// let server = TestServer::start_builder(|builder| builder.route("/hello", hello));
+    /// assert_eq!(server.client().get("/hello").unwrap().body, "hola");
+    /// ```
+    pub fn start_builder(build: impl FnOnce(ServerBuilder) -> ServerBuilder) -> Self {
+        let temp_dir = std::env::temp_dir().join(format!(
+            "redunix-test-{}-{}",
+            std::process::id(),
+            INSTANCE_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+
+        let builder = build(Server::builder().with_config(test_config(&temp_dir))).configure(|config| {
+            config.host = "127.0.0.1".to_string();
+            config.port = 0;
+        });
+
+        let errors = builder.config().check().errors;
+        assert!(errors.is_empty(), "invalid test config: {:?}", errors);
+        let data_dir = builder.config().data_dir.clone();
+
+        // `build` crea el directorio de datos y lo fija como sandbox
+        let (shutdown, stop) = mpsc::channel();
+        let (addr, handle) = builder.build().run_with_shutdown(stop).expect("start test server");
+        let data_dir = fs::canonicalize(data_dir).expect("test data dir");
+
+        Self { addr, data_dir, temp_dir, shutdown, handle: Some(handle) }
+    }
+
+    /// Dirección donde escucha el servidor
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Cliente apuntando a este servidor
+    pub fn client(&self) -> Client {
+        Client::new(self.addr.to_string())
+    }
+
+    /// Directorio de datos (sandbox) del servidor, canonicalizado
+    pub fn data_dir(&self) -> &Path {
+        &self.data_dir
+    }
+
+    /// Detiene el servidor y espera a que termine el accept loop
+    pub fn shutdown(mut self) {
+        self.stop();
+    }
+
+    fn stop(&mut self) {
+        let Some(handle) = self.handle.take() else {
+            return;
+        };
+
+        let _ = self.shutdown.send(());
+        if let Err(panic) = handle.join() {
+            if !thread::panicking() {
+                std::panic::resume_unwind(panic);
+            }
+        }
+
+        let _ = fs::remove_dir_all(&self.temp_dir);
+    }
+}
+
+impl Drop for TestServer {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Configuración por defecto de los tests: pocos workers, logs solo de
+/// errores y directorios dentro de `data_dir`
+pub fn test_config(data_dir: &Path) -> Config {
+    Config {
+        data_dir: data_dir.to_string_lossy().to_string(),
+        jobs_storage_path: data_dir.join("jobs.json").to_string_lossy().to_string(),
+        cpu_workers: 2,
+        io_workers: 2,
+        basic_workers: 1,
+        conn_workers: 8,
+        log_level: "error".to_string(),
+        ..Config::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_test_config_uses_temp_dirs() {
+        let dir = std::env::temp_dir().join("redunix-config-check");
+        let config = test_config(&dir);
+        assert!(config.jobs_storage_path.starts_with(&config.data_dir));
+        assert_eq!(config.log_level, "error");
+    }
+}
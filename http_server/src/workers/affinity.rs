@@ -0,0 +1,182 @@
+//! # Afinidad de CPU
+//! src/workers/affinity.rs
+//!
+//! Fijar cada worker a un core concreto (`--cpu-affinity`) permite medir el
+//! efecto de la caché y la localidad: con los workers fijos el scheduler no
+//! los mueve entre cores y los datos que tocan siguen calientes.
+//!
+//! La especificación es una lista de cores y rangos (`0-3,6`), que se aplica
+//! al pool CPU de jobs y al pool de conexiones, o una lista por pool
+//! separada con `;` (`cpu=0-3;conn=4-7`). Los workers de un pool se reparten
+//! en round-robin: el worker `i` usa `cores[i % cores.len()]`.
+//!
+//! Solo en Linux (`sched_setaffinity(2)`); en otros sistemas fijar un thread
+//! falla con `Unsupported` y los workers siguen sin afinidad.
+
+use std::cell::Cell;
+use std::io;
+
+/// Máximo de cores que se pueden nombrar (tamaño de la máscara)
+pub const MAX_CORES: usize = 1024;
+
+/// Cores asignados a cada pool; vacío = sin afinidad
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AffinityPlan {
+    /// Workers del pool CPU de jobs
+    pub cpu: Vec<usize>,
+
+    /// Workers del pool de conexiones
+    pub conn: Vec<usize>,
+}
+
+impl AffinityPlan {
+    /// Parsea la especificación de `--cpu-affinity`
+    ///
+    /// # Ejemplo
+    /// ```
+    /// use http_server::workers::affinity::AffinityPlan;
+    ///
+    /// let plan = AffinityPlan::parse("0-1").unwrap();
+    /// assert_eq!(plan.cpu, vec![0, 1]);
+    /// assert_eq!(plan.conn, vec![0, 1]);
+    ///
+    /// let plan = AffinityPlan::parse("cpu=0-3;conn=4,5").unwrap();
+    /// assert_eq!(plan.cpu, vec![0, 1, 2, 3]);
+    /// assert_eq!(plan.conn, vec![4, 5]);
+    /// ```
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        if !spec.contains('=') {
+            let cores = parse_cores(spec)?;
+            return Ok(Self { cpu: cores.clone(), conn: cores });
+        }
+
+        let mut plan = Self::default();
+        for entry in spec.split(';').map(str::trim).filter(|e| !e.is_empty()) {
+            let (pool, cores) = entry
+                .split_once('=')
+                .ok_or_else(|| format!("expected <pool>=<cores>, got '{}'", entry))?;
+            let cores = parse_cores(cores)?;
+            match pool.trim() {
+                "cpu" => plan.cpu = cores,
+                "conn" => plan.conn = cores,
+                other => return Err(format!("unknown pool '{}' (expected cpu or conn)", other)),
+            }
+        }
+        Ok(plan)
+    }
+
+    /// Core más alto nombrado en la especificación
+    pub fn max_core(&self) -> Option<usize> {
+        self.cpu.iter().chain(&self.conn).copied().max()
+    }
+}
+
+/// Parsea una lista de cores y rangos (`0-3,6`), sin repetidos y en orden
+pub fn parse_cores(spec: &str) -> Result<Vec<usize>, String> {
+    let mut cores = Vec::new();
+    for item in spec.split(',').map(str::trim) {
+        if item.is_empty() {
+            return Err(format!("empty core in '{}'", spec));
+        }
+        let (first, last) = match item.split_once('-') {
+            Some((a, b)) => (parse_core(a)?, parse_core(b)?),
+            None => {
+                let core = parse_core(item)?;
+                (core, core)
+            }
+        };
+        if first > last {
+            return Err(format!("invalid core range '{}'", item));
+        }
+        for core in first..=last {
+            if !cores.contains(&core) {
+                cores.push(core);
+            }
+        }
+    }
+    Ok(cores)
+}
+
+fn parse_core(text: &str) -> Result<usize, String> {
+    let core: usize = text.trim().parse().map_err(|_| format!("invalid core '{}'", text.trim()))?;
+    if core >= MAX_CORES {
+        return Err(format!("core {} out of range (max {})", core, MAX_CORES - 1));
+    }
+    Ok(core)
+}
+
+thread_local! {
+    static CURRENT_CORE: Cell<Option<usize>> = const { Cell::new(None) };
+}
+
+/// Core al que se fijó el thread actual, si se fijó
+pub fn current_core() -> Option<usize> {
+    CURRENT_CORE.with(Cell::get)
+}
+
+#[cfg(target_os = "linux")]
+mod ffi {
+    extern "C" {
+        /// `int sched_setaffinity(pid_t pid, size_t cpusetsize, const cpu_set_t *mask)`
+        pub fn sched_setaffinity(pid: i32, cpusetsize: usize, mask: *const u64) -> i32;
+    }
+}
+
+/// Fija el thread actual a `core`
+#[cfg(target_os = "linux")]
+pub fn pin_current_thread(core: usize) -> io::Result<()> {
+    if core >= MAX_CORES {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("core {} out of range", core)));
+    }
+    let mut mask = [0u64; MAX_CORES / 64];
+    mask[core / 64] |= 1 << (core % 64);
+
+    // pid 0 = el thread que llama
+    let result = unsafe { ffi::sched_setaffinity(0, std::mem::size_of_val(&mask), mask.as_ptr()) };
+    if result != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    CURRENT_CORE.with(|current| current.set(Some(core)));
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn pin_current_thread(_core: usize) -> io::Result<()> {
+    Err(io::Error::new(io::ErrorKind::Unsupported, "CPU affinity is only supported on Linux"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_cores_and_ranges() {
+        assert_eq!(parse_cores("0-2,5, 1").unwrap(), vec![0, 1, 2, 5]);
+        assert!(parse_cores("3-1").is_err());
+        assert!(parse_cores("a").is_err());
+        assert!(parse_cores("0,,1").is_err());
+        assert!(parse_cores("5000").is_err());
+    }
+
+    #[test]
+    fn test_plan_per_pool() {
+        let plan = AffinityPlan::parse("conn=2").unwrap();
+        assert!(plan.cpu.is_empty());
+        assert_eq!(plan.conn, vec![2]);
+        assert_eq!(plan.max_core(), Some(2));
+        assert!(AffinityPlan::parse("gpu=1").is_err());
+        assert!(AffinityPlan::parse("cpu=1;conn").is_err());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_pin_records_the_current_core() {
+        std::thread::spawn(|| {
+            assert_eq!(current_core(), None);
+            pin_current_thread(0).unwrap();
+            assert_eq!(current_core(), Some(0));
+        })
+        .join()
+        .unwrap();
+    }
+}
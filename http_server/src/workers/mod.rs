@@ -0,0 +1,22 @@
+//! # Pools de Workers
+//! src/workers/mod.rs
+//!
+//! Este módulo implementa el pool de threads reutilizable del servidor:
+//! - Workers con nombre, en número fijo o elástico (crecen a demanda y se
+//!   retiran tras un tiempo ociosos)
+//! - Cola de tareas acotada (backpressure al encolar)
+//! - Política de despacho configurable (FIFO, LIFO, least-busy)
+//! - Contención de panics por tarea
+//! - Apagado ordenado que termina las tareas encoladas
+//! - Prioridad de los requests interactivos sobre los jobs batch
+//! - Afinidad de CPU opcional por pool (`--cpu-affinity`)
+//!
+//! Lo usan tanto el manejo de conexiones como la ejecución de jobs.
+
+pub mod affinity;
+pub mod pool;
+pub mod priority;
+
+pub use affinity::AffinityPlan;
+pub use pool::{panic_message, DispatchPolicy, Elasticity, PoolStats, SubmitError, ThreadPool};
+pub use priority::{InteractiveGuard, PriorityGate};
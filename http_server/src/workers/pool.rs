@@ -0,0 +1,888 @@
+//! # Pool de Threads Genérico
+//! src/workers/pool.rs
+//!
+//! `ThreadPool` con un número fijo de workers y una cola de tareas acotada.
+//!
+//! - `execute` bloquea mientras la cola está llena; `try_execute` retorna
+//!   `SubmitError::Full` en vez de esperar.
+//! - `call` ejecuta una función en el pool y espera su resultado, sin
+//!   esperar en cola más de un máximo.
+//! - La política de despacho (`DispatchPolicy`) decide qué tarea toma un
+//!   worker libre (FIFO o LIFO) y a qué worker ocioso se despierta
+//!   (least-busy elige al que menos tiempo lleva ocupado).
+//! - Si una tarea hace panic, el worker lo atrapa, lo cuenta y sigue
+//!   atendiendo la cola (y lo publica como `WorkerPanicked` si el pool
+//!   tiene un bus de eventos).
+//! - Un pool elástico crea workers a demanda hasta un máximo y retira los
+//!   que sobran tras un tiempo ocioso.
+//! - `shutdown` deja de aceptar tareas, termina las ya encoladas y espera
+//!   a los workers (también se llama al hacer drop).
+
+use crate::events::{Event, EventBus};
+use crate::workers::affinity;
+use serde::Serialize;
+use std::any::Any;
+use std::collections::VecDeque;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, OnceLock};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// Tarea que ejecuta un worker
+pub type Task = Box<dyn FnOnce() + Send + 'static>;
+
+/// Error al enviar una tarea al pool
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SubmitError {
+    /// La cola de tareas está llena (solo `try_execute`)
+    Full,
+    
+    /// El pool se está apagando
+    ShutDown,
+    
+    /// La tarea no empezó dentro de la espera máxima (solo `call`)
+    TimedOut,
+    
+    /// La tarea hizo panic, con el mensaje del panic (solo `call`)
+    Panicked(String),
+}
+
+impl std::fmt::Display for SubmitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SubmitError::Full => write!(f, "Thread pool queue is full"),
+            SubmitError::ShutDown => write!(f, "Thread pool is shutting down"),
+            SubmitError::TimedOut => write!(f, "Task waited too long in the queue"),
+            SubmitError::Panicked(message) => write!(f, "Task panicked: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for SubmitError {}
+
+/// Extrae el mensaje de un panic (`panic!("...")` produce `&str` o `String`)
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+/// Estrategia con la que el pool reparte las tareas
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DispatchPolicy {
+    /// La tarea más antigua primero
+    #[default]
+    Fifo,
+    
+    /// La tarea más reciente primero (mejor localidad de caché)
+    Lifo,
+    
+    /// FIFO, despertando al worker ocioso con menos tiempo ocupado
+    LeastBusy,
+}
+
+impl DispatchPolicy {
+    /// Nombres válidos en la configuración
+    pub const NAMES: &'static [&'static str] = &["fifo", "lifo", "least-busy"];
+    
+    /// Parsea la política desde su nombre en la configuración
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name.to_lowercase().as_str() {
+            "fifo" => Ok(DispatchPolicy::Fifo),
+            "lifo" => Ok(DispatchPolicy::Lifo),
+            "least-busy" | "least_busy" => Ok(DispatchPolicy::LeastBusy),
+            _ => Err(format!(
+                "Invalid dispatch policy: {} (expected one of: {})",
+                name,
+                Self::NAMES.join(", ")
+            )),
+        }
+    }
+    
+    /// Nombre de la política
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DispatchPolicy::Fifo => "fifo",
+            DispatchPolicy::Lifo => "lifo",
+            DispatchPolicy::LeastBusy => "least-busy",
+        }
+    }
+}
+
+/// Crecimiento y retiro de workers de un pool elástico
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Elasticity {
+    /// Máximo de workers vivos (los que exceden el mínimo se crean a demanda)
+    pub max_workers: usize,
+    
+    /// Tiempo ocioso tras el cual se retira un worker por encima del mínimo
+    pub idle_timeout: Duration,
+    
+    /// Loguear la creación y el retiro de workers
+    pub log: bool,
+}
+
+impl Elasticity {
+    /// Pool que crece de `min_workers` hasta `max_workers` (0 = tamaño fijo)
+    pub fn new(min_workers: usize, max_workers: usize, idle_timeout: Duration, log: bool) -> Self {
+        let max_workers = if max_workers == 0 { min_workers } else { max_workers.max(min_workers) };
+        Self { max_workers, idle_timeout, log }
+    }
+    
+    /// Pool de tamaño fijo
+    pub fn fixed(workers: usize) -> Self {
+        Self { max_workers: workers, idle_timeout: Duration::MAX, log: false }
+    }
+}
+
+/// Estadísticas de un pool
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PoolStats {
+    pub name: String,
+    pub policy: DispatchPolicy,
+    
+    /// Workers vivos, mínimo y máximo
+    pub workers: usize,
+    pub min_workers: usize,
+    pub max_workers: usize,
+    
+    pub queued: usize,
+    pub capacity: usize,
+    pub active: usize,
+    pub completed: u64,
+    pub panicked: u64,
+    
+    /// Mensaje del último panic de una tarea
+    pub last_panic: Option<String>,
+    
+    /// Workers creados a demanda y retirados por ociosos
+    pub spawned: u64,
+    pub retired: u64,
+    
+    /// Espera promedio de una tarea en la cola (ms)
+    pub avg_wait_ms: f64,
+    
+    /// Tiempo ocupado de cada worker (ms), en orden de índice
+    pub busy_ms: Vec<u64>,
+    
+    /// Core al que está fijado cada worker, en orden de índice
+    /// (`None` sin afinidad o antes de su primera tarea)
+    pub cores: Vec<Option<usize>>,
+}
+
+/// Estado protegido por el mutex del pool
+struct PoolState {
+    /// Tareas en espera con el instante en que se encolaron
+    tasks: VecDeque<(Instant, Task)>,
+    active: usize,
+    completed: u64,
+    panicked: u64,
+    last_panic: Option<String>,
+    shutting_down: bool,
+    
+    /// Índices de los workers ociosos esperando tarea
+    idle: Vec<usize>,
+    
+    /// Worker `i` fue despertado por un envío
+    signaled: Vec<bool>,
+    
+    /// Slot `i` tiene un worker vivo
+    alive: Vec<bool>,
+    live: usize,
+    spawned: u64,
+    retired: u64,
+    
+    /// Tiempo ocupado acumulado de cada worker
+    busy: Vec<Duration>,
+    
+    /// Core al que se fijó cada worker
+    cores: Vec<Option<usize>>,
+    
+    /// Suma de esperas en cola y tareas iniciadas (para el promedio)
+    total_wait: Duration,
+    started: u64,
+}
+
+/// Estado compartido entre el pool y sus workers
+struct Shared {
+    name: String,
+    state: Mutex<PoolState>,
+    
+    /// Un condvar por slot de worker: el envío despierta a uno concreto
+    wakers: Vec<Condvar>,
+    
+    /// Notifica a quien encola que se liberó espacio
+    not_full: Condvar,
+    
+    capacity: usize,
+    policy: DispatchPolicy,
+    min_workers: usize,
+    elasticity: Elasticity,
+    
+    /// Bus donde se publican los panics de las tareas
+    events: OnceLock<EventBus>,
+    
+    /// Cores a los que se fijan los workers (`cores[slot % len]`)
+    affinity: OnceLock<Vec<usize>>,
+}
+
+impl Shared {
+    /// Fija el worker `index` a su core, si el pool tiene afinidad
+    ///
+    /// Retorna `true` si ya se intentó (si falló, el error se informa una
+    /// sola vez) y `false` si el pool todavía no tiene afinidad.
+    fn pin(&self, index: usize) -> bool {
+        let Some(cores) = self.affinity.get() else {
+            return false;
+        };
+        let core = cores[index % cores.len()];
+        match affinity::pin_current_thread(core) {
+            Ok(()) => self.state.lock().unwrap().cores[index] = Some(core),
+            Err(e) => eprintln!("⚠️  Pool {}: could not pin worker {}-{} to core {}: {}",
+                self.name, self.name, index, core, e),
+        }
+        true
+    }
+    
+    /// Toma la siguiente tarea según la política
+    fn pop(&self, state: &mut PoolState) -> Option<(Instant, Task)> {
+        match self.policy {
+            DispatchPolicy::Lifo => state.tasks.pop_back(),
+            DispatchPolicy::Fifo | DispatchPolicy::LeastBusy => state.tasks.pop_front(),
+        }
+    }
+    
+    /// Despierta a un worker ocioso para que tome una tarea nueva
+    /// 
+    /// Retorna false si no había ninguno ocioso.
+    fn wake_one(&self, state: &mut PoolState) -> bool {
+        let pick = match self.policy {
+            DispatchPolicy::LeastBusy => state.idle.iter()
+                .enumerate()
+                .min_by_key(|(_, &worker)| state.busy[worker])
+                .map(|(pos, _)| pos),
+            // El último en quedar ocioso tiene el caché más caliente
+            DispatchPolicy::Fifo | DispatchPolicy::Lifo => state.idle.len().checked_sub(1),
+        };
+        
+        match pick {
+            Some(pos) => {
+                let worker = state.idle.swap_remove(pos);
+                state.signaled[worker] = true;
+                self.wakers[worker].notify_one();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Pool de threads con cola acotada
+pub struct ThreadPool {
+    shared: Arc<Shared>,
+    
+    /// Handle del último worker lanzado en cada slot
+    handles: Mutex<Vec<Option<JoinHandle<()>>>>,
+}
+
+impl ThreadPool {
+    /// Crea un pool FIFO con `workers` threads y espacio para `capacity`
+    /// tareas en espera
+    /// 
+    /// Los threads se llaman `<name>-<i>`. Con 0 workers las tareas quedan
+    /// encoladas sin ejecutarse (útil en tests).
+    /// 
+    /// # Ejemplo
+    /// ```
+    /// use http_server::workers::ThreadPool;
+    /// 
+    /// let pool = ThreadPool::new("demo", 2, 16);
+    /// pool.execute(|| println!("hola desde el pool")).unwrap();
+    /// pool.shutdown();
+    /// ```
+    pub fn new(name: &str, workers: usize, capacity: usize) -> Self {
+        Self::with_policy(name, workers, capacity, DispatchPolicy::Fifo)
+    }
+    
+    /// Crea un pool de tamaño fijo con una política de despacho explícita
+    pub fn with_policy(name: &str, workers: usize, capacity: usize, policy: DispatchPolicy) -> Self {
+        Self::elastic(name, workers, capacity, policy, Elasticity::fixed(workers))
+    }
+    
+    /// Crea un pool con `min_workers` threads permanentes que crece a demanda
+    /// hasta `elasticity.max_workers`
+    /// 
+    /// Un worker extra se crea al encolar una tarea sin workers ociosos; los
+    /// que exceden el mínimo se retiran tras `elasticity.idle_timeout` sin
+    /// tareas.
+    pub fn elastic(
+        name: &str,
+        min_workers: usize,
+        capacity: usize,
+        policy: DispatchPolicy,
+        elasticity: Elasticity,
+    ) -> Self {
+        let max_workers = elasticity.max_workers.max(min_workers);
+        let shared = Arc::new(Shared {
+            name: name.to_string(),
+            state: Mutex::new(PoolState {
+                tasks: VecDeque::new(),
+                active: 0,
+                completed: 0,
+                panicked: 0,
+                last_panic: None,
+                shutting_down: false,
+                idle: Vec::with_capacity(max_workers),
+                signaled: vec![false; max_workers],
+                alive: vec![false; max_workers],
+                live: 0,
+                spawned: 0,
+                retired: 0,
+                busy: vec![Duration::ZERO; max_workers],
+                cores: vec![None; max_workers],
+                total_wait: Duration::ZERO,
+                started: 0,
+            }),
+            wakers: (0..max_workers).map(|_| Condvar::new()).collect(),
+            not_full: Condvar::new(),
+            capacity: capacity.max(1),
+            policy,
+            min_workers,
+            elasticity: Elasticity { max_workers, ..elasticity },
+            events: OnceLock::new(),
+            affinity: OnceLock::new(),
+        });
+        
+        let pool = Self {
+            shared,
+            handles: Mutex::new((0..max_workers).map(|_| None).collect()),
+        };
+        
+        {
+            let mut state = pool.shared.state.lock().unwrap();
+            for slot in 0..min_workers {
+                pool.spawn_worker(&mut state, slot);
+            }
+        }
+        
+        pool
+    }
+    
+    /// Lanza un worker en `slot`
+    fn spawn_worker(&self, state: &mut PoolState, slot: usize) {
+        let shared = Arc::clone(&self.shared);
+        let handle = thread::Builder::new()
+            .name(format!("{}-{}", self.shared.name, slot))
+            .spawn(move || Self::worker_loop(&shared, slot))
+            .expect("Failed to spawn pool worker");
+        
+        state.alive[slot] = true;
+        state.live += 1;
+        self.handles.lock().unwrap()[slot] = Some(handle);
+    }
+    
+    /// Encola una tarea, esperando si la cola está llena
+    pub fn execute<F>(&self, task: F) -> Result<(), SubmitError>
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        let mut state = self.shared.state.lock().unwrap();
+        while state.tasks.len() >= self.shared.capacity && !state.shutting_down {
+            state = self.shared.not_full.wait(state).unwrap();
+        }
+        self.push(state, Box::new(task))
+    }
+    
+    /// Encola una tarea sin esperar; falla con `Full` si no hay espacio
+    pub fn try_execute<F>(&self, task: F) -> Result<(), SubmitError>
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        let state = self.shared.state.lock().unwrap();
+        if state.tasks.len() >= self.shared.capacity && !state.shutting_down {
+            return Err(SubmitError::Full);
+        }
+        self.push(state, Box::new(task))
+    }
+    
+    /// Ejecuta `f` en el pool y espera su resultado
+    /// 
+    /// Falla con `Full` si la cola está llena y con `TimedOut` si la tarea no
+    /// empezó dentro de `max_wait` (en ese caso ya no se ejecuta). Una vez
+    /// que empezó, espera a que termine.
+    pub fn call<F, T>(&self, f: F, max_wait: Duration) -> Result<T, SubmitError>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        const WAITING: u8 = 0;
+        const RUNNING: u8 = 1;
+        const ABANDONED: u8 = 2;
+        
+        let phase = Arc::new(AtomicU8::new(WAITING));
+        let (tx, rx) = mpsc::channel();
+        
+        let task_phase = Arc::clone(&phase);
+        self.try_execute(move || {
+            if task_phase.compare_exchange(WAITING, RUNNING, Ordering::SeqCst, Ordering::SeqCst).is_err() {
+                return;
+            }
+            // Se reporta el mensaje y se relanza para que el pool lo cuente
+            match panic::catch_unwind(AssertUnwindSafe(f)) {
+                Ok(value) => { let _ = tx.send(Ok(value)); }
+                Err(payload) => {
+                    let _ = tx.send(Err(panic_message(payload.as_ref())));
+                    panic::resume_unwind(payload);
+                }
+            }
+        })?;
+        
+        let lost = || SubmitError::Panicked("task dropped without result".to_string());
+        let outcome = match rx.recv_timeout(max_wait) {
+            Ok(outcome) => outcome,
+            Err(RecvTimeoutError::Disconnected) => return Err(lost()),
+            Err(RecvTimeoutError::Timeout) => {
+                // Si todavía no empezó, se abandona; si ya empezó, se espera
+                if phase.compare_exchange(WAITING, ABANDONED, Ordering::SeqCst, Ordering::SeqCst).is_ok() {
+                    return Err(SubmitError::TimedOut);
+                }
+                rx.recv().map_err(|_| lost())?
+            }
+        };
+        outcome.map_err(SubmitError::Panicked)
+    }
+    
+    fn push(&self, mut state: MutexGuard<'_, PoolState>, task: Task) -> Result<(), SubmitError> {
+        if state.shutting_down {
+            return Err(SubmitError::ShutDown);
+        }
+        state.tasks.push_back((Instant::now(), task));
+        
+        // Sin workers ociosos, crecer si todavía hay slots libres
+        if !self.shared.wake_one(&mut state) && state.live < self.shared.elasticity.max_workers {
+            if let Some(slot) = state.alive.iter().position(|alive| !alive) {
+                self.spawn_worker(&mut state, slot);
+                if state.live > self.shared.min_workers {
+                    state.spawned += 1;
+                    if self.shared.elasticity.log {
+                        println!("🔼 Pool {}: spawned worker {}-{} ({} alive)",
+                            self.shared.name, self.shared.name, slot, state.live);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+    
+    /// Indica si la cola está llena (`execute` esperaría)
+    pub fn is_full(&self) -> bool {
+        self.shared.state.lock().unwrap().tasks.len() >= self.shared.capacity
+    }
+    
+    /// Tareas en cola y capacidad de la cola (sin armar todo `stats`)
+    pub fn queue_usage(&self) -> (usize, usize) {
+        (self.shared.state.lock().unwrap().tasks.len(), self.shared.capacity)
+    }
+    
+    /// Publica en `events` los panics de las tareas
+    /// 
+    /// Solo tiene efecto la primera vez.
+    pub fn set_events(&self, events: EventBus) {
+        let _ = self.shared.events.set(events);
+    }
+    
+    /// Fija los workers a `cores` en round-robin (el worker `i` usa
+    /// `cores[i % cores.len()]`)
+    ///
+    /// Cada worker se fija al tomar su próxima tarea. Solo tiene efecto la
+    /// primera vez; una lista vacía no cambia nada.
+    pub fn set_affinity(&self, cores: Vec<usize>) {
+        if !cores.is_empty() {
+            let _ = self.shared.affinity.set(cores);
+        }
+    }
+    
+    /// Estadísticas actuales del pool
+    pub fn stats(&self) -> PoolStats {
+        let state = self.shared.state.lock().unwrap();
+        let avg_wait_ms = if state.started > 0 {
+            state.total_wait.as_secs_f64() * 1000.0 / state.started as f64
+        } else {
+            0.0
+        };
+        
+        PoolStats {
+            name: self.shared.name.clone(),
+            policy: self.shared.policy,
+            workers: state.live,
+            min_workers: self.shared.min_workers,
+            max_workers: self.shared.elasticity.max_workers,
+            queued: state.tasks.len(),
+            capacity: self.shared.capacity,
+            active: state.active,
+            completed: state.completed,
+            panicked: state.panicked,
+            last_panic: state.last_panic.clone(),
+            spawned: state.spawned,
+            retired: state.retired,
+            avg_wait_ms,
+            busy_ms: state.busy.iter().map(|d| d.as_millis() as u64).collect(),
+            cores: state.cores.clone(),
+        }
+    }
+    
+    /// Número mínimo (permanente) de workers del pool
+    pub fn size(&self) -> usize {
+        self.shared.min_workers
+    }
+    
+    /// Nombres de los workers permanentes (`<name>-<i>`); los extras de un
+    /// pool elástico usan los índices siguientes
+    pub fn worker_names(&self) -> Vec<String> {
+        (0..self.shared.min_workers).map(|i| format!("{}-{}", self.shared.name, i)).collect()
+    }
+    
+    /// Deja de aceptar tareas, termina las encoladas y espera a los workers
+    /// 
+    /// Es idempotente: llamarlo de nuevo (o hacer drop) no hace nada.
+    pub fn shutdown(&self) {
+        self.shared.state.lock().unwrap().shutting_down = true;
+        for waker in &self.shared.wakers {
+            waker.notify_all();
+        }
+        self.shared.not_full.notify_all();
+        
+        let handles: Vec<_> = self.handles.lock().unwrap().iter_mut().filter_map(Option::take).collect();
+        for handle in handles {
+            let _ = handle.join();
+        }
+    }
+    
+    /// Loop del worker `index`: toma tareas hasta que el pool se apaga y la
+    /// cola queda vacía, o hasta retirarse por ocioso
+    fn worker_loop(shared: &Shared, index: usize) {
+        let mut pinned = false;
+        loop {
+            let task = {
+                let mut state = shared.state.lock().unwrap();
+                loop {
+                    if let Some((queued_at, task)) = shared.pop(&mut state) {
+                        state.active += 1;
+                        state.total_wait += queued_at.elapsed();
+                        state.started += 1;
+                        shared.not_full.notify_one();
+                        break task;
+                    }
+                    if state.shutting_down {
+                        state.alive[index] = false;
+                        state.cores[index] = None;
+                        state.live -= 1;
+                        return;
+                    }
+                    
+                    // Esperar a que un envío despierte a este worker
+                    state.signaled[index] = false;
+                    state.idle.push(index);
+                    let idle_since = Instant::now();
+                    while !state.signaled[index] && !state.shutting_down {
+                        if state.live <= shared.min_workers {
+                            state = shared.wakers[index].wait(state).unwrap();
+                            continue;
+                        }
+                        
+                        // Worker extra: se retira si pasa demasiado tiempo ocioso
+                        let idle_for = idle_since.elapsed();
+                        if idle_for >= shared.elasticity.idle_timeout {
+                            state.idle.retain(|&worker| worker != index);
+                            state.alive[index] = false;
+                            state.cores[index] = None;
+                            state.live -= 1;
+                            state.retired += 1;
+                            if shared.elasticity.log {
+                                println!("🔽 Pool {}: retired idle worker {}-{} ({} alive)",
+                                    shared.name, shared.name, index, state.live);
+                            }
+                            return;
+                        }
+                        let remaining = shared.elasticity.idle_timeout - idle_for;
+                        state = shared.wakers[index].wait_timeout(state, remaining).unwrap().0;
+                    }
+                    state.idle.retain(|&worker| worker != index);
+                }
+            };
+            
+            if !pinned {
+                pinned = shared.pin(index);
+            }
+            
+            // Un panic en la tarea no debe matar al worker
+            let started = Instant::now();
+            let outcome = panic::catch_unwind(AssertUnwindSafe(task));
+            
+            let mut state = shared.state.lock().unwrap();
+            state.active -= 1;
+            state.busy[index] += started.elapsed();
+            match outcome {
+                Ok(()) => state.completed += 1,
+                Err(payload) => {
+                    let message = panic_message(payload.as_ref());
+                    state.panicked += 1;
+                    state.last_panic = Some(message.clone());
+                    drop(state);
+                    
+                    if let Some(events) = shared.events.get() {
+                        events.publish(Event::WorkerPanicked { pool: shared.name.clone(), message, job_id: None });
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Drop for ThreadPool {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    #[test]
+    fn test_executes_all_tasks_before_shutdown() {
+        let pool = ThreadPool::new("test", 3, 100);
+        let counter = Arc::new(AtomicUsize::new(0));
+
+        for _ in 0..50 {
+            let counter = Arc::clone(&counter);
+            pool.execute(move || {
+                counter.fetch_add(1, Ordering::SeqCst);
+            }).unwrap();
+        }
+        pool.shutdown();
+
+        assert_eq!(counter.load(Ordering::SeqCst), 50);
+        assert_eq!(pool.stats().completed, 50);
+        assert_eq!(pool.execute(|| {}), Err(SubmitError::ShutDown));
+    }
+
+    #[test]
+    fn test_try_execute_full() {
+        let pool = ThreadPool::new("full", 0, 2);
+        assert!(pool.try_execute(|| {}).is_ok());
+        assert_eq!(pool.queue_usage(), (1, 2));
+        assert!(pool.try_execute(|| {}).is_ok());
+        assert_eq!(pool.queue_usage(), (2, 2));
+        assert_eq!(pool.try_execute(|| {}), Err(SubmitError::Full));
+        assert_eq!(pool.stats().queued, 2);
+        assert!(pool.is_full());
+    }
+
+    #[test]
+    fn test_panic_is_contained() {
+        let pool = ThreadPool::new("panic", 1, 10);
+        pool.execute(|| panic!("boom {}", 7)).unwrap();
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        pool.execute(move || tx.send(thread::current().name().map(str::to_string)).unwrap()).unwrap();
+
+        // El mismo worker sigue vivo después del panic
+        let name = rx.recv_timeout(Duration::from_secs(2)).unwrap();
+        assert_eq!(name.as_deref(), Some("panic-0"));
+
+        pool.shutdown();
+        let stats = pool.stats();
+        assert_eq!(stats.panicked, 1);
+        assert_eq!(stats.completed, 1);
+        assert_eq!(stats.last_panic.as_deref(), Some("boom 7"));
+    }
+
+    #[test]
+    fn test_panic_is_published() {
+        let pool = ThreadPool::new("events", 1, 10);
+        let events = EventBus::new();
+        let received = events.subscribe("test");
+        pool.set_events(events);
+
+        pool.execute(|| panic!("boom")).unwrap();
+
+        let event = received.recv_timeout(Duration::from_secs(2)).unwrap();
+        assert_eq!(event, Event::WorkerPanicked {
+            pool: "events".to_string(),
+            message: "boom".to_string(),
+            job_id: None,
+        });
+    }
+
+    #[test]
+    fn test_execute_blocks_until_space() {
+        let pool = Arc::new(ThreadPool::new("block", 1, 1));
+        let (release_tx, release_rx) = std::sync::mpsc::channel::<()>();
+
+        // Ocupa el worker y llena la cola
+        pool.execute(move || { let _ = release_rx.recv(); }).unwrap();
+        thread::sleep(Duration::from_millis(50));
+        pool.execute(|| {}).unwrap();
+        assert_eq!(pool.try_execute(|| {}), Err(SubmitError::Full));
+
+        let submitter = {
+            let pool = Arc::clone(&pool);
+            thread::spawn(move || pool.execute(|| {}))
+        };
+        thread::sleep(Duration::from_millis(50));
+        release_tx.send(()).unwrap();
+
+        assert!(submitter.join().unwrap().is_ok());
+    }
+
+    #[test]
+    fn test_dispatch_policy_parse() {
+        assert_eq!(DispatchPolicy::parse("FIFO").unwrap(), DispatchPolicy::Fifo);
+        assert_eq!(DispatchPolicy::parse("lifo").unwrap(), DispatchPolicy::Lifo);
+        assert_eq!(DispatchPolicy::parse("least_busy").unwrap(), DispatchPolicy::LeastBusy);
+        assert!(DispatchPolicy::parse("random").unwrap_err().contains("least-busy"));
+        assert_eq!(DispatchPolicy::LeastBusy.as_str(), "least-busy");
+    }
+
+    /// Encola 1..=5 mientras el único worker está ocupado y devuelve el
+    /// orden en que se ejecutaron
+    fn execution_order(policy: DispatchPolicy) -> Vec<usize> {
+        let pool = ThreadPool::with_policy("order", 1, 10, policy);
+        let (release_tx, release_rx) = std::sync::mpsc::channel::<()>();
+        let order = Arc::new(Mutex::new(Vec::new()));
+
+        pool.execute(move || { let _ = release_rx.recv(); }).unwrap();
+        thread::sleep(Duration::from_millis(50));
+        for i in 1..=5 {
+            let order = Arc::clone(&order);
+            pool.execute(move || order.lock().unwrap().push(i)).unwrap();
+        }
+        release_tx.send(()).unwrap();
+        pool.shutdown();
+
+        let order = order.lock().unwrap().clone();
+        order
+    }
+
+    #[test]
+    fn test_fifo_and_lifo_order() {
+        assert_eq!(execution_order(DispatchPolicy::Fifo), vec![1, 2, 3, 4, 5]);
+        assert_eq!(execution_order(DispatchPolicy::Lifo), vec![5, 4, 3, 2, 1]);
+    }
+
+    #[test]
+    fn test_least_busy_wakes_idle_worker_with_least_busy_time() {
+        let pool = ThreadPool::with_policy("lb", 2, 10, DispatchPolicy::LeastBusy);
+        let (tx, rx) = std::sync::mpsc::channel();
+
+        // Secuencialmente: cada tarea debe ir al worker que menos trabajó
+        for _ in 0..4 {
+            let tx = tx.clone();
+            pool.execute(move || {
+                thread::sleep(Duration::from_millis(30));
+                tx.send(thread::current().name().unwrap().to_string()).unwrap();
+            }).unwrap();
+            rx.recv_timeout(Duration::from_secs(2)).unwrap();
+            thread::sleep(Duration::from_millis(20));
+        }
+
+        let stats = pool.stats();
+        assert_eq!(stats.policy, DispatchPolicy::LeastBusy);
+        assert_eq!(stats.completed, 4);
+        // La carga quedó repartida entre ambos workers
+        assert!(stats.busy_ms.iter().all(|&ms| ms >= 30), "{:?}", stats.busy_ms);
+    }
+
+    #[test]
+    fn test_call_returns_result_or_times_out() {
+        let pool = ThreadPool::new("call", 1, 4);
+        assert_eq!(pool.call(|| 6 * 7, Duration::from_secs(1)), Ok(42));
+        assert_eq!(
+            pool.call(|| -> u8 { panic!("boom") }, Duration::from_secs(1)),
+            Err(SubmitError::Panicked("boom".to_string()))
+        );
+        // El worker cuenta el panic después de entregar el mensaje
+        let deadline = Instant::now() + Duration::from_secs(2);
+        while pool.stats().panicked == 0 && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(5));
+        }
+        assert_eq!(pool.stats().panicked, 1);
+
+        // Con el worker ocupado, la tarea no empieza a tiempo y se descarta
+        let (release_tx, release_rx) = std::sync::mpsc::channel::<()>();
+        pool.execute(move || { let _ = release_rx.recv(); }).unwrap();
+        let ran = Arc::new(AtomicUsize::new(0));
+        let flag = Arc::clone(&ran);
+        let result = pool.call(move || flag.fetch_add(1, Ordering::SeqCst), Duration::from_millis(50));
+        assert_eq!(result, Err(SubmitError::TimedOut));
+
+        release_tx.send(()).unwrap();
+        pool.shutdown();
+        assert_eq!(ran.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn test_elastic_pool_grows_and_retires_idle_workers() {
+        let elasticity = Elasticity::new(1, 3, Duration::from_millis(100), false);
+        let pool = ThreadPool::elastic("elastic", 1, 10, DispatchPolicy::Fifo, elasticity);
+        assert_eq!(pool.stats().workers, 1);
+        thread::sleep(Duration::from_millis(20)); // el worker permanente queda ocioso
+
+        // Tres tareas bloqueadas a la vez: el pool crece hasta el máximo
+        let (release_tx, release_rx) = std::sync::mpsc::channel::<()>();
+        let release_rx = Arc::new(Mutex::new(release_rx));
+        for _ in 0..3 {
+            let release_rx = Arc::clone(&release_rx);
+            pool.execute(move || { let _ = release_rx.lock().unwrap().recv(); }).unwrap();
+        }
+        thread::sleep(Duration::from_millis(50));
+        let stats = pool.stats();
+        assert_eq!((stats.workers, stats.max_workers, stats.spawned), (3, 3, 2));
+
+        for _ in 0..3 {
+            release_tx.send(()).unwrap();
+        }
+
+        // Los extras se retiran tras estar ociosos; el mínimo se mantiene
+        let deadline = Instant::now() + Duration::from_secs(3);
+        while pool.stats().workers > 1 && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(20));
+        }
+        let stats = pool.stats();
+        assert_eq!((stats.workers, stats.retired, stats.completed), (1, 2, 3));
+
+        // Y vuelve a crecer si hace falta
+        assert_eq!(pool.call(|| 1, Duration::from_secs(1)), Ok(1));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_affinity_pins_workers_round_robin() {
+        let pool = ThreadPool::new("pinned", 2, 4);
+        pool.set_affinity(vec![0]);
+        assert_eq!(pool.stats().cores, vec![None, None]);
+
+        // Cada worker se fija al tomar su primera tarea
+        assert_eq!(pool.call(affinity::current_core, Duration::from_secs(1)), Ok(Some(0)));
+        assert!(pool.stats().cores.contains(&Some(0)));
+        pool.shutdown();
+        assert_eq!(pool.stats().cores, vec![None, None]);
+    }
+
+    #[test]
+    fn test_elasticity_zero_max_means_fixed() {
+        assert_eq!(Elasticity::new(4, 0, Duration::from_secs(1), false).max_workers, 4);
+        assert_eq!(Elasticity::new(4, 2, Duration::from_secs(1), false).max_workers, 4);
+        assert_eq!(Elasticity::new(4, 8, Duration::from_secs(1), false).max_workers, 8);
+    }
+}
@@ -0,0 +1,130 @@
+//! # Prioridad Interactiva sobre Batch
+//! src/workers/priority.rs
+//!
+//! `PriorityGate` cuenta los requests interactivos (síncronos) en curso. Los
+//! workers batch (jobs) llaman a `yield_to_interactive` entre jobs: si hay
+//! requests interactivos en curso, esperan a que terminen (hasta un máximo,
+//! para que los jobs no se queden sin avanzar bajo carga constante).
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+/// Coordina a los requests interactivos con los workers batch
+#[derive(Default)]
+pub struct PriorityGate {
+    /// Requests interactivos en curso
+    interactive: Mutex<usize>,
+    
+    /// Notifica cuando no quedan requests interactivos
+    idle: Condvar,
+    
+    /// Veces que un worker batch cedió el paso
+    yields: AtomicU64,
+}
+
+/// Marca un request interactivo en curso mientras vive
+pub struct InteractiveGuard {
+    gate: Arc<PriorityGate>,
+}
+
+impl PriorityGate {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Registra un request interactivo hasta que se suelte el guard
+    pub fn enter(self: &Arc<Self>) -> InteractiveGuard {
+        *self.interactive.lock().unwrap() += 1;
+        InteractiveGuard { gate: Arc::clone(self) }
+    }
+    
+    /// Requests interactivos en curso
+    pub fn interactive(&self) -> usize {
+        *self.interactive.lock().unwrap()
+    }
+    
+    /// Veces que un worker batch cedió el paso
+    pub fn yields(&self) -> u64 {
+        self.yields.load(Ordering::Relaxed)
+    }
+    
+    /// Espera, hasta `max_wait`, a que no haya requests interactivos en curso
+    /// 
+    /// Retorna true si tuvo que ceder el paso. Con `max_wait` cero no espera.
+    pub fn yield_to_interactive(&self, max_wait: Duration) -> bool {
+        if max_wait.is_zero() {
+            return false;
+        }
+        
+        let mut interactive = self.interactive.lock().unwrap();
+        if *interactive == 0 {
+            return false;
+        }
+        
+        self.yields.fetch_add(1, Ordering::Relaxed);
+        let deadline = Instant::now() + max_wait;
+        while *interactive > 0 {
+            let now = Instant::now();
+            if now >= deadline {
+                break;
+            }
+            interactive = self.idle.wait_timeout(interactive, deadline - now).unwrap().0;
+        }
+        true
+    }
+}
+
+impl Drop for InteractiveGuard {
+    fn drop(&mut self) {
+        let mut interactive = self.gate.interactive.lock().unwrap();
+        *interactive -= 1;
+        if *interactive == 0 {
+            self.gate.idle.notify_all();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn test_no_wait_without_interactive_requests() {
+        let gate = PriorityGate::new();
+        assert!(!gate.yield_to_interactive(Duration::from_secs(1)));
+        assert_eq!(gate.yields(), 0);
+    }
+
+    #[test]
+    fn test_batch_waits_until_interactive_done() {
+        let gate = Arc::new(PriorityGate::new());
+        let guard = gate.enter();
+        assert_eq!(gate.interactive(), 1);
+
+        let releaser = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(50));
+            drop(guard);
+        });
+
+        let start = Instant::now();
+        assert!(gate.yield_to_interactive(Duration::from_secs(2)));
+        assert!(start.elapsed() >= Duration::from_millis(40));
+        assert!(start.elapsed() < Duration::from_secs(1));
+        assert_eq!(gate.interactive(), 0);
+        assert_eq!(gate.yields(), 1);
+        releaser.join().unwrap();
+    }
+
+    #[test]
+    fn test_wait_is_bounded() {
+        let gate = Arc::new(PriorityGate::new());
+        let _guard = gate.enter();
+
+        let start = Instant::now();
+        assert!(gate.yield_to_interactive(Duration::from_millis(30)));
+        assert!(start.elapsed() < Duration::from_millis(500));
+        assert!(!gate.yield_to_interactive(Duration::ZERO));
+    }
+}
@@ -1,185 +1,326 @@
 //! Tests de integración para el servidor HTTP
 //! src/tests/integration_test.rs
 //!
-//! IMPORTANTE: Estos tests requieren que el servidor esté corriendo.
-//! 
-//! Para ejecutar:
-//! 1. Terminal 1: cargo run
-//! 2. Terminal 2: cargo test --test integration_test
+//! Cada test habla con un servidor real levantado por `testing::TestServer`
+//! en un puerto efímero, así que no hace falta tener `cargo run` corriendo:
+//!
+//! cargo test --test integration_test
 
-use std::io::{Read, Write};
+use http_server::client::{ClientError, ClientResponse};
+use http_server::commands::sandbox;
+use http_server::http::{Request, Response};
+use http_server::metrics::MetricsCollector;
+use http_server::router::ServerError;
+use http_server::jobs::types::{JobPriority, JobStatus};
+use http_server::testing::TestServer;
 use std::net::TcpStream;
-use std::thread;
+use std::sync::{Arc, OnceLock};
 use std::time::Duration;
 
-/// Helper: envía un request HTTP y retorna la response completa
-fn send_request(path: &str) -> Result<String, Box<dyn std::error::Error>> {
-    // Dar tiempo al servidor a estar listo
-    thread::sleep(Duration::from_millis(50));
-    
-    // Conectar al servidor
-    let mut stream = TcpStream::connect("127.0.0.1:8080")?;
-    
-    // Configurar timeouts
-    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
-    stream.set_write_timeout(Some(Duration::from_secs(5)))?;
-    
-    // Construir request HTTP/1.0 (más simple)
-    let request = format!(
-        "GET {} HTTP/1.0\r\n\r\n",
-        path
-    );
-    
-    // Enviar request
-    stream.write_all(request.as_bytes())?;
-    stream.flush()?;
-    
-    // Leer response
-    let mut response = String::new();
-    stream.read_to_string(&mut response)?;
-    
-    Ok(response)
-}
-
-/// Helper: extrae el body JSON de una response HTTP
-fn extract_body(response: &str) -> &str {
-    // Buscar la línea vacía que separa headers del body
-    if let Some(pos) = response.find("\r\n\r\n") {
-        &response[pos + 4..]
-    } else {
-        ""
-    }
+/// Servidor compartido por los tests de este binario
+///
+/// El sandbox de archivos es global del proceso, así que todos los tests
+/// usan el mismo servidor (nunca se detiene; muere con el proceso).
+fn server() -> &'static TestServer {
+    static SERVER: OnceLock<TestServer> = OnceLock::new();
+    SERVER.get_or_init(TestServer::start)
+}
+
+/// Helper: envía `GET path` al servidor compartido
+fn send_request(path: &str) -> ClientResponse {
+    server().client().get(path).expect("Failed to send request")
 }
 
 #[test]
 fn test_help_endpoint() {
-    let response = send_request("/help").expect("Failed to send request");
-    
+    let response = send_request("/help");
+
     // Verificar que la response es exitosa
-    assert!(response.contains("200 OK"), "Expected 200 OK, got: {}", response);
-    
+    assert_eq!(response.status, 200, "Expected 200 OK, got: {:?}", response);
+
     // Verificar que el body contiene "commands"
-    let body = extract_body(&response);
-    assert!(body.contains("commands"), "Body should contain 'commands'");
-    assert!(body.contains("/fibonacci"), "Body should list fibonacci command");
+    assert!(response.body.contains("commands"), "Body should contain 'commands'");
+    assert!(response.body.contains("/fibonacci"), "Body should list fibonacci command");
+}
+
+#[test]
+fn test_routes_json_is_generated_from_the_router() {
+    let response = send_request("/routes.json");
+    assert_eq!(response.status, 200);
+
+    let spec: serde_json::Value = serde_json::from_str(&response.body).unwrap();
+    let fibonacci = &spec["paths"]["/fibonacci"]["get"];
+    assert_eq!(fibonacci["parameters"][0]["name"], "num");
+    assert_eq!(fibonacci["parameters"][0]["required"], true);
+    assert!(spec["paths"]["/jobs/submit"]["post"].is_object());
+    assert!(spec["paths"]["/uploadfile"]["get"].is_null());
+    assert!(spec["paths"]["/metrics"]["get"].is_object());
+    assert!(spec["paths"]["/admin/shutdown"]["post"].is_object());
+    assert!(spec["paths"]["/ws"]["get"].is_object());
 }
 
 #[test]
 fn test_status_endpoint() {
-    let response = send_request("/status").expect("Failed to send request");
-    
-    assert!(response.contains("200 OK"));
-    let body = extract_body(&response);
-    assert!(body.contains("status"));
-    assert!(body.contains("running"));
+    let response = send_request("/status");
+
+    assert_eq!(response.status, 200);
+    assert!(response.body.contains("status"));
+    assert!(response.body.contains("running"));
 }
 
 #[test]
 fn test_fibonacci_endpoint() {
-    let response = send_request("/fibonacci?num=10").expect("Failed to send request");
-    
-    assert!(response.contains("200 OK"));
-    let body = extract_body(&response);
-    assert!(body.contains("55"), "fib(10) should be 55, got: {}", body);
+    let response = send_request("/fibonacci?num=10");
+
+    assert_eq!(response.status, 200);
+    assert!(response.body.contains("55"), "fib(10) should be 55, got: {}", response.body);
 }
 
 #[test]
 fn test_fibonacci_larger_number() {
-    let response = send_request("/fibonacci?num=20").expect("Failed to send request");
-    
-    assert!(response.contains("200 OK"));
-    let body = extract_body(&response);
-    assert!(body.contains("6765"), "fib(20) should be 6765");
+    let response = send_request("/fibonacci?num=20");
+
+    assert_eq!(response.status, 200);
+    assert!(response.body.contains("6765"), "fib(20) should be 6765");
 }
 
 #[test]
 fn test_reverse_endpoint() {
-    let response = send_request("/reverse?text=hello").expect("Failed to send request");
-    
-    assert!(response.contains("200 OK"));
-    let body = extract_body(&response);
-    assert!(body.contains("olleh"));
+    let response = send_request("/reverse?text=hello");
+
+    assert_eq!(response.status, 200);
+    assert!(response.body.contains("olleh"));
 }
 
 #[test]
 fn test_reverse_with_spaces() {
-    let response = send_request("/reverse?text=hello%20world").expect("Failed to send request");
-    
-    assert!(response.contains("200 OK"));
-    let body = extract_body(&response);
-    assert!(body.contains("dlrow olleh"));
+    let response = send_request("/reverse?text=hello%20world");
+
+    assert_eq!(response.status, 200);
+    assert!(response.body.contains("dlrow olleh"));
 }
 
 #[test]
 fn test_toupper_endpoint() {
-    let response = send_request("/toupper?text=hello").expect("Failed to send request");
-    
-    assert!(response.contains("200 OK"));
-    let body = extract_body(&response);
-    assert!(body.contains("HELLO"));
+    let response = send_request("/toupper?text=hello");
+
+    assert_eq!(response.status, 200);
+    assert!(response.body.contains("HELLO"));
 }
 
 #[test]
 fn test_timestamp_endpoint() {
-    let response = send_request("/timestamp").expect("Failed to send request");
-    
-    assert!(response.contains("200 OK"));
-    let body = extract_body(&response);
-    assert!(body.contains("timestamp"));
-    
+    let response = send_request("/timestamp");
+
+    assert_eq!(response.status, 200);
+    assert!(response.body.contains("timestamp"));
+
     // Verificar que el timestamp es un número razonable (mayor que 2020-01-01)
-    assert!(body.contains("1"), "Should contain timestamp digits");
+    assert!(response.body.contains("1"), "Should contain timestamp digits");
 }
 
 #[test]
 fn test_not_found() {
-    let response = send_request("/nonexistent").expect("Failed to send request");
-    
-    assert!(response.contains("404"), "Expected 404 for non-existent route");
-    let body = extract_body(&response);
+    let response = send_request("/nonexistent");
+
+    assert_eq!(response.status, 404, "Expected 404 for non-existent route");
+    let body = &response.body;
     assert!(body.contains("error") || body.contains("not found") || body.contains("Route not found"));
 }
 
 #[test]
 fn test_fibonacci_missing_param() {
-    let response = send_request("/fibonacci").expect("Failed to send request");
-    
-    assert!(response.contains("400"), "Expected 400 for missing parameter");
-    let body = extract_body(&response);
-    assert!(body.contains("error"));
+    let response = send_request("/fibonacci");
+
+    assert_eq!(response.status, 400, "Expected 400 for missing parameter");
+    assert!(response.body.contains("error"));
 }
 
 #[test]
 fn test_fibonacci_invalid_param() {
-    let response = send_request("/fibonacci?num=abc").expect("Failed to send request");
-    
-    assert!(response.contains("400"), "Expected 400 for invalid parameter");
+    let response = send_request("/fibonacci?num=abc");
+
+    assert_eq!(response.status, 400, "Expected 400 for invalid parameter");
 }
 
 #[test]
 fn test_fibonacci_too_large() {
-    let response = send_request("/fibonacci?num=100").expect("Failed to send request");
-    
-    assert!(response.contains("400"), "Expected 400 for num > 90");
+    let response = send_request("/fibonacci?num=100");
+
+    assert_eq!(response.status, 400, "Expected 400 for num > 90");
 }
 
 #[test]
 fn test_multiple_requests_sequentially() {
     // Verificar que el servidor puede manejar múltiples requests
     for i in 0..5 {
-        let response = send_request(&format!("/fibonacci?num={}", i)).expect("Failed to send request");
-        assert!(response.contains("200 OK"), "Request {} failed", i);
+        let response = send_request(&format!("/fibonacci?num={}", i));
+        assert_eq!(response.status, 200, "Request {} failed", i);
     }
 }
 
 #[test]
 fn test_reverse_missing_param() {
-    let response = send_request("/reverse").expect("Failed to send request");
-    assert!(response.contains("400"));
+    let response = send_request("/reverse");
+    assert_eq!(response.status, 400);
 }
 
 #[test]
 fn test_toupper_missing_param() {
-    let response = send_request("/toupper").expect("Failed to send request");
-    assert!(response.contains("400"));
-}
\ No newline at end of file
+    let response = send_request("/toupper");
+    assert_eq!(response.status, 400);
+}
+
+#[test]
+fn test_observability_headers() {
+    let response = send_request("/status");
+    assert!(response.header("X-Request-Id").is_some());
+    assert!(response.header("X-Worker-Thread").is_some());
+}
+
+#[test]
+fn test_files_live_in_server_data_dir() {
+    // El `data_dir` del builder es el sandbox, no `./data`
+    assert_eq!(sandbox::root().unwrap(), server().data_dir());
+    let client = server().client();
+    let created = client.run_command("createfile", &[("name", "it_test.txt"), ("content", "hola"), ("repeat", "2")]);
+    assert!(created.is_ok(), "createfile failed: {:?}", created);
+    assert!(server().data_dir().join("it_test.txt").exists());
+    assert!(!std::path::Path::new("data/it_test.txt").exists());
+
+    client.run_command("deletefile", &[("name", "it_test.txt")]).unwrap();
+    assert!(!server().data_dir().join("it_test.txt").exists());
+}
+
+#[test]
+fn test_job_submit_and_wait_for_result() {
+    let client = server().client().with_poll_interval(Duration::from_millis(20));
+
+    let id = client.submit_job("isprime", &[("n", "97")], JobPriority::High).unwrap();
+    let result = client.wait_for_result(&id, Duration::from_secs(30)).unwrap();
+    assert_eq!(result["is_prime"], true, "unexpected result: {}", result);
+
+    assert_eq!(client.job_status(&id).unwrap().status, JobStatus::Done);
+    assert!(client.list_jobs(Some(JobStatus::Done), 500).unwrap().iter().any(|job| job.id == id));
+}
+
+#[test]
+fn test_job_errors_are_typed() {
+    let client = server().client();
+
+    match client.job_status("job-does-not-exist") {
+        Err(ClientError::Http { status, .. }) => assert_eq!(status, 404),
+        other => panic!("expected 404, got {:?}", other),
+    }
+    match client.submit_job("no-such-task", &[], JobPriority::Normal) {
+        Err(ClientError::Http { status, message, .. }) => {
+            assert_eq!(status, 400);
+            assert!(message.contains("Unknown task type"));
+        }
+        other => panic!("expected 400, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_shutdown_releases_port() {
+    // Comparte el directorio de datos para no mover el sandbox global
+    let shared_dir = server().data_dir().to_string_lossy().to_string();
+    let own = TestServer::start_with(|config| config.data_dir = shared_dir);
+    let addr = own.addr();
+    assert_eq!(own.client().get("/status").unwrap().status, 200);
+
+    own.shutdown();
+
+    assert!(TcpStream::connect_timeout(&addr, Duration::from_millis(500)).is_err());
+    assert!(server().data_dir().exists());
+}
+
+#[test]
+fn test_admin_shutdown_drains_and_stops() {
+    let shared_dir = server().data_dir().to_string_lossy().to_string();
+    let own = TestServer::start_with(|config| {
+        config.data_dir = shared_dir;
+        config.admin_token = Some("s3cret-token".to_string());
+    });
+    let addr = own.addr();
+
+    assert_eq!(own.client().get("/admin/shutdown").unwrap().status, 401);
+    let admin = own.client().with_bearer_token("s3cret-token");
+    assert_eq!(admin.get("/admin/shutdown").unwrap().status, 405);
+
+    let state = admin.get("/admin/state").unwrap().json().unwrap();
+    assert_eq!(state["state"], "running");
+    assert!(state["connections"]["in_flight"].as_u64().unwrap() >= 1);
+
+    let response = admin.post_json("/admin/shutdown?grace_ms=500", &serde_json::json!({})).unwrap();
+    assert_eq!(response.status, 202);
+    assert_eq!(response.json().unwrap()["state"], "draining");
+
+    // El accept loop termina por su cuenta y suelta el puerto
+    let stopped = (0..50).any(|_| {
+        std::thread::sleep(Duration::from_millis(100));
+        TcpStream::connect_timeout(&addr, Duration::from_millis(100)).is_err()
+    });
+    assert!(stopped, "server still accepting after /admin/shutdown");
+}
+
+fn custom_status(_request: &Request) -> Response {
+    Response::text("custom status")
+}
+
+fn ping(_request: &Request) -> Response {
+    Response::text("pong")
+}
+
+#[test]
+fn test_builder_composes_routes_and_subsystems() {
+    let shared_dir = server().data_dir().to_string_lossy().to_string();
+    let metrics = Arc::new(MetricsCollector::new());
+
+    // Una ruta propia reemplaza al comando integrado del mismo path
+    let overriding = TestServer::start_builder(|builder| {
+        builder
+            .configure(|config| config.data_dir = shared_dir.clone())
+            .route("/status", custom_status)
+            .with_metrics(Arc::clone(&metrics))
+    });
+    assert_eq!(overriding.client().get("/status").unwrap().body, "custom status");
+    assert_eq!(overriding.client().get("/fibonacci?num=10").unwrap().status, 200);
+    // El colector que recibió el builder es el que cuenta los requests
+    let counted = (0..50).any(|_| {
+        std::thread::sleep(Duration::from_millis(20));
+        metrics.get_snapshot().total_requests >= 2
+    });
+    assert!(counted, "requests not recorded in the injected collector");
+
+    // Sin los comandos integrados solo quedan las rutas propias (y las del servidor)
+    let bare = TestServer::start_builder(|builder| {
+        builder
+            .configure(|config| config.data_dir = shared_dir)
+            .without_builtin_routes()
+            .route("/ping", ping)
+    });
+    assert_eq!(bare.client().get("/ping").unwrap().body, "pong");
+    assert_eq!(bare.client().get("/fibonacci?num=10").unwrap().status, 404);
+    assert_eq!(bare.client().get("/metrics").unwrap().status, 200);
+}
+
+fn plain_error(error: &ServerError) -> Response {
+    Response::builder(error.status).body(&format!("{} ({})", error.message, error.cause.code()))
+}
+
+#[test]
+fn test_builder_installs_a_custom_error_handler() {
+    let shared_dir = server().data_dir().to_string_lossy().to_string();
+    let own = TestServer::start_builder(|builder| {
+        builder
+            .configure(|config| config.data_dir = shared_dir)
+            .error_handler(plain_error)
+    });
+
+    let missing = own.client().get("/nonexistent").unwrap();
+    assert_eq!(missing.status, 404);
+    assert_eq!(missing.body, "Route not found: /nonexistent (not_found)");
+    // Los errores de los comandos siguen siendo suyos
+    assert!(own.client().get("/fibonacci").unwrap().body.contains("Missing"));
+}